@@ -11,18 +11,29 @@ use bevy::core_pipeline::core_2d::Camera2d;
 use bevy::prelude::*;
 use bevy::render::camera::ClearColorConfig;
 use bevy_editor_assets::AssetBrowserPlugin;
-use bevy_editor_commands::EditorHistory;
+use bevy_editor_commands::{
+    flush_command_journal, handle_macro_requests, handle_undo_redo_shortcuts, CommandJournal,
+    CommandRegistry, CrashRecovery, EditorHistory, HistoryRequest, MacroRecorder, MacroRequest,
+};
 use bevy_editor_core::{handle_gizmo_mode_shortcuts, EditorCameraPlugin, EditorCorePlugin};
 use bevy_editor_foundation::{EditorState, EditorStatePlugin};
 use bevy_editor_frontend_api::{EditorAction, EditorEvent, EditorFrontend, ProjectCommand};
 use bevy_editor_project::{BevyCLIRunner, CLICommand, ProjectManagerPlugin, ProjectManagerSet};
-use bevy_editor_scene::{mark_loaded_scene_entities, SceneAutoLoader, SceneEditorPlugin};
+use bevy_editor_scene::{
+    handle_play_mode_requests, mark_loaded_scene_entities, PlayMode, PlayModeRequest,
+    SceneAutoLoader, SceneEditorPlugin,
+};
 use bevy_editor_tilemap::TilemapEditorPlugin;
 
-use scene_loader::{auto_load_scene_system, reset_auto_loader_on_project_change, LoadSceneEvent};
+use scene_loader::{
+    auto_load_scene_system, reset_auto_loader_on_project_change, sync_imported_types,
+    LoadSceneEvent,
+};
 use systems::{
-    cache_runtime_scene_on_scene_switch, handle_save_load, restore_tilemap_from_level,
-    sync_tilemap_on_scene_switch, PendingTilemapRestore, PreviousSceneIndex,
+    apply_crash_recovery, cache_runtime_scene_on_scene_switch, handle_save_load,
+    restore_tilemap_from_level, restore_tileset_collisions_from_project, sync_command_journal,
+    sync_scene_modified_flag, sync_tilemap_on_scene_switch, PendingTilemapRestore,
+    PreviousSceneIndex,
 };
 
 /// Tracks the currently running project command so we can emit lifecycle events.
@@ -49,8 +60,14 @@ impl<F: EditorFrontend + Clone> Plugin for EditorAppPlugin<F> {
             .add_event::<EditorEvent>()
             .add_event::<LoadSceneEvent>()
             .add_event::<bevy_editor_scene::SceneTabChanged>()
+            .add_event::<HistoryRequest>()
+            .add_event::<MacroRequest>()
             .init_resource::<ActiveProjectCommand>()
             .init_resource::<EditorHistory>()
+            .init_resource::<CommandRegistry>()
+            .init_resource::<CommandJournal>()
+            .init_resource::<MacroRecorder>()
+            .init_resource::<CrashRecovery>()
             .init_resource::<PendingTilemapRestore>()
             .init_resource::<PreviousSceneIndex>()
             .init_resource::<SceneAutoLoader>()
@@ -68,6 +85,15 @@ impl<F: EditorFrontend + Clone> Plugin for EditorAppPlugin<F> {
             .add_systems(Startup, setup_editor_camera)
             // Core systems
             .add_systems(Update, handle_gizmo_mode_shortcuts)
+            .add_systems(
+                Update,
+                (
+                    handle_undo_redo_shortcuts,
+                    handle_macro_requests,
+                    sync_scene_modified_flag,
+                )
+                    .chain(),
+            )
             .add_systems(Update, auto_load_scene_system.after(ProjectManagerSet))
             .add_systems(
                 Update,
@@ -79,18 +105,39 @@ impl<F: EditorFrontend + Clone> Plugin for EditorAppPlugin<F> {
                     .chain()
                     .after(ProjectManagerSet),
             )
-            .add_systems(Update, restore_tilemap_from_level.after(ProjectManagerSet))
+            .add_systems(
+                Update,
+                (
+                    restore_tilemap_from_level,
+                    restore_tileset_collisions_from_project,
+                )
+                    .after(ProjectManagerSet),
+            )
             .add_systems(
                 PostUpdate,
                 cache_runtime_scene_on_scene_switch
                     .in_set(bevy_editor_scene::SceneTabSystemSet::Cache),
             )
+            // Journal each frame's edits before a tab switch swaps the history
+            .add_systems(
+                PostUpdate,
+                (
+                    flush_command_journal.before(bevy_editor_scene::SceneTabSystemSet::Cache),
+                    (sync_command_journal, apply_crash_recovery)
+                        .chain()
+                        .after(bevy_editor_scene::SceneTabSystemSet::Apply),
+                ),
+            )
             .add_systems(
                 Update,
                 (
                     reset_auto_loader_on_project_change.after(ProjectManagerSet),
-                    handle_editor_actions.after(ProjectManagerSet),
+                    handle_editor_actions
+                        .after(ProjectManagerSet)
+                        .before(handle_play_mode_requests),
+                    sync_editor_play_state.after(handle_play_mode_requests),
                     monitor_cli_runner.after(ProjectManagerSet),
+                    sync_imported_types.after(ProjectManagerSet),
                 ),
             );
 
@@ -116,6 +163,7 @@ fn handle_editor_actions(
     mut cli_runner: ResMut<BevyCLIRunner>,
     mut active_command: ResMut<ActiveProjectCommand>,
     mut editor_events: EventWriter<EditorEvent>,
+    mut play_requests: EventWriter<PlayModeRequest>,
     open_scenes: Res<bevy_editor_scene::OpenScenes>,
 ) {
     for action in actions.read() {
@@ -167,6 +215,20 @@ fn handle_editor_actions(
                     cli_runner.stop_current_process();
                 }
             }
+            EditorAction::StartPlay => {
+                play_requests.write(PlayModeRequest::Play);
+            }
+            EditorAction::PausePlay => {
+                play_requests.write(PlayModeRequest::Pause);
+            }
+            EditorAction::StopPlay { keep_changes } => {
+                play_requests.write(PlayModeRequest::Stop {
+                    keep_changes: *keep_changes,
+                });
+            }
+            EditorAction::ArrangeSelection(_) => {
+                // Laid out by the frontend, which knows the entities' bounds
+            }
             EditorAction::RequestOpenProject { .. }
             | EditorAction::RequestCreateProject { .. }
             | EditorAction::RequestCloseProject
@@ -179,6 +241,13 @@ fn handle_editor_actions(
     }
 }
 
+/// Mirror the play session in [`EditorState::is_playing`].
+fn sync_editor_play_state(play_mode: Res<PlayMode>, mut editor_state: ResMut<EditorState>) {
+    if editor_state.is_playing != play_mode.is_active() {
+        editor_state.is_playing = play_mode.is_active();
+    }
+}
+
 /// Observe the CLI runner and emit completion events when processes end.
 fn monitor_cli_runner(
     cli_runner: Res<BevyCLIRunner>,
@@ -247,7 +316,9 @@ mod tests {
         app.add_plugins(MinimalPlugins);
         app.add_event::<EditorAction>();
         app.add_event::<EditorEvent>();
+        app.add_event::<PlayModeRequest>();
         app.insert_resource(EditorState::default());
+        app.init_resource::<bevy_editor_scene::OpenScenes>();
         app.insert_resource(BevyCLIRunner::default());
         app.insert_resource(ActiveProjectCommand::default());
         app.insert_resource(CapturedEvents::default());
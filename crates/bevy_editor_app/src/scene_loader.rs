@@ -1,6 +1,11 @@
 use bevy::prelude::*;
 use bevy_editor_project::CurrentProject;
-use bevy_editor_scene::{load_scene_into_open_scenes, OpenScenes, SceneAutoLoader};
+use bevy_editor_scene::{
+    load_scene_into_open_scenes, ImportedTypes, OpenScenes, SceneAutoLoader,
+};
+
+/// Seconds between checks for a newly exported type schema
+const TYPE_SCHEMA_POLL_INTERVAL: f32 = 1.0;
 
 /// Event to trigger scene loading
 #[derive(Event)]
@@ -65,3 +70,32 @@ pub fn reset_auto_loader_on_project_change(
         auto_loader.has_loaded = false;
     }
 }
+
+/// Keep [`ImportedTypes`] in sync with the types the project's game last
+/// exported when it was run
+pub fn sync_imported_types(
+    project: Option<Res<CurrentProject>>,
+    mut imported: ResMut<ImportedTypes>,
+    time: Res<Time>,
+    mut since_check: Local<Option<f32>>,
+) {
+    let Some(project) = project else {
+        return;
+    };
+    // Check right away when the project changes, then poll
+    let elapsed = since_check.get_or_insert(TYPE_SCHEMA_POLL_INTERVAL);
+    *elapsed += time.delta_secs();
+    if *elapsed < TYPE_SCHEMA_POLL_INTERVAL && !project.is_changed() {
+        return;
+    }
+    *elapsed = 0.0;
+
+    let path = project.metadata.type_schema_path();
+    if !imported.is_stale(&path) {
+        return;
+    }
+    match imported.reload(&path) {
+        Ok(()) => info!("Loaded game types from {}", path.display()),
+        Err(err) => error!("Failed to load game types from {}: {}", path.display(), err),
+    }
+}
@@ -1,7 +1,13 @@
 use bevy::prelude::*;
-use bevy::scene::{DynamicScene, DynamicSceneRoot};
+use bevy::scene::DynamicScene;
+use bevy_editor_commands::{
+    journal_path, read_journal, remove_journal, replay_in_group, CommandJournal, CrashRecovery,
+    EditorHistory, JournalError, PendingRecovery,
+};
 use bevy_editor_foundation::EditorState;
-use bevy_editor_project::CurrentProject;
+use bevy_editor_project::{scene_asset_path, CurrentProject, LiveLink};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
 /// Resource to track pending tilemap restoration
 #[derive(Resource, Default)]
@@ -14,6 +20,16 @@ pub fn handle_save_load(world: &mut World) {
     // Access resources through world
     let keyboard = world.resource::<ButtonInput<KeyCode>>().clone();
 
+    // The world holds play state until play stops, not the edited scene
+    if world.resource::<EditorState>().is_playing {
+        if keyboard.pressed(KeyCode::ControlLeft)
+            && (keyboard.just_pressed(KeyCode::KeyS) || keyboard.just_pressed(KeyCode::KeyO))
+        {
+            warn!("Stop playing before saving or opening a scene");
+        }
+        return;
+    }
+
     // Ctrl+S to save (new .scn.ron format)
     if keyboard.pressed(KeyCode::ControlLeft) && keyboard.just_pressed(KeyCode::KeyS) {
         // Clone the data we need before mutable borrow
@@ -37,6 +53,9 @@ pub fn handle_save_load(world: &mut World) {
                         let mut editor_scene =
                             world.resource_mut::<bevy_editor_scene::EditorScene>();
                         editor_scene.mark_saved();
+                        world.resource_mut::<EditorHistory>().mark_saved();
+                        clear_scene_journal(&path);
+                        save_tileset_collisions(world);
 
                         info!("Scene saved to: {}", path);
 
@@ -44,6 +63,18 @@ pub fn handle_save_load(world: &mut World) {
                         if let Some(mut project) = world.get_resource_mut::<CurrentProject>() {
                             update_last_opened_scene(&mut project, &path);
                         }
+
+                        // Hot reload the saved scene in the running game
+                        let asset_path = world
+                            .get_resource::<CurrentProject>()
+                            .map(|project| scene_asset_path(&project.metadata.assets_path, &path));
+                        if let (Some(asset_path), Some(mut live_link)) =
+                            (asset_path, world.get_resource_mut::<LiveLink>())
+                        {
+                            if live_link.is_connected() {
+                                live_link.reload_scene(asset_path);
+                            }
+                        }
                     }
                     Err(e) => {
                         error!("Failed to save scene: {}", e);
@@ -116,6 +147,9 @@ fn save_as_scene_dialog_world(world: &mut World) {
 
                 let mut editor_scene = world.resource_mut::<bevy_editor_scene::EditorScene>();
                 editor_scene.mark_saved();
+                world.resource_mut::<EditorHistory>().mark_saved();
+                clear_scene_journal(&path_str);
+                save_tileset_collisions(world);
 
                 info!("Scene saved to: {}", path_str);
             }
@@ -126,6 +160,92 @@ fn save_as_scene_dialog_world(world: &mut World) {
     }
 }
 
+/// The scene on disk now holds every journaled edit.
+fn clear_scene_journal(scene_path: &str) {
+    if let Err(e) = remove_journal(&journal_path(scene_path)) {
+        warn!("Failed to clear journal for {}: {}", scene_path, e);
+    }
+}
+
+/// Write the collision shapes of every loaded tileset to the project config,
+/// where [`restore_tileset_collisions_from_project`] picks them up again.
+fn save_tileset_collisions(world: &mut World) {
+    let Some(tileset_manager) = world.get_resource::<bevy_editor_tilemap::TilesetManager>() else {
+        return;
+    };
+    let collisions: Vec<_> = tileset_manager
+        .tilesets
+        .values()
+        .map(|info| {
+            (
+                info.data.relative_texture_path(),
+                info.data.collision_data.clone(),
+            )
+        })
+        .collect();
+    let Some(mut project) = world.get_resource_mut::<CurrentProject>() else {
+        return;
+    };
+
+    let result = project.update_config(|config| {
+        for (texture_path, collision_data) in collisions {
+            if collision_data.is_empty() {
+                config.tileset_collisions.remove(&texture_path);
+            } else {
+                config
+                    .tileset_collisions
+                    .insert(texture_path, collision_data);
+            }
+        }
+    });
+    if let Err(e) = result {
+        error!("Failed to save tileset collisions: {}", e);
+    }
+}
+
+/// System copying collision shapes saved in the project config into tilesets
+/// as they are loaded. Each tileset is filled in once, so shapes removed
+/// since then stay removed.
+pub fn restore_tileset_collisions_from_project(
+    mut tileset_manager: ResMut<bevy_editor_tilemap::TilesetManager>,
+    project: Option<Res<CurrentProject>>,
+    mut restored: Local<HashSet<u32>>,
+) {
+    let Some(project) = project else {
+        return;
+    };
+    if !tileset_manager.is_changed() && !project.is_changed() {
+        return;
+    }
+
+    // Only flag a change when shapes were added, or this would run every frame
+    let collisions = &project.metadata.config.tileset_collisions;
+    let mut changed = false;
+    for (id, info) in tileset_manager
+        .bypass_change_detection()
+        .tilesets
+        .iter_mut()
+    {
+        if !restored.insert(*id) {
+            continue;
+        }
+        let texture_path = info.data.relative_texture_path();
+        let Some(saved) = collisions.get(&texture_path) else {
+            continue;
+        };
+        for (tile_id, collision) in saved {
+            info.data
+                .collision_data
+                .entry(*tile_id)
+                .or_insert_with(|| collision.clone());
+        }
+        changed = true;
+    }
+    if changed {
+        tileset_manager.set_changed();
+    }
+}
+
 /// Open dialog for .scn.ron files (World-based version)
 fn open_scene_dialog_world(world: &mut World) {
     use rfd::FileDialog;
@@ -154,16 +274,9 @@ fn open_scene_dialog_world(world: &mut World) {
             world.despawn(entity);
         }
 
-        // Load scene using asset server
-        // Clone AssetServer path loading before taking mutable borrow
-        let scene_handle = {
-            let asset_server = world.resource::<AssetServer>();
-            asset_server.load::<DynamicScene>(path_str.clone())
-        };
-
-        // Spawn scene
+        // Spawn scene, loaded by `load_scene_files`
         world.commands().spawn((
-            DynamicSceneRoot(scene_handle.clone()),
+            bevy_editor_scene::SceneFile(path_str.clone()),
             bevy_editor_scene::EditorSceneEntity,
             bevy_editor_scene::LoadingSceneRoot,
         ));
@@ -175,227 +288,218 @@ fn open_scene_dialog_world(world: &mut World) {
             file_path: Some(path_str.clone()),
             level_data: bevy_editor_formats::LevelData::new(scene_name, 2000.0, 1000.0), // Deprecated, for compat
             is_modified: false,
+            untracked_changes: false,
             runtime_scene: None,
+            selection: Vec::new(),
+            history: EditorHistory::default(),
         };
         open_scenes.add_scene(new_scene);
         drop(open_scenes); // Release borrow
 
         let mut editor_scene = world.resource_mut::<bevy_editor_scene::EditorScene>();
-        editor_scene.is_modified = false;
+        editor_scene.mark_saved();
 
         info!("Scene loaded from: {}", path_str);
     }
 }
 
-// ==============================================================================
-// OLD TILEMAP-BASED SAVE/LOAD FUNCTIONS - DEPRECATED
-// These are kept temporarily for backward compatibility with .bscene files
-// TODO: Remove after migration period
-// ==============================================================================
-
-#[allow(dead_code)]
-fn save_level_with_tilemap(
-    scene: &mut bevy_editor_scene::OpenScene, // Changed from CurrentLevel
-    path: &str,
-    editor_state: &EditorState,
-    tileset_manager: &bevy_editor_tilemap::TilesetManager,
-    map_dimensions: &bevy_editor_tilemap::MapDimensions,
-    tilemap_query: &Query<
+/// System to restore tilemap data when a level is loaded
+pub fn restore_tilemap_from_level(
+    mut pending_restore: ResMut<PendingTilemapRestore>,
+    open_scenes: Res<bevy_editor_scene::OpenScenes>, // Changed from CurrentLevel
+    mut editor_state: ResMut<EditorState>,
+    mut map_dimensions: ResMut<bevy_editor_tilemap::MapDimensions>,
+    mut tileset_manager: ResMut<bevy_editor_tilemap::TilesetManager>,
+    mut layer_manager: ResMut<bevy_editor_tilemap::LayerManager>,
+    mut tile_grid: ResMut<bevy_editor_tilemap::TileGrid>,
+    mut load_tileset_events: EventWriter<bevy_editor_tilemap::LoadTilesetEvent>,
+    tilemap_query: Query<(
         &bevy_ecs_tilemap::prelude::TileStorage,
-        With<bevy_editor_tilemap::MapCanvas>,
-    >,
-    tile_query: &Query<(
-        &bevy_ecs_tilemap::prelude::TileTextureIndex,
-        &bevy_ecs_tilemap::prelude::TileVisible,
-        &bevy_ecs_tilemap::prelude::TilePos,
+        &bevy_editor_tilemap::MapCanvas,
+    )>,
+    mut tile_query: Query<(
+        &mut bevy_ecs_tilemap::prelude::TileTextureIndex,
+        &mut bevy_ecs_tilemap::prelude::TileVisible,
     )>,
 ) {
-    use bevy_editor_formats::{
-        LevelLayerData, LevelTileInstance, LevelTilemapData, LevelTilesetData,
-    };
-    use std::collections::HashSet;
+    // Only run if we have a pending restore
+    if !pending_restore.should_restore {
+        return;
+    }
 
-    // Build tilemap data
-    let mut tilemap_data = LevelTilemapData {
-        grid_size: editor_state.grid_size,
-        map_width: map_dimensions.width,
-        map_height: map_dimensions.height,
-        tilesets: Vec::new(),
-        selected_tileset_id: tileset_manager.selected_tileset_id,
-        layers: Vec::new(),
+    // Get active scene's tilemap data
+    let Some(tilemap_data) = open_scenes
+        .active_scene()
+        .and_then(|scene| scene.level_data.tilemap.as_ref())
+    else {
+        return;
     };
 
-    // Save tilesets - deduplicate by texture_path and convert to relative paths
-    let mut seen_paths = HashSet::new();
-    for (id, tileset_info) in tileset_manager.tilesets.iter() {
-        if seen_paths.insert(tileset_info.data.texture_path.clone()) {
-            // Convert absolute path to relative path for portability
-            let relative_path = convert_to_relative_asset_path(&tileset_info.data.texture_path);
-
-            tilemap_data.tilesets.push(LevelTilesetData {
-                id: *id,
-                identifier: tileset_info.data.identifier.clone(),
-                texture_path: relative_path,
-                tile_width: tileset_info.data.tile_width,
-                tile_height: tileset_info.data.tile_height,
+    // Update settings so canvases are created with correct dimensions
+    editor_state.grid_size = tilemap_data.grid_size;
+    map_dimensions.width = tilemap_data.map_width;
+    map_dimensions.height = tilemap_data.map_height;
+
+    // Load tilesets that aren't loaded yet - this triggers their canvas creation
+    let tileset_ids = resolve_level_tilesets(tilemap_data, &tileset_manager);
+    let mut waiting_for_tilesets = false;
+    for tileset in &tilemap_data.tilesets {
+        if !tileset_ids.contains_key(&tileset.id) {
+            load_tileset_events.write(bevy_editor_tilemap::LoadTilesetEvent {
+                path: tileset.texture_path.clone(),
+                identifier: tileset.identifier.clone(),
+                tile_width: tileset.tile_width,
+                tile_height: tileset.tile_height,
+                spacing: tileset.spacing,
+                padding: tileset.padding,
             });
+            waiting_for_tilesets = true;
         }
     }
 
-    // Save tile data
-    let mut layer_tiles = Vec::new();
-    if let Some(tile_storage) = tilemap_query.iter().next() {
-        for tile_entity in tile_storage.iter().flatten() {
-            if let Ok((texture_index, visible, tile_pos)) = tile_query.get(*tile_entity) {
-                if visible.0 {
-                    layer_tiles.push(LevelTileInstance {
-                        x: tile_pos.x,
-                        y: tile_pos.y,
-                        tile_id: texture_index.0,
-                    });
-                }
-            }
-        }
-        info!("Saving {} tiles to level", layer_tiles.len());
-    } else {
-        warn!("No tilemap found when trying to save!");
-    }
-
-    tilemap_data.layers.push(LevelLayerData {
-        id: 0,
-        name: "Layer 0".to_string(),
-        visible: true,
-        tiles: layer_tiles,
+    // Don't clear pending_restore - we'll restore tiles once every canvas exists
+    let all_canvases_ready = tileset_ids.values().all(|tileset_id| {
+        tilemap_query
+            .iter()
+            .any(|(_, canvas)| canvas.tileset_id == *tileset_id)
     });
+    if waiting_for_tilesets || !all_canvases_ready {
+        return;
+    }
 
-    // Update level data with tilemap
-    scene.level_data.tilemap = Some(tilemap_data);
+    info!("Restoring tilemap from level data...");
+    restore_tileset_collisions(tilemap_data, &tileset_ids, &mut tileset_manager);
+    restore_level_tiles(
+        tilemap_data,
+        &tileset_ids,
+        &tilemap_query,
+        &mut tile_query,
+        &mut layer_manager,
+        &mut tile_grid,
+    );
+
+    // Clear the pending restore flag
+    pending_restore.should_restore = false;
+}
 
-    // Create BevyScene and save to .bscene file
-    let bevy_scene = bevy_editor_formats::BevyScene::new(scene.level_data.clone());
-    if let Err(e) = bevy_scene.save_to_file(path) {
-        error!("Failed to save scene: {}", e);
-    } else {
-        scene.file_path = Some(path.to_string());
-        scene.is_modified = false;
+/// Map the tileset ids stored in a level to the ids of the matching loaded tilesets.
+/// Tilesets are matched by texture path since ids are assigned at load time.
+fn resolve_level_tilesets(
+    tilemap_data: &bevy_editor_formats::LevelTilemapData,
+    tileset_manager: &bevy_editor_tilemap::TilesetManager,
+) -> HashMap<u32, u32> {
+    tilemap_data
+        .tilesets
+        .iter()
+        .filter_map(|saved| {
+            tileset_manager
+                .find_by_relative_texture_path(&saved.texture_path)
+                .map(|id| (saved.id, id))
+        })
+        .collect()
+}
 
-        // Update scene name from filename
-        if let Some(filename) = std::path::Path::new(path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-        {
-            scene.name = filename.to_string();
+/// Copy collision shapes saved with a level into the matching loaded tilesets.
+/// Tiles that already have shapes keep them, so edits made since loading win.
+fn restore_tileset_collisions(
+    tilemap_data: &bevy_editor_formats::LevelTilemapData,
+    tileset_ids: &HashMap<u32, u32>,
+    tileset_manager: &mut bevy_editor_tilemap::TilesetManager,
+) {
+    for saved in &tilemap_data.tilesets {
+        let Some(tileset_info) = tileset_ids
+            .get(&saved.id)
+            .and_then(|id| tileset_manager.tilesets.get_mut(id))
+        else {
+            continue;
+        };
+        for (tile_id, collision) in &saved.collision_data {
+            tileset_info
+                .data
+                .collision_data
+                .entry(*tile_id)
+                .or_insert_with(|| collision.clone());
         }
+    }
+}
 
-        info!("Scene saved to {}", path);
+/// Loaded tileset a saved tile belongs to. Only tiles without a tileset id
+/// (legacy files) use the fallback; tiles whose tileset isn't loaded get `None`.
+fn resolve_tile_tileset(
+    tile_instance: &bevy_editor_formats::LevelTileInstance,
+    tileset_ids: &HashMap<u32, u32>,
+    fallback_tileset: Option<u32>,
+) -> Option<u32> {
+    match tile_instance.tileset_id {
+        Some(saved_id) => tileset_ids.get(&saved_id).copied(),
+        None => fallback_tileset,
     }
 }
 
-/// System to restore tilemap data when a level is loaded
-pub fn restore_tilemap_from_level(
-    mut pending_restore: ResMut<PendingTilemapRestore>,
-    open_scenes: Res<bevy_editor_scene::OpenScenes>, // Changed from CurrentLevel
-    mut editor_state: ResMut<EditorState>,
-    mut map_dimensions: ResMut<bevy_editor_tilemap::MapDimensions>,
-    mut load_tileset_events: EventWriter<bevy_editor_tilemap::LoadTilesetEvent>,
-    tilemap_query: Query<
+/// Write a level's tiles into the canvas of their tileset and the active layer.
+fn restore_level_tiles(
+    tilemap_data: &bevy_editor_formats::LevelTilemapData,
+    tileset_ids: &HashMap<u32, u32>,
+    tilemap_query: &Query<(
         &bevy_ecs_tilemap::prelude::TileStorage,
-        (
-            With<bevy_editor_tilemap::MapCanvas>,
-            Added<bevy_editor_tilemap::MapCanvas>,
-        ),
-    >,
-    mut tile_query: Query<(
+        &bevy_editor_tilemap::MapCanvas,
+    )>,
+    tile_query: &mut Query<(
         &mut bevy_ecs_tilemap::prelude::TileTextureIndex,
         &mut bevy_ecs_tilemap::prelude::TileVisible,
     )>,
+    layer_manager: &mut bevy_editor_tilemap::LayerManager,
+    tile_grid: &mut bevy_editor_tilemap::TileGrid,
 ) {
-    // Only run if we have a pending restore
-    if !pending_restore.should_restore {
-        return;
-    }
+    tile_grid.orientation = tilemap_data.orientation;
 
-    // Get active scene's tilemap data
-    let tilemap_data = open_scenes
-        .active_scene()
-        .and_then(|scene| scene.level_data.tilemap.as_ref());
-
-    if let Some(tilemap_data) = tilemap_data {
-        // Check if the tilemap canvas has been created
-        if let Some(tile_storage) = tilemap_query.iter().next() {
-            info!("Restoring tilemap from level data...");
-
-            // Update editor state
-            editor_state.grid_size = tilemap_data.grid_size;
-            map_dimensions.width = tilemap_data.map_width;
-            map_dimensions.height = tilemap_data.map_height;
-
-            // Load tilesets
-            for tileset in &tilemap_data.tilesets {
-                load_tileset_events.write(bevy_editor_tilemap::LoadTilesetEvent {
-                    path: tileset.texture_path.clone(),
-                    identifier: tileset.identifier.clone(),
-                    tile_width: tileset.tile_width,
-                    tile_height: tileset.tile_height,
-                });
-            }
-
-            // Restore tiles
-            if !tilemap_data.layers.is_empty() {
-                let layer = &tilemap_data.layers[0];
-                info!("Restoring {} tiles", layer.tiles.len());
+    let Some(layer) = tilemap_data.layers.first() else {
+        return;
+    };
+    info!("Restoring {} tiles", layer.tiles.len());
+
+    // Files written before tiles were tileset-qualified fall back to the selected tileset
+    let fallback_tileset = tilemap_data
+        .selected_tileset_id
+        .and_then(|id| tileset_ids.get(&id))
+        .copied();
+
+    for tile_instance in &layer.tiles {
+        let Some(tileset_id) = resolve_tile_tileset(tile_instance, tileset_ids, fallback_tileset)
+        else {
+            warn!(
+                "Skipping tile at ({}, {}): tileset {:?} is not loaded",
+                tile_instance.x, tile_instance.y, tile_instance.tileset_id
+            );
+            continue;
+        };
 
-                for tile_instance in &layer.tiles {
-                    let tile_pos = bevy_ecs_tilemap::prelude::TilePos {
-                        x: tile_instance.x,
-                        y: tile_instance.y,
-                    };
+        let tile_pos = bevy_ecs_tilemap::prelude::TilePos {
+            x: tile_instance.x,
+            y: tile_instance.y,
+        };
 
-                    if let Some(tile_entity) = tile_storage.get(&tile_pos) {
-                        if let Ok((mut texture_index, mut visible)) =
-                            tile_query.get_mut(tile_entity)
-                        {
-                            texture_index.0 = tile_instance.tile_id;
-                            visible.0 = true;
-                        }
-                    }
-                }
+        for (tile_storage, canvas) in tilemap_query.iter() {
+            if canvas.tileset_id != tileset_id {
+                continue;
             }
-
-            // Clear the pending restore flag
-            pending_restore.should_restore = false;
-        } else {
-            // Tilemap canvas not yet created, we need to initialize it
-            // Update settings so the canvas will be created with correct dimensions
-            editor_state.grid_size = tilemap_data.grid_size;
-            map_dimensions.width = tilemap_data.map_width;
-            map_dimensions.height = tilemap_data.map_height;
-
-            // Load tilesets - this will trigger tilemap canvas creation
-            for tileset in &tilemap_data.tilesets {
-                load_tileset_events.write(bevy_editor_tilemap::LoadTilesetEvent {
-                    path: tileset.texture_path.clone(),
-                    identifier: tileset.identifier.clone(),
-                    tile_width: tileset.tile_width,
-                    tile_height: tileset.tile_height,
-                });
+            if let Some(tile_entity) = tile_storage.get(&tile_pos) {
+                if let Ok((mut texture_index, mut visible)) = tile_query.get_mut(tile_entity) {
+                    texture_index.0 = tile_instance.tile_id;
+                    visible.0 = true;
+                }
             }
-            // Don't clear pending_restore - we'll restore tiles next frame when canvas exists
         }
-    }
-}
 
-/// Convert absolute asset path to relative path from assets folder
-fn convert_to_relative_asset_path(absolute_path: &str) -> String {
-    // Find "assets" in the path and return everything after it
-    if let Some(idx) = absolute_path.find("assets") {
-        // Skip "assets/" or "assets\" to get the relative path
-        let relative = &absolute_path[idx + 7..]; // "assets/" is 7 chars
-        return relative.replace('\\', "/"); // Normalize to forward slashes
+        layer_manager.add_tile(bevy_editor_formats::TileData {
+            x: tile_instance.x,
+            y: tile_instance.y,
+            tileset_id,
+            tile_id: tile_instance.tile_id,
+            flip_x: false,
+            flip_y: false,
+        });
     }
-
-    // Fallback: return the original path if "assets" not found
-    absolute_path.to_string()
 }
 
 /// Update the last opened scene in the project config
@@ -438,21 +542,18 @@ pub fn cache_runtime_scene_on_scene_switch(world: &mut World) {
         return;
     };
 
-    let scene_count = {
-        let open_scenes = world.resource::<bevy_editor_scene::OpenScenes>();
-        open_scenes.scenes.len()
-    };
-    if prev_idx >= scene_count {
-        return;
-    }
-
     world.resource_scope::<bevy_editor_scene::OpenScenes, _>(|world, mut open_scenes| {
+        // The outgoing tab is gone if it was closed; its history goes with it
+        let outgoing_history = std::mem::take(&mut *world.resource_mut::<EditorHistory>());
+
         if let Some(scene) = open_scenes.scenes.get_mut(prev_idx) {
             let dynamic_scene = bevy_editor_scene::capture_editor_scene_runtime(world);
-            let is_modified = world
+            let (is_modified, untracked_changes) = world
                 .get_resource::<bevy_editor_scene::EditorScene>()
-                .map(|editor_scene| editor_scene.is_modified)
-                .unwrap_or(false);
+                .map(|editor_scene| (editor_scene.is_modified, editor_scene.untracked_changes))
+                .unwrap_or_default();
+            // Entities are respawned when the tab comes back, so keep the selection by id
+            let selection = bevy_editor_scene::selection_ids(world);
 
             world.resource_scope::<Assets<DynamicScene>, _>(|_world, mut assets| {
                 if let Some(old_handle) = scene.runtime_scene.take() {
@@ -461,9 +562,146 @@ pub fn cache_runtime_scene_on_scene_switch(world: &mut World) {
                 let handle = assets.add(dynamic_scene);
                 scene.runtime_scene = Some(handle);
                 scene.is_modified = is_modified;
+                scene.untracked_changes = untracked_changes;
             });
+            scene.selection = selection;
+            scene.history = outgoing_history;
         }
+
+        // Commands address entities by `EditorEntityId`, so the incoming
+        // history still applies once its scene has been respawned
+        if let Some(scene) = open_scenes.scenes.get_mut(current_index) {
+            *world.resource_mut::<EditorHistory>() = std::mem::take(&mut scene.history);
+        }
+    });
+}
+
+/// Point the [`CommandJournal`] at the active scene's journal file.
+///
+/// The first time a saved scene is active this session, a journal left next to
+/// it means the last session ended without saving, so recovery is offered
+/// before anything new is appended to it.
+pub fn sync_command_journal(
+    open_scenes: Res<bevy_editor_scene::OpenScenes>,
+    editor_state: Res<EditorState>,
+    mut journal: ResMut<CommandJournal>,
+    mut recovery: ResMut<CrashRecovery>,
+    mut checked: Local<HashSet<PathBuf>>,
+) {
+    let scene = open_scenes.active_scene();
+    let path = scene
+        .and_then(|scene| scene.file_path.as_ref())
+        .map(journal_path);
+
+    if let (Some(scene), Some(path)) = (scene, &path) {
+        if recovery.pending.is_none() && checked.insert(path.clone()) {
+            match read_journal(path) {
+                Ok(entries) if !entries.is_empty() => {
+                    info!(
+                        "Found {} unsaved edits in {}",
+                        entries.len(),
+                        path.display()
+                    );
+                    recovery.pending = Some(PendingRecovery {
+                        journal_path: path.clone(),
+                        scene_name: scene.name.clone(),
+                        entries,
+                        decision: None,
+                    });
+                }
+                Ok(_) => {}
+                Err(JournalError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => warn!("Failed to read journal {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    let awaiting_recovery = |path: &PathBuf| {
+        recovery
+            .pending
+            .as_ref()
+            .is_some_and(|pending| pending.journal_path == *path)
+    };
+    // Edits made while playing are thrown away on Stop, so aren't journaled
+    let path = path.filter(|path| {
+        checked.contains(path) && !awaiting_recovery(path) && !editor_state.is_playing
     });
+    if journal.path != path {
+        journal.path = path;
+    }
+}
+
+/// Apply the user's answer to a pending [`CrashRecovery`]. Recovered edits are
+/// replayed as one undo step once the saved scene and its tilemap are loaded.
+pub fn apply_crash_recovery(world: &mut World) {
+    let Some((recover, pending_path)) = world
+        .resource::<CrashRecovery>()
+        .pending
+        .as_ref()
+        .and_then(|pending| Some((pending.decision?, pending.journal_path.clone())))
+    else {
+        return;
+    };
+
+    if recover {
+        let active_path = world
+            .resource::<bevy_editor_scene::OpenScenes>()
+            .active_scene()
+            .and_then(|scene| scene.file_path.as_ref())
+            .map(journal_path);
+        let loading = world
+            .query_filtered::<(), With<bevy_editor_scene::LoadingSceneRoot>>()
+            .iter(world)
+            .next()
+            .is_some()
+            || world.resource::<PendingTilemapRestore>().should_restore;
+        if loading || active_path != Some(pending_path) {
+            return;
+        }
+    }
+
+    let Some(pending) = world.resource_mut::<CrashRecovery>().pending.take() else {
+        return;
+    };
+    if let Err(e) = remove_journal(&pending.journal_path) {
+        warn!(
+            "Failed to remove journal {}: {}",
+            pending.journal_path.display(),
+            e
+        );
+    }
+
+    if recover {
+        let summary = replay_in_group(world, "Recover Unsaved Changes", &pending.entries);
+        info!(
+            "Recovered '{}': {} edits replayed, {} skipped",
+            pending.scene_name, summary.applied, summary.skipped
+        );
+    } else {
+        info!("Discarded unsaved edits of '{}'", pending.scene_name);
+    }
+}
+
+/// Derive the active scene's modified flag from the history save point, so
+/// undoing back to the saved state clears it again.
+pub fn sync_scene_modified_flag(
+    history: Res<EditorHistory>,
+    mut editor_scene: ResMut<bevy_editor_scene::EditorScene>,
+    mut open_scenes: ResMut<bevy_editor_scene::OpenScenes>,
+) {
+    let is_modified = editor_scene.untracked_changes || !history.is_at_save_point();
+
+    if editor_scene.is_modified != is_modified {
+        editor_scene.is_modified = is_modified;
+    }
+    if open_scenes
+        .active_scene()
+        .is_some_and(|scene| scene.is_modified != is_modified)
+    {
+        if let Some(scene) = open_scenes.active_scene_mut() {
+            scene.is_modified = is_modified;
+        }
+    }
 }
 
 /// System to sync tilemap when switching scenes
@@ -472,17 +710,19 @@ pub fn cache_runtime_scene_on_scene_switch(world: &mut World) {
 pub fn sync_tilemap_on_scene_switch(
     mut open_scenes: ResMut<bevy_editor_scene::OpenScenes>,
     mut previous_scene: Local<Option<usize>>,
-    tilemap_query: Query<
+    tilemap_query: Query<(
         &bevy_ecs_tilemap::prelude::TileStorage,
-        With<bevy_editor_tilemap::MapCanvas>,
-    >,
+        &bevy_editor_tilemap::MapCanvas,
+    )>,
     mut tile_query: Query<(
         &mut bevy_ecs_tilemap::prelude::TileTextureIndex,
         &mut bevy_ecs_tilemap::prelude::TileVisible,
     )>,
     editor_state: Res<EditorState>,
     map_dimensions: Res<bevy_editor_tilemap::MapDimensions>,
-    tileset_manager: Res<bevy_editor_tilemap::TilesetManager>,
+    mut tileset_manager: ResMut<bevy_editor_tilemap::TilesetManager>,
+    mut layer_manager: ResMut<bevy_editor_tilemap::LayerManager>,
+    mut tile_grid: ResMut<bevy_editor_tilemap::TileGrid>,
 ) {
     let current_index = open_scenes.active_index;
 
@@ -494,14 +734,15 @@ pub fn sync_tilemap_on_scene_switch(
     // PHASE 1: Save current tilemap to previous scene (if any)
     if let Some(prev_idx) = *previous_scene {
         if let Some(prev_scene) = open_scenes.scenes.get_mut(prev_idx) {
-            if let Some(tile_storage) = tilemap_query.iter().next() {
+            if !tilemap_query.is_empty() {
                 // Capture current tilemap state
                 let tilemap_data = capture_tilemap_state(
-                    tile_storage,
+                    &tilemap_query,
                     &tile_query,
                     &editor_state,
                     &map_dimensions,
                     &tileset_manager,
+                    &tile_grid,
                 );
                 prev_scene.level_data.tilemap = Some(tilemap_data);
                 info!("Saved tilemap state for scene '{}'", prev_scene.name);
@@ -510,7 +751,7 @@ pub fn sync_tilemap_on_scene_switch(
     }
 
     // PHASE 2: Clear tilemap (set all tiles invisible AND reset texture)
-    if let Some(tile_storage) = tilemap_query.iter().next() {
+    for (tile_storage, _) in tilemap_query.iter() {
         for y in 0..map_dimensions.height {
             for x in 0..map_dimensions.width {
                 let tile_pos = bevy_ecs_tilemap::prelude::TilePos { x, y };
@@ -523,34 +764,24 @@ pub fn sync_tilemap_on_scene_switch(
             }
         }
     }
+    for layer in layer_manager.layers.iter_mut() {
+        layer.tiles.clear();
+    }
 
     // PHASE 3: Load active scene's tilemap
     if let Some(active_scene) = open_scenes.active_scene() {
         if let Some(tilemap_data) = &active_scene.level_data.tilemap {
-            if let Some(tile_storage) = tilemap_query.iter().next() {
-                // Restore tiles from data
-                if !tilemap_data.layers.is_empty() {
-                    let layer = &tilemap_data.layers[0];
-                    info!(
-                        "Loading {} tiles for scene '{}'",
-                        layer.tiles.len(),
-                        active_scene.name
-                    );
-
-                    for tile_instance in &layer.tiles {
-                        let tile_pos = bevy_ecs_tilemap::prelude::TilePos {
-                            x: tile_instance.x,
-                            y: tile_instance.y,
-                        };
-                        if let Some(tile_entity) = tile_storage.get(&tile_pos) {
-                            if let Ok((mut tex, mut vis)) = tile_query.get_mut(tile_entity) {
-                                tex.0 = tile_instance.tile_id;
-                                vis.0 = true;
-                            }
-                        }
-                    }
-                }
-            }
+            info!("Loading tilemap for scene '{}'", active_scene.name);
+            let tileset_ids = resolve_level_tilesets(tilemap_data, &tileset_manager);
+            restore_tileset_collisions(tilemap_data, &tileset_ids, &mut tileset_manager);
+            restore_level_tiles(
+                tilemap_data,
+                &tileset_ids,
+                &tilemap_query,
+                &mut tile_query,
+                &mut layer_manager,
+                &mut tile_grid,
+            );
         } else {
             info!("Switched to scene '{}' (empty tilemap)", active_scene.name);
         }
@@ -560,9 +791,49 @@ pub fn sync_tilemap_on_scene_switch(
     *previous_scene = Some(current_index);
 }
 
+/// Tileset entries to save with a level, one per texture, and the saved id of
+/// every loaded tileset. Tilesets sharing a texture save as the lowest id.
+fn level_tilesets(
+    tileset_manager: &bevy_editor_tilemap::TilesetManager,
+) -> (
+    Vec<bevy_editor_formats::LevelTilesetData>,
+    HashMap<u32, u32>,
+) {
+    let mut ids: Vec<u32> = tileset_manager.tilesets.keys().copied().collect();
+    ids.sort_unstable();
+
+    let mut tilesets = Vec::new();
+    let mut saved_ids = HashMap::new();
+    let mut ids_by_path = HashMap::new();
+    for id in ids {
+        let tileset_info = &tileset_manager.tilesets[&id];
+        let relative_path = tileset_info.data.relative_texture_path();
+        if let Some(&saved_id) = ids_by_path.get(&relative_path) {
+            saved_ids.insert(id, saved_id);
+            continue;
+        }
+        ids_by_path.insert(relative_path.clone(), id);
+        saved_ids.insert(id, id);
+        tilesets.push(bevy_editor_formats::LevelTilesetData {
+            id,
+            identifier: tileset_info.data.identifier.clone(),
+            texture_path: relative_path,
+            tile_width: tileset_info.data.tile_width,
+            tile_height: tileset_info.data.tile_height,
+            spacing: tileset_info.data.spacing,
+            padding: tileset_info.data.padding,
+            collision_data: tileset_info.data.collision_data.clone(),
+        });
+    }
+    (tilesets, saved_ids)
+}
+
 /// Helper function to capture current tilemap state into LevelTilemapData
 fn capture_tilemap_state(
-    tile_storage: &bevy_ecs_tilemap::prelude::TileStorage,
+    tilemap_query: &Query<(
+        &bevy_ecs_tilemap::prelude::TileStorage,
+        &bevy_editor_tilemap::MapCanvas,
+    )>,
     tile_query: &Query<(
         &mut bevy_ecs_tilemap::prelude::TileTextureIndex,
         &mut bevy_ecs_tilemap::prelude::TileVisible,
@@ -570,53 +841,43 @@ fn capture_tilemap_state(
     editor_state: &EditorState,
     map_dimensions: &bevy_editor_tilemap::MapDimensions,
     tileset_manager: &bevy_editor_tilemap::TilesetManager,
+    tile_grid: &bevy_editor_tilemap::TileGrid,
 ) -> bevy_editor_formats::LevelTilemapData {
-    use bevy_editor_formats::{
-        LevelLayerData, LevelTileInstance, LevelTilemapData, LevelTilesetData,
-    };
-    use std::collections::HashSet;
+    use bevy_editor_formats::{LevelLayerData, LevelTileInstance, LevelTilemapData};
+
+    let (tilesets, saved_ids) = level_tilesets(tileset_manager);
 
     let mut tiles = Vec::new();
-    for y in 0..map_dimensions.height {
-        for x in 0..map_dimensions.width {
-            let tile_pos = bevy_ecs_tilemap::prelude::TilePos { x, y };
-            if let Some(tile_entity) = tile_storage.get(&tile_pos) {
-                if let Ok((tex, vis)) = tile_query.get(tile_entity) {
-                    if vis.0 {
-                        // Only save visible tiles
-                        tiles.push(LevelTileInstance {
-                            x,
-                            y,
-                            tile_id: tex.0,
-                        });
+    for (tile_storage, canvas) in tilemap_query.iter() {
+        for y in 0..map_dimensions.height {
+            for x in 0..map_dimensions.width {
+                let tile_pos = bevy_ecs_tilemap::prelude::TilePos { x, y };
+                if let Some(tile_entity) = tile_storage.get(&tile_pos) {
+                    if let Ok((tex, vis)) = tile_query.get(tile_entity) {
+                        if vis.0 {
+                            // Only save visible tiles
+                            tiles.push(LevelTileInstance {
+                                x,
+                                y,
+                                tileset_id: saved_ids.get(&canvas.tileset_id).copied(),
+                                tile_id: tex.0,
+                            });
+                        }
                     }
                 }
             }
         }
     }
 
-    // Build tileset data
-    let mut tilesets = Vec::new();
-    let mut seen_paths = HashSet::new();
-    for (id, tileset_info) in tileset_manager.tilesets.iter() {
-        if seen_paths.insert(tileset_info.data.texture_path.clone()) {
-            let relative_path = convert_to_relative_asset_path(&tileset_info.data.texture_path);
-            tilesets.push(LevelTilesetData {
-                id: *id,
-                identifier: tileset_info.data.identifier.clone(),
-                texture_path: relative_path,
-                tile_width: tileset_info.data.tile_width,
-                tile_height: tileset_info.data.tile_height,
-            });
-        }
-    }
-
     LevelTilemapData {
         grid_size: editor_state.grid_size,
         map_width: map_dimensions.width,
         map_height: map_dimensions.height,
+        orientation: tile_grid.orientation,
         tilesets,
-        selected_tileset_id: tileset_manager.selected_tileset_id,
+        selected_tileset_id: tileset_manager
+            .selected_tileset_id
+            .and_then(|id| saved_ids.get(&id).copied()),
         layers: vec![LevelLayerData {
             id: 0,
             name: "Layer 0".to_string(),
@@ -625,3 +886,205 @@ fn capture_tilemap_state(
         }],
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_editor_formats::{
+        CollisionShape, LevelTileInstance, MapOrientation, ProjectMetadata, TileCollisionData,
+        TilesetData,
+    };
+    use bevy_editor_scene::{EditorScene, OpenScenes};
+    use bevy_editor_tilemap::TilesetManager;
+
+    fn tileset_manager(texture_path: &str) -> TilesetManager {
+        let mut tileset_manager = TilesetManager::new();
+        tileset_manager.add_tileset(
+            TilesetData {
+                texture_path: texture_path.to_string(),
+                ..default()
+            },
+            Handle::default(),
+        );
+        tileset_manager
+    }
+
+    #[test]
+    fn only_legacy_tiles_fall_back_to_the_selected_tileset() {
+        let tileset_ids = HashMap::from([(0, 7)]);
+        let legacy = LevelTileInstance {
+            x: 1,
+            y: 2,
+            tileset_id: None,
+            tile_id: 3,
+        };
+        assert_eq!(
+            resolve_tile_tileset(&legacy, &tileset_ids, Some(7)),
+            Some(7)
+        );
+
+        let missing = LevelTileInstance {
+            tileset_id: Some(1),
+            ..legacy.clone()
+        };
+        assert_eq!(resolve_tile_tileset(&missing, &tileset_ids, Some(7)), None);
+
+        let resolved = LevelTileInstance {
+            tileset_id: Some(0),
+            ..legacy
+        };
+        assert_eq!(resolve_tile_tileset(&resolved, &tileset_ids, None), Some(7));
+    }
+
+    #[test]
+    fn tiles_of_duplicate_tilesets_save_with_the_kept_entry() {
+        let mut tileset_manager = tileset_manager("/project/assets/tilesets/tiles.png");
+        let duplicate = tileset_manager.add_tileset(
+            TilesetData {
+                texture_path: "/project/assets/tilesets/tiles.png".to_string(),
+                ..default()
+            },
+            Handle::default(),
+        );
+
+        let (tilesets, saved_ids) = level_tilesets(&tileset_manager);
+        assert_eq!(tilesets.len(), 1);
+        assert_eq!(saved_ids[&duplicate], tilesets[0].id);
+    }
+
+    #[test]
+    fn map_orientation_is_saved_with_the_level() {
+        let mut world = World::new();
+        world.init_resource::<EditorState>();
+        world.init_resource::<bevy_editor_tilemap::MapDimensions>();
+        world.init_resource::<bevy_editor_tilemap::LayerManager>();
+        world.insert_resource(tileset_manager("/project/assets/tilesets/tiles.png"));
+        world.insert_resource(bevy_editor_tilemap::TileGrid {
+            orientation: MapOrientation::HexRow,
+            ..default()
+        });
+
+        let tilemap_data = world
+            .run_system_once(
+                |tilemap_query: Query<(
+                    &bevy_ecs_tilemap::prelude::TileStorage,
+                    &bevy_editor_tilemap::MapCanvas,
+                )>,
+                 tile_query: Query<(
+                    &mut bevy_ecs_tilemap::prelude::TileTextureIndex,
+                    &mut bevy_ecs_tilemap::prelude::TileVisible,
+                )>,
+                 editor_state: Res<EditorState>,
+                 map_dimensions: Res<bevy_editor_tilemap::MapDimensions>,
+                 tileset_manager: Res<TilesetManager>,
+                 tile_grid: Res<bevy_editor_tilemap::TileGrid>| {
+                    capture_tilemap_state(
+                        &tilemap_query,
+                        &tile_query,
+                        &editor_state,
+                        &map_dimensions,
+                        &tileset_manager,
+                        &tile_grid,
+                    )
+                },
+            )
+            .unwrap();
+        assert_eq!(tilemap_data.orientation, MapOrientation::HexRow);
+
+        world.insert_resource(bevy_editor_tilemap::TileGrid::default());
+        world
+            .run_system_once(
+                move |tilemap_query: Query<(
+                    &bevy_ecs_tilemap::prelude::TileStorage,
+                    &bevy_editor_tilemap::MapCanvas,
+                )>,
+                      mut tile_query: Query<(
+                    &mut bevy_ecs_tilemap::prelude::TileTextureIndex,
+                    &mut bevy_ecs_tilemap::prelude::TileVisible,
+                )>,
+                      mut layer_manager: ResMut<bevy_editor_tilemap::LayerManager>,
+                      mut tile_grid: ResMut<bevy_editor_tilemap::TileGrid>| {
+                    restore_level_tiles(
+                        &tilemap_data,
+                        &HashMap::new(),
+                        &tilemap_query,
+                        &mut tile_query,
+                        &mut layer_manager,
+                        &mut tile_grid,
+                    );
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            world
+                .resource::<bevy_editor_tilemap::TileGrid>()
+                .orientation,
+            MapOrientation::HexRow
+        );
+    }
+
+    #[test]
+    fn saved_collision_shapes_come_back_with_the_tileset() {
+        let dir =
+            std::env::temp_dir().join(format!("bevy_editor_collisions_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let metadata = ProjectMetadata::from_project_path(&dir).unwrap();
+        let texture_path = metadata.assets_path.join("tilesets/tiles.png");
+        let texture_path = texture_path.to_string_lossy();
+        let scene_path = metadata.levels_path.join("level.scn.ron");
+
+        let mut world = World::new();
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        keyboard.press(KeyCode::ControlLeft);
+        keyboard.press(KeyCode::KeyS);
+        world.insert_resource(keyboard);
+        world.init_resource::<AppTypeRegistry>();
+        world.init_resource::<EditorState>();
+        world.init_resource::<EditorScene>();
+        world.init_resource::<EditorHistory>();
+        let mut open_scenes = OpenScenes::default();
+        open_scenes.scenes[0].file_path = Some(scene_path.to_string_lossy().to_string());
+        world.insert_resource(open_scenes);
+        world.insert_resource(CurrentProject { metadata });
+        let mut tilesets = tileset_manager(&texture_path);
+        let shape = CollisionShape::Rectangle {
+            x: 0.0,
+            y: 8.0,
+            width: 16.0,
+            height: 8.0,
+        };
+        tilesets
+            .tilesets
+            .values_mut()
+            .next()
+            .unwrap()
+            .data
+            .collision_data
+            .insert(
+                3,
+                TileCollisionData {
+                    tile_id: 3,
+                    shapes: vec![shape.clone()],
+                },
+            );
+        world.insert_resource(tilesets);
+
+        handle_save_load(&mut world);
+
+        // Reopen the project and load the tileset again
+        let metadata = ProjectMetadata::from_project_path(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        let mut world = World::new();
+        world.insert_resource(CurrentProject { metadata });
+        world.insert_resource(tileset_manager(&texture_path));
+        world
+            .run_system_once(restore_tileset_collisions_from_project)
+            .unwrap();
+
+        let tilesets = world.resource::<TilesetManager>();
+        let tileset = tilesets.tilesets.values().next().unwrap();
+        let collision = &tileset.data.collision_data[&3];
+        assert_eq!(collision.shapes, vec![shape]);
+    }
+}
@@ -1,8 +1,10 @@
+use crate::journal::{CommandRecord, GroupRecord, JournalEntry};
 use bevy::prelude::*;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashSet};
+use std::time::SystemTime;
 
-/// Maximum number of commands to keep in history (prevents unbounded memory growth)
-const MAX_HISTORY_SIZE: usize = 100;
+/// Default number of commands to keep in history (prevents unbounded memory growth)
+const DEFAULT_HISTORY_SIZE: usize = 100;
 
 /// Trait for all undoable editor commands.
 pub trait EditorCommand: Send + Sync {
@@ -25,32 +27,222 @@ pub trait EditorCommand: Send + Sync {
     fn merge(&mut self, _other: Box<dyn EditorCommand>) {
         // Default: do nothing
     }
+
+    /// Access to the concrete command type, so `can_merge_with` and `merge`
+    /// can downcast the other command.
+    fn as_any(&self) -> Option<&dyn std::any::Any> {
+        None
+    }
+
+    /// Approximate memory held by the command, for [`HistoryBudget::Bytes`].
+    /// Commands that own heap data should override this.
+    fn size_bytes(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+
+    /// Serialized form for macros and the crash-recovery journal, see
+    /// [`CommandRecord::of`]. Commands returning `None` can't be replayed.
+    fn to_record(&self) -> Option<CommandRecord> {
+        None
+    }
+}
+
+/// Several commands recorded as one undo step.
+///
+/// Built by [`EditorHistory::begin_group`]/[`EditorHistory::end_group`]; executes
+/// its commands in order and undoes them in reverse.
+pub struct CommandGroup {
+    name: String,
+    commands: Vec<Box<dyn EditorCommand>>,
 }
 
-/// Wrapper to make commands object-safe.
-struct CommandWrapper {
-    command: Box<dyn EditorCommand>,
-    /// Whether this command has been executed.
-    executed: bool,
+impl CommandGroup {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// A group of commands that have already been merged as needed.
+    pub(crate) fn with_commands(
+        name: impl Into<String>,
+        commands: Vec<Box<dyn EditorCommand>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            commands,
+        }
+    }
+
+    /// Number of commands in the group.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Append an already executed command, merging it into the previous one if possible.
+    pub(crate) fn push(&mut self, command: Box<dyn EditorCommand>) {
+        if let Some(last) = self.commands.last_mut() {
+            if last.can_merge_with(command.as_ref()) {
+                last.merge(command);
+                return;
+            }
+        }
+        self.commands.push(command);
+    }
+}
+
+impl EditorCommand for CommandGroup {
+    fn execute(&mut self, world: &mut World) {
+        for command in &mut self.commands {
+            command.execute(world);
+        }
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        for command in self.commands.iter_mut().rev() {
+            command.undo(world);
+        }
+    }
+
+    fn description(&self) -> String {
+        self.name.clone()
+    }
+
+    fn size_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.commands.iter().map(|c| c.size_bytes()).sum::<usize>()
+    }
+
+    /// Only serializable if every command in it is.
+    fn to_record(&self) -> Option<CommandRecord> {
+        let commands = self
+            .commands
+            .iter()
+            .map(|command| command.to_record())
+            .collect::<Option<Vec<_>>>()?;
+        CommandRecord::group(&GroupRecord {
+            name: self.name.clone(),
+            commands,
+        })
+    }
+}
+
+/// How much history to keep before the oldest entries are discarded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryBudget {
+    /// Maximum number of commands, across all branches.
+    Commands(usize),
+    /// Maximum approximate memory of all commands, see [`EditorCommand::size_bytes`].
+    Bytes(usize),
+}
+
+impl Default for HistoryBudget {
+    fn default() -> Self {
+        Self::Commands(DEFAULT_HISTORY_SIZE)
+    }
+}
+
+/// Identifier of a node in the undo tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HistoryNodeId(u64);
+
+/// A node of the undo tree. The root holds no command and stands for the
+/// state before any recorded edit.
+struct HistoryNode {
+    parent: Option<HistoryNodeId>,
+    /// Branches in creation order.
+    children: Vec<HistoryNodeId>,
+    /// Branch that redo follows: the one most recently undone or created.
+    active_child: Option<HistoryNodeId>,
+    command: Option<Box<dyn EditorCommand>>,
+    timestamp: SystemTime,
+}
+
+/// Read-only view of an undo tree node, for history panels.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub id: HistoryNodeId,
+    pub parent: Option<HistoryNodeId>,
+    /// Distance from the root; the root itself has depth 0.
+    pub depth: usize,
+    pub description: String,
+    pub timestamp: SystemTime,
+    /// Whether this is the current state.
+    pub is_current: bool,
+    /// Whether the command is applied (on the path from the root to the current node).
+    pub is_applied: bool,
+    /// Number of branches starting at this node.
+    pub branch_count: usize,
 }
 
 /// Resource that manages undo/redo history.
-#[derive(Resource, Default)]
+///
+/// History is a tree: recording a command after an undo starts a new branch
+/// instead of discarding the undone commands, and [`EditorHistory::jump_to`]
+/// moves to any node of any branch.
+#[derive(Resource)]
 pub struct EditorHistory {
-    /// Stack of commands that have been executed (can be undone).
-    undo_stack: VecDeque<CommandWrapper>,
+    nodes: BTreeMap<HistoryNodeId, HistoryNode>,
+    root: HistoryNodeId,
+    /// Node whose state the world is currently in.
+    current: HistoryNodeId,
+    next_id: u64,
+
+    budget: HistoryBudget,
 
-    /// Stack of commands that have been undone (can be redone).
-    redo_stack: VecDeque<CommandWrapper>,
+    /// Node matching the state on disk. `None` once that node was discarded,
+    /// so the saved state can't be reached any more.
+    save_point: Option<HistoryNodeId>,
+
+    /// Groups opened with `begin_group`, innermost last.
+    open_groups: Vec<CommandGroup>,
 
     /// Whether we're currently executing/undoing a command (prevents recursion).
     is_executing: bool,
 
     /// Number of commands executed this session (for statistics).
     total_commands: usize,
+
+    /// Steps applied since the last `take_journal`, in order.
+    journal: Vec<JournalEntry>,
+}
+
+impl Default for EditorHistory {
+    fn default() -> Self {
+        let root = HistoryNodeId(0);
+        let mut nodes = BTreeMap::new();
+        nodes.insert(root, Self::root_node());
+
+        Self {
+            nodes,
+            root,
+            current: root,
+            next_id: 1,
+            budget: HistoryBudget::default(),
+            save_point: Some(root),
+            open_groups: Vec::new(),
+            is_executing: false,
+            total_commands: 0,
+            journal: Vec::new(),
+        }
+    }
 }
 
 impl EditorHistory {
+    fn root_node() -> HistoryNode {
+        HistoryNode {
+            parent: None,
+            children: Vec::new(),
+            active_child: None,
+            command: None,
+            timestamp: SystemTime::now(),
+        }
+    }
+
     /// Execute a new command and add it to the history.
     pub fn execute(&mut self, mut command: Box<dyn EditorCommand>, world: &mut World) {
         if self.is_executing {
@@ -59,37 +251,11 @@ impl EditorHistory {
         }
 
         self.is_executing = true;
-
-        // Execute the command
         command.execute(world);
-
-        // Clear redo stack when new command is executed
-        self.redo_stack.clear();
-
-        // Try to merge with last command if possible
-        if let Some(last) = self.undo_stack.back_mut() {
-            if last.command.can_merge_with(command.as_ref()) {
-                last.command.merge(command);
-                self.is_executing = false;
-                return;
-            }
-        }
-
-        // Add to undo stack
-        self.undo_stack.push_back(CommandWrapper {
-            command,
-            executed: true,
-        });
-
-        // Limit history size
-        if self.undo_stack.len() > MAX_HISTORY_SIZE {
-            self.undo_stack.pop_front();
-        }
-
-        self.total_commands += 1;
         self.is_executing = false;
 
-        info!("Executed command (history size: {})", self.undo_stack.len());
+        self.record(command);
+        info!("Executed command (history size: {})", self.len());
     }
 
     /// Add a command to history without executing it (for commands already applied).
@@ -99,166 +265,712 @@ impl EditorHistory {
             return;
         }
 
-        // Clear redo stack when new command is added
-        self.redo_stack.clear();
+        self.record(command);
+        info!("Added executed command to history (size: {})", self.len());
+    }
 
-        // Try to merge with last command if possible
-        if let Some(last) = self.undo_stack.back_mut() {
-            if last.command.can_merge_with(command.as_ref()) {
-                last.command.merge(command);
-                return;
+    /// Put an executed command into the open group, or into the tree below the current node.
+    fn record(&mut self, command: Box<dyn EditorCommand>) {
+        if let Some(group) = self.open_groups.last_mut() {
+            group.push(command);
+            return;
+        }
+        self.journal_command(command.as_ref(), JournalEntry::Execute);
+
+        // Merge with the current command, unless that would rewrite the
+        // state other branches or the save point refer to
+        let at_save_point = self.is_at_save_point();
+        let current = self
+            .nodes
+            .get_mut(&self.current)
+            .expect("current node exists");
+        if current.children.is_empty() && !at_save_point {
+            if let Some(last) = current.command.as_mut() {
+                if last.can_merge_with(command.as_ref()) {
+                    last.merge(command);
+                    self.enforce_budget();
+                    return;
+                }
             }
         }
 
-        // Add to undo stack (already executed)
-        self.undo_stack.push_back(CommandWrapper {
-            command,
-            executed: true,
-        });
+        let id = HistoryNodeId(self.next_id);
+        self.next_id += 1;
+        self.nodes.insert(
+            id,
+            HistoryNode {
+                parent: Some(self.current),
+                children: Vec::new(),
+                active_child: None,
+                command: Some(command),
+                timestamp: SystemTime::now(),
+            },
+        );
+        let parent = self
+            .nodes
+            .get_mut(&self.current)
+            .expect("current node exists");
+        parent.children.push(id);
+        parent.active_child = Some(id);
+        self.current = id;
+
+        self.total_commands += 1;
+        self.enforce_budget();
+    }
+
+    /// Start collecting commands into a single undo step named `name`.
+    ///
+    /// Groups nest: an inner group becomes one command of the outer group.
+    /// Undo and redo are refused while a group is open.
+    pub fn begin_group(&mut self, name: impl Into<String>) {
+        self.open_groups.push(CommandGroup::new(name));
+    }
+
+    /// Close the innermost group. Empty groups leave no history entry.
+    pub fn end_group(&mut self) {
+        let Some(group) = self.open_groups.pop() else {
+            warn!("end_group called without a matching begin_group");
+            return;
+        };
 
-        // Limit history size
-        if self.undo_stack.len() > MAX_HISTORY_SIZE {
-            self.undo_stack.pop_front();
+        if group.is_empty() {
+            return;
         }
 
-        self.total_commands += 1;
+        info!("Recorded group '{}' ({} commands)", group.name, group.len());
+        self.record(Box::new(group));
+    }
 
-        info!(
-            "Added executed command to history (size: {})",
-            self.undo_stack.len()
-        );
+    /// Close the innermost group and undo everything recorded in it.
+    pub fn cancel_group(&mut self, world: &mut World) {
+        let Some(mut group) = self.open_groups.pop() else {
+            warn!("cancel_group called without a matching begin_group");
+            return;
+        };
+
+        self.is_executing = true;
+        group.undo(world);
+        self.is_executing = false;
+
+        info!("Rolled back group '{}'", group.name);
+    }
+
+    /// Whether a group is currently being recorded.
+    pub fn in_group(&self) -> bool {
+        !self.open_groups.is_empty()
+    }
+
+    /// Run `f` inside a group. The group is committed if `f` returns `Ok`,
+    /// and rolled back if it returns `Err` or panics (the panic is resumed
+    /// after the rollback).
+    pub fn transaction<T, E>(
+        &mut self,
+        name: impl Into<String>,
+        world: &mut World,
+        f: impl FnOnce(&mut Self, &mut World) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let depth = self.open_groups.len();
+        self.begin_group(name);
+
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(&mut *self, &mut *world)));
+
+        // Close anything `f` left open so the group we opened is innermost again
+        self.is_executing = false;
+        while self.open_groups.len() > depth + 1 {
+            self.end_group();
+        }
+
+        match result {
+            Ok(Ok(value)) => {
+                self.end_group();
+                Ok(value)
+            }
+            Ok(Err(error)) => {
+                self.cancel_group(world);
+                Err(error)
+            }
+            Err(panic) => {
+                self.cancel_group(world);
+                std::panic::resume_unwind(panic)
+            }
+        }
     }
 
     /// Undo the last command.
     pub fn undo(&mut self, world: &mut World) -> bool {
-        if self.is_executing {
-            warn!("Attempted to undo while executing command - ignoring");
+        if self.is_executing || self.in_group() {
+            warn!("Attempted to undo while executing command or recording a group - ignoring");
             return false;
         }
 
-        if let Some(mut wrapper) = self.undo_stack.pop_back() {
-            self.is_executing = true;
+        let id = self.current;
+        let Some(parent) = self.nodes[&id].parent else {
+            return false;
+        };
 
-            let desc = wrapper.command.description();
-            wrapper.command.undo(world);
-            wrapper.executed = false;
+        self.is_executing = true;
+        let node = self.nodes.get_mut(&id).expect("current node exists");
+        let desc = node.command.as_ref().map(|c| c.description());
+        if let Some(command) = node.command.as_mut() {
+            command.undo(world);
+        }
+        self.is_executing = false;
+        self.journal_node(id, JournalEntry::Undo);
 
-            self.redo_stack.push_back(wrapper);
-            self.is_executing = false;
+        // Redo returns to the branch we just left
+        self.nodes
+            .get_mut(&parent)
+            .expect("parent exists")
+            .active_child = Some(id);
+        self.current = parent;
 
-            info!("Undid: {}", desc);
-            true
-        } else {
-            false
-        }
+        info!("Undid: {}", desc.unwrap_or_default());
+        true
     }
 
-    /// Redo the last undone command.
+    /// Redo the last undone command on the active branch.
     pub fn redo(&mut self, world: &mut World) -> bool {
-        if self.is_executing {
-            warn!("Attempted to redo while executing command - ignoring");
+        if self.is_executing || self.in_group() {
+            warn!("Attempted to redo while executing command or recording a group - ignoring");
+            return false;
+        }
+
+        let Some(child) = self.redo_target(self.current) else {
+            return false;
+        };
+        self.redo_into(child, world);
+        true
+    }
+
+    /// Execute `child`'s command and move the cursor onto it.
+    fn redo_into(&mut self, child: HistoryNodeId, world: &mut World) {
+        self.is_executing = true;
+        let node = self.nodes.get_mut(&child).expect("child exists");
+        let desc = node.command.as_ref().map(|c| c.description());
+        if let Some(command) = node.command.as_mut() {
+            command.execute(world);
+        }
+        self.is_executing = false;
+        self.journal_node(child, JournalEntry::Redo);
+
+        self.nodes
+            .get_mut(&self.current)
+            .expect("current node exists")
+            .active_child = Some(child);
+        self.current = child;
+
+        info!("Redid: {}", desc.unwrap_or_default());
+    }
+
+    fn redo_target(&self, id: HistoryNodeId) -> Option<HistoryNodeId> {
+        let node = &self.nodes[&id];
+        node.active_child.or_else(|| node.children.last().copied())
+    }
+
+    /// Undo and redo along the tree until `target` is the current node.
+    /// Returns `false` if the node doesn't exist or history is busy.
+    pub fn jump_to(&mut self, target: HistoryNodeId, world: &mut World) -> bool {
+        if self.is_executing || self.in_group() || !self.nodes.contains_key(&target) {
             return false;
         }
 
-        if let Some(mut wrapper) = self.redo_stack.pop_back() {
-            self.is_executing = true;
+        let target_path = self.path_to(target);
+        let target_set: HashSet<HistoryNodeId> = target_path.iter().copied().collect();
 
-            let desc = wrapper.command.description();
-            wrapper.command.execute(world);
-            wrapper.executed = true;
+        while !target_set.contains(&self.current) {
+            self.undo(world);
+        }
+
+        let start = target_path
+            .iter()
+            .position(|id| *id == self.current)
+            .expect("common ancestor is on the target path");
+        for &id in &target_path[start + 1..] {
+            self.redo_into(id, world);
+        }
+        true
+    }
 
-            self.undo_stack.push_back(wrapper);
-            self.is_executing = false;
+    /// Node ids from the root to `id`, inclusive.
+    fn path_to(&self, id: HistoryNodeId) -> Vec<HistoryNodeId> {
+        let mut path = vec![id];
+        let mut node = id;
+        while let Some(parent) = self.nodes[&node].parent {
+            path.push(parent);
+            node = parent;
+        }
+        path.reverse();
+        path
+    }
 
-            info!("Redid: {}", desc);
-            true
-        } else {
-            false
+    /// Nodes redo would visit from the current node, in order.
+    fn redo_chain(&self) -> Vec<HistoryNodeId> {
+        let mut chain = Vec::new();
+        let mut node = self.current;
+        while let Some(child) = self.redo_target(node) {
+            chain.push(child);
+            node = child;
         }
+        chain
+    }
+
+    fn node_description(&self, id: HistoryNodeId) -> String {
+        self.nodes[&id]
+            .command
+            .as_ref()
+            .map(|c| c.description())
+            .unwrap_or_else(|| "Initial State".to_string())
     }
 
     /// Check if there are commands to undo.
     pub fn can_undo(&self) -> bool {
-        !self.undo_stack.is_empty()
+        self.current != self.root
     }
 
     /// Check if there are commands to redo.
     pub fn can_redo(&self) -> bool {
-        !self.redo_stack.is_empty()
+        self.redo_target(self.current).is_some()
     }
 
     /// Get the description of the next command to undo.
     pub fn undo_description(&self) -> Option<String> {
-        self.undo_stack.back().map(|w| w.command.description())
+        self.can_undo().then(|| self.node_description(self.current))
     }
 
     /// Get the description of the next command to redo.
     pub fn redo_description(&self) -> Option<String> {
-        self.redo_stack.back().map(|w| w.command.description())
+        self.redo_target(self.current)
+            .map(|id| self.node_description(id))
     }
 
-    /// Get all commands in the undo stack (for history panel).
+    /// Get the applied commands, oldest first (for history panel).
     pub fn get_undo_history(&self) -> Vec<String> {
-        self.undo_stack
-            .iter()
-            .map(|w| w.command.description())
+        self.path_to(self.current)
+            .into_iter()
+            .skip(1)
+            .map(|id| self.node_description(id))
             .collect()
     }
 
-    /// Get all commands in the redo stack (for history panel).
+    /// Get the commands redo would replay on the active branch, in order (for history panel).
     pub fn get_redo_history(&self) -> Vec<String> {
-        self.redo_stack
-            .iter()
-            .rev() // Reverse to show in chronological order
-            .map(|w| w.command.description())
+        self.redo_chain()
+            .into_iter()
+            .map(|id| self.node_description(id))
             .collect()
     }
 
+    /// The node whose state the world is currently in.
+    pub fn current(&self) -> HistoryNodeId {
+        self.current
+    }
+
+    /// All nodes of the undo tree in depth-first order, branches in creation order.
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        let applied: HashSet<HistoryNodeId> = self.path_to(self.current).into_iter().collect();
+        let mut entries = Vec::with_capacity(self.nodes.len());
+        let mut stack = vec![(self.root, 0)];
+
+        while let Some((id, depth)) = stack.pop() {
+            let node = &self.nodes[&id];
+            entries.push(HistoryEntry {
+                id,
+                parent: node.parent,
+                depth,
+                description: self.node_description(id),
+                timestamp: node.timestamp,
+                is_current: id == self.current,
+                is_applied: applied.contains(&id),
+                branch_count: node.children.len(),
+            });
+            stack.extend(node.children.iter().rev().map(|child| (*child, depth + 1)));
+        }
+
+        entries
+    }
+
+    /// Number of recorded commands across all branches.
+    pub fn len(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Approximate memory held by all recorded commands.
+    pub fn memory_bytes(&self) -> usize {
+        self.nodes
+            .values()
+            .filter_map(|node| node.command.as_ref())
+            .map(|command| command.size_bytes())
+            .sum()
+    }
+
+    pub fn budget(&self) -> HistoryBudget {
+        self.budget
+    }
+
+    /// Change how much history is kept, discarding old entries if needed.
+    pub fn set_budget(&mut self, budget: HistoryBudget) {
+        self.budget = budget;
+        self.enforce_budget();
+    }
+
+    fn over_budget(&self) -> bool {
+        match self.budget {
+            HistoryBudget::Commands(max) => self.len() > max,
+            HistoryBudget::Bytes(max) => self.memory_bytes() > max,
+        }
+    }
+
+    /// Discard the oldest abandoned branches first, then the oldest applied
+    /// commands, until the history fits the budget.
+    fn enforce_budget(&mut self) {
+        while self.over_budget() {
+            if !self.prune_oldest() {
+                break;
+            }
+        }
+    }
+
+    fn prune_oldest(&mut self) -> bool {
+        let applied: HashSet<HistoryNodeId> = self.path_to(self.current).into_iter().collect();
+
+        // Oldest leaf that isn't part of the current state
+        let leaf = self
+            .nodes
+            .iter()
+            .find(|(id, node)| node.children.is_empty() && !applied.contains(id))
+            .map(|(id, _)| *id);
+
+        if let Some(id) = leaf {
+            if self.save_point == Some(id) {
+                self.save_point = None;
+            }
+            let node = self.nodes.remove(&id).expect("leaf exists");
+            if let Some(parent) = node.parent.and_then(|p| self.nodes.get_mut(&p)) {
+                parent.children.retain(|c| *c != id);
+                if parent.active_child == Some(id) {
+                    parent.active_child = None;
+                }
+            }
+            return true;
+        }
+
+        // Only the applied path is left: its first command becomes the new root
+        if self.current == self.root {
+            return false;
+        }
+        if self.save_point == Some(self.root) {
+            self.save_point = None;
+        }
+        let old_root = self.nodes.remove(&self.root).expect("root exists");
+        let new_root = old_root.children[0];
+        let node = self.nodes.get_mut(&new_root).expect("root child exists");
+        node.parent = None;
+        node.command = None;
+        self.root = new_root;
+        true
+    }
+
+    /// Record the current node as the state on disk.
+    pub fn mark_saved(&mut self) {
+        self.save_point = Some(self.current);
+    }
+
+    /// Whether undo/redo has brought the world back to the saved state.
+    pub fn is_at_save_point(&self) -> bool {
+        self.save_point == Some(self.current) && !self.in_group()
+    }
+
     /// Clear all history (e.g., when loading a new scene).
     pub fn clear(&mut self) {
-        self.undo_stack.clear();
-        self.redo_stack.clear();
+        self.nodes.clear();
+        self.root = HistoryNodeId(self.next_id);
+        self.next_id += 1;
+        self.nodes.insert(self.root, Self::root_node());
+        self.current = self.root;
+        self.save_point = Some(self.root);
+        self.open_groups.clear();
         info!("Cleared editor history");
     }
 
+    /// Take the steps applied since the last call, for macro recording and
+    /// the crash-recovery journal. Grouped commands appear once the group ends.
+    pub fn take_journal(&mut self) -> Vec<JournalEntry> {
+        std::mem::take(&mut self.journal)
+    }
+
+    fn journal_command(
+        &mut self,
+        command: &dyn EditorCommand,
+        entry: fn(CommandRecord) -> JournalEntry,
+    ) {
+        self.journal.push(journal_entry(command, entry));
+    }
+
+    fn journal_node(&mut self, id: HistoryNodeId, entry: fn(CommandRecord) -> JournalEntry) {
+        if let Some(command) = self.nodes[&id].command.as_ref() {
+            self.journal.push(journal_entry(command.as_ref(), entry));
+        }
+    }
+
     /// Get statistics about the history.
     pub fn stats(&self) -> HistoryStats {
         HistoryStats {
-            undo_count: self.undo_stack.len(),
-            redo_count: self.redo_stack.len(),
+            undo_count: self.path_to(self.current).len() - 1,
+            redo_count: self.redo_chain().len(),
+            node_count: self.len(),
+            memory_bytes: self.memory_bytes(),
             total_commands: self.total_commands,
         }
     }
 }
 
+fn journal_entry(
+    command: &dyn EditorCommand,
+    entry: fn(CommandRecord) -> JournalEntry,
+) -> JournalEntry {
+    match command.to_record() {
+        Some(record) => entry(record),
+        None => JournalEntry::Unrecorded(command.description()),
+    }
+}
+
 /// Statistics about the editor history.
 #[derive(Debug, Clone, Copy)]
 pub struct HistoryStats {
     pub undo_count: usize,
     pub redo_count: usize,
+    /// Commands kept across all branches.
+    pub node_count: usize,
+    pub memory_bytes: usize,
     pub total_commands: usize,
 }
 
-/// System to handle keyboard shortcuts for undo/redo.
-pub fn handle_undo_redo_shortcuts(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mut history: ResMut<EditorHistory>,
-    world: &mut World,
-) {
-    // Ctrl+Z - Undo
-    if keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight) {
-        if keyboard.just_pressed(KeyCode::KeyZ) {
-            // Ctrl+Shift+Z - Redo (alternative)
-            if keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight) {
-                history.redo(world);
-            } else {
-                // Ctrl+Z - Undo
-                history.undo(world);
+/// Undo/redo request sent by UI (menus, history panels) that can't reach the
+/// world directly.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryRequest {
+    Undo,
+    Redo,
+    /// Move to any node of the undo tree, switching branches if needed.
+    JumpTo(HistoryNodeId),
+}
+
+/// System to handle keyboard shortcuts and [`HistoryRequest`] events for undo/redo.
+///
+/// Exclusive because commands need the whole world to apply themselves.
+pub fn handle_undo_redo_shortcuts(world: &mut World) {
+    let mut requests: Vec<HistoryRequest> = world
+        .get_resource_mut::<Events<HistoryRequest>>()
+        .map(|mut events| events.drain().collect())
+        .unwrap_or_default();
+
+    if let Some(keyboard) = world.get_resource::<ButtonInput<KeyCode>>() {
+        if keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight) {
+            // Ctrl+Z - Undo, Ctrl+Shift+Z - Redo
+            if keyboard.just_pressed(KeyCode::KeyZ) {
+                if keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight) {
+                    requests.push(HistoryRequest::Redo);
+                } else {
+                    requests.push(HistoryRequest::Undo);
+                }
+            }
+            // Ctrl+Y - Redo (Windows-style)
+            else if keyboard.just_pressed(KeyCode::KeyY) {
+                requests.push(HistoryRequest::Redo);
             }
         }
-        // Ctrl+Y - Redo (Windows-style)
-        else if keyboard.just_pressed(KeyCode::KeyY) {
-            history.redo(world);
+    }
+
+    if requests.is_empty() || !world.contains_resource::<EditorHistory>() {
+        return;
+    }
+
+    world.resource_scope(|world, mut history: Mut<EditorHistory>| {
+        for request in requests {
+            match request {
+                HistoryRequest::Undo => history.undo(world),
+                HistoryRequest::Redo => history.redo(world),
+                HistoryRequest::JumpTo(node) => history.jump_to(node, world),
+            };
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Counter(i32);
+
+    struct Add(i32);
+
+    impl EditorCommand for Add {
+        fn execute(&mut self, world: &mut World) {
+            world.resource_mut::<Counter>().0 += self.0;
         }
+
+        fn undo(&mut self, world: &mut World) {
+            world.resource_mut::<Counter>().0 -= self.0;
+        }
+
+        fn description(&self) -> String {
+            format!("Add {}", self.0)
+        }
+    }
+
+    fn setup() -> (EditorHistory, World) {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        (EditorHistory::default(), world)
+    }
+
+    #[test]
+    fn group_is_a_single_undo_step() {
+        let (mut history, mut world) = setup();
+
+        history.begin_group("Add Several");
+        history.execute(Box::new(Add(1)), &mut world);
+        history.execute(Box::new(Add(2)), &mut world);
+        assert!(!history.undo(&mut world), "undo is refused inside a group");
+        history.end_group();
+
+        assert_eq!(world.resource::<Counter>().0, 3);
+        assert_eq!(history.get_undo_history(), vec!["Add Several".to_string()]);
+
+        history.undo(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 0);
+        history.redo(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 3);
+
+        // Empty groups leave nothing behind
+        history.begin_group("Nothing");
+        history.end_group();
+        assert_eq!(history.stats().undo_count, 1);
+    }
+
+    #[test]
+    fn failed_transaction_rolls_back() {
+        let (mut history, mut world) = setup();
+
+        let result: Result<(), &str> =
+            history.transaction("Fails", &mut world, |history, world| {
+                history.execute(Box::new(Add(5)), world);
+                Err("boom")
+            });
+
+        assert_eq!(result, Err("boom"));
+        assert_eq!(world.resource::<Counter>().0, 0);
+        assert!(!history.can_undo());
+        assert!(!history.in_group());
+    }
+
+    #[test]
+    fn panicking_transaction_rolls_back() {
+        let (mut history, mut world) = setup();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            history.transaction::<(), ()>("Panics", &mut world, |history, world| {
+                history.execute(Box::new(Add(5)), world);
+                history.begin_group("Nested");
+                history.execute(Box::new(Add(7)), world);
+                panic!("command failed");
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(world.resource::<Counter>().0, 0);
+        assert!(!history.can_undo());
+        assert!(!history.in_group());
+    }
+
+    #[test]
+    fn new_command_after_undo_starts_a_branch() {
+        let (mut history, mut world) = setup();
+
+        history.execute(Box::new(Add(1)), &mut world);
+        let first = history.current();
+        history.execute(Box::new(Add(10)), &mut world);
+        let abandoned = history.current();
+
+        history.undo(&mut world);
+        history.execute(Box::new(Add(100)), &mut world);
+        assert_eq!(world.resource::<Counter>().0, 101);
+        assert_eq!(history.len(), 3);
+
+        let entries = history.entries();
+        let branch_point = entries.iter().find(|e| e.id == first).unwrap();
+        assert_eq!(branch_point.branch_count, 2);
+        assert!(
+            !entries
+                .iter()
+                .find(|e| e.id == abandoned)
+                .unwrap()
+                .is_applied
+        );
+
+        // Jumping to the other branch undoes +100 and redoes +10
+        assert!(history.jump_to(abandoned, &mut world));
+        assert_eq!(world.resource::<Counter>().0, 11);
+        assert_eq!(history.current(), abandoned);
+
+        // Redo follows the branch most recently visited
+        history.undo(&mut world);
+        history.redo(&mut world);
+        assert_eq!(world.resource::<Counter>().0, 11);
+    }
+
+    #[test]
+    fn budget_discards_abandoned_branches_first() {
+        let (mut history, mut world) = setup();
+        history.set_budget(HistoryBudget::Commands(3));
+
+        history.execute(Box::new(Add(1)), &mut world);
+        history.execute(Box::new(Add(2)), &mut world);
+        history.undo(&mut world);
+        history.execute(Box::new(Add(3)), &mut world);
+        history.execute(Box::new(Add(4)), &mut world);
+
+        // The undone +2 branch went first, the applied commands are kept
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.get_undo_history(), vec!["Add 1", "Add 3", "Add 4"]);
+
+        // Then the oldest applied command stops being undoable
+        history.execute(Box::new(Add(5)), &mut world);
+        assert_eq!(history.get_undo_history(), vec!["Add 3", "Add 4", "Add 5"]);
+        while history.undo(&mut world) {}
+        assert_eq!(world.resource::<Counter>().0, 1);
+
+        history.set_budget(HistoryBudget::Bytes(0));
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn save_point_follows_undo_and_redo() {
+        let (mut history, mut world) = setup();
+        assert!(history.is_at_save_point());
+
+        history.execute(Box::new(Add(1)), &mut world);
+        assert!(!history.is_at_save_point());
+        history.mark_saved();
+        assert!(history.is_at_save_point());
+
+        history.execute(Box::new(Add(2)), &mut world);
+        assert!(!history.is_at_save_point());
+        history.undo(&mut world);
+        assert!(history.is_at_save_point());
+
+        // Branching away and discarding the saved node leaves it unreachable
+        history.undo(&mut world);
+        history.execute(Box::new(Add(3)), &mut world);
+        history.set_budget(HistoryBudget::Commands(1));
+        assert!(!history.is_at_save_point());
+        history.undo(&mut world);
+        assert!(!history.is_at_save_point());
     }
 }
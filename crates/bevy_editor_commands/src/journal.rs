@@ -0,0 +1,565 @@
+//! Serializable commands, macro recording and the crash-recovery journal.
+//!
+//! Commands implementing [`SerializableCommand`] describe themselves as a
+//! [`CommandRecord`]. [`EditorHistory`] turns every execute, undo and redo into
+//! a [`JournalEntry`], which [`flush_command_journal`] appends to the active
+//! scene's journal file and to a macro being recorded. Either can later be
+//! replayed onto a scene through the [`CommandRegistry`].
+
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::history::{CommandGroup, EditorCommand, EditorHistory};
+
+const GROUP_KIND: &str = "group";
+const REVERT_KIND: &str = "revert";
+
+/// A command that can be written to disk and rebuilt by the [`CommandRegistry`].
+///
+/// Implementors return [`CommandRecord::of`] from [`EditorCommand::to_record`]
+/// and should refer to entities by a stable id rather than [`Entity`].
+pub trait SerializableCommand: EditorCommand + Serialize + DeserializeOwned + 'static {
+    /// Unique name the command is registered and recorded under.
+    const KIND: &'static str;
+}
+
+/// A command in serialized form.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandRecord {
+    pub kind: String,
+    pub data: serde_json::Value,
+}
+
+impl CommandRecord {
+    /// Serialize `command`, for implementing [`EditorCommand::to_record`].
+    pub fn of<T: SerializableCommand>(command: &T) -> Option<Self> {
+        Self::new(T::KIND, command)
+    }
+
+    pub(crate) fn group(group: &GroupRecord) -> Option<Self> {
+        Self::new(GROUP_KIND, group)
+    }
+
+    fn new(kind: &str, data: &impl Serialize) -> Option<Self> {
+        match serde_json::to_value(data) {
+            Ok(data) => Some(Self {
+                kind: kind.to_string(),
+                data,
+            }),
+            Err(error) => {
+                warn!("Failed to serialize '{}' command: {}", kind, error);
+                None
+            }
+        }
+    }
+}
+
+/// Serialized [`CommandGroup`].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct GroupRecord {
+    pub name: String,
+    pub commands: Vec<CommandRecord>,
+}
+
+/// One step applied to the world. A journal is a list of these in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JournalEntry {
+    Execute(CommandRecord),
+    /// The command as it was when undone.
+    Undo(CommandRecord),
+    Redo(CommandRecord),
+    /// A command that can't be serialized, by description. Replay skips it.
+    Unrecorded(String),
+}
+
+/// Errors reading journals or rebuilding commands from them.
+#[derive(Debug, Error)]
+pub enum JournalError {
+    #[error("No command registered as '{0}'")]
+    UnknownKind(String),
+
+    #[error("Invalid command data: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Journal I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+type CommandBuilder = fn(serde_json::Value) -> serde_json::Result<Box<dyn EditorCommand>>;
+
+/// Rebuilds commands from their [`CommandRecord`]. Crates register the
+/// serializable commands they define when their plugin is built.
+#[derive(Resource, Default)]
+pub struct CommandRegistry {
+    builders: HashMap<&'static str, CommandBuilder>,
+}
+
+impl CommandRegistry {
+    pub fn register<T: SerializableCommand>(&mut self) -> &mut Self {
+        self.builders.insert(T::KIND, |data| {
+            Ok(Box::new(serde_json::from_value::<T>(data)?) as Box<dyn EditorCommand>)
+        });
+        self
+    }
+
+    pub fn is_registered(&self, kind: &str) -> bool {
+        matches!(kind, GROUP_KIND | REVERT_KIND) || self.builders.contains_key(kind)
+    }
+
+    /// Rebuild the command `record` was made from.
+    pub fn build(&self, record: &CommandRecord) -> Result<Box<dyn EditorCommand>, JournalError> {
+        match record.kind.as_str() {
+            GROUP_KIND => {
+                let group: GroupRecord = serde_json::from_value(record.data.clone())?;
+                let commands = group
+                    .commands
+                    .iter()
+                    .map(|record| self.build(record))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Box::new(CommandGroup::with_commands(group.name, commands)))
+            }
+            REVERT_KIND => {
+                let inner: CommandRecord = serde_json::from_value(record.data.clone())?;
+                Ok(Box::new(RevertCommand(self.build(&inner)?)))
+            }
+            kind => {
+                let builder = self
+                    .builders
+                    .get(kind)
+                    .ok_or_else(|| JournalError::UnknownKind(kind.to_string()))?;
+                Ok(builder(record.data.clone())?)
+            }
+        }
+    }
+}
+
+/// Replays an undo step as a command of its own: executing it undoes the
+/// wrapped command.
+struct RevertCommand(Box<dyn EditorCommand>);
+
+impl EditorCommand for RevertCommand {
+    fn execute(&mut self, world: &mut World) {
+        self.0.undo(world);
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        self.0.execute(world);
+    }
+
+    fn description(&self) -> String {
+        format!("Undo {}", self.0.description())
+    }
+
+    fn size_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.0.size_bytes()
+    }
+
+    fn to_record(&self) -> Option<CommandRecord> {
+        CommandRecord::new(REVERT_KIND, &self.0.to_record()?)
+    }
+}
+
+/// Outcome of [`replay_journal`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplaySummary {
+    pub applied: usize,
+    pub skipped: usize,
+}
+
+/// Apply `entries` to the world as new history commands, so the replay can
+/// be undone. Undo steps become commands reverting the recorded command.
+/// Entries that can't be rebuilt are skipped with a warning.
+pub fn replay_journal(
+    entries: &[JournalEntry],
+    registry: &CommandRegistry,
+    history: &mut EditorHistory,
+    world: &mut World,
+) -> ReplaySummary {
+    let mut summary = ReplaySummary::default();
+
+    for entry in entries {
+        let command = match entry {
+            JournalEntry::Execute(record) | JournalEntry::Redo(record) => registry.build(record),
+            JournalEntry::Undo(record) => registry
+                .build(record)
+                .map(|command| Box::new(RevertCommand(command)) as Box<dyn EditorCommand>),
+            JournalEntry::Unrecorded(description) => {
+                warn!("Skipping '{}': it was not recorded", description);
+                summary.skipped += 1;
+                continue;
+            }
+        };
+
+        match command {
+            Ok(command) => {
+                history.execute(command, world);
+                summary.applied += 1;
+            }
+            Err(error) => {
+                warn!("Skipping journal entry: {}", error);
+                summary.skipped += 1;
+            }
+        }
+    }
+
+    summary
+}
+
+/// Journal file kept next to a saved scene until the scene is saved again.
+pub fn journal_path(scene_path: impl AsRef<Path>) -> PathBuf {
+    let mut path = scene_path.as_ref().as_os_str().to_owned();
+    path.push(".journal");
+    PathBuf::from(path)
+}
+
+/// Append entries to a journal file, one JSON object per line. The file is
+/// synced so the entries survive a crash.
+pub fn append_journal(path: &Path, entries: &[JournalEntry]) -> Result<(), JournalError> {
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let mut writer = BufWriter::new(file);
+    for entry in entries {
+        serde_json::to_writer(&mut writer, entry)?;
+        writer.write_all(b"\n")?;
+    }
+    let file = writer.into_inner().map_err(|error| error.into_error())?;
+    file.sync_data()?;
+    Ok(())
+}
+
+/// Write a whole journal, replacing the file. Used for macro files.
+pub fn write_journal(path: &Path, entries: &[JournalEntry]) -> Result<(), JournalError> {
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    append_journal(path, entries)
+}
+
+/// Read a journal or macro file. A truncated last line, left by a crash
+/// while writing, is ignored.
+pub fn read_journal(path: &Path) -> Result<Vec<JournalEntry>, JournalError> {
+    let lines = BufReader::new(fs::File::open(path)?)
+        .lines()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut entries = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(error) if index + 1 == lines.len() => {
+                warn!("Ignoring truncated journal line: {}", error);
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Ok(entries)
+}
+
+/// Delete a journal file, if there is one.
+pub fn remove_journal(path: &Path) -> Result<(), JournalError> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Where [`flush_command_journal`] appends the active scene's steps. `None`
+/// for scenes that have never been saved.
+#[derive(Resource, Default)]
+pub struct CommandJournal {
+    pub path: Option<PathBuf>,
+}
+
+/// Collects journal entries into a macro while recording.
+#[derive(Resource, Default)]
+pub struct MacroRecorder {
+    recording: Option<Vec<JournalEntry>>,
+}
+
+impl MacroRecorder {
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Number of steps recorded so far.
+    pub fn len(&self) -> usize {
+        self.recording.as_ref().map_or(0, Vec::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn start(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    /// Stop recording and return the recorded steps.
+    pub fn stop(&mut self) -> Vec<JournalEntry> {
+        self.recording.take().unwrap_or_default()
+    }
+
+    pub fn record(&mut self, entries: &[JournalEntry]) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.extend_from_slice(entries);
+        }
+    }
+}
+
+/// Macro controls sent by UI.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub enum MacroRequest {
+    StartRecording,
+    /// Stop recording, saving the macro if a path is given.
+    StopRecording {
+        save_to: Option<PathBuf>,
+    },
+    /// Replay a macro file onto the active scene as one undo step.
+    Play(PathBuf),
+}
+
+/// A journal left behind by a session that ended without saving, waiting
+/// for the user to recover or discard it.
+#[derive(Debug, Clone)]
+pub struct PendingRecovery {
+    pub journal_path: PathBuf,
+    pub scene_name: String,
+    pub entries: Vec<JournalEntry>,
+    /// Set by UI: `true` to replay the journal, `false` to discard it.
+    pub decision: Option<bool>,
+}
+
+#[derive(Resource, Default)]
+pub struct CrashRecovery {
+    pub pending: Option<PendingRecovery>,
+}
+
+/// System taking the history's new journal entries, appending them to the
+/// [`CommandJournal`] file and the [`MacroRecorder`].
+pub fn flush_command_journal(world: &mut World) {
+    let Some(entries) = world
+        .get_resource_mut::<EditorHistory>()
+        .map(|mut history| history.take_journal())
+    else {
+        return;
+    };
+    if entries.is_empty() {
+        return;
+    }
+
+    if let Some(mut recorder) = world.get_resource_mut::<MacroRecorder>() {
+        recorder.record(&entries);
+    }
+
+    let path = world
+        .get_resource::<CommandJournal>()
+        .and_then(|journal| journal.path.clone());
+    if let Some(path) = path {
+        if let Err(error) = append_journal(&path, &entries) {
+            error!("Failed to write journal {}: {}", path.display(), error);
+        }
+    }
+}
+
+/// System handling [`MacroRequest`]s.
+pub fn handle_macro_requests(world: &mut World) {
+    let requests: Vec<MacroRequest> = world
+        .get_resource_mut::<Events<MacroRequest>>()
+        .map(|mut events| events.drain().collect())
+        .unwrap_or_default();
+
+    for request in requests {
+        match request {
+            MacroRequest::StartRecording => {
+                world.resource_mut::<MacroRecorder>().start();
+                info!("Started recording macro");
+            }
+            MacroRequest::StopRecording { save_to } => {
+                // Steps executed this frame haven't been flushed yet
+                flush_command_journal(world);
+                let entries = world.resource_mut::<MacroRecorder>().stop();
+                let Some(path) = save_to else {
+                    continue;
+                };
+                match write_journal(&path, &entries) {
+                    Ok(()) => info!(
+                        "Saved macro ({} steps) to {}",
+                        entries.len(),
+                        path.display()
+                    ),
+                    Err(error) => error!("Failed to save macro: {}", error),
+                }
+            }
+            MacroRequest::Play(path) => {
+                let entries = match read_journal(&path) {
+                    Ok(entries) => entries,
+                    Err(error) => {
+                        error!("Failed to read macro {}: {}", path.display(), error);
+                        continue;
+                    }
+                };
+                let name = path
+                    .file_stem()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let summary = replay_in_group(world, &format!("Play Macro '{}'", name), &entries);
+                info!(
+                    "Played macro '{}': {} steps applied, {} skipped",
+                    name, summary.applied, summary.skipped
+                );
+            }
+        }
+    }
+}
+
+/// Replay `entries` onto the world's [`EditorHistory`] as a single undo step.
+pub fn replay_in_group(world: &mut World, name: &str, entries: &[JournalEntry]) -> ReplaySummary {
+    world.resource_scope(|world, registry: Mut<CommandRegistry>| {
+        world.resource_scope(|world, mut history: Mut<EditorHistory>| {
+            history.begin_group(name);
+            let summary = replay_journal(entries, &registry, &mut history, world);
+            history.end_group();
+            summary
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Counter(i32);
+
+    #[derive(Serialize, Deserialize)]
+    struct Add(i32);
+
+    impl EditorCommand for Add {
+        fn execute(&mut self, world: &mut World) {
+            world.resource_mut::<Counter>().0 += self.0;
+        }
+
+        fn undo(&mut self, world: &mut World) {
+            world.resource_mut::<Counter>().0 -= self.0;
+        }
+
+        fn description(&self) -> String {
+            format!("Add {}", self.0)
+        }
+
+        fn to_record(&self) -> Option<CommandRecord> {
+            CommandRecord::of(self)
+        }
+    }
+
+    impl SerializableCommand for Add {
+        const KIND: &'static str = "add";
+    }
+
+    fn setup() -> (EditorHistory, CommandRegistry, World) {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let mut registry = CommandRegistry::default();
+        registry.register::<Add>();
+        (EditorHistory::default(), registry, world)
+    }
+
+    #[test]
+    fn history_journals_execute_undo_and_redo() {
+        let (mut history, _, mut world) = setup();
+
+        history.execute(Box::new(Add(2)), &mut world);
+        history.begin_group("Both");
+        history.execute(Box::new(Add(3)), &mut world);
+        history.execute(Box::new(Add(4)), &mut world);
+        history.end_group();
+        history.undo(&mut world);
+        history.redo(&mut world);
+
+        let journal = history.take_journal();
+        assert_eq!(journal.len(), 4);
+        assert!(matches!(&journal[0], JournalEntry::Execute(r) if r.kind == "add"));
+        assert!(matches!(&journal[1], JournalEntry::Execute(r) if r.kind == GROUP_KIND));
+        assert!(matches!(&journal[2], JournalEntry::Undo(r) if r.kind == GROUP_KIND));
+        assert!(matches!(&journal[3], JournalEntry::Redo(_)));
+        assert!(history.take_journal().is_empty());
+    }
+
+    #[test]
+    fn replay_reproduces_the_session_on_another_world() {
+        let (mut history, registry, mut world) = setup();
+        history.execute(Box::new(Add(5)), &mut world);
+        history.begin_group("Pair");
+        history.execute(Box::new(Add(1)), &mut world);
+        history.execute(Box::new(Add(1)), &mut world);
+        history.end_group();
+        history.undo(&mut world);
+        history.execute(Box::new(Add(10)), &mut world);
+        assert_eq!(world.resource::<Counter>().0, 15);
+        let journal = history.take_journal();
+
+        let (mut replay_history, _, mut replay_world) = setup();
+        let summary = replay_journal(&journal, &registry, &mut replay_history, &mut replay_world);
+        assert_eq!(summary.applied, 4);
+        assert_eq!(replay_world.resource::<Counter>().0, 15);
+
+        // The replay is undoable step by step
+        while replay_history.undo(&mut replay_world) {}
+        assert_eq!(replay_world.resource::<Counter>().0, 0);
+    }
+
+    #[test]
+    fn journal_file_round_trips_and_tolerates_a_torn_line() {
+        let (mut history, _, mut world) = setup();
+        history.execute(Box::new(Add(1)), &mut world);
+        history.undo(&mut world);
+        let journal = history.take_journal();
+
+        let path =
+            std::env::temp_dir().join(format!("journal_test_{}.journal", std::process::id()));
+        write_journal(&path, &journal).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"Execute\":{\"kind\":")
+            .unwrap();
+
+        assert_eq!(read_journal(&path).unwrap(), journal);
+        remove_journal(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn unknown_and_unrecorded_entries_are_skipped() {
+        let (mut history, registry, mut world) = setup();
+        let entries = vec![
+            JournalEntry::Unrecorded("Paint".into()),
+            JournalEntry::Execute(CommandRecord {
+                kind: "missing".into(),
+                data: serde_json::Value::Null,
+            }),
+            JournalEntry::Execute(CommandRecord::of(&Add(2)).unwrap()),
+        ];
+        let summary = replay_journal(&entries, &registry, &mut history, &mut world);
+        assert_eq!(
+            summary,
+            ReplaySummary {
+                applied: 1,
+                skipped: 2
+            }
+        );
+        assert_eq!(world.resource::<Counter>().0, 2);
+    }
+}
@@ -3,7 +3,20 @@
 //! This crate provides the undo/redo history resource and the [`EditorCommand`]
 //! trait used across the workspace. Backends and applications can register
 //! their own commands while relying on this shared implementation.
+//!
+//! Commands that can be serialized are also written to a journal, which is
+//! used for macro recording and to recover sessions that ended without saving.
 
 mod history;
+mod journal;
 
-pub use history::{handle_undo_redo_shortcuts, EditorCommand, EditorHistory, HistoryStats};
+pub use history::{
+    handle_undo_redo_shortcuts, CommandGroup, EditorCommand, EditorHistory, HistoryBudget,
+    HistoryEntry, HistoryNodeId, HistoryRequest, HistoryStats,
+};
+pub use journal::{
+    append_journal, flush_command_journal, handle_macro_requests, journal_path, read_journal,
+    remove_journal, replay_in_group, replay_journal, write_journal, CommandJournal, CommandRecord,
+    CommandRegistry, CrashRecovery, JournalEntry, JournalError, MacroRecorder, MacroRequest,
+    PendingRecovery, ReplaySummary, SerializableCommand,
+};
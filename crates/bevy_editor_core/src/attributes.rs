@@ -0,0 +1,17 @@
+//! Reflect attributes game types can use to describe themselves to the editor
+
+use bevy::prelude::*;
+
+/// Places a component in a named group of the Add Component menu instead of
+/// its module path. Nested groups are separated by `::`.
+///
+/// ```rust,no_run
+/// use bevy::prelude::*;
+/// use bevy_editor_core::EditorCategory;
+///
+/// #[derive(Component, Reflect, Default)]
+/// #[reflect(Component, Default, @EditorCategory("Gameplay::Combat"))]
+/// struct Health(f32);
+/// ```
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditorCategory(pub &'static str);
@@ -5,6 +5,8 @@
 
 use bevy::prelude::*;
 
+use crate::snapping::SnapSettings;
+
 /// Gizmo manipulation mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Default)]
@@ -36,11 +38,47 @@ impl GizmoMode {
     }
 }
 
+/// Point a selection is rotated and scaled around
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Default)]
+pub enum PivotMode {
+    /// Center of the selection's bounds
+    #[default]
+    SelectionCenter,
+    /// Origin of the primary selected entity
+    Primary,
+    /// Each entity turns and scales around its own origin
+    IndividualOrigins,
+    /// The 2D cursor placed in the viewport
+    Cursor,
+}
+
+impl PivotMode {
+    pub const ALL: [PivotMode; 4] = [
+        PivotMode::SelectionCenter,
+        PivotMode::Primary,
+        PivotMode::IndividualOrigins,
+        PivotMode::Cursor,
+    ];
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            PivotMode::SelectionCenter => "Selection Center",
+            PivotMode::Primary => "Primary Entity",
+            PivotMode::IndividualOrigins => "Individual Origins",
+            PivotMode::Cursor => "2D Cursor",
+        }
+    }
+}
+
 /// Resource to track current gizmo mode
 #[derive(Resource, Default, Reflect)]
 #[reflect(Resource, Default)]
 pub struct GizmoState {
     pub mode: GizmoMode,
+    pub pivot: PivotMode,
+    /// World position of the 2D cursor, the pivot in [`PivotMode::Cursor`]
+    pub cursor: Vec2,
 }
 
 /// System to handle gizmo mode switching with keyboard shortcuts
@@ -75,7 +113,10 @@ pub struct GizmoPlugin;
 impl Plugin for GizmoPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<GizmoState>()
+            .init_resource::<SnapSettings>()
             .register_type::<GizmoMode>()
-            .register_type::<GizmoState>();
+            .register_type::<PivotMode>()
+            .register_type::<GizmoState>()
+            .register_type::<SnapSettings>();
     }
 }
@@ -6,7 +6,9 @@
 //! - **Camera**: Editor camera with pan and zoom controls
 //! - **Selection**: Entity selection system with multi-select support
 //! - **Gizmos**: Transform gizmo modes (Move, Rotate, Scale)
+//! - **Snapping**: Grid, bounds, rotation and scale snapping for gizmo drags
 //! - **Shortcuts**: Keyboard shortcut management
+//! - **Attributes**: Reflect attributes game types use to describe themselves to the editor
 //!
 //! ## Example
 //!
@@ -26,18 +28,23 @@
 //! }
 //! ```
 
+pub mod attributes;
 pub mod camera;
 pub mod gizmos;
 pub mod selection;
 pub mod shortcuts;
+pub mod snapping;
 
 // Re-export commonly used types
+pub use attributes::EditorCategory;
 pub use camera::{camera_pan_system, camera_zoom_system, EditorCamera};
-pub use gizmos::{handle_gizmo_mode_shortcuts, GizmoMode, GizmoPlugin, GizmoState};
+pub use gizmos::{handle_gizmo_mode_shortcuts, GizmoMode, GizmoPlugin, GizmoState, PivotMode};
 pub use selection::{
-    handle_2d_selection_system, Selectable, Selection, SelectionEvent, SelectionPlugin,
+    handle_2d_selection_system, SelectMode, Selectable, Selection, SelectionChanged,
+    SelectionPlugin,
 };
 pub use shortcuts::{KeyboardShortcut, ShortcutRegistry};
+pub use snapping::{SnapGuide, SnapSettings};
 
 /// Convenience plugin that adds all editor core systems
 pub struct EditorCorePlugin;
@@ -1,107 +1,16 @@
 //! Generic selection system for editor entities
 //!
 //! This module provides a reusable selection system that can be integrated into
-//! any Bevy editor. It handles multi-selection, selection visualization, and
-//! basic selection operations.
+//! any Bevy editor, on top of the shared [`Selection`] resource of
+//! `bevy_editor_foundation`.
 
 use bevy::prelude::*;
-use std::collections::HashSet;
+pub use bevy_editor_foundation::{SelectMode, Selection, SelectionChanged, SelectionPlugin};
 
 /// Marker component for entities that can be selected in the editor
 #[derive(Component, Debug, Clone)]
 pub struct Selectable;
 
-/// Resource tracking currently selected entities
-#[derive(Resource, Default)]
-pub struct Selection {
-    pub selected: HashSet<Entity>,
-}
-
-impl Selection {
-    pub fn clear(&mut self) {
-        self.selected.clear();
-    }
-
-    pub fn select(&mut self, entity: Entity) {
-        self.selected.clear();
-        self.selected.insert(entity);
-    }
-
-    pub fn add(&mut self, entity: Entity) {
-        self.selected.insert(entity);
-    }
-
-    pub fn remove(&mut self, entity: Entity) {
-        self.selected.remove(&entity);
-    }
-
-    pub fn toggle(&mut self, entity: Entity) {
-        if self.selected.contains(&entity) {
-            self.selected.remove(&entity);
-        } else {
-            self.selected.insert(entity);
-        }
-    }
-
-    pub fn is_selected(&self, entity: Entity) -> bool {
-        self.selected.contains(&entity)
-    }
-
-    pub fn is_empty(&self) -> bool {
-        self.selected.is_empty()
-    }
-
-    pub fn len(&self) -> usize {
-        self.selected.len()
-    }
-
-    pub fn iter(&self) -> impl Iterator<Item = &Entity> {
-        self.selected.iter()
-    }
-}
-
-/// Event for selection changes
-#[derive(Event, Debug, Clone)]
-pub enum SelectionEvent {
-    /// An entity was selected (replacing previous selection)
-    Selected(Entity),
-    /// An entity was added to the selection
-    Added(Entity),
-    /// An entity was removed from the selection
-    Removed(Entity),
-    /// Selection was cleared
-    Cleared,
-}
-
-/// System to emit SelectionEvent when selection changes
-pub fn emit_selection_events(
-    selection: Res<Selection>,
-    mut last_selection: Local<HashSet<Entity>>,
-    mut events: EventWriter<SelectionEvent>,
-) {
-    if selection.is_changed() {
-        // Check what changed
-        let current = &selection.selected;
-
-        // Check for removals
-        for entity in last_selection.difference(current) {
-            events.write(SelectionEvent::Removed(*entity));
-        }
-
-        // Check for additions
-        for entity in current.difference(&*last_selection) {
-            events.write(SelectionEvent::Added(*entity));
-        }
-
-        // If completely cleared
-        if current.is_empty() && !last_selection.is_empty() {
-            events.write(SelectionEvent::Cleared);
-        }
-
-        *last_selection = current.clone();
-    }
-}
-
 /// Helper function to get cursor world position in 2D
 pub fn get_cursor_world_pos_2d(
     windows: &Query<&Window>,
@@ -135,11 +44,8 @@ pub fn handle_2d_selection_system(
         return;
     }
 
+    let mode = SelectMode::from_keyboard(&keyboard);
     let Some(cursor_world_pos) = get_cursor_world_pos_2d(&windows, &camera_q) else {
-        // Clicked on nothing, clear selection if not holding Ctrl
-        if !keyboard.pressed(KeyCode::ControlLeft) && !keyboard.pressed(KeyCode::ControlRight) {
-            selection.clear();
-        }
         return;
     };
 
@@ -158,26 +64,9 @@ pub fn handle_2d_selection_system(
     }
 
     if let Some(entity) = closest_entity {
-        if keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight) {
-            // Multi-select with Ctrl
-            selection.toggle(entity);
-        } else {
-            // Single select
-            selection.select(entity);
-        }
-    } else if !keyboard.pressed(KeyCode::ControlLeft) && !keyboard.pressed(KeyCode::ControlRight) {
+        selection.click(entity, mode);
+    } else if mode == SelectMode::Replace {
         // Clicked on empty space, clear selection
         selection.clear();
     }
 }
-
-/// Plugin to add selection system to your editor
-pub struct SelectionPlugin;
-
-impl Plugin for SelectionPlugin {
-    fn build(&self, app: &mut App) {
-        app.init_resource::<Selection>()
-            .add_event::<SelectionEvent>()
-            .add_systems(Update, emit_selection_events);
-    }
-}
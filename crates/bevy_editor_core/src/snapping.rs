@@ -0,0 +1,166 @@
+//! Snapping for gizmo moves, rotations and scales
+//!
+//! [`SnapSettings`] holds what transforms snap to; the helpers here do the
+//! math and report [`SnapGuide`]s so editors can show what was snapped to.
+//! Grid snapping follows the editor's own grid toggle, see
+//! [`bevy_editor_foundation::EditorState`].
+
+use bevy::prelude::*;
+
+/// What gizmo drags snap to
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource, Default)]
+pub struct SnapSettings {
+    /// Moves snap edges and centers of the selection to those of other entities
+    pub to_entities: bool,
+    /// Moves snap the primary entity's origin to tile cell centers
+    pub to_tiles: bool,
+    /// How close, in screen pixels, edges and centers must come to snap
+    pub entity_distance: f32,
+    /// Rotations snap to multiples of `rotation_step`
+    pub rotation: bool,
+    /// Rotation increment in degrees
+    pub rotation_step: f32,
+    /// Scaling snaps the scale factor to multiples of `scale_step`
+    pub scale: bool,
+    pub scale_step: f32,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            to_entities: true,
+            to_tiles: false,
+            entity_distance: 8.0,
+            rotation: false,
+            rotation_step: 15.0,
+            scale: false,
+            scale_step: 0.25,
+        }
+    }
+}
+
+/// Something a drag snapped to, drawn while dragging
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnapGuide {
+    /// An alignment line, e.g. between two edges that line up
+    Line { from: Vec2, to: Vec2 },
+    /// A snapped point, e.g. a grid intersection
+    Point(Vec2),
+}
+
+/// `value` rounded to the nearest multiple of `step`; a step of zero or less
+/// leaves it as is
+pub fn snap_to_step(value: f32, step: f32) -> f32 {
+    if step > 0.0 {
+        (value / step).round() * step
+    } else {
+        value
+    }
+}
+
+/// `point` rounded to the nearest intersection of a grid of `cell_size`
+pub fn snap_to_grid(point: Vec2, cell_size: Vec2) -> Vec2 {
+    Vec2::new(
+        snap_to_step(point.x, cell_size.x),
+        snap_to_step(point.y, cell_size.y),
+    )
+}
+
+/// `angle` in radians rounded to the nearest multiple of `step_degrees`
+pub fn snap_angle(angle: f32, step_degrees: f32) -> f32 {
+    snap_to_step(angle, step_degrees.to_radians())
+}
+
+/// Offset that lines up an edge or the center of `moving` with an edge or
+/// center of one of `targets`, on each axis where one lies within
+/// `max_distance`, and guides along the lines that now match
+pub fn snap_to_bounds(moving: Rect, targets: &[Rect], max_distance: f32) -> (Vec2, Vec<SnapGuide>) {
+    let x = closest_feature(moving, targets, max_distance, |rect| {
+        [rect.min.x, rect.center().x, rect.max.x]
+    });
+    let y = closest_feature(moving, targets, max_distance, |rect| {
+        [rect.min.y, rect.center().y, rect.max.y]
+    });
+    let offset = Vec2::new(
+        x.map_or(0.0, |(offset, _, _)| offset),
+        y.map_or(0.0, |(offset, _, _)| offset),
+    );
+
+    let moved = Rect::from_center_size(moving.center() + offset, moving.size());
+    let mut guides = Vec::new();
+    if let Some((_, line, target)) = x {
+        let span = moved.union(target);
+        guides.push(SnapGuide::Line {
+            from: Vec2::new(line, span.min.y),
+            to: Vec2::new(line, span.max.y),
+        });
+    }
+    if let Some((_, line, target)) = y {
+        let span = moved.union(target);
+        guides.push(SnapGuide::Line {
+            from: Vec2::new(span.min.x, line),
+            to: Vec2::new(span.max.x, line),
+        });
+    }
+    (offset, guides)
+}
+
+/// The smallest move along one axis that brings a feature of `moving` onto a
+/// feature of a target, as (offset, matched coordinate, target)
+fn closest_feature(
+    moving: Rect,
+    targets: &[Rect],
+    max_distance: f32,
+    features: impl Fn(Rect) -> [f32; 3],
+) -> Option<(f32, f32, Rect)> {
+    let moving_features = features(moving);
+    targets
+        .iter()
+        .flat_map(|&target| {
+            features(target).into_iter().flat_map(move |line| {
+                moving_features
+                    .into_iter()
+                    .map(move |feature| (line - feature, line, target))
+            })
+        })
+        .filter(|(offset, _, _)| offset.abs() <= max_distance)
+        .min_by(|(a, _, _), (b, _, _)| a.abs().total_cmp(&b.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_round_to_the_nearest_multiple() {
+        assert_eq!(snap_to_step(47.0, 32.0), 32.0);
+        assert_eq!(snap_to_step(49.0, 32.0), 64.0);
+        assert_eq!(snap_to_step(-17.0, 32.0), -32.0);
+        assert_eq!(snap_to_step(3.3, 0.0), 3.3);
+        assert!((snap_angle(50f32.to_radians(), 15.0) - 45f32.to_radians()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn bounds_snap_to_the_nearest_edge_or_center() {
+        let target = Rect::new(100.0, 0.0, 140.0, 40.0);
+        // Left edge 3 units from the target's right edge, centers 1 unit apart vertically
+        let moving = Rect::new(143.0, 1.0, 163.0, 41.0);
+        let (offset, guides) = snap_to_bounds(moving, &[target], 5.0);
+        assert_eq!(offset, Vec2::new(-3.0, -1.0));
+        assert_eq!(guides.len(), 2);
+        assert!(guides.contains(&SnapGuide::Line {
+            from: Vec2::new(140.0, 0.0),
+            to: Vec2::new(140.0, 40.0),
+        }));
+
+        // Too far away to snap
+        let (offset, guides) = snap_to_bounds(
+            moving.inflate(-5.0),
+            &[Rect::new(0.0, 200.0, 10.0, 210.0)],
+            5.0,
+        );
+        assert_eq!(offset, Vec2::ZERO);
+        assert!(guides.is_empty());
+    }
+}
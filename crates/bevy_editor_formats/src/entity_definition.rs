@@ -1,6 +1,12 @@
+use bevy::asset::uuid::Uuid;
+use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Custom field type for entity definitions
+///
+/// `EntityRef` points at another entity instance, optionally restricted to one
+/// definition; `AssetPath` is a path relative to the project assets folder.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum FieldType {
     Int { min: Option<i32>, max: Option<i32> },
@@ -11,6 +17,8 @@ pub enum FieldType {
     Color,
     Point,
     Array { element_type: Box<FieldType> },
+    EntityRef { entity_def_id: Option<u32> },
+    AssetPath { extensions: Vec<String> },
 }
 
 impl FieldType {
@@ -24,8 +32,37 @@ impl FieldType {
             FieldType::Color => "Color",
             FieldType::Point => "Point",
             FieldType::Array { .. } => "Array",
+            FieldType::EntityRef { .. } => "EntityRef",
+            FieldType::AssetPath { .. } => "AssetPath",
         }
     }
+
+    /// For reference fields (or arrays of them), the definition the target must
+    /// have: `Some(None)` accepts any entity, `None` means not a reference field
+    pub fn entity_ref_definition(&self) -> Option<Option<u32>> {
+        match self {
+            FieldType::EntityRef { entity_def_id } => Some(*entity_def_id),
+            FieldType::Array { element_type } => element_type.entity_ref_definition(),
+            _ => None,
+        }
+    }
+
+    /// Whether `path` has one of the accepted extensions (any path if none are listed)
+    pub fn accepts_asset_path(&self, path: &str) -> bool {
+        let FieldType::AssetPath { extensions } = self else {
+            return false;
+        };
+        if extensions.is_empty() {
+            return true;
+        }
+        let extension = std::path::Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+        extensions
+            .iter()
+            .any(|accepted| accepted.eq_ignore_ascii_case(extension))
+    }
 }
 
 /// Custom field definition
@@ -38,7 +75,8 @@ pub struct CustomField {
 }
 
 /// Field value - runtime value for a custom field
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Reflect)]
+#[reflect(no_field_bounds)]
 pub enum FieldValue {
     Int(i32),
     Float(f32),
@@ -46,8 +84,14 @@ pub enum FieldValue {
     Bool(bool),
     Enum(String),
     Color(String), // hex color
-    Point { x: f32, y: f32 },
+    Point {
+        x: f32,
+        y: f32,
+    },
     Array(Vec<FieldValue>),
+    /// Instance id of the referenced entity, `None` until one is picked
+    EntityRef(Option<Uuid>),
+    AssetPath(String),
     Null,
 }
 
@@ -62,8 +106,74 @@ impl FieldValue {
             FieldType::Color => FieldValue::Color("#FFFFFF".to_string()),
             FieldType::Point => FieldValue::Point { x: 0.0, y: 0.0 },
             FieldType::Array { .. } => FieldValue::Array(Vec::new()),
+            FieldType::EntityRef { .. } => FieldValue::EntityRef(None),
+            FieldType::AssetPath { .. } => FieldValue::AssetPath(String::new()),
+        }
+    }
+
+    /// Ids of all entities referenced by this value, including inside arrays
+    pub fn entity_refs(&self) -> Vec<Uuid> {
+        match self {
+            FieldValue::EntityRef(Some(id)) => vec![*id],
+            FieldValue::Array(values) => values.iter().flat_map(Self::entity_refs).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Why an `EntityRef` field doesn't resolve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceProblem {
+    /// No instance with the referenced id exists
+    Missing,
+    /// The target exists but is not of the definition the field requires
+    /// (`found` is `None` for a target without custom fields)
+    WrongDefinition { expected: u32, found: Option<u32> },
+}
+
+/// An `EntityRef` field that points at a missing or unsuitable entity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceIssue {
+    pub source: Uuid,
+    pub field: String,
+    pub target: Uuid,
+    pub problem: ReferenceProblem,
+}
+
+/// Check the `EntityRef` fields of one instance. `target_definition` looks up the
+/// instance with the given id: `None` if it doesn't exist, otherwise its
+/// definition id (if it has one).
+pub fn check_entity_refs(
+    source: Uuid,
+    definition: &EntityDefinitionData,
+    values: &HashMap<String, FieldValue>,
+    target_definition: impl Fn(Uuid) -> Option<Option<u32>>,
+) -> Vec<ReferenceIssue> {
+    let mut issues = Vec::new();
+    for field in &definition.field_definitions {
+        let Some(required) = field.field_type.entity_ref_definition() else {
+            continue;
+        };
+        let Some(value) = values.get(&field.name) else {
+            continue;
+        };
+        for target in value.entity_refs() {
+            let problem = match (target_definition(target), required) {
+                (None, _) => ReferenceProblem::Missing,
+                (Some(found), Some(expected)) if found != Some(expected) => {
+                    ReferenceProblem::WrongDefinition { expected, found }
+                }
+                _ => continue,
+            };
+            issues.push(ReferenceIssue {
+                source,
+                field: field.name.clone(),
+                target,
+                problem,
+            });
         }
     }
+    issues
 }
 
 /// Entity definition - custom entity class
@@ -128,21 +238,25 @@ impl EntityDefinitionData {
 /// Entity instance - actual entity placement with field values
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntityInstanceData {
+    /// Stable instance id, the target of `EntityRef` fields
+    #[serde(default = "Uuid::new_v4")]
+    pub iid: Uuid,
     pub level_id: u32,
     pub entity_def_id: u32,
     pub x: f32,
     pub y: f32,
-    pub field_values: std::collections::HashMap<String, FieldValue>,
+    pub field_values: HashMap<String, FieldValue>,
 }
 
 impl EntityInstanceData {
     pub fn new(level_id: u32, entity_def_id: u32, x: f32, y: f32) -> Self {
         Self {
+            iid: Uuid::new_v4(),
             level_id,
             entity_def_id,
             x,
             y,
-            field_values: std::collections::HashMap::new(),
+            field_values: HashMap::new(),
         }
     }
 
@@ -165,6 +279,31 @@ impl EntityInstanceData {
     }
 }
 
+/// Custom field values of an editor scene entity, saved with the scene.
+/// `values` holds the instance's values keyed by field name, like
+/// [`EntityInstanceData::field_values`]; the entity's `EditorEntityId` is its
+/// instance id.
+#[derive(Component, Reflect, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[reflect(Component, Default)]
+pub struct EntityFields {
+    pub definition_id: u32,
+    pub values: HashMap<String, FieldValue>,
+}
+
+impl EntityFields {
+    /// Fields of `definition`, each set to its default value
+    pub fn from_definition(definition: &EntityDefinitionData) -> Self {
+        Self {
+            definition_id: definition.id,
+            values: definition
+                .field_definitions
+                .iter()
+                .map(|field| (field.name.clone(), field.default_value.clone()))
+                .collect(),
+        }
+    }
+}
+
 /// Enum definition - custom enum for entity fields
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnumDefinitionData {
@@ -200,3 +339,83 @@ impl EnumDefinitionData {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorldExport;
+
+    #[test]
+    fn dangling_and_mistyped_references_are_reported() {
+        let lever = EntityDefinitionData::new(1, "Lever");
+        let door = EntityDefinitionData::new(2, "Door")
+            .with_field(
+                "target",
+                FieldType::EntityRef {
+                    entity_def_id: Some(1),
+                },
+            )
+            .with_field(
+                "chain",
+                FieldType::Array {
+                    element_type: Box::new(FieldType::EntityRef {
+                        entity_def_id: None,
+                    }),
+                },
+            );
+
+        let lever_instance = EntityInstanceData::new(0, 1, 0.0, 0.0);
+        let other_door = EntityInstanceData::new(0, 2, 0.0, 0.0);
+        let missing = Uuid::new_v4();
+        let door_instance = EntityInstanceData::new(0, 2, 10.0, 0.0)
+            .with_field("target", FieldValue::EntityRef(Some(other_door.iid)))
+            .with_field(
+                "chain",
+                FieldValue::Array(vec![
+                    FieldValue::EntityRef(Some(lever_instance.iid)),
+                    FieldValue::EntityRef(Some(missing)),
+                    FieldValue::EntityRef(None),
+                ]),
+            );
+
+        let world = WorldExport::new("1.0")
+            .with_entity_definition(lever)
+            .with_entity_definition(door)
+            .with_entity_instance(lever_instance)
+            .with_entity_instance(other_door)
+            .with_entity_instance(door_instance.clone());
+
+        let mut issues = world.validate_references();
+        issues.sort_by(|a, b| a.field.cmp(&b.field));
+        assert_eq!(
+            issues,
+            [
+                ReferenceIssue {
+                    source: door_instance.iid,
+                    field: "chain".to_string(),
+                    target: missing,
+                    problem: ReferenceProblem::Missing,
+                },
+                ReferenceIssue {
+                    source: door_instance.iid,
+                    field: "target".to_string(),
+                    target: world.entity_instances[1].iid,
+                    problem: ReferenceProblem::WrongDefinition {
+                        expected: 1,
+                        found: Some(2),
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn asset_path_extensions_are_case_insensitive() {
+        let field = FieldType::AssetPath {
+            extensions: vec!["png".to_string()],
+        };
+        assert!(field.accepts_asset_path("sprites/door.PNG"));
+        assert!(!field.accepts_asset_path("sounds/door.ogg"));
+        assert!(FieldType::AssetPath { extensions: vec![] }.accepts_asset_path("any"));
+    }
+}
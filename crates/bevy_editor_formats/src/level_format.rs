@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use super::entities::EntitySpawnConfig;
 use super::math::Vector2;
+use super::tilemap::{MapOrientation, TileCollisionData};
 
 /// Level metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +30,15 @@ pub struct LevelTilesetData {
     pub texture_path: String,
     pub tile_width: u32,
     pub tile_height: u32,
+    /// Gap in pixels between neighbouring tiles
+    #[serde(default)]
+    pub spacing: u32,
+    /// Margin in pixels around the edge of the image
+    #[serde(default)]
+    pub padding: u32,
+    /// Collision shapes keyed by tile id
+    #[serde(default)]
+    pub collision_data: HashMap<u32, TileCollisionData>,
 }
 
 /// Layer data with tile placements
@@ -44,6 +55,10 @@ pub struct LevelLayerData {
 pub struct LevelTileInstance {
     pub x: u32,
     pub y: u32,
+    /// Id of the entry in `LevelTilemapData::tilesets` this tile comes from.
+    /// `None` only in files written before tiles were tileset-qualified.
+    #[serde(default)]
+    pub tileset_id: Option<u32>,
     pub tile_id: u32,
 }
 
@@ -53,6 +68,9 @@ pub struct LevelTilemapData {
     pub grid_size: f32,
     pub map_width: u32,
     pub map_height: u32,
+    /// Grid orientation shared by every layer
+    #[serde(default)]
+    pub orientation: MapOrientation,
     pub tilesets: Vec<LevelTilesetData>,
     pub selected_tileset_id: Option<u32>,
     pub layers: Vec<LevelLayerData>,
@@ -64,6 +82,7 @@ impl Default for LevelTilemapData {
             grid_size: 32.0,
             map_width: 64,
             map_height: 64,
+            orientation: MapOrientation::Square,
             tilesets: Vec::new(),
             selected_tileset_id: None,
             layers: vec![LevelLayerData {
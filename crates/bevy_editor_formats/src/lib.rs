@@ -1,8 +1,9 @@
 //! Data formats shared by Bevy editor crates.
 //!
 //! This crate centralizes serialization-friendly structures for projects,
-//! scenes, tilemaps, and entity definitions so they can be reused across
-//! multiple editor plugins and applications.
+//! scenes, tilemaps, entity definitions, game type schemas, and the remote
+//! protocol spoken with running games so they can be reused across multiple
+//! editor plugins and applications.
 
 mod components;
 mod entities;
@@ -10,8 +11,11 @@ mod entity_definition;
 mod level_format;
 mod math;
 mod project_format;
+mod remote_protocol;
+mod scene_components;
 mod scene_format;
 mod tilemap;
+mod type_schema;
 mod world_export;
 
 // Re-export commonly used types so downstream crates can `use bevy_editor_formats::*`.
@@ -23,6 +27,9 @@ pub use entity_definition::*;
 pub use level_format::*;
 pub use math::*;
 pub use project_format::*;
+pub use remote_protocol::*;
+pub use scene_components::*;
 pub use scene_format::*;
 pub use tilemap::*;
+pub use type_schema::*;
 pub use world_export::*;
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use super::entity_definition::EntityDefinitionData;
+use super::tilemap::{BrushPreset, TileCollisionData};
+
 /// Client/game configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientConfig {
@@ -37,6 +41,16 @@ pub struct ProjectConfig {
     /// Path to the default scene to auto-load (relative to assets/world/)
     #[serde(default)]
     pub default_scene: Option<String>,
+    /// Variation brush presets saved from the tile painter
+    #[serde(default)]
+    pub brush_presets: Vec<BrushPreset>,
+    /// Collision shapes of each tileset by tile id, keyed by the tileset's
+    /// texture path relative to the assets folder
+    #[serde(default)]
+    pub tileset_collisions: HashMap<String, HashMap<u32, TileCollisionData>>,
+    /// Entity classes whose custom fields can be attached to scene entities
+    #[serde(default)]
+    pub entity_definitions: Vec<EntityDefinitionData>,
 }
 
 impl Default for ProjectConfig {
@@ -49,6 +63,9 @@ impl Default for ProjectConfig {
             client_config: ClientConfig::default(),
             last_opened_scene: None,
             default_scene: Some("main.bscene".to_string()),
+            brush_presets: Vec::new(),
+            tileset_collisions: HashMap::new(),
+            entity_definitions: Vec::new(),
         }
     }
 }
@@ -136,6 +153,11 @@ impl ProjectMetadata {
         self.levels_path.join(level_name)
     }
 
+    /// Path of the game's exported [`crate::TypeSchemaFile`]
+    pub fn type_schema_path(&self) -> PathBuf {
+        self.root_path.join(crate::TYPE_SCHEMA_FILE)
+    }
+
     /// Save the current config back to disk
     pub fn save_config(&self) -> Result<(), Box<dyn std::error::Error>> {
         let config_path = self.root_path.join("project.bvy");
@@ -0,0 +1,351 @@
+//! Bevy Remote Protocol messages shared by the game and the editor
+//!
+//! The protocol is JSON-RPC 2.0 over HTTP on localhost, the same wire format
+//! `bevy_remote` serves, so the editor's live link can talk to either. Entities
+//! are sent as their `Entity::to_bits` value and component values as reflected
+//! JSON, keyed by type path.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+/// Environment variable telling `bevy_editor_runtime` which port to serve the
+/// remote protocol on
+pub const REMOTE_PORT_ENV_VAR: &str = "BEVY_EDITOR_REMOTE_PORT";
+
+/// Port `bevy_remote` listens on by default
+pub const DEFAULT_REMOTE_PORT: u16 = 15702;
+
+/// Fetch components of one entity, [`GetParams`]
+pub const BRP_GET: &str = "bevy/get";
+/// Fetch components of every entity matching a filter, [`QueryParams`]
+pub const BRP_QUERY: &str = "bevy/query";
+/// Insert or replace components, [`InsertParams`]
+pub const BRP_INSERT: &str = "bevy/insert";
+/// Remove components, [`RemoveParams`]
+pub const BRP_REMOVE: &str = "bevy/remove";
+/// Despawn an entity, [`DestroyParams`]
+pub const BRP_DESTROY: &str = "bevy/destroy";
+/// List the reflected components of an entity or the app, [`ListParams`]
+pub const BRP_LIST: &str = "bevy/list";
+/// Editor extension: reload a scene file the editor saved, [`ReloadSceneParams`]
+pub const EDITOR_RELOAD_SCENE: &str = "bevy_editor/reload_scene";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteRequest {
+    pub jsonrpc: String,
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl RemoteRequest {
+    pub fn new(id: u64, method: &str, params: impl Serialize) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: Some(id.into()),
+            method: method.to_string(),
+            params: serde_json::to_value(params)
+                .ok()
+                .filter(|params| !params.is_null()),
+        }
+    }
+
+    /// The params, read as `T`
+    pub fn params<T: for<'de> Deserialize<'de>>(&self) -> Result<T, RemoteError> {
+        serde_json::from_value(self.params.clone().unwrap_or(Value::Null)).map_err(|error| {
+            RemoteError::new(
+                RemoteError::INVALID_PARAMS,
+                format!("invalid params for `{}`: {error}", self.method),
+            )
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteResponse {
+    pub jsonrpc: String,
+    pub id: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RemoteError>,
+}
+
+impl RemoteResponse {
+    pub fn new(id: Option<Value>, result: Result<Value, RemoteError>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result,
+            error,
+        }
+    }
+
+    /// The result, read as `T`
+    pub fn into_result<T: for<'de> Deserialize<'de>>(self) -> Result<T, RemoteError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        serde_json::from_value(self.result.unwrap_or(Value::Null)).map_err(|error| {
+            RemoteError::new(
+                RemoteError::PARSE_ERROR,
+                format!("unexpected result: {error}"),
+            )
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteError {
+    pub code: i32,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RemoteError {
+    pub const PARSE_ERROR: i32 = -32700;
+    pub const INVALID_REQUEST: i32 = -32600;
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    pub const INVALID_PARAMS: i32 = -32602;
+    pub const INTERNAL_ERROR: i32 = -32603;
+    /// The entity doesn't exist
+    pub const ENTITY_NOT_FOUND: i32 = -23401;
+    /// A component type isn't registered or can't be reflected
+    pub const COMPONENT_ERROR: i32 = -23402;
+    /// The entity doesn't have a requested component
+    pub const COMPONENT_NOT_PRESENT: i32 = -23403;
+
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl std::fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for RemoteError {}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GetParams {
+    pub entity: u64,
+    pub components: Vec<String>,
+    /// Fail on the first missing component instead of reporting it in
+    /// [`GetResponse::errors`]
+    #[serde(default)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GetResponse {
+    pub components: Map<String, Value>,
+    #[serde(default)]
+    pub errors: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueryParams {
+    pub data: QueryData,
+    #[serde(default)]
+    pub filter: QueryFilter,
+    #[serde(default)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueryData {
+    /// Components every returned entity has, with their values
+    #[serde(default)]
+    pub components: Vec<String>,
+    /// Components returned when present
+    #[serde(default)]
+    pub option: Vec<String>,
+    /// Components reported as present or not, without their values
+    #[serde(default)]
+    pub has: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueryFilter {
+    #[serde(default)]
+    pub with: Vec<String>,
+    #[serde(default)]
+    pub without: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueryRow {
+    pub entity: u64,
+    pub components: Map<String, Value>,
+    #[serde(default)]
+    pub has: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InsertParams {
+    pub entity: u64,
+    pub components: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RemoveParams {
+    pub entity: u64,
+    pub components: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DestroyParams {
+    pub entity: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListParams {
+    /// Components of this entity, or every registered component if `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReloadSceneParams {
+    /// Asset path of the scene, like `world/level1.scn.ron`
+    pub path: String,
+}
+
+/// Send `request` to the server at `address` and wait for its response
+pub fn send_remote_request(
+    address: SocketAddr,
+    request: &RemoteRequest,
+    timeout: Duration,
+) -> io::Result<RemoteResponse> {
+    let mut stream = TcpStream::connect_timeout(&address, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let body = serde_json::to_vec(request)?;
+    write!(
+        stream,
+        "POST / HTTP/1.1\r\nHost: {address}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    let body = read_http_message(&mut reader)?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed"))?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+/// Largest HTTP body accepted, so a bad `Content-Length` can't make the
+/// reader allocate without bound
+pub const MAX_HTTP_BODY_SIZE: usize = 8 * 1024 * 1024;
+
+/// Read one HTTP request or response and return its body, or `None` if the
+/// connection was closed before it started
+pub fn read_http_message(reader: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidData, "invalid Content-Length")
+                })?;
+            }
+        }
+    }
+    if content_length > MAX_HTTP_BODY_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Content-Length {content_length} exceeds {MAX_HTTP_BODY_SIZE} bytes"),
+        ));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+/// Write `response` as an HTTP response
+pub fn write_http_response(writer: &mut impl Write, response: &RemoteResponse) -> io::Result<()> {
+    let body = serde_json::to_vec(response)?;
+    write!(
+        writer,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+        body.len()
+    )?;
+    writer.write_all(&body)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_messages_round_trip() {
+        let response = RemoteResponse::new(Some(7.into()), Ok(serde_json::json!({"a": [1, 2]})));
+        let mut bytes = Vec::new();
+        write_http_response(&mut bytes, &response).unwrap();
+
+        let mut reader = BufReader::new(bytes.as_slice());
+        let body = read_http_message(&mut reader).unwrap().unwrap();
+        let read: RemoteResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(read, response);
+        assert!(read_http_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn oversized_bodies_are_rejected() {
+        let request = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_HTTP_BODY_SIZE + 1
+        );
+        let mut reader = BufReader::new(request.as_bytes());
+        let error = read_http_message(&mut reader).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn errors_are_returned_from_results() {
+        let error = RemoteError::new(RemoteError::ENTITY_NOT_FOUND, "no entity");
+        let response = RemoteResponse::new(Some(1.into()), Err(error.clone()));
+        assert_eq!(response.into_result::<Value>().unwrap_err(), error);
+
+        let request = RemoteRequest::new(1, BRP_LIST, ListParams::default());
+        assert_eq!(
+            request.params::<ListParams>().unwrap(),
+            ListParams::default()
+        );
+        let request = RemoteRequest::new(2, BRP_GET, ());
+        assert_eq!(
+            request.params::<GetParams>().unwrap_err().code,
+            RemoteError::INVALID_PARAMS
+        );
+    }
+}
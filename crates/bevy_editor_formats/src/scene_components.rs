@@ -0,0 +1,530 @@
+//! Keeping unknown components of `.scn.ron` files in [`GameComponents`]
+//!
+//! Bevy's scene loader rejects component types missing from the type registry,
+//! and the editor never registers the game's own types. Before a scene is
+//! loaded, [`collect_game_components`] moves the components the editor doesn't
+//! know into a [`GameComponents`] entry of their entity, verbatim;
+//! [`expand_game_components`] moves them back out when the scene is saved.
+
+use crate::type_schema::{GameComponent, GameComponents};
+use bevy::reflect::TypePath;
+
+/// Move the components `is_known` rejects into a [`GameComponents`] entry
+pub fn collect_game_components(
+    scene: &str,
+    is_known: impl Fn(&str) -> bool,
+) -> Result<String, Box<dyn std::error::Error>> {
+    rewrite_components(scene, |map| {
+        if map.entries.iter().all(|entry| is_known(&entry.type_path)) {
+            return Ok(None);
+        }
+        let mut unknown = GameComponents::default();
+        let mut entries = Vec::new();
+        for entry in &map.entries {
+            if is_known(&entry.type_path) {
+                entries.push((entry.type_path.clone(), entry.value.clone()));
+            } else {
+                if unknown.0.is_empty() {
+                    // Saved back together where the first one was
+                    entries.push((GameComponents::type_path().to_string(), String::new()));
+                }
+                unknown.0.push(GameComponent {
+                    type_path: entry.type_path.clone(),
+                    ron: dedent(&entry.value, &map.indent),
+                });
+            }
+        }
+        let value = ron::to_string(&unknown)?;
+        for entry in &mut entries {
+            if entry.0 == GameComponents::type_path() {
+                entry.1.clone_from(&value);
+            }
+        }
+        Ok(Some(entries))
+    })
+}
+
+/// Replace [`GameComponents`] entries with the components they hold
+pub fn expand_game_components(scene: &str) -> Result<String, Box<dyn std::error::Error>> {
+    rewrite_components(scene, |map| {
+        if !map
+            .entries
+            .iter()
+            .any(|entry| entry.type_path == GameComponents::type_path())
+        {
+            return Ok(None);
+        }
+        let mut entries = Vec::new();
+        for entry in &map.entries {
+            if entry.type_path == GameComponents::type_path() {
+                let components: GameComponents = ron::from_str(&entry.value)?;
+                entries.extend(
+                    components.0.into_iter().map(|component| {
+                        (component.type_path, indent(&component.ron, &map.indent))
+                    }),
+                );
+            } else {
+                entries.push((entry.type_path.clone(), entry.value.clone()));
+            }
+        }
+        Ok(Some(entries))
+    })
+}
+
+/// Rewrite the components map of every entity for which `rewrite` returns new
+/// `(type path, value)` entries
+fn rewrite_components(
+    scene: &str,
+    mut rewrite: impl FnMut(
+        &ComponentsMap,
+    ) -> Result<Option<Vec<(String, String)>>, Box<dyn std::error::Error>>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let maps = Scanner::new(scene).scene()?;
+    let mut output = String::with_capacity(scene.len());
+    let mut copied = 0;
+    for map in &maps {
+        let Some(entries) = rewrite(map)? else {
+            continue;
+        };
+        output.push_str(&scene[copied..map.open]);
+        for (type_path, value) in entries {
+            output.push('\n');
+            output.push_str(&map.indent);
+            output.push_str(&format!("{type_path:?}: {value},"));
+        }
+        output.push('\n');
+        output.push_str(&map.close_indent);
+        copied = map.close;
+    }
+    output.push_str(&scene[copied..]);
+    Ok(output)
+}
+
+/// Remove `indent` from the lines after the first
+fn dedent(value: &str, indent: &str) -> String {
+    let mut lines = value.split('\n');
+    let mut result = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        result.push('\n');
+        result.push_str(line.strip_prefix(indent).unwrap_or(line));
+    }
+    result
+}
+
+/// Add `indent` to the lines after the first
+fn indent(value: &str, indent: &str) -> String {
+    value.replace('\n', &format!("\n{indent}"))
+}
+
+/// The `components` map of one scene entity
+struct ComponentsMap {
+    /// Just after the opening brace
+    open: usize,
+    /// The closing brace
+    close: usize,
+    entries: Vec<ComponentEntry>,
+    /// Indentation of the entries
+    indent: String,
+    /// Indentation of the closing brace
+    close_indent: String,
+}
+
+struct ComponentEntry {
+    type_path: String,
+    value: String,
+}
+
+/// Just enough of a RON parser to find the component maps of a scene
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, pos: 0 }
+    }
+
+    fn scene(&mut self) -> Result<Vec<ComponentsMap>, String> {
+        let mut maps = Vec::new();
+        self.structure(|scanner, field| {
+            if field != "entities" {
+                return scanner.skip_value();
+            }
+            scanner.expect(b'{')?;
+            while !scanner.consume(b'}') {
+                scanner.skip_value()?;
+                scanner.expect(b':')?;
+                scanner.structure(|scanner, field| {
+                    if field == "components" {
+                        maps.push(scanner.components()?);
+                        Ok(())
+                    } else {
+                        scanner.skip_value()
+                    }
+                })?;
+                scanner.separator(b'}')?;
+            }
+            Ok(())
+        })?;
+        Ok(maps)
+    }
+
+    /// A struct, calling `field` to read the value of each field
+    fn structure(
+        &mut self,
+        mut field: impl FnMut(&mut Self, &str) -> Result<(), String>,
+    ) -> Result<(), String> {
+        if self.peek().is_some_and(is_ident_start) {
+            self.ident()?;
+        }
+        self.expect(b'(')?;
+        while !self.consume(b')') {
+            let name = self.ident()?;
+            self.expect(b':')?;
+            field(self, name)?;
+            self.separator(b')')?;
+        }
+        Ok(())
+    }
+
+    fn components(&mut self) -> Result<ComponentsMap, String> {
+        self.expect(b'{')?;
+        let open = self.pos;
+        let mut entries = Vec::new();
+        let mut indent = None;
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(b'}') {
+                break;
+            }
+            indent.get_or_insert_with(|| self.line_indent(self.pos));
+            let type_path = self.string()?;
+            self.expect(b':')?;
+            self.skip_whitespace();
+            let start = self.pos;
+            let end = self.skip_value_end()?;
+            entries.push(ComponentEntry {
+                type_path,
+                value: self.text[start..end].to_string(),
+            });
+            self.separator(b'}')?;
+        }
+        let close = self.pos;
+        let close_indent = self.line_indent(close);
+        self.pos += 1;
+        Ok(ComponentsMap {
+            open,
+            close,
+            entries,
+            indent: indent.unwrap_or_else(|| format!("{close_indent}  ")),
+            close_indent,
+        })
+    }
+
+    /// Whitespace before `pos` on its line, or nothing if other text precedes it
+    fn line_indent(&self, pos: usize) -> String {
+        let line_start = self.text[..pos].rfind('\n').map_or(0, |index| index + 1);
+        let prefix = &self.text[line_start..pos];
+        if prefix.chars().all(char::is_whitespace) {
+            prefix.to_string()
+        } else {
+            String::new()
+        }
+    }
+
+    fn skip_value(&mut self) -> Result<(), String> {
+        self.skip_value_end().map(|_| ())
+    }
+
+    /// Skip a value, returning where its last token ends
+    fn skip_value_end(&mut self) -> Result<usize, String> {
+        let mut depth = 0usize;
+        let mut end = self.pos;
+        loop {
+            self.skip_whitespace();
+            let Some(byte) = self.peek() else {
+                return Err(self.error("unexpected end of file"));
+            };
+            match byte {
+                b'(' | b'[' | b'{' => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                b')' | b']' | b'}' | b',' if depth == 0 => break,
+                b')' | b']' | b'}' => {
+                    depth -= 1;
+                    self.pos += 1;
+                }
+                b'"' => {
+                    self.string()?;
+                }
+                b'\'' => self.skip_char()?,
+                b'r' if self.is_raw_string() => {
+                    self.raw_string()?;
+                }
+                byte if is_ident_start(byte) || byte.is_ascii_digit() => {
+                    while self.peek_raw().is_some_and(|byte| {
+                        is_ident_continue(byte) || byte == b'.' || byte == b'+' || byte == b'-'
+                    }) {
+                        self.pos += 1;
+                    }
+                }
+                _ => self.pos += 1,
+            }
+            end = self.pos;
+            if depth == 0 && self.value_complete() {
+                break;
+            }
+        }
+        Ok(end)
+    }
+
+    /// Whether the value so far is followed by the end of its container or
+    /// the next entry, rather than more of the value (`Some (`, `1 .0`)
+    fn value_complete(&mut self) -> bool {
+        let pos = self.pos;
+        self.skip_whitespace();
+        let complete = matches!(self.peek(), None | Some(b')' | b']' | b'}' | b',' | b':'));
+        self.pos = pos;
+        complete
+    }
+
+    /// Consume the `,` after an entry, unless `close` ends the container
+    fn separator(&mut self, close: u8) -> Result<(), String> {
+        if self.consume(b',') || self.peek() == Some(close) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `,` or `{}`", close as char)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<&'a str, String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        if !self.peek_raw().is_some_and(is_ident_start) {
+            return Err(self.error("expected an identifier"));
+        }
+        while self.peek_raw().is_some_and(is_ident_continue) {
+            self.pos += 1;
+        }
+        Ok(&self.text[start..self.pos])
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        if self.is_raw_string() {
+            return self.raw_string();
+        }
+        self.expect(b'"')?;
+        let mut value = String::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += offset + 1;
+                    return Ok(value);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, 'r')) => value.push('\r'),
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break,
+                },
+                c => value.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn is_raw_string(&self) -> bool {
+        let rest = &self.text.as_bytes()[self.pos..];
+        rest.first() == Some(&b'r') && matches!(rest.get(1), Some(b'"' | b'#'))
+    }
+
+    fn raw_string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let hashes = self.text[self.pos..]
+            .bytes()
+            .take_while(|&byte| byte == b'#')
+            .count();
+        self.pos += hashes;
+        self.expect(b'"')?;
+        let terminator = format!("\"{}", "#".repeat(hashes));
+        let length = self.text[self.pos..]
+            .find(&terminator)
+            .ok_or_else(|| self.error("unterminated raw string"))?;
+        let value = self.text[self.pos..self.pos + length].to_string();
+        self.pos += length + terminator.len();
+        Ok(value)
+    }
+
+    fn skip_char(&mut self) -> Result<(), String> {
+        self.pos += 1;
+        let mut escaped = false;
+        for (offset, c) in self.text[self.pos..].char_indices() {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '\'' if !escaped => {
+                    self.pos += offset + 1;
+                    return Ok(());
+                }
+                _ => escaped = false,
+            }
+        }
+        Err(self.error("unterminated character"))
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = &self.text[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                self.skip_block_comment();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Block comments nest in RON
+    fn skip_block_comment(&mut self) {
+        let mut depth = 0usize;
+        while self.pos < self.text.len() {
+            let rest = &self.text[self.pos..];
+            if rest.starts_with("/*") {
+                depth += 1;
+                self.pos += 2;
+            } else if rest.starts_with("*/") {
+                depth -= 1;
+                self.pos += 2;
+                if depth == 0 {
+                    return;
+                }
+            } else {
+                self.pos += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.peek_raw()
+    }
+
+    fn peek_raw(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn consume(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.consume(byte) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", byte as char)))
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = self.text[..self.pos].matches('\n').count() + 1;
+        format!("{message} on line {line} of the scene")
+    }
+}
+
+fn is_ident_start(byte: u8) -> bool {
+    byte.is_ascii_alphabetic() || byte == b'_'
+}
+
+fn is_ident_continue(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENE: &str = r##"(
+  resources: {},
+  entities: {
+    4294967296: (
+      components: {
+        "bevy_transform::components::transform::Transform": (
+          translation: (1.0, 2.0, 0.0),
+          rotation: (0.0, 0.0, 0.0, 1.0),
+          scale: (1.0, 1.0, 1.0),
+        ),
+        "game::Health": (
+          current: 10.0,
+          // "}" inside comments and strings doesn't end the map
+          label: "}, (",
+        ),
+        "game::Team": Custom(name: r#"Red"#, tint: Some((1.0, 0.5))),
+        "bevy_ecs::name::Name": "Player",
+      },
+    ),
+    4294967297: (
+      components: {
+        "bevy_ecs::name::Name": "Camera",
+      },
+    ),
+  },
+)"##;
+
+    fn is_known(type_path: &str) -> bool {
+        !type_path.starts_with("game::")
+    }
+
+    #[test]
+    fn unknown_components_are_collected_and_restored() {
+        let collected = collect_game_components(SCENE, is_known).unwrap();
+        assert!(!collected.contains("\"game::Health\":"), "{collected}");
+        assert!(collected.contains(GameComponents::type_path()));
+
+        let maps = Scanner::new(&collected).scene().unwrap();
+        let entry = maps[0]
+            .entries
+            .iter()
+            .find(|entry| entry.type_path == GameComponents::type_path())
+            .unwrap();
+        let components: GameComponents = ron::from_str(&entry.value).unwrap();
+        assert_eq!(components.0.len(), 2);
+        assert_eq!(components.0[0].type_path, "game::Health");
+        assert!(components.0[0].ron.starts_with("(\n  current: 10.0,"));
+        assert_eq!(
+            components.0[1].ron,
+            r##"Custom(name: r#"Red"#, tint: Some((1.0, 0.5)))"##
+        );
+
+        // Entities without unknown components are left alone
+        assert!(collected.contains("\"bevy_ecs::name::Name\": \"Camera\",\n      },"));
+
+        assert_eq!(expand_game_components(&collected).unwrap(), SCENE);
+    }
+
+    #[test]
+    fn scenes_without_unknown_components_are_unchanged() {
+        let scene = "(resources: {}, entities: {})";
+        assert_eq!(collect_game_components(scene, is_known).unwrap(), scene);
+        assert_eq!(expand_game_components(SCENE).unwrap(), SCENE);
+    }
+
+    #[test]
+    fn malformed_scenes_report_the_line() {
+        let error =
+            collect_game_components("(\n  entities: {\n    0: (components: {\"a\" 1})", is_known)
+                .unwrap_err();
+        assert!(error.to_string().contains("line 3"), "{error}");
+    }
+}
@@ -1,8 +1,12 @@
 use super::math::Vector2;
+use bevy::asset::RenderAssetUsages;
+use bevy::image::Image;
+use bevy::render::render_resource::{Extent3d, TextureDimension};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-/// Collision shape types - Tiled-style per-tile collision shapes
+/// Collision shape types - Tiled-style per-tile collision shapes.
+/// Coordinates are tile-local pixels: origin at the tile's top-left, y down.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CollisionShape {
     Rectangle {
@@ -46,7 +50,9 @@ pub struct TilesetData {
     pub tile_height: u32,
     pub columns: u32,
     pub rows: u32,
+    /// Gap in pixels between neighbouring tiles
     pub spacing: u32,
+    /// Margin in pixels around the edge of the image
     pub padding: u32,
     pub collision_data: HashMap<u32, TileCollisionData>,
 }
@@ -68,6 +74,62 @@ impl Default for TilesetData {
     }
 }
 
+impl TilesetData {
+    /// Texture path relative to the assets folder with forward slashes.
+    /// Stays the same across sessions, unlike the runtime tileset id.
+    pub fn relative_texture_path(&self) -> String {
+        // Everything after "assets/" (or "assets\"); paths outside it are kept as-is
+        match self.texture_path.find("assets") {
+            Some(idx) => self.texture_path[idx + 7..].replace('\\', "/"),
+            None => self.texture_path.clone(),
+        }
+    }
+
+    /// Columns and rows of tiles that fit in an image of the given size,
+    /// taking spacing and padding into account
+    pub fn grid_for_image(&self, image_width: u32, image_height: u32) -> (u32, u32) {
+        (
+            fit_tiles(image_width, self.tile_width, self.spacing, self.padding).0,
+            fit_tiles(image_height, self.tile_height, self.spacing, self.padding).0,
+        )
+    }
+
+    /// Pixels left over on each axis after fitting the tile grid into the image.
+    /// Non-zero values usually mean the tile size, spacing or padding is wrong.
+    pub fn leftover_pixels(&self, image_width: u32, image_height: u32) -> (u32, u32) {
+        (
+            fit_tiles(image_width, self.tile_width, self.spacing, self.padding).1,
+            fit_tiles(image_height, self.tile_height, self.spacing, self.padding).1,
+        )
+    }
+
+    /// Pixel rectangle `(x, y, width, height)` of a tile within the tileset image
+    pub fn tile_rect(&self, tile_id: u32) -> Option<(u32, u32, u32, u32)> {
+        if self.columns == 0 || tile_id >= self.columns * self.rows {
+            return None;
+        }
+        let col = tile_id % self.columns;
+        let row = tile_id / self.columns;
+        Some((
+            self.padding + col * (self.tile_width + self.spacing),
+            self.padding + row * (self.tile_height + self.spacing),
+            self.tile_width,
+            self.tile_height,
+        ))
+    }
+}
+
+/// Number of tiles fitting along one image axis, and the pixels left over
+fn fit_tiles(length: u32, tile_size: u32, spacing: u32, padding: u32) -> (u32, u32) {
+    let usable = length.saturating_sub(padding * 2);
+    if tile_size == 0 || usable < tile_size {
+        return (0, usable);
+    }
+    let stride = tile_size + spacing;
+    let count = (usable + spacing) / stride;
+    (count, usable - (count * stride - spacing))
+}
+
 /// Layer type - matches LDTk layer types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum LayerType {
@@ -98,6 +160,41 @@ impl LayerType {
     }
 }
 
+/// Grid orientation of a tile map - matches the map types of bevy_ecs_tilemap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MapOrientation {
+    #[default]
+    Square,
+    /// Isometric grid with rows and columns running diagonally
+    IsometricDiamond,
+    /// Isometric grid where every other row is shifted by half a tile
+    IsometricStaggered,
+    /// Pointy-top hexagons, odd rows shifted by half a tile
+    HexRow,
+    /// Flat-top hexagons, odd columns shifted by half a tile
+    HexColumn,
+}
+
+impl MapOrientation {
+    pub const ALL: [MapOrientation; 5] = [
+        MapOrientation::Square,
+        MapOrientation::IsometricDiamond,
+        MapOrientation::IsometricStaggered,
+        MapOrientation::HexRow,
+        MapOrientation::HexColumn,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MapOrientation::Square => "Square",
+            MapOrientation::IsometricDiamond => "Isometric (Diamond)",
+            MapOrientation::IsometricStaggered => "Isometric (Staggered)",
+            MapOrientation::HexRow => "Hexagonal (Row)",
+            MapOrientation::HexColumn => "Hexagonal (Column)",
+        }
+    }
+}
+
 /// Layer metadata - describes a single layer
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LayerMetadata {
@@ -134,16 +231,84 @@ impl Default for LayerMetadata {
     }
 }
 
+/// Tile reference qualified by the tileset it belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TileRef {
+    pub tileset_id: u32,
+    pub tile_id: u32,
+}
+
+impl TileRef {
+    pub fn new(tileset_id: u32, tile_id: u32) -> Self {
+        Self {
+            tileset_id,
+            tile_id,
+        }
+    }
+}
+
 /// Tile data - individual tile placement
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TileData {
     pub x: u32,
     pub y: u32,
+    /// Tileset the tile comes from (older files without it use tileset 0)
+    #[serde(default)]
+    pub tileset_id: u32,
     pub tile_id: u32,
     pub flip_x: bool,
     pub flip_y: bool,
 }
 
+impl TileData {
+    pub fn tile_ref(&self) -> TileRef {
+        TileRef::new(self.tileset_id, self.tile_id)
+    }
+}
+
+/// Tile id with a relative weight, used by random variation brushes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedTile {
+    pub tileset_id: u32,
+    pub tile_id: u32,
+    pub weight: f32,
+}
+
+impl WeightedTile {
+    pub fn new(tile: TileRef, weight: f32) -> Self {
+        Self {
+            tileset_id: tile.tileset_id,
+            tile_id: tile.tile_id,
+            weight,
+        }
+    }
+
+    pub fn tile_ref(&self) -> TileRef {
+        TileRef::new(self.tileset_id, self.tile_id)
+    }
+}
+
+/// Weighted variant of a saved brush preset. The tileset is referenced by its
+/// texture path relative to the assets folder, since runtime tileset ids
+/// depend on load order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresetTile {
+    #[serde(default)]
+    pub tileset: String,
+    pub tile_id: u32,
+    pub weight: f32,
+}
+
+/// Named variation brush saved with the project (e.g. "Grass variants")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BrushPreset {
+    pub name: String,
+    pub tiles: Vec<PresetTile>,
+    /// Fixed RNG seed so strokes are reproducible; `None` picks a fresh seed
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
 /// Complete layer data including tiles and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LayerData {
@@ -165,3 +330,38 @@ impl LayerData {
         self
     }
 }
+
+/// Copy of a tileset image without the first `margin` columns and rows.
+/// bevy_ecs_tilemap supports spacing but no outer margin, so tilesets with
+/// padding are rendered from this copy. `None` for unsupported formats.
+pub fn crop_tileset_margin(image: &Image, margin: u32) -> Option<Image> {
+    let data = image.data.as_ref()?;
+    let format = image.texture_descriptor.format;
+    let pixel_size = format.block_copy_size(None)? as usize;
+    let width = image.width();
+    let height = image.height();
+    if margin >= width || margin >= height {
+        return None;
+    }
+
+    let row_bytes = width as usize * pixel_size;
+    let skip_bytes = margin as usize * pixel_size;
+    let mut cropped = Vec::with_capacity((height - margin) as usize * (row_bytes - skip_bytes));
+    for row in data.chunks_exact(row_bytes).skip(margin as usize) {
+        cropped.extend_from_slice(&row[skip_bytes..]);
+    }
+
+    let mut result = Image::new(
+        Extent3d {
+            width: width - margin,
+            height: height - margin,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        cropped,
+        format,
+        RenderAssetUsages::default(),
+    );
+    result.sampler = image.sampler.clone();
+    Some(result)
+}
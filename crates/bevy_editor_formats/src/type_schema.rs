@@ -0,0 +1,1062 @@
+//! Reflected types exported by a game for the editor
+//!
+//! The editor doesn't link against game code. Instead the game's
+//! `bevy_editor_runtime` writes its type registry to a [`TypeSchemaFile`], and
+//! the editor reads and writes values of those types in the scene RON format
+//! through [`TypeSchemas`], as dynamic reflect values. Components of types the
+//! editor doesn't know are kept in [`GameComponents`].
+
+use bevy::prelude::*;
+use bevy::reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
+use bevy::reflect::{
+    attributes::CustomAttributes, DynamicArray, DynamicEnum, DynamicList, DynamicMap, DynamicSet,
+    DynamicStruct, DynamicTuple, DynamicTupleStruct, DynamicVariant, Map, PartialReflect,
+    ReflectRef, TypeInfo, TypeRegistration, TypeRegistry, VariantInfo,
+};
+use serde::de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::ser::{
+    self, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Current version of the [`TypeSchemaFile`] format
+pub const TYPE_SCHEMA_FORMAT_VERSION: u32 = 1;
+
+/// Environment variable telling `bevy_editor_runtime` where to write the
+/// game's [`TypeSchemaFile`]
+pub const EXPORT_TYPES_ENV_VAR: &str = "BEVY_EDITOR_EXPORT_TYPES";
+
+/// Where the editor has the game export its types, relative to the project root
+pub const TYPE_SCHEMA_FILE: &str = ".bevy_editor/types.json";
+
+/// Type path of `bevy_editor_core::EditorCategory`, read from the attributes
+/// of exported types without depending on the editor
+const EDITOR_CATEGORY_PATH: &str = "bevy_editor_core::attributes::EditorCategory";
+
+/// The reflected types of a game
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TypeSchemaFile {
+    pub format_version: u32,
+    pub types: Vec<TypeSchema>,
+}
+
+impl TypeSchemaFile {
+    /// Describe every type in `registry`
+    pub fn from_registry(registry: &TypeRegistry) -> Self {
+        let mut types: Vec<TypeSchema> = registry
+            .iter()
+            .map(|registration| TypeSchema::from_registration(registration, registry))
+            .collect();
+        types.sort_by(|a, b| a.type_path.cmp(&b.type_path));
+        Self {
+            format_version: TYPE_SCHEMA_FORMAT_VERSION,
+            types,
+        }
+    }
+
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let json = std::fs::read_to_string(path)?;
+        let file: TypeSchemaFile = serde_json::from_str(&json)?;
+        if file.format_version > TYPE_SCHEMA_FORMAT_VERSION {
+            return Err(format!(
+                "type schema format version {} is newer than supported version {}",
+                file.format_version, TYPE_SCHEMA_FORMAT_VERSION
+            )
+            .into());
+        }
+        Ok(file)
+    }
+
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// Description of one reflected type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeSchema {
+    pub type_path: String,
+    pub short_path: String,
+    #[serde(default)]
+    pub module_path: Option<String>,
+    /// The type's `EditorCategory` attribute
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Whether the type is registered as a component
+    #[serde(default)]
+    pub component: bool,
+    /// Default value in scene RON, for types registered with `ReflectDefault`
+    #[serde(default)]
+    pub default: Option<String>,
+    pub kind: TypeSchemaKind,
+}
+
+impl TypeSchema {
+    pub fn from_registration(registration: &TypeRegistration, registry: &TypeRegistry) -> Self {
+        let info = registration.type_info();
+        let table = info.type_path_table();
+        let default = registration.data::<ReflectDefault>().and_then(|default| {
+            let value = default.default();
+            bevy::scene::serialize_ron(TypedReflectSerializer::new(
+                value.as_partial_reflect(),
+                registry,
+            ))
+            .ok()
+        });
+
+        Self {
+            type_path: table.path().to_string(),
+            short_path: table.short_path().to_string(),
+            module_path: table.module_path().map(str::to_string),
+            category: custom_attributes(info).and_then(editor_category),
+            component: registration.data::<ReflectComponent>().is_some(),
+            default,
+            kind: TypeSchemaKind::from_type_info(info),
+        }
+    }
+
+    /// Whether values are written as `None` / `Some(..)`, like `Option<T>`
+    fn is_option(&self) -> bool {
+        self.type_path.starts_with("core::option::Option<")
+    }
+}
+
+fn custom_attributes(info: &TypeInfo) -> Option<&CustomAttributes> {
+    match info {
+        TypeInfo::Struct(info) => Some(info.custom_attributes()),
+        TypeInfo::TupleStruct(info) => Some(info.custom_attributes()),
+        TypeInfo::Enum(info) => Some(info.custom_attributes()),
+        _ => None,
+    }
+}
+
+fn editor_category(attributes: &CustomAttributes) -> Option<String> {
+    attributes.iter().find_map(|(_, attribute)| {
+        if attribute.reflect_type_path() != EDITOR_CATEGORY_PATH {
+            return None;
+        }
+        let ReflectRef::TupleStruct(category) = attribute.reflect_ref() else {
+            return None;
+        };
+        category
+            .field(0)?
+            .try_downcast_ref::<&'static str>()
+            .map(|category| category.to_string())
+    })
+}
+
+/// Shape of a [`TypeSchema`], naming other types by type path
+///
+/// `TupleStruct` and `Tuple` list their field types; `Opaque` types can only
+/// be read and written when the editor registers them itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum TypeSchemaKind {
+    Struct { fields: Vec<FieldSchema> },
+    TupleStruct { fields: Vec<String> },
+    Tuple { fields: Vec<String> },
+    List { item: String },
+    Array { item: String, len: usize },
+    Set { item: String },
+    Map { key: String, value: String },
+    Enum { variants: Vec<VariantSchema> },
+    Opaque,
+}
+
+impl TypeSchemaKind {
+    fn from_type_info(info: &TypeInfo) -> Self {
+        match info {
+            TypeInfo::Struct(info) => Self::Struct {
+                fields: info
+                    .iter()
+                    .map(|field| FieldSchema::new(field.name(), field.type_path()))
+                    .collect(),
+            },
+            TypeInfo::TupleStruct(info) => Self::TupleStruct {
+                fields: type_paths(info.iter().map(|field| field.type_path())),
+            },
+            TypeInfo::Tuple(info) => Self::Tuple {
+                fields: type_paths(info.iter().map(|field| field.type_path())),
+            },
+            TypeInfo::List(info) => Self::List {
+                item: info.item_ty().path().to_string(),
+            },
+            TypeInfo::Array(info) => Self::Array {
+                item: info.item_ty().path().to_string(),
+                len: info.capacity(),
+            },
+            TypeInfo::Set(info) => Self::Set {
+                item: info.value_ty().path().to_string(),
+            },
+            TypeInfo::Map(info) => Self::Map {
+                key: info.key_ty().path().to_string(),
+                value: info.value_ty().path().to_string(),
+            },
+            TypeInfo::Enum(info) => Self::Enum {
+                variants: info
+                    .iter()
+                    .map(|variant| VariantSchema {
+                        name: variant.name().to_string(),
+                        kind: match variant {
+                            VariantInfo::Unit(_) => VariantSchemaKind::Unit,
+                            VariantInfo::Tuple(variant) => VariantSchemaKind::Tuple {
+                                fields: type_paths(variant.iter().map(|field| field.type_path())),
+                            },
+                            VariantInfo::Struct(variant) => VariantSchemaKind::Struct {
+                                fields: variant
+                                    .iter()
+                                    .map(|field| FieldSchema::new(field.name(), field.type_path()))
+                                    .collect(),
+                            },
+                        },
+                    })
+                    .collect(),
+            },
+            TypeInfo::Opaque(_) => Self::Opaque,
+        }
+    }
+}
+
+fn type_paths<'a>(fields: impl Iterator<Item = &'a str>) -> Vec<String> {
+    fields.map(str::to_string).collect()
+}
+
+/// Named field of a struct or struct variant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldSchema {
+    pub name: String,
+    pub type_path: String,
+}
+
+impl FieldSchema {
+    fn new(name: &str, type_path: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            type_path: type_path.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariantSchema {
+    pub name: String,
+    pub kind: VariantSchemaKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum VariantSchemaKind {
+    Unit,
+    Tuple { fields: Vec<String> },
+    Struct { fields: Vec<FieldSchema> },
+}
+
+/// Schemas of a game's types by type path, to read and write their values
+#[derive(Debug, Clone, Default)]
+pub struct TypeSchemas {
+    types: HashMap<String, TypeSchema>,
+}
+
+impl From<TypeSchemaFile> for TypeSchemas {
+    fn from(file: TypeSchemaFile) -> Self {
+        Self {
+            types: file
+                .types
+                .into_iter()
+                .map(|schema| (schema.type_path.clone(), schema))
+                .collect(),
+        }
+    }
+}
+
+impl TypeSchemas {
+    pub fn get(&self, type_path: &str) -> Option<&TypeSchema> {
+        self.types.get(type_path)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TypeSchema> {
+        self.types.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Read a value of `type_path` from scene RON. Types registered in
+    /// `registry` are read through it, the others through their schema.
+    pub fn read_value(
+        &self,
+        type_path: &str,
+        ron: &str,
+        registry: &TypeRegistry,
+    ) -> Result<Box<dyn PartialReflect>, ron::error::SpannedError> {
+        ron::Options::default().from_str_seed(
+            ron,
+            ValueSeed {
+                schemas: self,
+                registry,
+                type_path,
+            },
+        )
+    }
+
+    /// Write a value read by [`Self::read_value`] back to scene RON
+    pub fn write_value(
+        &self,
+        type_path: &str,
+        value: &dyn PartialReflect,
+        registry: &TypeRegistry,
+    ) -> Result<String, ron::Error> {
+        bevy::scene::serialize_ron(ValueSerializer {
+            schemas: self,
+            registry,
+            type_path,
+            value,
+        })
+    }
+
+    /// Read a value of `type_path` from reflected JSON, as sent by the remote
+    /// protocol
+    pub fn read_json_value(
+        &self,
+        type_path: &str,
+        json: &serde_json::Value,
+        registry: &TypeRegistry,
+    ) -> Result<Box<dyn PartialReflect>, serde_json::Error> {
+        ValueSeed {
+            schemas: self,
+            registry,
+            type_path,
+        }
+        .deserialize(json)
+    }
+
+    /// Write a value read by [`Self::read_json_value`] back to reflected JSON
+    pub fn write_json_value(
+        &self,
+        type_path: &str,
+        value: &dyn PartialReflect,
+        registry: &TypeRegistry,
+    ) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::to_value(ValueSerializer {
+            schemas: self,
+            registry,
+            type_path,
+            value,
+        })
+    }
+}
+
+/// Serializers take names as `&'static str`, but schema names are loaded at
+/// runtime. Each distinct name is leaked once.
+fn intern(name: &str) -> &'static str {
+    static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+    let mut names = NAMES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(interned) = names.get(name) {
+        return interned;
+    }
+    let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+    names.insert(interned);
+    interned
+}
+
+#[derive(Clone, Copy)]
+struct ValueSeed<'a> {
+    schemas: &'a TypeSchemas,
+    registry: &'a TypeRegistry,
+    type_path: &'a str,
+}
+
+impl<'a> ValueSeed<'a> {
+    fn with_type(self, type_path: &'a str) -> Self {
+        Self { type_path, ..self }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for ValueSeed<'_> {
+    type Value = Box<dyn PartialReflect>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        if let Some(registration) = self.registry.get_with_type_path(self.type_path) {
+            return TypedReflectDeserializer::new(registration, self.registry)
+                .deserialize(deserializer);
+        }
+        let schema = self
+            .schemas
+            .get(self.type_path)
+            .ok_or_else(|| de::Error::custom(format_args!("unknown type `{}`", self.type_path)))?;
+
+        Ok(match &schema.kind {
+            TypeSchemaKind::Struct { fields } => Box::new(deserializer.deserialize_struct(
+                "",
+                &[],
+                StructVisitor { seed: self, fields },
+            )?),
+            TypeSchemaKind::TupleStruct { fields } if fields.len() == 1 => {
+                let value = deserializer
+                    .deserialize_newtype_struct("", NewtypeVisitor(self.with_type(&fields[0])))?;
+                Box::new(DynamicTupleStruct::from_iter([value]))
+            }
+            TypeSchemaKind::TupleStruct { fields } => {
+                let values = deserializer.deserialize_tuple_struct(
+                    "",
+                    fields.len(),
+                    ValuesVisitor::each(self, fields),
+                )?;
+                Box::new(DynamicTupleStruct::from_iter(values))
+            }
+            TypeSchemaKind::Tuple { fields } => {
+                let values = deserializer
+                    .deserialize_tuple(fields.len(), ValuesVisitor::each(self, fields))?;
+                Box::new(DynamicTuple::from_iter(values))
+            }
+            TypeSchemaKind::List { item } => {
+                let values = deserializer.deserialize_seq(ValuesVisitor::all(self, item))?;
+                Box::new(DynamicList::from_iter(values))
+            }
+            TypeSchemaKind::Array { item, len } => {
+                let values =
+                    deserializer.deserialize_tuple(*len, ValuesVisitor::all(self, item))?;
+                Box::new(DynamicArray::from_iter(values))
+            }
+            TypeSchemaKind::Set { item } => {
+                let values = deserializer.deserialize_seq(ValuesVisitor::all(self, item))?;
+                Box::new(DynamicSet::from_iter(values))
+            }
+            TypeSchemaKind::Map { key, value } => {
+                Box::new(deserializer.deserialize_map(MapVisitor {
+                    key: self.with_type(key),
+                    value: self.with_type(value),
+                })?)
+            }
+            TypeSchemaKind::Enum { variants } if schema.is_option() => {
+                let some = variants
+                    .iter()
+                    .find_map(|variant| match &variant.kind {
+                        VariantSchemaKind::Tuple { fields } if variant.name == "Some" => {
+                            fields.first()
+                        }
+                        _ => None,
+                    })
+                    .ok_or_else(|| de::Error::custom("option without a `Some` variant"))?;
+                Box::new(deserializer.deserialize_option(OptionVisitor(self.with_type(some)))?)
+            }
+            TypeSchemaKind::Enum { variants } => Box::new(deserializer.deserialize_enum(
+                "",
+                &[],
+                EnumVisitor {
+                    seed: self,
+                    variants,
+                },
+            )?),
+            TypeSchemaKind::Opaque => {
+                return Err(de::Error::custom(format_args!(
+                    "`{}` can't be read without the game's code",
+                    self.type_path
+                )))
+            }
+        })
+    }
+}
+
+/// Field or variant name
+struct Ident(String);
+
+impl<'de> Deserialize<'de> for Ident {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IdentVisitor;
+
+        impl Visitor<'_> for IdentVisitor {
+            type Value = Ident;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an identifier")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(Ident(value.to_string()))
+            }
+        }
+
+        deserializer.deserialize_identifier(IdentVisitor)
+    }
+}
+
+struct StructVisitor<'a> {
+    seed: ValueSeed<'a>,
+    fields: &'a [FieldSchema],
+}
+
+impl<'de> Visitor<'de> for StructVisitor<'_> {
+    type Value = DynamicStruct;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "struct `{}`", self.seed.type_path)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut value = DynamicStruct::default();
+        while let Some(Ident(name)) = map.next_key()? {
+            let field = self
+                .fields
+                .iter()
+                .find(|field| field.name == name)
+                .ok_or_else(|| {
+                    de::Error::custom(format_args!(
+                        "`{}` has no field `{name}`",
+                        self.seed.type_path
+                    ))
+                })?;
+            let field_value = map.next_value_seed(self.seed.with_type(&field.type_path))?;
+            value.insert_boxed(name, field_value);
+        }
+        Ok(value)
+    }
+}
+
+/// Reads a sequence whose items either each have their own type (tuples) or
+/// all share one (lists)
+struct ValuesVisitor<'a> {
+    seed: ValueSeed<'a>,
+    each: Option<&'a [String]>,
+}
+
+impl<'a> ValuesVisitor<'a> {
+    fn each(seed: ValueSeed<'a>, types: &'a [String]) -> Self {
+        Self {
+            seed,
+            each: Some(types),
+        }
+    }
+
+    fn all(seed: ValueSeed<'a>, item: &'a str) -> Self {
+        Self {
+            seed: seed.with_type(item),
+            each: None,
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for ValuesVisitor<'_> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of `{}`", self.seed.type_path)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::new();
+        loop {
+            let seed = match self.each {
+                Some(types) => match types.get(values.len()) {
+                    Some(type_path) => self.seed.with_type(type_path),
+                    None => break,
+                },
+                None => self.seed,
+            };
+            match seq.next_element_seed(seed)? {
+                Some(value) => values.push(value),
+                None => break,
+            }
+        }
+        if let Some(types) = self.each {
+            if values.len() != types.len() {
+                return Err(de::Error::invalid_length(values.len(), &self));
+            }
+        }
+        Ok(values)
+    }
+}
+
+struct MapVisitor<'a> {
+    key: ValueSeed<'a>,
+    value: ValueSeed<'a>,
+}
+
+impl<'de> Visitor<'de> for MapVisitor<'_> {
+    type Value = DynamicMap;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a map of `{}` to `{}`",
+            self.key.type_path, self.value.type_path
+        )
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut value = DynamicMap::default();
+        while let Some(key) = map.next_key_seed(self.key)? {
+            value.insert_boxed(key, map.next_value_seed(self.value)?);
+        }
+        Ok(value)
+    }
+}
+
+struct NewtypeVisitor<'a>(ValueSeed<'a>);
+
+impl<'de> Visitor<'de> for NewtypeVisitor<'_> {
+    type Value = Box<dyn PartialReflect>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a tuple struct of `{}`", self.0.type_path)
+    }
+
+    fn visit_newtype_struct<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        self.0.deserialize(deserializer)
+    }
+}
+
+struct OptionVisitor<'a>(ValueSeed<'a>);
+
+impl<'de> Visitor<'de> for OptionVisitor<'_> {
+    type Value = DynamicEnum;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an optional `{}`", self.0.type_path)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(DynamicEnum::new("None", DynamicVariant::Unit))
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        let value = self.0.deserialize(deserializer)?;
+        Ok(DynamicEnum::new(
+            "Some",
+            DynamicVariant::Tuple(DynamicTuple::from_iter([value])),
+        ))
+    }
+}
+
+struct EnumVisitor<'a> {
+    seed: ValueSeed<'a>,
+    variants: &'a [VariantSchema],
+}
+
+impl<'de> Visitor<'de> for EnumVisitor<'_> {
+    type Value = DynamicEnum;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "enum `{}`", self.seed.type_path)
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (Ident(name), access) = data.variant()?;
+        let variant = self
+            .variants
+            .iter()
+            .find(|variant| variant.name == name)
+            .ok_or_else(|| {
+                de::Error::custom(format_args!(
+                    "`{}` has no variant `{name}`",
+                    self.seed.type_path
+                ))
+            })?;
+        let value = match &variant.kind {
+            VariantSchemaKind::Unit => {
+                access.unit_variant()?;
+                DynamicVariant::Unit
+            }
+            VariantSchemaKind::Tuple { fields } if fields.len() == 1 => {
+                let value = access.newtype_variant_seed(self.seed.with_type(&fields[0]))?;
+                DynamicVariant::Tuple(DynamicTuple::from_iter([value]))
+            }
+            VariantSchemaKind::Tuple { fields } => {
+                let values =
+                    access.tuple_variant(fields.len(), ValuesVisitor::each(self.seed, fields))?;
+                DynamicVariant::Tuple(DynamicTuple::from_iter(values))
+            }
+            VariantSchemaKind::Struct { fields } => DynamicVariant::Struct(access.struct_variant(
+                &[],
+                StructVisitor {
+                    seed: self.seed,
+                    fields,
+                },
+            )?),
+        };
+        Ok(DynamicEnum::new(name, value))
+    }
+}
+
+struct ValueSerializer<'a> {
+    schemas: &'a TypeSchemas,
+    registry: &'a TypeRegistry,
+    type_path: &'a str,
+    value: &'a dyn PartialReflect,
+}
+
+impl<'a> ValueSerializer<'a> {
+    fn child(&self, type_path: &'a str, value: &'a dyn PartialReflect) -> Self {
+        Self {
+            schemas: self.schemas,
+            registry: self.registry,
+            type_path,
+            value,
+        }
+    }
+
+    fn mismatch<E: ser::Error>(&self) -> E {
+        E::custom(format_args!(
+            "value of kind {:?} doesn't match `{}`",
+            self.value.reflect_kind(),
+            self.type_path
+        ))
+    }
+}
+
+impl Serialize for ValueSerializer<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.registry.get_with_type_path(self.type_path).is_some() {
+            return TypedReflectSerializer::new(self.value, self.registry).serialize(serializer);
+        }
+        let schema = self
+            .schemas
+            .get(self.type_path)
+            .ok_or_else(|| ser::Error::custom(format_args!("unknown type `{}`", self.type_path)))?;
+
+        match (&schema.kind, self.value.reflect_ref()) {
+            (TypeSchemaKind::Struct { fields }, ReflectRef::Struct(value)) => {
+                let mut state = serializer.serialize_struct("", value.field_len())?;
+                for field in fields {
+                    if let Some(field_value) = value.field(&field.name) {
+                        state.serialize_field(
+                            intern(&field.name),
+                            &self.child(&field.type_path, field_value),
+                        )?;
+                    }
+                }
+                state.end()
+            }
+            (TypeSchemaKind::TupleStruct { fields }, ReflectRef::TupleStruct(value))
+                if fields.len() == 1 =>
+            {
+                let field = value.field(0).ok_or_else(|| self.mismatch())?;
+                serializer.serialize_newtype_struct("", &self.child(&fields[0], field))
+            }
+            (TypeSchemaKind::TupleStruct { fields }, ReflectRef::TupleStruct(value)) => {
+                let mut state = serializer.serialize_tuple_struct("", fields.len())?;
+                for (index, type_path) in fields.iter().enumerate() {
+                    let field = value.field(index).ok_or_else(|| self.mismatch())?;
+                    state.serialize_field(&self.child(type_path, field))?;
+                }
+                state.end()
+            }
+            (TypeSchemaKind::Tuple { fields }, ReflectRef::Tuple(value)) => {
+                let mut state = serializer.serialize_tuple(fields.len())?;
+                for (index, type_path) in fields.iter().enumerate() {
+                    let field = value.field(index).ok_or_else(|| self.mismatch())?;
+                    state.serialize_element(&self.child(type_path, field))?;
+                }
+                state.end()
+            }
+            (TypeSchemaKind::List { item }, ReflectRef::List(value)) => {
+                let mut state = serializer.serialize_seq(Some(value.len()))?;
+                for element in value.iter() {
+                    state.serialize_element(&self.child(item, element))?;
+                }
+                state.end()
+            }
+            (TypeSchemaKind::Array { item, .. }, ReflectRef::Array(value)) => {
+                let mut state = serializer.serialize_tuple(value.len())?;
+                for element in value.iter() {
+                    state.serialize_element(&self.child(item, element))?;
+                }
+                state.end()
+            }
+            (TypeSchemaKind::Set { item }, ReflectRef::Set(value)) => {
+                let mut state = serializer.serialize_seq(Some(value.len()))?;
+                for element in value.iter() {
+                    state.serialize_element(&self.child(item, element))?;
+                }
+                state.end()
+            }
+            (
+                TypeSchemaKind::Map {
+                    key,
+                    value: value_type,
+                },
+                ReflectRef::Map(value),
+            ) => {
+                let mut state = serializer.serialize_map(Some(value.len()))?;
+                for (entry_key, entry_value) in value.iter() {
+                    state.serialize_entry(
+                        &self.child(key, entry_key),
+                        &self.child(value_type, entry_value),
+                    )?;
+                }
+                state.end()
+            }
+            (TypeSchemaKind::Enum { variants }, ReflectRef::Enum(value)) => {
+                let name = value.variant_name();
+                let (index, variant) = variants
+                    .iter()
+                    .enumerate()
+                    .find(|(_, variant)| variant.name == name)
+                    .ok_or_else(|| self.mismatch())?;
+                let index = index as u32;
+                match &variant.kind {
+                    VariantSchemaKind::Unit if schema.is_option() => serializer.serialize_none(),
+                    VariantSchemaKind::Unit => {
+                        serializer.serialize_unit_variant("", index, intern(name))
+                    }
+                    VariantSchemaKind::Tuple { fields } if fields.len() == 1 => {
+                        let field = value.field_at(0).ok_or_else(|| self.mismatch())?;
+                        let field = self.child(&fields[0], field);
+                        if schema.is_option() {
+                            serializer.serialize_some(&field)
+                        } else {
+                            serializer.serialize_newtype_variant("", index, intern(name), &field)
+                        }
+                    }
+                    VariantSchemaKind::Tuple { fields } => {
+                        let mut state = serializer.serialize_tuple_variant(
+                            "",
+                            index,
+                            intern(name),
+                            fields.len(),
+                        )?;
+                        for (index, type_path) in fields.iter().enumerate() {
+                            let field = value.field_at(index).ok_or_else(|| self.mismatch())?;
+                            state.serialize_field(&self.child(type_path, field))?;
+                        }
+                        state.end()
+                    }
+                    VariantSchemaKind::Struct { fields } => {
+                        let mut state = serializer.serialize_struct_variant(
+                            "",
+                            index,
+                            intern(name),
+                            value.field_len(),
+                        )?;
+                        for field in fields {
+                            if let Some(field_value) = value.field(&field.name) {
+                                state.serialize_field(
+                                    intern(&field.name),
+                                    &self.child(&field.type_path, field_value),
+                                )?;
+                            }
+                        }
+                        state.end()
+                    }
+                }
+            }
+            (TypeSchemaKind::Opaque, _) => Err(ser::Error::custom(format_args!(
+                "`{}` can't be written without the game's code",
+                self.type_path
+            ))),
+            _ => Err(self.mismatch()),
+        }
+    }
+}
+
+/// Components of types the editor doesn't know, kept as scene RON so they
+/// survive loading and saving the scene
+#[derive(Component, Reflect, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[reflect(Component, Default)]
+pub struct GameComponents(pub Vec<GameComponent>);
+
+#[derive(Reflect, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameComponent {
+    pub type_path: String,
+    /// The component's value in scene RON
+    pub ron: String,
+}
+
+impl GameComponents {
+    pub fn get(&self, type_path: &str) -> Option<&GameComponent> {
+        self.0
+            .iter()
+            .find(|component| component.type_path == type_path)
+    }
+
+    pub fn contains(&self, type_path: &str) -> bool {
+        self.get(type_path).is_some()
+    }
+
+    /// Replace the value of the `type_path` component, or add it
+    pub fn set(&mut self, type_path: &str, ron: String) {
+        match self
+            .0
+            .iter_mut()
+            .find(|component| component.type_path == type_path)
+        {
+            Some(component) => component.ron = ron,
+            None => self.0.push(GameComponent {
+                type_path: type_path.to_string(),
+                ron,
+            }),
+        }
+    }
+
+    pub fn remove(&mut self, type_path: &str) -> Option<GameComponent> {
+        let index = self
+            .0
+            .iter()
+            .position(|component| component.type_path == type_path)?;
+        Some(self.0.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Reflect, Default)]
+    #[reflect(Default)]
+    enum Team {
+        #[default]
+        Neutral,
+        Player(u8),
+        Custom {
+            name: String,
+            color: Color,
+        },
+    }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component, Default)]
+    struct Unit {
+        health: f32,
+        team: Team,
+        target: Option<Vec2>,
+        tags: Vec<String>,
+        speed: Speed,
+    }
+
+    #[derive(Reflect, Default)]
+    struct Speed(f32);
+
+    /// The editor registry with only the engine types in [`Unit`]
+    fn editor_registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::default();
+        registry.register::<f32>();
+        registry.register::<u8>();
+        registry.register::<String>();
+        registry.register::<Vec2>();
+        registry.register::<Color>();
+        registry
+    }
+
+    fn game_schemas() -> TypeSchemas {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Unit>();
+        let file = TypeSchemaFile::from_registry(&registry);
+        let json = serde_json::to_string(&file).unwrap();
+        let file: TypeSchemaFile = serde_json::from_str(&json).unwrap();
+        file.into()
+    }
+
+    fn unit_path() -> &'static str {
+        Unit::type_path()
+    }
+
+    #[test]
+    fn registry_types_are_described() {
+        let schemas = game_schemas();
+        let unit = schemas.get(unit_path()).unwrap();
+        assert!(unit.component);
+        assert_eq!(unit.short_path, "Unit");
+        let TypeSchemaKind::Struct { fields } = &unit.kind else {
+            panic!("expected a struct, got {:?}", unit.kind);
+        };
+        assert_eq!(fields[0], FieldSchema::new("health", "f32"));
+
+        let team = schemas.get(Team::type_path()).unwrap();
+        let TypeSchemaKind::Enum { variants } = &team.kind else {
+            panic!("expected an enum, got {:?}", team.kind);
+        };
+        assert_eq!(variants.len(), 3);
+        assert_eq!(team.default.as_deref(), Some("Neutral"));
+    }
+
+    #[test]
+    fn values_round_trip_through_schemas() {
+        let schemas = game_schemas();
+        let registry = editor_registry();
+
+        // Written by the game with its own types
+        let mut game_registry = TypeRegistry::default();
+        game_registry.register::<Unit>();
+        let unit = Unit {
+            health: 12.5,
+            team: Team::Custom {
+                name: "Red".to_string(),
+                color: Color::srgb(1.0, 0.0, 0.0),
+            },
+            target: Some(Vec2::new(3.0, 4.0)),
+            tags: vec!["boss".to_string()],
+            speed: Speed(2.0),
+        };
+        let ron =
+            bevy::scene::serialize_ron(TypedReflectSerializer::new(&unit, &game_registry)).unwrap();
+
+        let value = schemas.read_value(unit_path(), &ron, &registry).unwrap();
+        let ReflectRef::Struct(fields) = value.reflect_ref() else {
+            panic!("expected a struct");
+        };
+        assert_eq!(
+            fields.field("health").unwrap().try_downcast_ref::<f32>(),
+            Some(&12.5)
+        );
+        assert_eq!(
+            schemas
+                .write_value(unit_path(), &*value, &registry)
+                .unwrap(),
+            ron
+        );
+
+        let default = schemas.get(unit_path()).unwrap().default.clone().unwrap();
+        let value = schemas
+            .read_value(unit_path(), &default, &registry)
+            .unwrap();
+        assert_eq!(
+            schemas
+                .write_value(unit_path(), &*value, &registry)
+                .unwrap(),
+            default
+        );
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let schemas = game_schemas();
+        let error = schemas
+            .read_value(unit_path(), "(mana: 3.0)", &editor_registry())
+            .unwrap_err();
+        assert!(error.to_string().contains("mana"), "{error}");
+    }
+
+    #[test]
+    fn game_components_are_replaced_in_place() {
+        let mut components = GameComponents::default();
+        components.set("game::A", "(1)".to_string());
+        components.set("game::B", "(2)".to_string());
+        components.set("game::A", "(3)".to_string());
+        assert_eq!(components.0.len(), 2);
+        assert_eq!(components.get("game::A").unwrap().ron, "(3)");
+        assert!(components.remove("game::B").is_some());
+        assert!(!components.contains("game::B"));
+    }
+}
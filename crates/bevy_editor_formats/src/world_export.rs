@@ -1,4 +1,7 @@
-use super::{EntityDefinitionData, EntityInstanceData, EnumDefinitionData, LayerData, TilesetData};
+use super::{
+    check_entity_refs, EntityDefinitionData, EntityInstanceData, EnumDefinitionData, LayerData,
+    MapOrientation, ReferenceIssue, TilesetData,
+};
 use serde::{Deserialize, Serialize};
 
 /// Complete world export format
@@ -7,6 +10,9 @@ use serde::{Deserialize, Serialize};
 pub struct WorldExport {
     pub version: String,
     pub tilesets: Vec<TilesetData>,
+    /// Grid orientation shared by every layer
+    #[serde(default)]
+    pub orientation: MapOrientation,
     pub layers: Vec<LayerExportData>,
     pub entity_definitions: Vec<EntityDefinitionData>,
     pub entity_instances: Vec<EntityInstanceData>,
@@ -19,6 +25,7 @@ impl WorldExport {
         Self {
             version: version.to_string(),
             tilesets: Vec::new(),
+            orientation: MapOrientation::Square,
             layers: Vec::new(),
             entity_definitions: Vec::new(),
             entity_instances: Vec::new(),
@@ -32,6 +39,11 @@ impl WorldExport {
         self
     }
 
+    pub fn with_orientation(mut self, orientation: MapOrientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
     pub fn with_layer(mut self, layer: LayerExportData) -> Self {
         self.layers.push(layer);
         self
@@ -52,6 +64,33 @@ impl WorldExport {
         self
     }
 
+    /// Find `EntityRef` fields that don't resolve to a suitable entity instance
+    pub fn validate_references(&self) -> Vec<ReferenceIssue> {
+        let target_definition = |iid| {
+            self.entity_instances
+                .iter()
+                .find(|instance| instance.iid == iid)
+                .map(|instance| Some(instance.entity_def_id))
+        };
+        self.entity_instances
+            .iter()
+            .flat_map(|instance| {
+                self.entity_definitions
+                    .iter()
+                    .find(|def| def.id == instance.entity_def_id)
+                    .map(|def| {
+                        check_entity_refs(
+                            instance.iid,
+                            def,
+                            &instance.field_values,
+                            target_definition,
+                        )
+                    })
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
     /// Save to JSON file
     pub fn save_to_file(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
         let json = serde_json::to_string_pretty(self)?;
@@ -131,6 +170,8 @@ impl LayerExportData {
 pub struct TileExportData {
     pub x: u32,
     pub y: u32,
+    #[serde(default)]
+    pub tileset_id: u32,
     pub tile_id: u32,
     pub flip_x: bool,
     pub flip_y: bool,
@@ -141,6 +182,7 @@ impl TileExportData {
         Self {
             x: tile.x,
             y: tile.y,
+            tileset_id: tile.tileset_id,
             tile_id: tile.tile_id,
             flip_x: tile.flip_x,
             flip_y: tile.flip_y,
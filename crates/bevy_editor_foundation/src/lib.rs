@@ -10,12 +10,13 @@
 //! - [`state`]: Core editor resources such as [`EditorState`] and
 //!   [`EditorTool`].
 //! - [`palette`]: Generic selection palettes that editor UIs can reuse.
-//! - [`selection`]: Shared entity selection resource.
+//! - [`selection`]: The editor's entity selection, shared by every tool, and
+//!   the [`SelectionChanged`] events sent when it changes.
 
 pub mod palette;
 pub mod selection;
 pub mod state;
 
 pub use palette::EditorPalette;
-pub use selection::Selection;
+pub use selection::{SelectMode, Selection, SelectionChanged, SelectionPlugin};
 pub use state::{EditorState, EditorStatePlugin, EditorTool};
@@ -1,47 +1,249 @@
+use bevy::ecs::entity::Entities;
 use bevy::prelude::*;
-use std::collections::HashSet;
 
-/// Tracks the set of currently selected entities within the editor world.
-#[derive(Resource, Default, Debug, Clone)]
+/// The editor's selection, shared by every panel and tool.
+///
+/// Entities are kept in the order they were selected. The primary entity is
+/// the one last clicked; tools acting on a single entity (the inspector's
+/// details, the gizmo handles) use it.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
 pub struct Selection {
-    /// Selected entities, stored as a hash set for quick membership checks.
-    pub selected: HashSet<Entity>,
+    entities: Vec<Entity>,
+    primary: Option<Entity>,
+}
+
+/// How a click changes the [`Selection`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectMode {
+    /// Select only the clicked entity.
+    #[default]
+    Replace,
+    /// Add the clicked entity to the selection (Shift).
+    Add,
+    /// Add or remove the clicked entity (Ctrl).
+    Toggle,
+    /// Remove the clicked entity from the selection (Alt).
+    Subtract,
+}
+
+impl SelectMode {
+    /// Mode for a click with the given modifiers held; Ctrl wins over Alt,
+    /// which wins over Shift.
+    pub fn from_modifiers(shift: bool, ctrl: bool, alt: bool) -> Self {
+        if ctrl {
+            SelectMode::Toggle
+        } else if alt {
+            SelectMode::Subtract
+        } else if shift {
+            SelectMode::Add
+        } else {
+            SelectMode::Replace
+        }
+    }
+
+    /// Mode for a click with the modifiers currently held on `keyboard`.
+    pub fn from_keyboard(keyboard: &ButtonInput<KeyCode>) -> Self {
+        Self::from_modifiers(
+            keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+            keyboard.any_pressed([
+                KeyCode::ControlLeft,
+                KeyCode::ControlRight,
+                KeyCode::SuperLeft,
+                KeyCode::SuperRight,
+            ]),
+            keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]),
+        )
+    }
 }
 
 impl Selection {
     /// Clears all selections.
     pub fn clear(&mut self) {
-        self.selected.clear();
+        self.entities.clear();
+        self.primary = None;
     }
 
     /// Selects only the provided entity.
     pub fn select(&mut self, entity: Entity) {
-        self.selected.clear();
-        self.selected.insert(entity);
+        self.entities.clear();
+        self.entities.push(entity);
+        self.primary = Some(entity);
+    }
+
+    /// Adds an entity to the selection and makes it the primary one.
+    pub fn add(&mut self, entity: Entity) {
+        if !self.entities.contains(&entity) {
+            self.entities.push(entity);
+        }
+        self.primary = Some(entity);
+    }
+
+    /// Removes an entity; the primary passes to the last selected remaining one.
+    pub fn remove(&mut self, entity: Entity) {
+        self.entities.retain(|selected| *selected != entity);
+        if self.primary == Some(entity) {
+            self.primary = self.entities.last().copied();
+        }
     }
 
     /// Toggles selection state for the given entity.
     pub fn toggle(&mut self, entity: Entity) {
-        if !self.selected.remove(&entity) {
-            self.selected.insert(entity);
+        if self.is_selected(entity) {
+            self.remove(entity);
+        } else {
+            self.add(entity);
+        }
+    }
+
+    /// Applies a click on `entity`.
+    pub fn click(&mut self, entity: Entity, mode: SelectMode) {
+        match mode {
+            SelectMode::Replace => self.select(entity),
+            SelectMode::Add => self.add(entity),
+            SelectMode::Toggle => self.toggle(entity),
+            SelectMode::Subtract => self.remove(entity),
+        }
+    }
+
+    /// Applies a box selection of `entities`; replacing with none clears
+    /// the selection.
+    pub fn apply(&mut self, entities: impl IntoIterator<Item = Entity>, mode: SelectMode) {
+        if mode == SelectMode::Replace {
+            self.clear();
+        }
+        for entity in entities {
+            match mode {
+                SelectMode::Replace | SelectMode::Add => self.add(entity),
+                SelectMode::Toggle => self.toggle(entity),
+                SelectMode::Subtract => self.remove(entity),
+            }
+        }
+    }
+
+    /// Replaces the selection with `entities`, the last one becoming primary.
+    pub fn set(&mut self, entities: impl IntoIterator<Item = Entity>) {
+        self.clear();
+        self.extend(entities);
+    }
+
+    /// Adds `entities` to the selection, the last one becoming primary.
+    pub fn extend(&mut self, entities: impl IntoIterator<Item = Entity>) {
+        for entity in entities {
+            self.add(entity);
+        }
+    }
+
+    /// Removes every entity `keep` rejects.
+    pub fn retain(&mut self, mut keep: impl FnMut(Entity) -> bool) {
+        let removed: Vec<Entity> = self
+            .entities
+            .iter()
+            .copied()
+            .filter(|entity| !keep(*entity))
+            .collect();
+        for entity in removed {
+            self.remove(entity);
         }
     }
 
     /// Returns true if the entity is currently selected.
     pub fn is_selected(&self, entity: Entity) -> bool {
-        self.selected.contains(&entity)
+        self.entities.contains(&entity)
     }
 
     /// Returns true if no entities are selected.
     pub fn is_empty(&self) -> bool {
-        self.selected.is_empty()
+        self.entities.is_empty()
+    }
+
+    /// Number of selected entities.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// The entity last clicked, if any is selected.
+    pub fn primary(&self) -> Option<Entity> {
+        self.primary
+    }
+
+    /// Makes a selected entity the primary one.
+    pub fn set_primary(&mut self, entity: Entity) {
+        if self.is_selected(entity) {
+            self.primary = Some(entity);
+        }
+    }
+
+    /// The selected entities, in the order they were selected.
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Iterates the selected entities in the order they were selected.
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter().copied()
+    }
+}
+
+/// Sent when the [`Selection`] changed since the last frame.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct SelectionChanged {
+    /// Entities that were selected.
+    pub added: Vec<Entity>,
+    /// Entities that are no longer selected.
+    pub removed: Vec<Entity>,
+    /// The primary entity after the change.
+    pub primary: Option<Entity>,
+}
+
+/// System sending [`SelectionChanged`] when the selection changed.
+pub fn emit_selection_changes(
+    selection: Res<Selection>,
+    mut last_selection: Local<Selection>,
+    mut events: EventWriter<SelectionChanged>,
+) {
+    if !selection.is_changed() || *selection == *last_selection {
+        return;
+    }
+    let added = selection
+        .iter()
+        .filter(|entity| !last_selection.is_selected(*entity))
+        .collect();
+    let removed = last_selection
+        .iter()
+        .filter(|entity| !selection.is_selected(*entity))
+        .collect();
+    events.write(SelectionChanged {
+        added,
+        removed,
+        primary: selection.primary(),
+    });
+    *last_selection = selection.clone();
+}
+
+/// System dropping despawned entities from the selection.
+pub fn retain_live_selection(mut selection: ResMut<Selection>, entities: &Entities) {
+    if selection.iter().any(|entity| !entities.contains(entity)) {
+        selection.retain(|entity| entities.contains(entity));
+    }
+}
+
+/// Plugin that registers [`Selection`] and sends [`SelectionChanged`].
+pub struct SelectionPlugin;
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Selection>()
+            .add_event::<SelectionChanged>()
+            .add_systems(
+                PostUpdate,
+                (retain_live_selection, emit_selection_changes).chain(),
+            );
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Selection;
-    use bevy::prelude::Entity;
+    use super::*;
 
     #[test]
     fn selection_tracks_entities() {
@@ -59,4 +261,79 @@ mod tests {
         selection.clear();
         assert!(selection.is_empty());
     }
+
+    #[test]
+    fn clicks_follow_modifiers() {
+        let mut selection = Selection::default();
+        let [a, b, c] = [1, 2, 3].map(Entity::from_raw);
+
+        selection.click(a, SelectMode::Replace);
+        selection.click(b, SelectMode::from_modifiers(true, false, false));
+        selection.click(c, SelectMode::from_modifiers(true, false, false));
+        assert_eq!(selection.entities(), &[a, b, c]);
+        assert_eq!(selection.primary(), Some(c));
+
+        selection.click(c, SelectMode::from_modifiers(false, true, false));
+        assert_eq!(selection.entities(), &[a, b]);
+        assert_eq!(selection.primary(), Some(b));
+
+        selection.click(a, SelectMode::Add);
+        assert_eq!(selection.entities(), &[a, b]);
+        assert_eq!(selection.primary(), Some(a));
+
+        selection.click(b, SelectMode::Replace);
+        assert_eq!(selection.entities(), &[b]);
+    }
+
+    #[test]
+    fn box_selection_applies_modes() {
+        let mut selection = Selection::default();
+        let [a, b, c] = [1, 2, 3].map(Entity::from_raw);
+
+        selection.apply([a, b], SelectMode::Replace);
+        assert_eq!(selection.entities(), &[a, b]);
+        selection.apply([c], SelectMode::from_modifiers(true, false, false));
+        assert_eq!(selection.entities(), &[a, b, c]);
+        selection.apply([a, c], SelectMode::from_modifiers(false, false, true));
+        assert_eq!(selection.entities(), &[b]);
+        assert_eq!(selection.primary(), Some(b));
+
+        selection.apply([], SelectMode::Replace);
+        assert!(selection.is_empty());
+    }
+
+    #[test]
+    fn changes_are_sent_as_events() {
+        let mut app = App::new();
+        app.add_plugins(SelectionPlugin);
+        let a = app.world_mut().spawn_empty().id();
+        let b = app.world_mut().spawn_empty().id();
+
+        app.world_mut().resource_mut::<Selection>().set([a, b]);
+        app.update();
+        app.world_mut().despawn(a);
+        app.update();
+
+        let events: Vec<SelectionChanged> = app
+            .world_mut()
+            .resource_mut::<Events<SelectionChanged>>()
+            .drain()
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                SelectionChanged {
+                    added: vec![a, b],
+                    removed: vec![],
+                    primary: Some(b),
+                },
+                SelectionChanged {
+                    added: vec![],
+                    removed: vec![a],
+                    primary: Some(b),
+                },
+            ]
+        );
+        assert_eq!(app.world().resource::<Selection>().entities(), &[b]);
+    }
 }
@@ -83,6 +83,56 @@ pub enum EditorAction {
     RunProjectCommand { command: ProjectCommand },
     /// Request that any running project command is interrupted.
     CancelProjectCommand,
+    /// Play the active scene inside the editor, or resume it when paused.
+    StartPlay,
+    /// Pause play-in-editor without leaving it.
+    PausePlay,
+    /// Leave play-in-editor, restoring the scene unless `keep_changes` is set.
+    StopPlay { keep_changes: bool },
+    /// Lay out the selected entities by their bounds, as one undo step.
+    ArrangeSelection(ArrangeOperation),
+}
+
+/// Layout operations on the selected entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArrangeOperation {
+    /// Line the entities' bounds up with an edge or center of the selection's bounds.
+    Align(AlignEdge),
+    /// Space the entities evenly between the two outermost ones.
+    Distribute {
+        axis: ArrangeAxis,
+        spacing: DistributeSpacing,
+    },
+    /// Scale the entities so their bounds match the primary entity's size.
+    MatchSize,
+    /// Turn the entities to the primary entity's rotation.
+    MatchRotation,
+}
+
+/// Edge or center line that [`ArrangeOperation::Align`] lines entities up on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlignEdge {
+    Left,
+    Center,
+    Right,
+    Top,
+    Middle,
+    Bottom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArrangeAxis {
+    Horizontal,
+    Vertical,
+}
+
+/// What [`ArrangeOperation::Distribute`] makes equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DistributeSpacing {
+    /// The distance between neighboring centers.
+    Centers,
+    /// The empty space between neighboring bounds.
+    Gaps,
 }
 
 /// Notifications sent from backend crates to frontends.
@@ -13,8 +13,8 @@ pub mod scene_tree;
 
 pub use asset_browser::AssetBrowserPanelState;
 pub use frontend::{
-    EditorAction, EditorEvent, EditorFrontend, EditorPanel, FrontendCapabilities, FrontendKind,
-    ProjectCommand,
+    AlignEdge, ArrangeAxis, ArrangeOperation, DistributeSpacing, EditorAction, EditorEvent,
+    EditorFrontend, EditorPanel, FrontendCapabilities, FrontendKind, ProjectCommand,
 };
 pub use inspector::{EntityComponentData, InspectorPanelState};
 pub use panels::{CliOutputPanelState, SceneTreePanelState};
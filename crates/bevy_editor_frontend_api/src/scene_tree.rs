@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 /// Lightweight view-model representing a single node in the editor scene tree.
 #[derive(Clone, Debug)]
@@ -7,6 +8,10 @@ pub struct SceneTreeNode {
     pub name: String,
     pub has_children: bool,
     pub children: Vec<Entity>,
+    /// Whether the entity is locked against viewport picking
+    pub locked: bool,
+    /// Whether the entity is hidden in the editor
+    pub hidden: bool,
 }
 
 impl SceneTreeNode {
@@ -16,6 +21,8 @@ impl SceneTreeNode {
             name,
             has_children,
             children,
+            locked: false,
+            hidden: false,
         }
     }
 }
@@ -41,10 +48,20 @@ pub enum SceneTreeCommand {
         entity: Entity,
         new_parent: Option<Entity>,
     },
+    /// Lock or unlock the entity and all its descendants
+    SetLocked {
+        entity: Entity,
+        locked: bool,
+    },
+    /// Hide or show the entity and all its descendants in the editor
+    SetHidden {
+        entity: Entity,
+        hidden: bool,
+    },
 }
 
 /// Identifiers for the built-in entity templates the editor understands.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SceneEntityTemplate {
     Empty,
     Sprite,
@@ -164,6 +164,19 @@ impl BevyCLIRunner {
             }
         }
 
+        // Have the game export its reflected types for the inspector
+        if matches!(command, CLICommand::Run | CLICommand::RunScene) {
+            command_builder.env(
+                bevy_editor_formats::EXPORT_TYPES_ENV_VAR,
+                project_path.join(bevy_editor_formats::TYPE_SCHEMA_FILE),
+            );
+            // Serve the remote protocol for the editor's live link
+            command_builder.env(
+                bevy_editor_formats::REMOTE_PORT_ENV_VAR,
+                bevy_editor_formats::DEFAULT_REMOTE_PORT.to_string(),
+            );
+        }
+
         // Spawn the process
         let mut child = command_builder
             .spawn()
@@ -7,11 +7,12 @@
 //! - **Workspace Tracking**: Recent projects and session persistence
 //! - **Project Templates**: Generate new projects from templates (Empty, Tilemap2D, etc.)
 //! - **CLI Integration**: Bevy CLI runner for building and running projects
+//! - **Live Link**: Inspect and edit the running game over the remote protocol
 //!
 //! ## Features
 //!
 //! - `workspace` (default): Workspace and recent project tracking
-//! - `cli`: Bevy CLI integration for build/run commands
+//! - `cli`: Bevy CLI integration for build/run commands and the live link
 //! - `ui`: egui-powered UI for project selection and the project creation wizard
 //!
 //! ## Example
@@ -38,6 +39,9 @@ pub mod workspace;
 #[cfg(feature = "cli")]
 pub mod bevy_cli_runner;
 
+#[cfg(feature = "cli")]
+pub mod live_link;
+
 #[cfg(feature = "ui")]
 // UI components (note: these have egui dependencies and should eventually move to UI crate)
 pub mod project_wizard;
@@ -61,6 +65,9 @@ pub use workspace::{load_workspace_system, save_workspace_on_exit, EditorWorkspa
 #[cfg(feature = "cli")]
 pub use bevy_cli_runner::{update_cli_runner, BevyCLIRunner, CLICommand, CLIOutput, CLIOutputLine};
 
+#[cfg(feature = "cli")]
+pub use live_link::{scene_asset_path, update_live_link, LiveEntity, LiveLink, LiveLinkStatus};
+
 #[cfg(feature = "ui")]
 pub use project_wizard::{project_wizard_ui, ProjectWizard};
 
@@ -81,6 +88,7 @@ impl Plugin for ProjectManagerPlugin {
         #[cfg(feature = "cli")]
         {
             app.init_resource::<BevyCLIRunner>();
+            app.init_resource::<LiveLink>();
         }
 
         #[cfg(feature = "workspace")]
@@ -94,7 +102,10 @@ impl Plugin for ProjectManagerPlugin {
 
         #[cfg(feature = "cli")]
         {
-            app.add_systems(Update, update_cli_runner.in_set(ProjectManagerSet));
+            app.add_systems(
+                Update,
+                (update_cli_runner, update_live_link).in_set(ProjectManagerSet),
+            );
         }
 
         #[cfg(feature = "ui")]
@@ -122,6 +133,7 @@ impl Plugin for ProjectCorePlugin {
         #[cfg(feature = "cli")]
         {
             app.init_resource::<BevyCLIRunner>();
+            app.init_resource::<LiveLink>();
         }
 
         app.configure_sets(Update, ProjectManagerSet);
@@ -131,7 +143,10 @@ impl Plugin for ProjectCorePlugin {
 
         #[cfg(feature = "cli")]
         {
-            app.add_systems(Update, update_cli_runner.in_set(ProjectManagerSet));
+            app.add_systems(
+                Update,
+                (update_cli_runner, update_live_link).in_set(ProjectManagerSet),
+            );
         }
     }
 }
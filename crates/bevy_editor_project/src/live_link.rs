@@ -0,0 +1,369 @@
+//! Live link to the game started by the [`BevyCLIRunner`]
+//!
+//! When the editor runs the game it sets `BEVY_EDITOR_REMOTE_PORT`, and the
+//! game's `bevy_editor_runtime` serves the Bevy Remote Protocol on that port.
+//! [`LiveLink`] polls the game's entities and the components of the selected
+//! one, and sends component edits and scene reloads back. Requests are sent
+//! from a worker thread, so a game that's still compiling or has stopped never
+//! blocks a frame.
+
+use bevy::prelude::*;
+use bevy_editor_formats::{
+    send_remote_request, GetParams, GetResponse, InsertParams, ListParams, QueryData, QueryParams,
+    QueryRow, ReloadSceneParams, RemoteRequest, RemoteResponse, RemoveParams, BRP_GET, BRP_INSERT,
+    BRP_LIST, BRP_QUERY, BRP_REMOVE, DEFAULT_REMOTE_PORT, EDITOR_RELOAD_SCENE,
+};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::bevy_cli_runner::{BevyCLIRunner, CLICommand};
+
+/// Seconds between refreshes of the game's entities
+const REFRESH_INTERVAL: f64 = 0.5;
+
+/// How long to wait for the game to answer
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiveLinkStatus {
+    /// The game isn't running
+    Disconnected,
+    /// The game is running but hasn't answered yet
+    Connecting,
+    Connected,
+}
+
+/// Entity of the running game
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveEntity {
+    /// `Entity::to_bits` in the game's world
+    pub entity: u64,
+    pub name: Option<String>,
+    pub parent: Option<u64>,
+}
+
+impl LiveEntity {
+    pub fn label(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            Entity::try_from_bits(self.entity)
+                .map(|entity| format!("Entity {entity}"))
+                .unwrap_or_else(|_| format!("Entity {}", self.entity))
+        })
+    }
+}
+
+/// What a request in flight was for
+#[derive(Debug, Clone, Copy)]
+enum Pending {
+    Entities,
+    ComponentList(u64),
+    Components(u64),
+    Edit,
+    Reload,
+}
+
+type Reply = (u64, Result<RemoteResponse, String>);
+
+/// Connection to the running game's remote protocol server
+#[derive(Resource)]
+pub struct LiveLink {
+    pub port: u16,
+    pub status: LiveLinkStatus,
+    /// Entities of the game's world, refreshed every half second
+    pub entities: Vec<LiveEntity>,
+    pub selected: Option<u64>,
+    /// Components of the selected entity as reflected JSON, by type path
+    pub components: BTreeMap<String, Value>,
+    requests: Sender<(SocketAddr, RemoteRequest)>,
+    replies: Receiver<Reply>,
+    pending: HashMap<u64, Pending>,
+    next_id: u64,
+    last_refresh: Option<f64>,
+    /// Component values read before this request predate the last edit
+    last_edit: u64,
+}
+
+impl Default for LiveLink {
+    fn default() -> Self {
+        Self::new(DEFAULT_REMOTE_PORT)
+    }
+}
+
+impl LiveLink {
+    pub fn new(port: u16) -> Self {
+        let (requests, request_receiver) = unbounded::<(SocketAddr, RemoteRequest)>();
+        let (reply_sender, replies) = unbounded();
+        std::thread::spawn(move || {
+            for (address, request) in request_receiver {
+                let id = request
+                    .id
+                    .as_ref()
+                    .and_then(Value::as_u64)
+                    .unwrap_or_default();
+                let response = send_remote_request(address, &request, REQUEST_TIMEOUT)
+                    .map_err(|err| err.to_string());
+                if reply_sender.send((id, response)).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Self {
+            port,
+            status: LiveLinkStatus::Disconnected,
+            entities: Vec::new(),
+            selected: None,
+            components: BTreeMap::new(),
+            requests,
+            replies,
+            pending: HashMap::new(),
+            next_id: 0,
+            last_refresh: None,
+            last_edit: 0,
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.status == LiveLinkStatus::Connected
+    }
+
+    /// Inspect `entity`, fetching its components on the next refresh
+    pub fn select(&mut self, entity: Option<u64>) {
+        if self.selected != entity {
+            self.selected = entity;
+            self.components.clear();
+            self.last_refresh = None;
+        }
+    }
+
+    /// Insert or replace a component of a game entity
+    pub fn insert_component(&mut self, entity: u64, type_path: &str, value: Value) {
+        if self.selected == Some(entity) {
+            self.components.insert(type_path.to_string(), value.clone());
+        }
+        let mut components = Map::new();
+        components.insert(type_path.to_string(), value);
+        self.last_edit = self.send(
+            Pending::Edit,
+            BRP_INSERT,
+            InsertParams { entity, components },
+        );
+    }
+
+    pub fn remove_component(&mut self, entity: u64, type_path: &str) {
+        if self.selected == Some(entity) {
+            self.components.remove(type_path);
+        }
+        self.last_edit = self.send(
+            Pending::Edit,
+            BRP_REMOVE,
+            RemoveParams {
+                entity,
+                components: vec![type_path.to_string()],
+            },
+        );
+    }
+
+    /// Have the game reload a scene file at the asset path `path`
+    pub fn reload_scene(&mut self, path: impl Into<String>) {
+        self.send(
+            Pending::Reload,
+            EDITOR_RELOAD_SCENE,
+            ReloadSceneParams { path: path.into() },
+        );
+    }
+
+    /// Poll the game while `running`, and handle its answers. `time` is in
+    /// seconds.
+    pub fn update(&mut self, running: bool, time: f64) {
+        if !running {
+            if self.status != LiveLinkStatus::Disconnected {
+                self.disconnect();
+            }
+            return;
+        }
+        if self.status == LiveLinkStatus::Disconnected {
+            self.status = LiveLinkStatus::Connecting;
+        }
+
+        let due = self
+            .last_refresh
+            .is_none_or(|last| time - last >= REFRESH_INTERVAL);
+        // Don't queue refreshes faster than the game answers them
+        let refreshing = self
+            .pending
+            .values()
+            .any(|pending| matches!(pending, Pending::Entities));
+        if due && !refreshing {
+            self.last_refresh = Some(time);
+            self.refresh();
+        }
+
+        while let Ok((id, response)) = self.replies.try_recv() {
+            let Some(pending) = self.pending.remove(&id) else {
+                continue;
+            };
+            match response {
+                Ok(response) => {
+                    self.status = LiveLinkStatus::Connected;
+                    self.handle_response(id, pending, response);
+                }
+                // Connections are refused until the game has started
+                Err(err) if self.is_connected() => {
+                    warn!("Lost the live link to the game: {}", err);
+                    self.status = LiveLinkStatus::Connecting;
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.send(
+            Pending::Entities,
+            BRP_QUERY,
+            QueryParams {
+                data: QueryData {
+                    option: vec![
+                        Name::type_path().to_string(),
+                        ChildOf::type_path().to_string(),
+                    ],
+                    ..default()
+                },
+                ..default()
+            },
+        );
+        if let Some(entity) = self.selected {
+            self.send(
+                Pending::ComponentList(entity),
+                BRP_LIST,
+                ListParams {
+                    entity: Some(entity),
+                },
+            );
+        }
+    }
+
+    fn handle_response(&mut self, id: u64, pending: Pending, response: RemoteResponse) {
+        match pending {
+            Pending::Entities => match response.into_result::<Vec<QueryRow>>() {
+                Ok(rows) => {
+                    self.entities = rows.into_iter().map(live_entity).collect();
+                    let selected_exists = self
+                        .selected
+                        .is_none_or(|selected| self.entities.iter().any(|e| e.entity == selected));
+                    if !selected_exists {
+                        self.select(None);
+                    }
+                }
+                Err(err) => warn!("Failed to list the game's entities: {}", err),
+            },
+            Pending::ComponentList(entity) => {
+                if self.selected != Some(entity) {
+                    return;
+                }
+                match response.into_result::<Vec<String>>() {
+                    Ok(components) => {
+                        self.send(
+                            Pending::Components(entity),
+                            BRP_GET,
+                            GetParams {
+                                entity,
+                                components,
+                                strict: false,
+                            },
+                        );
+                    }
+                    Err(err) => warn!("Failed to list components of the game entity: {}", err),
+                }
+            }
+            Pending::Components(entity) => {
+                // Values read before the last edit would undo it in the inspector
+                if self.selected != Some(entity) || id < self.last_edit {
+                    return;
+                }
+                match response.into_result::<GetResponse>() {
+                    Ok(response) => self.components = response.components.into_iter().collect(),
+                    Err(err) => warn!("Failed to read components of the game entity: {}", err),
+                }
+            }
+            Pending::Edit => {
+                if let Err(err) = response.into_result::<Value>() {
+                    error!("Failed to edit the game entity: {}", err);
+                }
+            }
+            Pending::Reload => match response.into_result::<Value>() {
+                Ok(_) => info!("Game reloaded the scene"),
+                Err(err) => error!("Failed to reload the scene in the game: {}", err),
+            },
+        }
+    }
+
+    fn send(&mut self, pending: Pending, method: &str, params: impl Serialize) -> u64 {
+        self.next_id += 1;
+        let id = self.next_id;
+        let address = SocketAddr::from(([127, 0, 0, 1], self.port));
+        if self
+            .requests
+            .send((address, RemoteRequest::new(id, method, params)))
+            .is_ok()
+        {
+            self.pending.insert(id, pending);
+        }
+        id
+    }
+
+    fn disconnect(&mut self) {
+        self.status = LiveLinkStatus::Disconnected;
+        self.entities.clear();
+        self.select(None);
+        self.pending.clear();
+        self.last_refresh = None;
+    }
+}
+
+fn live_entity(row: QueryRow) -> LiveEntity {
+    LiveEntity {
+        entity: row.entity,
+        name: row
+            .components
+            .get(Name::type_path())
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        parent: row
+            .components
+            .get(ChildOf::type_path())
+            .and_then(Value::as_u64),
+    }
+}
+
+/// Asset path of a scene file inside the project's assets directory, as the
+/// game loads it (`world/level1.scn.ron`)
+pub fn scene_asset_path(assets_path: &Path, file_path: &str) -> String {
+    let file_path = Path::new(file_path);
+    file_path
+        .strip_prefix(assets_path)
+        .unwrap_or(file_path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Keep the [`LiveLink`] connected while the editor runs the game
+pub fn update_live_link(
+    mut live_link: ResMut<LiveLink>,
+    cli_runner: Res<BevyCLIRunner>,
+    time: Res<Time>,
+) {
+    let running = matches!(
+        cli_runner.current_command(),
+        Some(CLICommand::Run | CLICommand::RunScene)
+    );
+    live_link.update(running, time.elapsed_secs_f64());
+}
@@ -3,6 +3,7 @@ pub const SCENE_LOADER_TEMPLATE: &str = r#"// Scene loading data structures and
 // This module contains minimal data structures needed to load .bscene files
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Bevy Scene Format (.bscene) - JSON-based scene storage
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +43,9 @@ pub struct LevelTilemapData {
     pub grid_size: f32,
     pub map_width: u32,
     pub map_height: u32,
+    /// Grid orientation shared by every layer
+    #[serde(default)]
+    pub orientation: MapOrientation,
     pub tilesets: Vec<LevelTilesetData>,
     pub selected_tileset_id: Option<u32>,
     pub layers: Vec<LevelLayerData>,
@@ -55,6 +59,50 @@ pub struct LevelTilesetData {
     pub texture_path: String,
     pub tile_width: u32,
     pub tile_height: u32,
+    /// Gap in pixels between neighbouring tiles
+    #[serde(default)]
+    pub spacing: u32,
+    /// Margin in pixels around the edge of the image
+    #[serde(default)]
+    pub padding: u32,
+    /// Collision shapes keyed by tile id
+    #[serde(default)]
+    pub collision_data: HashMap<u32, TileCollisionData>,
+}
+
+impl LevelTilesetData {
+    /// Spacing to build this tileset's `TilemapBundle` with. bevy_ecs_tilemap has
+    /// no outer margin, so `padding` pixels must be cropped off the top/left of
+    /// the image before it's used as the tilemap texture.
+    pub fn tilemap_spacing(&self) -> bevy_ecs_tilemap::prelude::TilemapSpacing {
+        bevy_ecs_tilemap::prelude::TilemapSpacing {
+            x: self.spacing as f32,
+            y: self.spacing as f32,
+        }
+    }
+}
+
+/// Collision shapes of a single tile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileCollisionData {
+    pub tile_id: u32,
+    pub shapes: Vec<CollisionShape>,
+}
+
+/// Collision shape in tile-local pixels (origin top-left, y down)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CollisionShape {
+    Rectangle { x: f32, y: f32, width: f32, height: f32 },
+    Ellipse { x: f32, y: f32, rx: f32, ry: f32 },
+    Polygon { points: Vec<Vector2> },
+    Polyline { points: Vec<Vector2> },
+    Point { x: f32, y: f32 },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Vector2 {
+    pub x: f32,
+    pub y: f32,
 }
 
 /// Layer data with tile placements
@@ -66,11 +114,44 @@ pub struct LevelLayerData {
     pub tiles: Vec<LevelTileInstance>,
 }
 
+/// Grid orientation of a tile map
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MapOrientation {
+    #[default]
+    Square,
+    /// `TilemapType::Isometric(IsoCoordSystem::Diamond)`
+    IsometricDiamond,
+    /// `TilemapType::Isometric(IsoCoordSystem::Staggered)`
+    IsometricStaggered,
+    /// `TilemapType::Hexagon(HexCoordSystem::RowOdd)`
+    HexRow,
+    /// `TilemapType::Hexagon(HexCoordSystem::ColumnOdd)`
+    HexColumn,
+}
+
+impl MapOrientation {
+    /// Map type to build every `TilemapBundle` of the level with
+    pub fn tilemap_type(&self) -> bevy_ecs_tilemap::prelude::TilemapType {
+        use bevy_ecs_tilemap::prelude::{HexCoordSystem, IsoCoordSystem, TilemapType};
+        match self {
+            MapOrientation::Square => TilemapType::Square,
+            MapOrientation::IsometricDiamond => TilemapType::Isometric(IsoCoordSystem::Diamond),
+            MapOrientation::IsometricStaggered => TilemapType::Isometric(IsoCoordSystem::Staggered),
+            MapOrientation::HexRow => TilemapType::Hexagon(HexCoordSystem::RowOdd),
+            MapOrientation::HexColumn => TilemapType::Hexagon(HexCoordSystem::ColumnOdd),
+        }
+    }
+}
+
 /// Individual tile instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LevelTileInstance {
     pub x: u32,
     pub y: u32,
+    /// Id of the entry in `LevelTilemapData::tilesets` this tile comes from.
+    /// `None` only in files written before tiles were tileset-qualified.
+    #[serde(default)]
+    pub tileset_id: Option<u32>,
     pub tile_id: u32,
 }
 "#;
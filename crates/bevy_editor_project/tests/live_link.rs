@@ -0,0 +1,85 @@
+#![cfg(feature = "cli")]
+
+use bevy::prelude::*;
+use bevy_editor_project::{scene_asset_path, LiveLink};
+use bevy_editor_runtime::{EditorRemotePlugin, RemoteServerAddress};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+struct Health(f32);
+
+/// Update the game and the live link until `done` holds
+fn run_until(
+    app: &mut App,
+    live_link: &mut LiveLink,
+    mut done: impl FnMut(&App, &LiveLink) -> bool,
+) {
+    let start = Instant::now();
+    while !done(app, live_link) {
+        assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+        app.update();
+        live_link.update(true, start.elapsed().as_secs_f64());
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn live_link_lists_and_edits_game_entities() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .register_type::<Health>()
+        .register_type::<Name>()
+        .add_plugins(EditorRemotePlugin { port: 0 });
+    let player = app
+        .world_mut()
+        .spawn((Name::new("Player"), Health(10.0)))
+        .id();
+    let port = app.world().resource::<RemoteServerAddress>().0.port();
+
+    let mut live_link = LiveLink::new(port);
+    run_until(&mut app, &mut live_link, |_, live_link| {
+        live_link
+            .entities
+            .iter()
+            .any(|entity| entity.entity == player.to_bits())
+    });
+    assert!(live_link.is_connected());
+    let entity = live_link
+        .entities
+        .iter()
+        .find(|entity| entity.entity == player.to_bits())
+        .unwrap();
+    assert_eq!(entity.label(), "Player");
+
+    live_link.select(Some(player.to_bits()));
+    run_until(&mut app, &mut live_link, |_, live_link| {
+        live_link.components.contains_key(Health::type_path())
+    });
+    assert_eq!(live_link.components[Health::type_path()], 10.0);
+
+    live_link.insert_component(player.to_bits(), Health::type_path(), 25.0.into());
+    run_until(&mut app, &mut live_link, |app, _| {
+        app.world().get::<Health>(player).unwrap().0 == 25.0
+    });
+
+    live_link.update(false, 0.0);
+    assert!(!live_link.is_connected());
+    assert!(live_link.entities.is_empty());
+}
+
+#[test]
+fn scene_asset_paths_are_relative_to_assets() {
+    assert_eq!(
+        scene_asset_path(
+            Path::new("/game/assets"),
+            "/game/assets/world/level1.scn.ron"
+        ),
+        "world/level1.scn.ron"
+    );
+    assert_eq!(
+        scene_asset_path(Path::new("/game/assets"), "world/level1.scn.ron"),
+        "world/level1.scn.ron"
+    );
+}
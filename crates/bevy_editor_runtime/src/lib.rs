@@ -27,8 +27,40 @@
 //! Example: `BEVY_EDITOR_SCENE=level1 cargo run`
 //!
 //! This will load `assets/world/level1.scn.ron` when the game starts.
+//!
+//! # Tilemaps
+//!
+//! [`load_level_tilemap`] reads the tilemap of a level saved by the editor and
+//! [`spawn_level_tilemap`] renders it with bevy_ecs_tilemap, honoring the map's
+//! orientation and each tileset's spacing and margin. Add [`LevelTilemapPlugin`] so tilesets with a
+//! margin get cropped once loaded.
+//!
+//! # Type Export
+//!
+//! When `BEVY_EDITOR_EXPORT_TYPES` is set, the plugin writes the game's
+//! reflected types to that path after startup. The editor sets it when running
+//! the game and reads the file, so game components can be inspected and added
+//! to scenes without the editor linking against the game.
+//!
+//! # Live Link
+//!
+//! When `BEVY_EDITOR_REMOTE_PORT` is set, the plugin serves the Bevy Remote
+//! Protocol on that localhost port (see [`EditorRemotePlugin`]). The editor
+//! connects to it to list the game's entities, edit their components while
+//! the game runs, and hot reload scenes it saves.
 
 use bevy::prelude::*;
+use bevy_editor_formats::{TypeSchemaFile, EXPORT_TYPES_ENV_VAR, REMOTE_PORT_ENV_VAR};
+use std::path::PathBuf;
+
+mod remote;
+mod tilemap;
+
+pub use remote::{process_remote_requests, EditorRemotePlugin, RemoteServerAddress};
+pub use tilemap::{
+    load_level_tilemap, spawn_level_tilemap, tilemap_type, tileset_spacing, LevelTilemapPlugin,
+    TilesetMargin,
+};
 
 /// Plugin that loads editor scenes based on environment variables.
 ///
@@ -48,10 +80,54 @@ impl Plugin for EditorSceneLoaderPlugin {
                 loaded: false,
             });
         } else {
-            info!("Editor scene loader: No scene specified (set BEVY_EDITOR_SCENE to load a scene)");
+            info!(
+                "Editor scene loader: No scene specified (set BEVY_EDITOR_SCENE to load a scene)"
+            );
         }
 
         app.add_systems(Startup, load_editor_scene);
+
+        if let Ok(path) = std::env::var(EXPORT_TYPES_ENV_VAR) {
+            app.insert_resource(TypeExportPath(PathBuf::from(path)))
+                .add_systems(PostStartup, export_types);
+        }
+
+        if let Ok(port) = std::env::var(REMOTE_PORT_ENV_VAR) {
+            match port.parse() {
+                Ok(port) => {
+                    app.add_plugins(EditorRemotePlugin { port });
+                }
+                Err(_) => warn!("Ignoring invalid {}: '{}'", REMOTE_PORT_ENV_VAR, port),
+            }
+        }
+    }
+}
+
+/// Root of a scene loaded for the editor, spawned with a [`DynamicSceneRoot`]
+#[derive(Component, Debug, Clone)]
+pub struct EditorSceneRoot {
+    /// Asset path of the scene
+    pub path: String,
+}
+
+/// Where to write the game's reflected types
+#[derive(Resource)]
+struct TypeExportPath(PathBuf);
+
+/// Write the type registry once every plugin has registered its types.
+fn export_types(export_path: Res<TypeExportPath>, type_registry: Res<AppTypeRegistry>) {
+    let schema = TypeSchemaFile::from_registry(&type_registry.read());
+    match schema.save_to_file(&export_path.0) {
+        Ok(()) => info!(
+            "Exported {} reflected types to {}",
+            schema.types.len(),
+            export_path.0.display()
+        ),
+        Err(err) => error!(
+            "Failed to export reflected types to {}: {}",
+            export_path.0.display(),
+            err
+        ),
     }
 }
 
@@ -79,6 +155,9 @@ fn load_editor_scene(
             // Spawn the scene
             commands.spawn((
                 Name::new(format!("EditorScene[{}]", scene_to_load.name)),
+                EditorSceneRoot {
+                    path: scene_to_load.path.clone(),
+                },
                 DynamicSceneRoot(scene_handle),
             ));
 
@@ -99,4 +178,31 @@ mod tests {
         app.add_plugins(EditorSceneLoaderPlugin);
         // Should not panic
     }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component, Default)]
+    struct Health(f32);
+
+    #[test]
+    fn registered_types_are_exported() {
+        let path = std::env::temp_dir().join(format!(
+            "bevy_editor_runtime_types_{}.json",
+            std::process::id()
+        ));
+        let mut app = App::new();
+        app.register_type::<Health>()
+            .insert_resource(TypeExportPath(path.clone()))
+            .add_systems(PostStartup, export_types);
+        app.update();
+
+        let schema = TypeSchemaFile::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let health = schema
+            .types
+            .iter()
+            .find(|schema| schema.short_path == "Health")
+            .unwrap();
+        assert!(health.component);
+        assert_eq!(health.default.as_deref(), Some("(0.0)"));
+    }
 }
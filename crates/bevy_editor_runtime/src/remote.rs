@@ -0,0 +1,464 @@
+//! A Bevy Remote Protocol server for the editor's live link
+//!
+//! Serves the subset of BRP the editor uses (`bevy/get`, `bevy/query`,
+//! `bevy/insert`, `bevy/remove`, `bevy/destroy` and `bevy/list`) plus
+//! `bevy_editor/reload_scene`, on a localhost port. Connections are read on
+//! background threads and the requests handed to [`process_remote_requests`],
+//! which answers them from the world once per frame.
+
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::prelude::*;
+use bevy::reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
+use bevy::reflect::TypeRegistry;
+use bevy_editor_formats::{
+    read_http_message, write_http_response, DestroyParams, GetParams, GetResponse, InsertParams,
+    ListParams, QueryParams, QueryRow, ReloadSceneParams, RemoteError, RemoteRequest,
+    RemoteResponse, RemoveParams, BRP_DESTROY, BRP_GET, BRP_INSERT, BRP_LIST, BRP_QUERY,
+    BRP_REMOVE, EDITOR_RELOAD_SCENE,
+};
+use serde::de::DeserializeSeed;
+use serde_json::Value;
+use std::io::{BufReader, BufWriter};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::EditorSceneRoot;
+
+/// How long a connection waits for the app to answer a request
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serves the remote protocol on `127.0.0.1:port`, `0` picking a free port.
+///
+/// Added by [`crate::EditorSceneLoaderPlugin`] when `BEVY_EDITOR_REMOTE_PORT`
+/// is set.
+pub struct EditorRemotePlugin {
+    pub port: u16,
+}
+
+impl Plugin for EditorRemotePlugin {
+    fn build(&self, app: &mut App) {
+        let listener = match TcpListener::bind(("127.0.0.1", self.port)) {
+            Ok(listener) => listener,
+            Err(err) => {
+                error!(
+                    "Failed to serve the remote protocol on port {}: {}",
+                    self.port, err
+                );
+                return;
+            }
+        };
+        let Ok(address) = listener.local_addr() else {
+            return;
+        };
+        info!("Serving the remote protocol on {}", address);
+
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().map_while(Result::ok) {
+                let sender = sender.clone();
+                std::thread::spawn(move || serve_connection(stream, sender));
+            }
+        });
+
+        app.insert_resource(RemoteServerAddress(address))
+            .insert_resource(RemoteRequests(Mutex::new(receiver)))
+            .add_systems(Update, process_remote_requests);
+    }
+}
+
+/// Address the remote protocol is served on
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct RemoteServerAddress(pub SocketAddr);
+
+/// A request and where to send its response
+type PendingRequest = (RemoteRequest, Sender<RemoteResponse>);
+
+/// Requests read by the connection threads
+#[derive(Resource)]
+struct RemoteRequests(Mutex<Receiver<PendingRequest>>);
+
+fn serve_connection(stream: TcpStream, requests: Sender<PendingRequest>) {
+    let Ok(write_stream) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream);
+    let mut writer = BufWriter::new(write_stream);
+    // Keep answering until the client closes the connection
+    while let Ok(Some(body)) = read_http_message(&mut reader) {
+        let response = match serde_json::from_slice::<RemoteRequest>(&body) {
+            Ok(request) => {
+                let id = request.id.clone();
+                let (sender, receiver) = channel();
+                if requests.send((request, sender)).is_err() {
+                    return;
+                }
+                receiver.recv_timeout(RESPONSE_TIMEOUT).unwrap_or_else(|_| {
+                    RemoteResponse::new(
+                        id,
+                        Err(RemoteError::new(
+                            RemoteError::INTERNAL_ERROR,
+                            "the app didn't answer in time",
+                        )),
+                    )
+                })
+            }
+            Err(err) => RemoteResponse::new(
+                None,
+                Err(RemoteError::new(RemoteError::PARSE_ERROR, err.to_string())),
+            ),
+        };
+        if write_http_response(&mut writer, &response).is_err() {
+            return;
+        }
+    }
+}
+
+/// Answer the requests received since the last frame
+pub fn process_remote_requests(world: &mut World) {
+    let requests: Vec<PendingRequest> = {
+        let requests = world.resource::<RemoteRequests>();
+        let receiver = requests
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        receiver.try_iter().collect()
+    };
+    for (request, sender) in requests {
+        let result = handle_request(world, &request);
+        let _ = sender.send(RemoteResponse::new(request.id, result));
+    }
+}
+
+fn handle_request(world: &mut World, request: &RemoteRequest) -> Result<Value, RemoteError> {
+    match request.method.as_str() {
+        BRP_GET => get(world, request.params()?),
+        BRP_QUERY => query(world, request.params()?),
+        BRP_INSERT => insert(world, request.params()?),
+        BRP_REMOVE => remove(world, request.params()?),
+        BRP_DESTROY => destroy(world, request.params()?),
+        BRP_LIST => list(world, request.params()?),
+        EDITOR_RELOAD_SCENE => reload_scene(world, request.params()?),
+        method => Err(RemoteError::new(
+            RemoteError::METHOD_NOT_FOUND,
+            format!("method `{method}` not found"),
+        )),
+    }
+}
+
+fn get(world: &World, params: GetParams) -> Result<Value, RemoteError> {
+    let entity = world_entity(world, params.entity)?;
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut response = GetResponse::default();
+    for type_path in params.components {
+        match component_value(world, entity, &type_path, &registry) {
+            Ok(value) => {
+                response.components.insert(type_path, value);
+            }
+            Err(error) if !params.strict => {
+                response
+                    .errors
+                    .insert(type_path, serde_json::to_value(error).unwrap_or_default());
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    to_result(response)
+}
+
+fn query(world: &World, params: QueryParams) -> Result<Value, RemoteError> {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let strict = params.strict;
+    let required = query_components(&registry, &params.data.components, strict)?;
+    let optional = query_components(&registry, &params.data.option, strict)?;
+    let has = query_components(&registry, &params.data.has, strict)?;
+    let with = query_components(&registry, &params.filter.with, strict)?;
+    let without = query_components(&registry, &params.filter.without, strict)?;
+    // Entities can't have components the registry doesn't know
+    if required.len() < params.data.components.len() || with.len() < params.filter.with.len() {
+        return to_result(Vec::<QueryRow>::new());
+    }
+
+    let mut rows = Vec::new();
+    for entity in world.iter_entities() {
+        let matches = required
+            .iter()
+            .chain(&with)
+            .all(|(_, component)| component.contains(entity))
+            && !without
+                .iter()
+                .any(|(_, component)| component.contains(entity));
+        if !matches {
+            continue;
+        }
+        let mut row = QueryRow {
+            entity: entity.id().to_bits(),
+            ..default()
+        };
+        for (type_path, component) in required.iter().chain(&optional) {
+            if let Some(value) = component.reflect(entity) {
+                row.components.insert(
+                    type_path.to_string(),
+                    serialize_value(value.as_partial_reflect(), &registry)?,
+                );
+            }
+        }
+        for (type_path, component) in &has {
+            row.has
+                .insert(type_path.to_string(), component.contains(entity).into());
+        }
+        rows.push(row);
+    }
+    to_result(rows)
+}
+
+/// Components named in a query. Unknown types only fail strict queries.
+fn query_components<'a>(
+    registry: &'a TypeRegistry,
+    type_paths: &'a [String],
+    strict: bool,
+) -> Result<Vec<(&'a String, &'a ReflectComponent)>, RemoteError> {
+    type_paths
+        .iter()
+        .filter_map(|type_path| match reflect_component(registry, type_path) {
+            Ok(component) => Some(Ok((type_path, component))),
+            Err(_) if !strict => None,
+            Err(error) => Some(Err(error)),
+        })
+        .collect()
+}
+
+fn insert(world: &mut World, params: InsertParams) -> Result<Value, RemoteError> {
+    let entity = world_entity(world, params.entity)?;
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    for (type_path, value) in params.components {
+        let component = reflect_component(&registry, &type_path)?;
+        let registration = registry
+            .get_with_type_path(&type_path)
+            .ok_or_else(|| unknown_component(&type_path))?;
+        let value = TypedReflectDeserializer::new(registration, &registry)
+            .deserialize(&value)
+            .map_err(|err| {
+                RemoteError::new(
+                    RemoteError::COMPONENT_ERROR,
+                    format!("invalid value for `{type_path}`: {err}"),
+                )
+            })?;
+        component.insert(&mut world.entity_mut(entity), value.as_ref(), &registry);
+    }
+    Ok(Value::Null)
+}
+
+fn remove(world: &mut World, params: RemoveParams) -> Result<Value, RemoteError> {
+    let entity = world_entity(world, params.entity)?;
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    for type_path in params.components {
+        reflect_component(&registry, &type_path)?.remove(&mut world.entity_mut(entity));
+    }
+    Ok(Value::Null)
+}
+
+fn destroy(world: &mut World, params: DestroyParams) -> Result<Value, RemoteError> {
+    let entity = world_entity(world, params.entity)?;
+    world.despawn(entity);
+    Ok(Value::Null)
+}
+
+fn list(world: &World, params: ListParams) -> Result<Value, RemoteError> {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut type_paths: Vec<&str> = match params.entity {
+        Some(bits) => {
+            let entity = world_entity(world, bits)?;
+            world
+                .inspect_entity(entity)
+                .map_err(|err| RemoteError::new(RemoteError::ENTITY_NOT_FOUND, err.to_string()))?
+                .filter_map(|info| info.type_id())
+                .filter_map(|type_id| registry.get(type_id))
+                .filter(|registration| registration.data::<ReflectComponent>().is_some())
+                .map(|registration| registration.type_info().type_path())
+                .collect()
+        }
+        None => registry
+            .iter_with_data::<ReflectComponent>()
+            .map(|(registration, _)| registration.type_info().type_path())
+            .collect(),
+    };
+    type_paths.sort_unstable();
+    to_result(type_paths)
+}
+
+/// Reload a scene the editor saved, spawning it if it isn't already
+fn reload_scene(world: &mut World, params: ReloadSceneParams) -> Result<Value, RemoteError> {
+    info!("Reloading scene {} for the editor", params.path);
+    world.resource::<AssetServer>().reload(params.path.clone());
+    let mut roots = world.query::<&EditorSceneRoot>();
+    if !roots.iter(world).any(|root| root.path == params.path) {
+        let handle = world
+            .resource::<AssetServer>()
+            .load::<DynamicScene>(params.path.clone());
+        world.spawn((
+            Name::new(format!("EditorScene[{}]", params.path)),
+            EditorSceneRoot { path: params.path },
+            DynamicSceneRoot(handle),
+        ));
+    }
+    Ok(Value::Null)
+}
+
+fn world_entity(world: &World, bits: u64) -> Result<Entity, RemoteError> {
+    Entity::try_from_bits(bits)
+        .ok()
+        .filter(|&entity| world.get_entity(entity).is_ok())
+        .ok_or_else(|| {
+            RemoteError::new(
+                RemoteError::ENTITY_NOT_FOUND,
+                format!("entity {bits} not found"),
+            )
+        })
+}
+
+fn reflect_component<'a>(
+    registry: &'a TypeRegistry,
+    type_path: &str,
+) -> Result<&'a ReflectComponent, RemoteError> {
+    registry
+        .get_with_type_path(type_path)
+        .and_then(|registration| registration.data::<ReflectComponent>())
+        .ok_or_else(|| unknown_component(type_path))
+}
+
+fn unknown_component(type_path: &str) -> RemoteError {
+    RemoteError::new(
+        RemoteError::COMPONENT_ERROR,
+        format!("`{type_path}` isn't a registered component"),
+    )
+}
+
+fn component_value(
+    world: &World,
+    entity: Entity,
+    type_path: &str,
+    registry: &TypeRegistry,
+) -> Result<Value, RemoteError> {
+    let value = reflect_component(registry, type_path)?
+        .reflect(world.entity(entity))
+        .ok_or_else(|| {
+            RemoteError::new(
+                RemoteError::COMPONENT_NOT_PRESENT,
+                format!("entity has no `{type_path}`"),
+            )
+        })?;
+    serialize_value(value.as_partial_reflect(), registry)
+}
+
+fn serialize_value(
+    value: &dyn PartialReflect,
+    registry: &TypeRegistry,
+) -> Result<Value, RemoteError> {
+    serde_json::to_value(TypedReflectSerializer::new(value, registry))
+        .map_err(|err| RemoteError::new(RemoteError::COMPONENT_ERROR, err.to_string()))
+}
+
+fn to_result(value: impl serde::Serialize) -> Result<Value, RemoteError> {
+    serde_json::to_value(value)
+        .map_err(|err| RemoteError::new(RemoteError::INTERNAL_ERROR, err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_editor_formats::{send_remote_request, QueryData};
+    use serde_json::Map;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component, Default)]
+    struct Health(f32);
+
+    /// Send `request` from another thread while the app keeps updating
+    fn request(app: &mut App, method: &str, params: impl serde::Serialize) -> RemoteResponse {
+        let address = app.world().resource::<RemoteServerAddress>().0;
+        let request = RemoteRequest::new(1, method, params);
+        let client = std::thread::spawn(move || {
+            send_remote_request(address, &request, Duration::from_secs(5))
+        });
+        while !client.is_finished() {
+            app.update();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        client.join().unwrap().unwrap()
+    }
+
+    #[test]
+    fn components_are_queried_and_edited_remotely() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .register_type::<Health>()
+            .register_type::<Name>()
+            .add_plugins(EditorRemotePlugin { port: 0 });
+        let player = app
+            .world_mut()
+            .spawn((Name::new("Player"), Health(10.0)))
+            .id();
+
+        let rows: Vec<QueryRow> = request(
+            &mut app,
+            BRP_QUERY,
+            QueryParams {
+                data: QueryData {
+                    components: vec![Health::type_path().to_string()],
+                    option: vec![Name::type_path().to_string()],
+                    ..default()
+                },
+                ..default()
+            },
+        )
+        .into_result()
+        .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].entity, player.to_bits());
+        assert_eq!(rows[0].components[Name::type_path()], "Player");
+        assert_eq!(rows[0].components[Health::type_path()], 10.0);
+
+        let mut components = Map::new();
+        components.insert(Health::type_path().to_string(), 25.0.into());
+        request(
+            &mut app,
+            BRP_INSERT,
+            InsertParams {
+                entity: player.to_bits(),
+                components,
+            },
+        )
+        .into_result::<Value>()
+        .unwrap();
+        assert_eq!(app.world().get::<Health>(player).unwrap().0, 25.0);
+
+        let listed: Vec<String> = request(
+            &mut app,
+            BRP_LIST,
+            ListParams {
+                entity: Some(player.to_bits()),
+            },
+        )
+        .into_result()
+        .unwrap();
+        assert!(listed.contains(&Health::type_path().to_string()));
+
+        let missing = request(
+            &mut app,
+            BRP_GET,
+            GetParams {
+                entity: player.to_bits(),
+                components: vec!["game::Missing".to_string()],
+                strict: true,
+            },
+        );
+        assert_eq!(
+            missing.into_result::<Value>().unwrap_err().code,
+            RemoteError::COMPONENT_ERROR
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Rendering the tilemap of a level saved by the editor with bevy_ecs_tilemap.
+
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_editor_formats::{
+    crop_tileset_margin, BevyScene, LevelTilemapData, LevelTilesetData, MapOrientation,
+};
+use std::path::Path;
+
+/// Plugin finishing the tilemaps spawned by [`spawn_level_tilemap`].
+///
+/// Rendering needs bevy_ecs_tilemap's `TilemapPlugin` as well.
+pub struct LevelTilemapPlugin;
+
+impl Plugin for LevelTilemapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, crop_tileset_margins);
+    }
+}
+
+/// Outer margin of the tileset a tilemap is rendered from, waiting to be
+/// cropped off once the image has loaded
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TilesetMargin(pub u32);
+
+/// Read the tilemap of a level (`.bscene`) file, `None` if the level has none
+pub fn load_level_tilemap(
+    path: impl AsRef<Path>,
+) -> Result<Option<LevelTilemapData>, Box<dyn std::error::Error>> {
+    Ok(BevyScene::load_from_file(path)?.data.tilemap)
+}
+
+/// Spawn a level's tilemap: one bevy_ecs_tilemap tilemap per layer and tileset,
+/// laid out like the editor's map canvases. Returns the root entity.
+pub fn spawn_level_tilemap(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    tilemap: &LevelTilemapData,
+) -> Entity {
+    let root = commands
+        .spawn((
+            Name::new("Level Tilemap"),
+            Transform::default(),
+            Visibility::default(),
+        ))
+        .id();
+
+    let map_size = TilemapSize {
+        x: tilemap.map_width,
+        y: tilemap.map_height,
+    };
+    for (z, layer) in tilemap.layers.iter().enumerate() {
+        for tileset in &tilemap.tilesets {
+            // Files written before tiles were tileset-qualified use the selected tileset
+            let tiles: Vec<_> = layer
+                .tiles
+                .iter()
+                .filter(|tile| tile.tileset_id.or(tilemap.selected_tileset_id) == Some(tileset.id))
+                .collect();
+            if tiles.is_empty() {
+                continue;
+            }
+
+            let tilemap_entity = commands.spawn_empty().id();
+            let mut storage = TileStorage::empty(map_size);
+            for tile in tiles {
+                let position = TilePos {
+                    x: tile.x,
+                    y: tile.y,
+                };
+                if !position.within_map_bounds(&map_size) {
+                    continue;
+                }
+                let tile_entity = commands
+                    .spawn(TileBundle {
+                        position,
+                        tilemap_id: TilemapId(tilemap_entity),
+                        texture_index: TileTextureIndex(tile.tile_id),
+                        ..default()
+                    })
+                    .id();
+                storage.set(&position, tile_entity);
+            }
+
+            // Like the editor, cells are the tileset's tile size, offset by half a
+            // cell so square cells start on grid intersections
+            let tile_size = TilemapTileSize {
+                x: tileset.tile_width as f32,
+                y: tileset.tile_height as f32,
+            };
+            let origin = Vec2::from(tile_size) / 2.0;
+            let mut tilemap_commands = commands.entity(tilemap_entity);
+            tilemap_commands.insert((
+                Name::new(format!("{} [{}]", layer.name, tileset.identifier)),
+                TilemapBundle {
+                    grid_size: tile_size.into(),
+                    map_type: tilemap_type(tilemap.orientation),
+                    size: map_size,
+                    storage,
+                    texture: TilemapTexture::Single(asset_server.load(&tileset.texture_path)),
+                    tile_size,
+                    spacing: tileset_spacing(tileset),
+                    transform: Transform::from_xyz(origin.x, origin.y, z as f32),
+                    visibility: if layer.visible {
+                        Visibility::Inherited
+                    } else {
+                        Visibility::Hidden
+                    },
+                    ..default()
+                },
+                ChildOf(root),
+            ));
+            if tileset.padding > 0 {
+                tilemap_commands.insert(TilesetMargin(tileset.padding));
+            }
+        }
+    }
+    root
+}
+
+/// Gap between neighbouring tiles in the tileset image
+pub fn tileset_spacing(tileset: &LevelTilesetData) -> TilemapSpacing {
+    TilemapSpacing {
+        x: tileset.spacing as f32,
+        y: tileset.spacing as f32,
+    }
+}
+
+/// bevy_ecs_tilemap map type of a level's grid orientation
+pub fn tilemap_type(orientation: MapOrientation) -> TilemapType {
+    match orientation {
+        MapOrientation::Square => TilemapType::Square,
+        MapOrientation::IsometricDiamond => TilemapType::Isometric(IsoCoordSystem::Diamond),
+        MapOrientation::IsometricStaggered => TilemapType::Isometric(IsoCoordSystem::Staggered),
+        MapOrientation::HexRow => TilemapType::Hexagon(HexCoordSystem::RowOdd),
+        MapOrientation::HexColumn => TilemapType::Hexagon(HexCoordSystem::ColumnOdd),
+    }
+}
+
+/// System swapping the texture of tilemaps with a [`TilesetMargin`] for a copy
+/// without the margin, once their image has loaded
+fn crop_tileset_margins(
+    mut commands: Commands,
+    mut tilemaps: Query<(Entity, &TilesetMargin, &mut TilemapTexture)>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (entity, margin, mut texture) in &mut tilemaps {
+        let TilemapTexture::Single(handle) = &*texture else {
+            continue;
+        };
+        let Some(image) = images.get(handle) else {
+            continue;
+        };
+        match crop_tileset_margin(image, margin.0) {
+            Some(cropped) => *texture = TilemapTexture::Single(images.add(cropped)),
+            None => warn!(
+                "Could not crop {}px tileset margin, rendering without it",
+                margin.0
+            ),
+        }
+        commands.entity(entity).remove::<TilesetMargin>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_editor_formats::{LevelData, LevelTileInstance};
+
+    fn tileset(id: u32, tile_size: u32) -> LevelTilesetData {
+        LevelTilesetData {
+            id,
+            identifier: format!("tileset_{id}"),
+            texture_path: format!("tilesets/tileset_{id}.png"),
+            tile_width: tile_size,
+            tile_height: tile_size,
+            spacing: 0,
+            padding: 0,
+            collision_data: Default::default(),
+        }
+    }
+
+    fn tile(tileset_id: u32) -> LevelTileInstance {
+        LevelTileInstance {
+            x: 0,
+            y: 0,
+            tileset_id: Some(tileset_id),
+            tile_id: 0,
+        }
+    }
+
+    fn spawn_app(tilemap: LevelTilemapData) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>();
+        app.world_mut()
+            .run_system_once(
+                move |mut commands: Commands, asset_server: Res<AssetServer>| {
+                    spawn_level_tilemap(&mut commands, &asset_server, &tilemap);
+                },
+            )
+            .unwrap();
+        app
+    }
+
+    #[test]
+    fn gutter_tilesets_load_with_their_spacing() {
+        let mut level = LevelData::new("Gutters".to_string(), 256.0, 256.0);
+        let mut tilemap = LevelTilemapData::default();
+        tilemap.tilesets.push(LevelTilesetData {
+            id: 0,
+            identifier: "dungeon".to_string(),
+            texture_path: "tilesets/dungeon.png".to_string(),
+            tile_width: 16,
+            tile_height: 16,
+            spacing: 2,
+            padding: 1,
+            collision_data: Default::default(),
+        });
+        tilemap.layers[0].tiles.push(LevelTileInstance {
+            x: 3,
+            y: 4,
+            tileset_id: Some(0),
+            tile_id: 5,
+        });
+        level.tilemap = Some(tilemap);
+        let path = std::env::temp_dir().join(format!(
+            "bevy_editor_runtime_gutters_{}.bscene",
+            std::process::id()
+        ));
+        BevyScene::new(level).save_to_file(&path).unwrap();
+        let tilemap = load_level_tilemap(&path).unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut app = spawn_app(tilemap);
+        let world = app.world_mut();
+        let (spacing, tile_size, margin) = world
+            .query::<(&TilemapSpacing, &TilemapTileSize, Option<&TilesetMargin>)>()
+            .single(world)
+            .unwrap();
+        assert_eq!(*spacing, TilemapSpacing { x: 2.0, y: 2.0 });
+        assert_eq!(*tile_size, TilemapTileSize { x: 16.0, y: 16.0 });
+        assert_eq!(margin.map(|margin| margin.0), Some(1));
+    }
+
+    #[test]
+    fn tilemaps_use_the_map_orientation_and_their_tileset_cell_size() {
+        let mut tilemap = LevelTilemapData {
+            orientation: MapOrientation::IsometricDiamond,
+            ..default()
+        };
+        tilemap.tilesets = vec![tileset(0, 16), tileset(1, 32)];
+        tilemap.layers[0].tiles = vec![tile(0), tile(1)];
+        let mut app = spawn_app(tilemap);
+
+        let world = app.world_mut();
+        let mut tilemaps: Vec<_> = world
+            .query::<(&TilemapType, &TilemapGridSize, &Transform)>()
+            .iter(world)
+            .map(|(map_type, grid_size, transform)| (*map_type, *grid_size, transform.translation))
+            .collect();
+        tilemaps.sort_by(|a, b| a.1.x.total_cmp(&b.1.x));
+        assert_eq!(
+            tilemaps,
+            vec![
+                (
+                    TilemapType::Isometric(IsoCoordSystem::Diamond),
+                    TilemapGridSize { x: 16.0, y: 16.0 },
+                    Vec3::new(8.0, 8.0, 0.0),
+                ),
+                (
+                    TilemapType::Isometric(IsoCoordSystem::Diamond),
+                    TilemapGridSize { x: 32.0, y: 32.0 },
+                    Vec3::new(16.0, 16.0, 0.0),
+                ),
+            ]
+        );
+    }
+}
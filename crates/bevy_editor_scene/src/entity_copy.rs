@@ -0,0 +1,339 @@
+//! Copying scene entities with their children as text
+//!
+//! [`copy_entities`] writes entity subtrees as a small `.scn.ron` scene,
+//! which is what the editor puts on the system clipboard, so entities can be
+//! pasted into another scene or another editor instance. [`paste_entities`]
+//! spawns such a copy with new [`EditorEntityId`]s; references between the
+//! copied entities are pointed at their copies. [`restore_entities`] spawns
+//! it with the original ids instead, for undoing a delete.
+
+use crate::{deserialize_scene, EditorEntityId, EditorSceneEntity, EditorViewState};
+use bevy::asset::uuid::Uuid;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+use bevy::scene::DynamicSceneBuilder;
+use bevy_editor_formats::{expand_game_components, EntityFields, FieldValue};
+use std::collections::{HashMap, HashSet};
+
+/// First line of text written by [`copy_entities`], telling copied entities
+/// apart from other clipboard contents
+pub const COPIED_ENTITIES_HEADER: &str = "// bevy_editor entities\n";
+
+/// Whether `text` was written by [`copy_entities`]
+pub fn is_copied_entities(text: &str) -> bool {
+    text.starts_with(COPIED_ENTITIES_HEADER)
+}
+
+/// Write `roots` and all their descendants as scene text. Roots inside
+/// another root's subtree are copied once, as part of that subtree.
+pub fn copy_entities(
+    world: &mut World,
+    roots: &[Entity],
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut entities = Vec::new();
+    let mut stack: Vec<Entity> = roots.iter().rev().copied().collect();
+    while let Some(entity) = stack.pop() {
+        if entities.contains(&entity) || world.get_entity(entity).is_err() {
+            continue;
+        }
+        entities.push(entity);
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter().rev());
+        }
+    }
+    if entities.is_empty() {
+        return Err("no entities to copy".into());
+    }
+    let copied: HashSet<Entity> = entities.iter().copied().collect();
+
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .deny_component::<bevy::render::view::visibility::VisibilityClass>()
+        .deny_component::<EditorViewState>()
+        .extract_entities(entities.into_iter())
+        .build();
+
+    // Roots are pasted under a new parent, so their old one isn't copied
+    for entity in &mut scene.entities {
+        let parent = world.get::<ChildOf>(entity.entity).map(ChildOf::parent);
+        if parent.is_some_and(|parent| !copied.contains(&parent)) {
+            entity
+                .components
+                .retain(|component| !is_type::<ChildOf>(component.as_ref()));
+        }
+    }
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let scene = expand_game_components(&scene.serialize(&type_registry.read())?)?;
+    Ok(format!("{COPIED_ENTITIES_HEADER}{scene}"))
+}
+
+/// Spawn the entities of `text`, written by [`copy_entities`], and return the
+/// copied roots. Roots are moved by `offset` and parented to `parent`.
+///
+/// The copies get the ids in `ids`, in the order of the copied scene, when
+/// it holds one per entity; otherwise new ids are generated and stored there,
+/// so pasting the same text again with them recreates the same entities.
+pub fn paste_entities(
+    world: &mut World,
+    text: &str,
+    parent: Option<Entity>,
+    offset: Vec3,
+    ids: &mut Vec<EditorEntityId>,
+) -> Result<Vec<Entity>, Box<dyn std::error::Error>> {
+    let Some(scene) = text.strip_prefix(COPIED_ENTITIES_HEADER) else {
+        return Err("text doesn't hold copied entities".into());
+    };
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let mut scene = deserialize_scene(scene, &type_registry.read())?;
+
+    // The originals may still be live, so the copies don't keep their ids
+    let mut old_ids = Vec::new();
+    for entity in &mut scene.entities {
+        let id = entity
+            .components
+            .iter()
+            .find(|component| is_type::<EditorEntityId>(component.as_ref()))
+            .and_then(|component| EditorEntityId::from_reflect(component.as_ref()));
+        old_ids.push(id);
+        entity
+            .components
+            .retain(|component| !is_type::<EditorEntityId>(component.as_ref()));
+    }
+    if ids.len() != scene.entities.len() {
+        *ids = scene
+            .entities
+            .iter()
+            .map(|_| EditorEntityId::new())
+            .collect();
+    }
+
+    let mut entity_map = EntityHashMap::default();
+    scene.write_to_world(world, &mut entity_map)?;
+
+    let mut pasted = Vec::new();
+    let mut new_ids = HashMap::new();
+    for ((entity, old_id), id) in scene.entities.iter().zip(old_ids).zip(ids.iter()) {
+        let Some(&entity) = entity_map.get(&entity.entity) else {
+            continue;
+        };
+        world.entity_mut(entity).insert((*id, EditorSceneEntity));
+        if let Some(old_id) = old_id {
+            new_ids.insert(old_id.0, id.0);
+        }
+        pasted.push(entity);
+    }
+
+    let mut roots = Vec::new();
+    for &entity in &pasted {
+        let mut entity_mut = world.entity_mut(entity);
+        if let Some(mut fields) = entity_mut.get_mut::<EntityFields>() {
+            for value in fields.values.values_mut() {
+                remap_entity_refs(value, &new_ids);
+            }
+        }
+        if entity_mut.contains::<ChildOf>() {
+            continue;
+        }
+        if let Some(mut transform) = entity_mut.get_mut::<Transform>() {
+            transform.translation += offset;
+        }
+        if let Some(parent) = parent {
+            entity_mut.insert(ChildOf(parent));
+        }
+        roots.push(entity);
+    }
+    Ok(roots)
+}
+
+/// Spawn the entities of `text`, written by [`copy_entities`], again with the
+/// ids they were copied with, to bring back deleted entities. Roots are
+/// parented to `parent`.
+pub fn restore_entities(
+    world: &mut World,
+    text: &str,
+    parent: Option<Entity>,
+) -> Result<Vec<Entity>, Box<dyn std::error::Error>> {
+    let Some(scene) = text.strip_prefix(COPIED_ENTITIES_HEADER) else {
+        return Err("text doesn't hold copied entities".into());
+    };
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let scene = deserialize_scene(scene, &type_registry.read())?;
+    let mut ids = scene
+        .entities
+        .iter()
+        .map(|entity| {
+            entity
+                .components
+                .iter()
+                .find(|component| is_type::<EditorEntityId>(component.as_ref()))
+                .and_then(|component| EditorEntityId::from_reflect(component.as_ref()))
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or("copied entities without ids can't be restored")?;
+    paste_entities(world, text, parent, Vec3::ZERO, &mut ids)
+}
+
+fn is_type<T: TypePath>(component: &dyn PartialReflect) -> bool {
+    component
+        .get_represented_type_info()
+        .is_some_and(|info| info.type_path() == T::type_path())
+}
+
+fn remap_entity_refs(value: &mut FieldValue, new_ids: &HashMap<Uuid, Uuid>) {
+    match value {
+        FieldValue::EntityRef(Some(id)) => {
+            if let Some(new_id) = new_ids.get(id) {
+                *id = *new_id;
+            }
+        }
+        FieldValue::Array(values) => {
+            for value in values {
+                remap_entity_refs(value, new_ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SceneEditorPlugin;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(SceneEditorPlugin)
+            .register_type::<ChildOf>()
+            .register_type::<Children>();
+        app.world_mut().insert_resource(Assets::<Image>::default());
+        app.update();
+        app
+    }
+
+    #[test]
+    fn pasted_subtrees_get_new_ids_and_offset() {
+        let mut app = app();
+        let world = app.world_mut();
+        let root = world.spawn((Name::new("Root"), Transform::default())).id();
+        let parent = world
+            .spawn((
+                Name::new("Parent"),
+                Transform::from_xyz(10.0, 20.0, 0.0),
+                EditorSceneEntity,
+                ChildOf(root),
+            ))
+            .id();
+        let child_id = EditorEntityId::new();
+        let child = world
+            .spawn((
+                Name::new("Child"),
+                Transform::from_xyz(1.0, 0.0, 0.0),
+                EditorSceneEntity,
+                child_id,
+                ChildOf(parent),
+            ))
+            .id();
+        world.entity_mut(parent).insert(EntityFields {
+            definition_id: 1,
+            values: [(
+                "target".to_string(),
+                FieldValue::EntityRef(Some(child_id.0)),
+            )]
+            .into_iter()
+            .collect(),
+        });
+
+        let text = copy_entities(world, &[parent, child]).unwrap();
+        assert!(is_copied_entities(&text));
+
+        let mut ids = Vec::new();
+        let roots = paste_entities(
+            world,
+            &text,
+            Some(root),
+            Vec3::new(16.0, -16.0, 0.0),
+            &mut ids,
+        )
+        .unwrap();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(ids.len(), 2);
+        let copy = roots[0];
+        assert_ne!(copy, parent);
+        assert_eq!(world.get::<ChildOf>(copy).unwrap().parent(), root);
+        assert_eq!(
+            world.get::<Transform>(copy).unwrap().translation,
+            Vec3::new(26.0, 4.0, 0.0)
+        );
+
+        let children = world.get::<Children>(copy).unwrap().to_vec();
+        assert_eq!(children.len(), 1);
+        let child_copy = children[0];
+        assert_eq!(world.get::<Name>(child_copy).unwrap().as_str(), "Child");
+        assert_eq!(
+            world.get::<Transform>(child_copy).unwrap().translation.x,
+            1.0
+        );
+        let child_copy_id = *world.get::<EditorEntityId>(child_copy).unwrap();
+        assert_ne!(child_copy_id, child_id);
+        assert!(ids.contains(&child_copy_id));
+        assert_eq!(
+            world.get::<EntityFields>(copy).unwrap().values["target"],
+            FieldValue::EntityRef(Some(child_copy_id.0))
+        );
+
+        // Pasting again with the same ids recreates the same entities
+        world.entity_mut(copy).despawn();
+        let pasted_ids = ids.clone();
+        paste_entities(world, &text, Some(root), Vec3::ZERO, &mut ids).unwrap();
+        assert_eq!(ids, pasted_ids);
+        assert!(world
+            .resource::<crate::EditorEntityIndex>()
+            .contains(child_copy_id));
+    }
+
+    #[test]
+    fn other_text_is_not_pasted() {
+        let mut app = app();
+        let result = paste_entities(app.world_mut(), "hello", None, Vec3::ZERO, &mut Vec::new());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn restored_subtrees_keep_their_ids_and_components() {
+        let mut app = app();
+        let world = app.world_mut();
+        let root = world.spawn((Name::new("Root"), Transform::default())).id();
+        let parent_id = EditorEntityId::new();
+        let parent = world
+            .spawn((
+                Name::new("Parent"),
+                Transform::from_xyz(10.0, 20.0, 0.0),
+                Visibility::Hidden,
+                EditorSceneEntity,
+                parent_id,
+                ChildOf(root),
+            ))
+            .id();
+        let child_id = EditorEntityId::new();
+        world.spawn((
+            Name::new("Child"),
+            Transform::default(),
+            EditorSceneEntity,
+            child_id,
+            ChildOf(parent),
+        ));
+
+        let text = copy_entities(world, &[parent]).unwrap();
+        world.entity_mut(parent).despawn();
+        let roots = restore_entities(world, &text, Some(root)).unwrap();
+
+        assert_eq!(roots.len(), 1);
+        let restored = roots[0];
+        assert_eq!(*world.get::<EditorEntityId>(restored).unwrap(), parent_id);
+        assert_eq!(world.get::<ChildOf>(restored).unwrap().parent(), root);
+        assert_eq!(world.get::<Visibility>(restored), Some(&Visibility::Hidden));
+        let children = world.get::<Children>(restored).unwrap().to_vec();
+        assert_eq!(*world.get::<EditorEntityId>(children[0]).unwrap(), child_id);
+    }
+}
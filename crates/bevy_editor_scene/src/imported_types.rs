@@ -0,0 +1,171 @@
+//! Scene files with components of the game's own types
+//!
+//! The editor doesn't register the game's types, so scene files are read with
+//! [`load_scene_file`] rather than Bevy's scene loader: components of unknown
+//! types are kept in [`GameComponents`] and written back out when saving.
+//! [`ImportedTypes`] holds the types the game exported, which the inspector
+//! uses to edit and add those components.
+
+use bevy::prelude::*;
+use bevy::reflect::TypeRegistry;
+use bevy::scene::serde::SceneDeserializer;
+use bevy::scene::{DynamicScene, DynamicSceneRoot};
+use bevy_editor_formats::{collect_game_components, TypeSchemaFile, TypeSchemas};
+use serde::de::DeserializeSeed;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Reflected types of the project's game, exported by `bevy_editor_runtime`
+/// when the editor runs the game
+#[derive(Resource, Default)]
+pub struct ImportedTypes {
+    pub schemas: TypeSchemas,
+    /// File the schemas were read from and its modification time
+    source: Option<(PathBuf, Option<SystemTime>)>,
+}
+
+impl ImportedTypes {
+    /// Whether `path` changed since the types were last read from it
+    pub fn is_stale(&self, path: &Path) -> bool {
+        match &self.source {
+            Some((source, modified)) => source != path || *modified != modified_time(path),
+            None => true,
+        }
+    }
+
+    /// Read the types from `path`, or forget them if it doesn't exist
+    pub fn reload(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let modified = modified_time(path);
+        self.source = Some((path.to_path_buf(), modified));
+        self.schemas = match modified {
+            Some(_) => TypeSchemaFile::load_from_file(path)?.into(),
+            None => TypeSchemas::default(),
+        };
+        Ok(())
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Scene file to load into this scene root, see [`load_scene_file`]
+#[derive(Component, Debug, Clone)]
+pub struct SceneFile(pub String);
+
+/// Read a `.scn.ron` file, keeping components of types missing from
+/// `type_registry` in [`bevy_editor_formats::GameComponents`]
+pub fn load_scene_file(
+    path: impl AsRef<Path>,
+    type_registry: &TypeRegistry,
+) -> Result<DynamicScene, Box<dyn std::error::Error>> {
+    deserialize_scene(&std::fs::read_to_string(path)?, type_registry)
+}
+
+/// Read a scene from RON text the way [`load_scene_file`] reads files
+pub fn deserialize_scene(
+    scene: &str,
+    type_registry: &TypeRegistry,
+) -> Result<DynamicScene, Box<dyn std::error::Error>> {
+    let scene = collect_game_components(scene, |type_path| {
+        type_registry.get_with_type_path(type_path).is_some()
+    })?;
+    let mut deserializer = ron::de::Deserializer::from_str(&scene)?;
+    let scene = SceneDeserializer { type_registry }.deserialize(&mut deserializer)?;
+    Ok(scene)
+}
+
+/// Load the scene files of [`SceneFile`] roots and spawn them
+pub fn load_scene_files(
+    mut commands: Commands,
+    roots: Query<(Entity, &SceneFile)>,
+    type_registry: Res<AppTypeRegistry>,
+    scenes: Option<ResMut<Assets<DynamicScene>>>,
+) {
+    // Headless apps without scene assets can't load scene files
+    let Some(mut scenes) = scenes else {
+        return;
+    };
+    for (root, SceneFile(path)) in &roots {
+        let mut root = commands.entity(root);
+        root.remove::<SceneFile>();
+        match load_scene_file(path, &type_registry.read()) {
+            Ok(scene) => {
+                root.insert(DynamicSceneRoot(scenes.add(scene)));
+            }
+            Err(err) => error!("Failed to load scene {}: {}", path, err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{save_editor_scene_to_file, EditorSceneEntity, SceneEditorPlugin};
+    use bevy_editor_formats::GameComponents;
+
+    #[test]
+    fn game_components_survive_load_and_save() {
+        let dir = std::env::temp_dir().join(format!("bevy_editor_scene_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("level.scn.ron");
+        std::fs::write(
+            &path,
+            r#"(
+  resources: {},
+  entities: {
+    4294967296: (
+      components: {
+        "bevy_ecs::name::Name": "Player",
+        "game::Health": (
+          current: 10.0,
+        ),
+      },
+    ),
+  },
+)"#,
+        )
+        .unwrap();
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(SceneEditorPlugin);
+        app.world_mut().insert_resource(Assets::<Image>::default());
+        app.update();
+
+        let scene =
+            load_scene_file(&path, &app.world().resource::<AppTypeRegistry>().read()).unwrap();
+        let world = app.world_mut();
+        scene
+            .write_to_world(world, &mut Default::default())
+            .unwrap();
+        let mut query = world.query::<(Entity, &GameComponents)>();
+        let (entity, components) = query.single(world).unwrap();
+        assert_eq!(
+            components.get("game::Health").unwrap().ron,
+            "(\n  current: 10.0,\n)"
+        );
+        world.entity_mut(entity).insert(EditorSceneEntity);
+
+        save_editor_scene_to_file(world, path.to_str().unwrap()).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(!saved.contains("GameComponents"), "{saved}");
+        assert!(
+            saved.contains("\"game::Health\": (\n          current: 10.0,\n        ),"),
+            "{saved}"
+        );
+    }
+
+    #[test]
+    fn missing_schema_files_clear_imported_types() {
+        let path = std::env::temp_dir().join("bevy_editor_scene_missing_types.json");
+        let mut imported = ImportedTypes::default();
+        assert!(imported.is_stale(&path));
+        imported.reload(&path).unwrap();
+        assert!(!imported.is_stale(&path));
+        assert!(imported.schemas.is_empty());
+    }
+}
@@ -1,26 +1,230 @@
 //! Scene editor module for managing game entities (separate from tilemap)
 //! Uses Bevy's DynamicScene for serialization/deserialization
 
+use bevy::asset::uuid::Uuid;
+use bevy::ecs::component::HookContext;
+use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
+use bevy::render::view::VisibilitySystems;
 use bevy::scene::{DynamicScene, DynamicSceneBuilder, DynamicSceneRoot};
-use bevy_editor_formats::{BevyScene, LevelData};
+use bevy_editor_commands::EditorHistory;
+use bevy_editor_formats::{
+    expand_game_components, BevyScene, EntityFields, GameComponents, LevelData,
+};
+use bevy_editor_foundation::Selection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
+mod entity_copy;
+mod imported_types;
+mod play_mode;
+
+pub use entity_copy::{
+    copy_entities, is_copied_entities, paste_entities, restore_entities, COPIED_ENTITIES_HEADER,
+};
+pub use imported_types::{
+    deserialize_scene, load_scene_file, load_scene_files, ImportedTypes, SceneFile,
+};
+pub use play_mode::{
+    handle_play_mode_requests, run_play_mode_schedule, start_play, stop_play, PlayMode,
+    PlayModeRequest, PlayModeUpdate, PlayState,
+};
+
 /// Marker component for entities that are part of the edited scene
 /// (not editor UI elements)
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct EditorSceneEntity;
 
+/// Editor-only lock and hide state of a scene entity, toggled from the scene
+/// tree. Locked entities can't be picked in the viewport and hidden ones
+/// aren't drawn, while their [`Visibility`] stays as the game sees it.
+///
+/// Tab switches and play mode keep it, but it's never written to scene files
+/// or copied entities, so games don't know about it.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct EditorViewState {
+    pub locked: bool,
+    pub hidden: bool,
+}
+
+/// System hiding [`EditorViewState::hidden`] entities from every view once
+/// visibility has been checked, without touching their [`Visibility`].
+pub fn hide_editor_hidden_entities(mut query: Query<(&EditorViewState, &mut ViewVisibility)>) {
+    for (view_state, mut view_visibility) in &mut query {
+        if view_state.hidden && view_visibility.get() {
+            *view_visibility = ViewVisibility::HIDDEN;
+        }
+    }
+}
+
+/// Identifier of a scene entity that survives despawning and reloading the
+/// scene, unlike [`Entity`]. Saved with the scene, so commands and references
+/// should store this and resolve it with [`find_entity_by_id`] when applied.
+///
+/// Ids are unique among live entities: an entity inserted with an id another
+/// live entity already has is a copy (duplicate, paste, loading a scene
+/// twice) and gets a fresh id, while moving an entity keeps its id.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[reflect(Component)]
+#[component(on_insert = index_editor_entity_id, on_replace = unindex_editor_entity_id)]
+pub struct EditorEntityId(pub Uuid);
+
+impl EditorEntityId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for EditorEntityId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lookup from [`EditorEntityId`] to the live [`Entity`] carrying it, kept up
+/// to date by the component's hooks.
+#[derive(Resource, Default, Debug)]
+pub struct EditorEntityIndex {
+    entities: HashMap<EditorEntityId, Entity>,
+}
+
+impl EditorEntityIndex {
+    pub fn get(&self, id: EditorEntityId) -> Option<Entity> {
+        self.entities.get(&id).copied()
+    }
+
+    pub fn contains(&self, id: EditorEntityId) -> bool {
+        self.entities.contains_key(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EditorEntityId, Entity)> + '_ {
+        self.entities.iter().map(|(id, entity)| (*id, *entity))
+    }
+}
+
+fn index_editor_entity_id(mut world: DeferredWorld, context: HookContext) {
+    let Some(id) = world.get::<EditorEntityId>(context.entity).copied() else {
+        return;
+    };
+    let Some(mut index) = world.get_resource_mut::<EditorEntityIndex>() else {
+        return;
+    };
+
+    let owner = *index.entities.entry(id).or_insert(context.entity);
+    if owner != context.entity {
+        // A copy of a live entity; replacing the id re-runs this hook
+        debug!(
+            "Entity {:?} copies id {:?} of {:?}, assigning a new one",
+            context.entity, id, owner
+        );
+        world
+            .commands()
+            .entity(context.entity)
+            .insert(EditorEntityId::new());
+    }
+}
+
+fn unindex_editor_entity_id(mut world: DeferredWorld, context: HookContext) {
+    let Some(id) = world.get::<EditorEntityId>(context.entity).copied() else {
+        return;
+    };
+    if let Some(mut index) = world.get_resource_mut::<EditorEntityIndex>() {
+        if index.entities.get(&id) == Some(&context.entity) {
+            index.entities.remove(&id);
+        }
+    }
+}
+
+/// Find the live entity carrying `id`, if it is currently spawned.
+pub fn find_entity_by_id(world: &mut World, id: EditorEntityId) -> Option<Entity> {
+    if let Some(index) = world.get_resource::<EditorEntityIndex>() {
+        return index.get(id);
+    }
+    world
+        .query::<(Entity, &EditorEntityId)>()
+        .iter(world)
+        .find(|(_, entity_id)| **entity_id == id)
+        .map(|(entity, _)| entity)
+}
+
+/// Ids of the selected entities, primary last, so the selection can be
+/// restored with [`EditorScene::pending_selection`] once the scene is respawned.
+pub fn selection_ids(world: &World) -> Vec<EditorEntityId> {
+    let Some(selection) = world.get_resource::<Selection>() else {
+        return Vec::new();
+    };
+    selection
+        .iter()
+        .filter(|entity| Some(*entity) != selection.primary())
+        .chain(selection.primary())
+        .filter_map(|entity| world.get::<EditorEntityId>(entity).copied())
+        .collect()
+}
+
+/// System selecting [`EditorScene::pending_selection`] once all its entities
+/// are spawned.
+pub fn restore_pending_selection(
+    index: Res<EditorEntityIndex>,
+    mut editor_scene: ResMut<EditorScene>,
+    mut selection: ResMut<Selection>,
+) {
+    if editor_scene.pending_selection.is_empty() {
+        return;
+    }
+    let entities: Option<Vec<Entity>> = editor_scene
+        .pending_selection
+        .iter()
+        .map(|id| index.get(*id))
+        .collect();
+    if let Some(entities) = entities {
+        editor_scene.pending_selection.clear();
+        selection.set(entities);
+    }
+}
+
+/// System giving every scene entity without an [`EditorEntityId`] a new one.
+pub fn assign_editor_entity_ids(
+    mut commands: Commands,
+    unassigned: Query<
+        Entity,
+        (
+            With<EditorSceneEntity>,
+            Without<EditorEntityId>,
+            Without<LoadingSceneRoot>,
+        ),
+    >,
+) {
+    for entity in &unassigned {
+        commands.entity(entity).insert(EditorEntityId::new());
+    }
+}
+
 /// Resource managing the currently edited scene
 #[derive(Resource, Default)]
 pub struct EditorScene {
     /// Root entity that all scene entities are parented to
     pub root_entity: Option<Entity>,
-    /// Currently selected entity in the scene tree
-    pub selected_entity: Option<Entity>,
-    /// Whether the scene has unsaved changes
+    /// Selection to restore once the entities with these ids are spawned,
+    /// after a tab switch reloaded the scene. The selection itself is the
+    /// [`Selection`] resource.
+    pub pending_selection: Vec<EditorEntityId>,
+    /// Whether the scene has unsaved changes. Derived from the history save
+    /// point and `untracked_changes`, so don't set it directly.
     pub is_modified: bool,
+    /// Edits that weren't recorded in the undo history, so undoing can't
+    /// return the scene to its saved state.
+    pub untracked_changes: bool,
 }
 
 impl EditorScene {
@@ -37,33 +241,21 @@ impl EditorScene {
 
         Self {
             root_entity: Some(root_entity),
-            selected_entity: None,
+            pending_selection: Vec::new(),
             is_modified: false,
+            untracked_changes: false,
         }
     }
 
-    /// Select an entity in the scene tree
-    pub fn select_entity(&mut self, entity: Entity) {
-        self.selected_entity = Some(entity);
-    }
-
-    /// Clear selection
-    pub fn clear_selection(&mut self) {
-        self.selected_entity = None;
-    }
-
-    /// Check if an entity is selected
-    pub fn is_selected(&self, entity: Entity) -> bool {
-        self.selected_entity == Some(entity)
-    }
-
-    /// Mark scene as modified
+    /// Mark scene as modified by an edit that isn't recorded in the undo history
     pub fn mark_modified(&mut self) {
+        self.untracked_changes = true;
         self.is_modified = true;
     }
 
     /// Mark scene as saved
     pub fn mark_saved(&mut self) {
+        self.untracked_changes = false;
         self.is_modified = false;
     }
 }
@@ -76,13 +268,20 @@ pub struct SceneAutoLoader {
 }
 
 /// Represents a single open scene/level managed by the editor.
-#[derive(Clone)]
 pub struct OpenScene {
     pub name: String,
     pub file_path: Option<String>,
     pub level_data: LevelData,
     pub is_modified: bool,
+    /// [`EditorScene::untracked_changes`] while the scene is in the background.
+    pub untracked_changes: bool,
     pub runtime_scene: Option<Handle<DynamicScene>>,
+    /// Selected entities while the scene is in the background, primary last.
+    pub selection: Vec<EditorEntityId>,
+    /// Undo history while the scene is in the background. The active scene's
+    /// history lives in the [`EditorHistory`] resource and is swapped in and
+    /// out on tab change.
+    pub history: EditorHistory,
 }
 
 impl OpenScene {
@@ -92,7 +291,10 @@ impl OpenScene {
             file_path: None,
             level_data,
             is_modified: false,
+            untracked_changes: false,
             runtime_scene: None,
+            selection: Vec::new(),
+            history: EditorHistory::default(),
         }
     }
 
@@ -110,7 +312,10 @@ impl OpenScene {
             file_path: Some(path_ref.to_string_lossy().to_string()),
             level_data: scene.data,
             is_modified: false,
+            untracked_changes: false,
             runtime_scene: None,
+            selection: Vec::new(),
+            history: EditorHistory::default(),
         })
     }
 }
@@ -197,7 +402,8 @@ impl OpenScenes {
     pub fn get_active_scene_name(&self) -> Option<String> {
         self.active_scene().map(|scene| {
             // Extract just the filename without extension
-            scene.file_path
+            scene
+                .file_path
                 .as_ref()
                 .and_then(|path| {
                     std::path::Path::new(path)
@@ -243,12 +449,12 @@ pub struct LoadingSceneRoot;
 pub fn sync_active_scene(
     commands: &mut Commands,
     editor_scene: &mut EditorScene,
+    selection: &mut Selection,
     open_scenes: &OpenScenes,
-    asset_server: &AssetServer,
     new_index: usize,
     existing_entities: Vec<(Entity, Option<Entity>)>,
 ) {
-    editor_scene.selected_entity = None;
+    selection.clear();
 
     let existing_set: std::collections::HashSet<Entity> = existing_entities
         .iter()
@@ -268,11 +474,12 @@ pub fn sync_active_scene(
 
     if let Some(scene) = open_scenes.scenes.get(new_index) {
         editor_scene.is_modified = scene.is_modified;
+        editor_scene.untracked_changes = scene.untracked_changes;
+        editor_scene.pending_selection.clone_from(&scene.selection);
         if let Some(file_path) = &scene.file_path {
-            let scene_handle = asset_server.load::<DynamicScene>(file_path.clone());
             let root = commands
                 .spawn((
-                    DynamicSceneRoot(scene_handle),
+                    SceneFile(file_path.clone()),
                     EditorSceneEntity,
                     LoadingSceneRoot,
                 ))
@@ -310,8 +517,8 @@ pub trait SceneNameBuffer {
 pub fn apply_scene_tab_change<B>(
     commands: &mut Commands,
     editor_scene: &mut EditorScene,
+    selection: &mut Selection,
     open_scenes: &OpenScenes,
-    asset_server: &AssetServer,
     new_index: usize,
     existing_entities: Vec<(Entity, Option<Entity>)>,
     name_buffer: &mut B,
@@ -321,8 +528,8 @@ pub fn apply_scene_tab_change<B>(
     sync_active_scene(
         commands,
         editor_scene,
+        selection,
         open_scenes,
-        asset_server,
         new_index,
         existing_entities,
     );
@@ -432,14 +639,15 @@ pub fn save_editor_scene_to_file(
         }
     }
 
-    // Build DynamicScene from all scene entities
-    let scene_builder =
-        DynamicSceneBuilder::from_world(world).extract_entities(scene_entities.into_iter());
+    // Build DynamicScene from all scene entities, leaving out editor-only state
+    let scene_builder = DynamicSceneBuilder::from_world(world)
+        .deny_component::<EditorViewState>()
+        .extract_entities(scene_entities.into_iter());
     let dynamic_scene = scene_builder.build();
 
-    // Serialize to RON
+    // Serialize to RON, writing the game's components back out
     let type_registry = type_registry.read();
-    let ron_string = dynamic_scene.serialize(&type_registry)?;
+    let ron_string = expand_game_components(&dynamic_scene.serialize(&type_registry)?)?;
 
     // Write to file
     std::fs::write(scene_path, ron_string)?;
@@ -519,22 +727,125 @@ mod tests {
             .any(|name| name.as_str() == "Test Entity");
         assert!(found, "Deserialized scene should restore entity name");
     }
+
+    #[test]
+    fn editor_entity_id_survives_respawn() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(SceneEditorPlugin);
+        app.world_mut().insert_resource(Assets::<Image>::default());
+        app.update();
+
+        let entity = app
+            .world_mut()
+            .spawn((
+                Name::new("Tracked"),
+                Transform::default(),
+                EditorSceneEntity,
+            ))
+            .id();
+        app.update();
+
+        let id = *app
+            .world()
+            .get::<EditorEntityId>(entity)
+            .expect("scene entities are assigned an id");
+        assert_eq!(find_entity_by_id(app.world_mut(), id), Some(entity));
+
+        // Despawn and respawn the scene the way a tab switch does
+        let dynamic_scene = capture_editor_scene_runtime(app.world_mut());
+        app.world_mut().despawn(entity);
+        assert_eq!(find_entity_by_id(app.world_mut(), id), None);
+        dynamic_scene
+            .write_to_world(app.world_mut(), &mut EntityHashMap::default())
+            .expect("write scene back to world");
+
+        let respawned = find_entity_by_id(app.world_mut(), id).expect("id is restored");
+        assert_ne!(respawned, entity);
+        assert_eq!(
+            app.world().get::<Name>(respawned).map(|n| n.as_str()),
+            Some("Tracked")
+        );
+    }
+
+    #[test]
+    fn view_state_survives_tab_switches_but_not_saving() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(SceneEditorPlugin);
+        app.world_mut().insert_resource(Assets::<Image>::default());
+        app.update();
+
+        let view_state = EditorViewState {
+            locked: true,
+            hidden: true,
+        };
+        let entity = app
+            .world_mut()
+            .spawn((Name::new("Background"), EditorSceneEntity, view_state))
+            .id();
+        app.update();
+        let id = *app.world().get::<EditorEntityId>(entity).unwrap();
+
+        let dynamic_scene = capture_editor_scene_runtime(app.world_mut());
+        app.world_mut().despawn(entity);
+        dynamic_scene
+            .write_to_world(app.world_mut(), &mut EntityHashMap::default())
+            .expect("write scene back to world");
+        let respawned = find_entity_by_id(app.world_mut(), id).unwrap();
+        assert_eq!(app.world().get(respawned), Some(&view_state));
+
+        let path = std::env::temp_dir().join(format!(
+            "bevy_editor_scene_view_state_{}.scn.ron",
+            std::process::id()
+        ));
+        save_editor_scene_to_file(app.world_mut(), path.to_str().unwrap()).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(saved.contains("Background"), "{saved}");
+        assert!(!saved.contains("EditorViewState"), "{saved}");
+    }
+
+    #[test]
+    fn entity_index_follows_ids_and_rerolls_copies() {
+        let mut world = World::new();
+        world.init_resource::<EditorEntityIndex>();
+
+        let id = EditorEntityId::new();
+        let original = world.spawn(id).id();
+        assert_eq!(
+            world.resource::<EditorEntityIndex>().get(id),
+            Some(original)
+        );
+
+        // A second live entity with the same id is a copy and gets its own id
+        let copy = world.spawn(id).id();
+        world.flush();
+        let copy_id = *world.get::<EditorEntityId>(copy).unwrap();
+        assert_ne!(copy_id, id);
+        let index = world.resource::<EditorEntityIndex>();
+        assert_eq!(index.get(id), Some(original));
+        assert_eq!(index.get(copy_id), Some(copy));
+        assert_eq!(index.len(), 2);
+
+        // Moving an entity (despawn, then spawn again) keeps the id
+        world.despawn(original);
+        assert_eq!(world.resource::<EditorEntityIndex>().get(id), None);
+        let moved = world.spawn(id).id();
+        assert_eq!(find_entity_by_id(&mut world, id), Some(moved));
+    }
 }
 
-/// Load .scn.ron file into EditorScene using Bevy's asset system
+/// Load .scn.ron file into EditorScene, see [`load_scene_file`]
 pub fn load_editor_scene_from_file(
     commands: &mut Commands,
-    asset_server: &AssetServer,
     scene_path: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     info!("Loading scene from: {}", scene_path);
 
-    // Load scene using Bevy's asset system
-    let scene_handle: Handle<DynamicScene> = asset_server.load(scene_path);
-
     // Spawn scene into world with marker
     commands.spawn((
-        DynamicSceneRoot(scene_handle.clone()),
+        SceneFile(scene_path.to_string()),
         EditorSceneEntity, // Mark the root so we can find it
     ));
 
@@ -603,10 +914,12 @@ pub struct SpriteTextureEvent {
 }
 
 /// System to handle transform edit events
+///
+/// The undo layer records the edits in the history, which marks the scene
+/// modified.
 pub fn handle_transform_edit_events(
     mut events: EventReader<TransformEditEvent>,
     mut entity_query: Query<&mut Transform, With<EditorSceneEntity>>,
-    mut editor_scene: ResMut<EditorScene>,
 ) {
     for event in events.read() {
         match event {
@@ -614,7 +927,6 @@ pub fn handle_transform_edit_events(
                 if let Ok(mut transform) = entity_query.get_mut(*entity) {
                     transform.translation.x = position.x;
                     transform.translation.y = position.y;
-                    editor_scene.mark_modified();
                     info!("Set entity {:?} position to {:?}", entity, position);
                 }
             }
@@ -622,14 +934,12 @@ pub fn handle_transform_edit_events(
                 if let Ok(mut transform) = entity_query.get_mut(*entity) {
                     transform.translation.x += delta.x;
                     transform.translation.y += delta.y;
-                    editor_scene.mark_modified();
                     info!("Translated entity {:?} by {:?}", entity, delta);
                 }
             }
             TransformEditEvent::SetRotation { entity, rotation } => {
                 if let Ok(mut transform) = entity_query.get_mut(*entity) {
                     transform.rotation = Quat::from_rotation_z(*rotation);
-                    editor_scene.mark_modified();
                     info!("Set entity {:?} rotation to {}", entity, rotation);
                 }
             }
@@ -637,7 +947,6 @@ pub fn handle_transform_edit_events(
                 if let Ok(mut transform) = entity_query.get_mut(*entity) {
                     transform.scale.x = scale.x;
                     transform.scale.y = scale.y;
-                    editor_scene.mark_modified();
                     info!("Set entity {:?} scale to {:?}", entity, scale);
                 }
             }
@@ -646,47 +955,46 @@ pub fn handle_transform_edit_events(
 }
 
 /// System to handle name edit events
+///
+/// The undo layer records the renames in the history.
 pub fn handle_name_edit_events(
     mut events: EventReader<NameEditEvent>,
     mut entity_query: Query<&mut Name, With<EditorSceneEntity>>,
-    mut editor_scene: ResMut<EditorScene>,
 ) {
     for event in events.read() {
         if let Ok(mut name) = entity_query.get_mut(event.entity) {
             name.set(event.new_name.clone());
-            editor_scene.mark_modified();
             info!("Renamed entity {:?} to '{}'", event.entity, event.new_name);
         }
     }
 }
 
+/// `sprite` showing `texture`: untinted, and sized to the texture if it has
+/// loaded, otherwise at its natural size once it does
+pub fn sprite_with_texture(
+    sprite: &Sprite,
+    texture: &Handle<Image>,
+    images: &Assets<Image>,
+) -> Sprite {
+    Sprite {
+        image: texture.clone(),
+        color: Color::WHITE,
+        custom_size: images.get(texture).map(|image| image.size().as_vec2()),
+        ..sprite.clone()
+    }
+}
+
 /// System to handle sprite texture assignment events
+///
+/// The undo layer records the assignments in the history.
 pub fn handle_sprite_texture_events(
     mut events: EventReader<SpriteTextureEvent>,
     mut sprite_query: Query<&mut Sprite, With<EditorSceneEntity>>,
-    mut editor_scene: ResMut<EditorScene>,
     images: Res<Assets<Image>>,
 ) {
     for event in events.read() {
-        // Update sprite's image handle and reset color to white for proper texture display
         if let Ok(mut sprite) = sprite_query.get_mut(event.entity) {
-            sprite.image = event.texture_handle.clone();
-            // Set color to white so the texture displays without tinting
-            sprite.color = Color::WHITE;
-
-            // Update custom_size to match texture dimensions if texture is loaded
-            if let Some(image) = images.get(&event.texture_handle) {
-                sprite.custom_size = Some(image.size().as_vec2());
-                info!(
-                    "Set sprite custom_size to texture dimensions: {:?}",
-                    image.size()
-                );
-            } else {
-                // Texture not loaded yet - remove custom_size to use natural texture size when it loads
-                sprite.custom_size = None;
-            }
-
-            editor_scene.mark_modified();
+            *sprite = sprite_with_texture(&sprite, &event.texture_handle, &images);
             info!("Assigned texture to sprite entity {:?}", event.entity);
         } else {
             warn!(
@@ -703,8 +1011,12 @@ pub struct SceneEditorPlugin;
 impl Plugin for SceneEditorPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<EditorScene>()
+            .init_resource::<EditorEntityIndex>()
+            .init_resource::<Selection>()
             // Register marker component
             .register_type::<EditorSceneEntity>()
+            .register_type::<EditorEntityId>()
+            .register_type::<EditorViewState>()
             // Register core Bevy components for scene serialization
             .register_type::<Name>()
             .register_type::<Transform>()
@@ -714,10 +1026,19 @@ impl Plugin for SceneEditorPlugin {
             .register_type::<ViewVisibility>()
             // Register rendering components
             .register_type::<Sprite>()
+            // Custom entity fields (entity definitions)
+            .register_type::<EntityFields>()
+            // Components of the game's own types
+            .register_type::<GameComponents>()
+            .init_resource::<ImportedTypes>()
+            // Play-in-editor
+            .init_resource::<PlayMode>()
+            .init_schedule(PlayModeUpdate)
             // Events
             .add_event::<TransformEditEvent>()
             .add_event::<NameEditEvent>()
             .add_event::<SpriteTextureEvent>()
+            .add_event::<PlayModeRequest>()
             // Systems
             .add_systems(Startup, setup_editor_scene)
             .add_systems(
@@ -726,8 +1047,16 @@ impl Plugin for SceneEditorPlugin {
                     handle_transform_edit_events,
                     handle_name_edit_events,
                     handle_sprite_texture_events,
+                    load_scene_files,
                     tag_spawned_scene_entities, // Tag entities after scene loads
+                    assign_editor_entity_ids,
+                    restore_pending_selection,
+                    (handle_play_mode_requests, run_play_mode_schedule).chain(),
                 ),
+            )
+            .add_systems(
+                PostUpdate,
+                hide_editor_hidden_entities.after(VisibilitySystems::CheckVisibility),
             );
     }
 }
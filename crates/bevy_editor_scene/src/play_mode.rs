@@ -0,0 +1,418 @@
+//! Play-in-editor: run gameplay systems on the edited scene, then put it back
+//!
+//! [`PlayModeRequest::Play`] snapshots the scene with
+//! [`capture_editor_scene_runtime`] and runs the [`PlayModeUpdate`] schedule
+//! every frame until the play session stops. Games add their gameplay systems
+//! to that schedule:
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_editor_scene::PlayModeUpdate;
+//!
+//! fn fall(time: Res<Time>, mut query: Query<&mut Transform, With<Name>>) {
+//!     for mut transform in &mut query {
+//!         transform.translation.y -= 100.0 * time.delta_secs();
+//!     }
+//! }
+//!
+//! App::new().add_systems(PlayModeUpdate, fall);
+//! ```
+//!
+//! Stopping despawns everything gameplay spawned and restores the snapshot, so
+//! changes made while playing are thrown away. Entities marked with
+//! [`PlayMode::keep`] hold on to their play state instead.
+
+use bevy::ecs::entity::{EntityHashMap, EntityHashSet};
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+use bevy::scene::{DynamicScene, DynamicSceneBuilder};
+use bevy_editor_commands::EditorHistory;
+use bevy_editor_foundation::Selection;
+
+use crate::{
+    capture_editor_scene_runtime, selection_ids, EditorEntityId, EditorScene, EditorSceneEntity,
+};
+
+/// Schedule running the game's systems while the editor plays the scene
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayModeUpdate;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlayState {
+    /// Not playing, the scene is edited as usual
+    #[default]
+    Editing,
+    Playing,
+    /// Playing, but [`PlayModeUpdate`] doesn't run
+    Paused,
+}
+
+/// Play mode controls sent by UI.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayModeRequest {
+    /// Start playing, or resume when paused
+    Play,
+    Pause,
+    /// Stop playing and restore the scene. With `keep_changes`, every scene
+    /// entity keeps its play state instead.
+    Stop {
+        keep_changes: bool,
+    },
+}
+
+/// The running play session, if any
+#[derive(Resource, Default)]
+pub struct PlayMode {
+    state: PlayState,
+    /// The scene as it was when play started
+    snapshot: Option<DynamicScene>,
+    /// Scene entities whose play state survives Stop
+    kept: EntityHashSet,
+    /// Entities spawned by [`PlayModeUpdate`], despawned on Stop
+    spawned: Vec<Entity>,
+    root: Option<EditorEntityId>,
+    selection: Vec<EditorEntityId>,
+    /// Undo history of the edited scene. Edits made while playing get a
+    /// history of their own, dropped on Stop.
+    history: Option<EditorHistory>,
+}
+
+impl PlayMode {
+    pub fn state(&self) -> PlayState {
+        self.state
+    }
+
+    /// Whether a play session is running, paused or not
+    pub fn is_active(&self) -> bool {
+        self.state != PlayState::Editing
+    }
+
+    /// Keep the play state of a scene entity when play stops
+    pub fn keep(&mut self, entity: Entity, keep: bool) {
+        if keep {
+            self.kept.insert(entity);
+        } else {
+            self.kept.remove(&entity);
+        }
+    }
+
+    pub fn is_kept(&self, entity: Entity) -> bool {
+        self.kept.contains(&entity)
+    }
+}
+
+/// System handling [`PlayModeRequest`]s.
+pub fn handle_play_mode_requests(world: &mut World) {
+    let requests: Vec<PlayModeRequest> = world
+        .get_resource_mut::<Events<PlayModeRequest>>()
+        .map(|mut events| events.drain().collect())
+        .unwrap_or_default();
+
+    for request in requests {
+        match request {
+            PlayModeRequest::Play => start_play(world),
+            PlayModeRequest::Pause => {
+                let mut play_mode = world.resource_mut::<PlayMode>();
+                if play_mode.state == PlayState::Playing {
+                    play_mode.state = PlayState::Paused;
+                }
+            }
+            PlayModeRequest::Stop { keep_changes } => stop_play(world, keep_changes),
+        }
+    }
+}
+
+/// Snapshot the scene and start playing, or resume a paused session.
+pub fn start_play(world: &mut World) {
+    match world.resource::<PlayMode>().state {
+        PlayState::Playing => return,
+        PlayState::Paused => {
+            world.resource_mut::<PlayMode>().state = PlayState::Playing;
+            return;
+        }
+        PlayState::Editing => {}
+    }
+
+    let snapshot = capture_editor_scene_runtime(world);
+    let root = world
+        .resource::<EditorScene>()
+        .root_entity
+        .and_then(|entity| world.get::<EditorEntityId>(entity).copied());
+    let selection = selection_ids(world);
+    let history = world
+        .get_resource_mut::<EditorHistory>()
+        .map(|mut history| std::mem::take(&mut *history));
+
+    info!(
+        "Playing scene ({} entities snapshotted)",
+        snapshot.entities.len()
+    );
+    *world.resource_mut::<PlayMode>() = PlayMode {
+        state: PlayState::Playing,
+        snapshot: Some(snapshot),
+        root,
+        selection,
+        history,
+        ..default()
+    };
+}
+
+/// Stop playing: despawn what gameplay spawned and restore the snapshot,
+/// except for kept entities, or every scene entity with `keep_changes`.
+pub fn stop_play(world: &mut World, keep_changes: bool) {
+    let mut play_mode = std::mem::take(&mut *world.resource_mut::<PlayMode>());
+    let Some(snapshot) = play_mode.snapshot.take() else {
+        return;
+    };
+
+    let mut query = world.query_filtered::<Entity, With<EditorSceneEntity>>();
+    let scene_entities: Vec<(Entity, Option<EditorEntityId>, Option<Entity>)> = query
+        .iter(world)
+        .map(|entity| {
+            let id = world.get::<EditorEntityId>(entity).copied();
+            (
+                entity,
+                id,
+                world.get::<ChildOf>(entity).map(ChildOf::parent),
+            )
+        })
+        .collect();
+
+    for entity in std::mem::take(&mut play_mode.spawned) {
+        // Gameplay may spawn scene entities too, and keeping changes keeps them
+        if keep_changes && world.get::<EditorSceneEntity>(entity).is_some() {
+            continue;
+        }
+        if let Ok(entity) = world.get_entity_mut(entity) {
+            entity.despawn();
+        }
+    }
+
+    let mut kept_changes = keep_changes;
+    if !keep_changes {
+        let kept: Vec<Entity> = scene_entities
+            .iter()
+            .map(|(entity, ..)| *entity)
+            .filter(|entity| play_mode.kept.contains(entity) && world.get_entity(*entity).is_ok())
+            .collect();
+        let kept_scene = DynamicSceneBuilder::from_world(world)
+            .deny_component::<bevy::render::view::visibility::VisibilityClass>()
+            .extract_entities(kept.into_iter())
+            .build();
+
+        // Despawning the roots takes their children with them
+        let scene_set: EntityHashSet = scene_entities.iter().map(|(entity, ..)| *entity).collect();
+        for (entity, _, parent) in &scene_entities {
+            if parent.is_none_or(|parent| !scene_set.contains(&parent)) {
+                if let Ok(entity) = world.get_entity_mut(*entity) {
+                    entity.despawn();
+                }
+            }
+        }
+
+        if let Err(err) = snapshot.write_to_world(world, &mut EntityHashMap::default()) {
+            error!("Failed to restore the scene after playing: {}", err);
+        }
+        world.flush();
+
+        // Write the kept entities over their restored copies
+        if !kept_scene.entities.is_empty() {
+            let index = world.resource::<crate::EditorEntityIndex>();
+            let mut entity_map: EntityHashMap<Entity> = scene_entities
+                .iter()
+                .filter_map(|(entity, id, _)| Some((*entity, index.get((*id)?)?)))
+                .collect();
+            match kept_scene.write_to_world(world, &mut entity_map) {
+                Ok(()) => kept_changes = true,
+                Err(err) => error!("Failed to keep play changes: {}", err),
+            }
+            world.flush();
+        }
+    }
+
+    let root = play_mode
+        .root
+        .and_then(|id| world.resource::<crate::EditorEntityIndex>().get(id));
+    if !keep_changes {
+        let mut editor_scene = world.resource_mut::<EditorScene>();
+        editor_scene.root_entity = root;
+        editor_scene.pending_selection = play_mode.selection;
+        world.resource_mut::<Selection>().clear();
+    }
+    if let Some(history) = play_mode.history {
+        *world.resource_mut::<EditorHistory>() = history;
+    }
+    if kept_changes {
+        world.resource_mut::<EditorScene>().mark_modified();
+    }
+    info!(
+        "Stopped playing{}",
+        if keep_changes {
+            ", keeping all changes"
+        } else {
+            ""
+        }
+    );
+}
+
+/// Run [`PlayModeUpdate`] while playing, noting the entities it spawns.
+pub fn run_play_mode_schedule(world: &mut World) {
+    if world.resource::<PlayMode>().state != PlayState::Playing {
+        return;
+    }
+
+    let before: EntityHashSet = world.iter_entities().map(|entity| entity.id()).collect();
+    if world.try_run_schedule(PlayModeUpdate).is_err() {
+        return;
+    }
+    let spawned: Vec<Entity> = world
+        .iter_entities()
+        .map(|entity| entity.id())
+        .filter(|entity| !before.contains(entity))
+        .collect();
+    world.resource_mut::<PlayMode>().spawned.extend(spawned);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_entity_by_id, SceneEditorPlugin};
+
+    #[derive(Component)]
+    struct Bullet;
+
+    fn move_right(mut query: Query<&mut Transform, With<EditorSceneEntity>>) {
+        for mut transform in &mut query {
+            transform.translation.x += 1.0;
+        }
+    }
+
+    fn spawn_bullet(mut commands: Commands) {
+        commands.spawn(Bullet);
+    }
+
+    fn setup() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(SceneEditorPlugin)
+            .insert_resource(Assets::<Image>::default())
+            .init_resource::<EditorHistory>()
+            .add_systems(PlayModeUpdate, (move_right, spawn_bullet));
+        app.update();
+
+        let root = app.world().resource::<EditorScene>().root_entity.unwrap();
+        let player = app
+            .world_mut()
+            .spawn((
+                Name::new("Player"),
+                Transform::default(),
+                EditorSceneEntity,
+                ChildOf(root),
+            ))
+            .id();
+        app.world_mut().resource_mut::<Selection>().select(player);
+        app.update();
+        (app, player)
+    }
+
+    fn request(app: &mut App, request: PlayModeRequest) {
+        app.world_mut().send_event(request);
+        app.update();
+    }
+
+    fn bullets(app: &mut App) -> usize {
+        app.world_mut()
+            .query_filtered::<(), With<Bullet>>()
+            .iter(app.world())
+            .count()
+    }
+
+    #[test]
+    fn stopping_restores_the_scene() {
+        let (mut app, player) = setup();
+        let id = *app.world().get::<EditorEntityId>(player).unwrap();
+
+        request(&mut app, PlayModeRequest::Play);
+        app.update();
+        assert_eq!(
+            app.world().resource::<PlayMode>().state(),
+            PlayState::Playing
+        );
+        assert_eq!(
+            app.world().get::<Transform>(player).unwrap().translation.x,
+            2.0
+        );
+        assert_eq!(bullets(&mut app), 2);
+
+        // Nothing runs while paused
+        request(&mut app, PlayModeRequest::Pause);
+        app.update();
+        assert_eq!(
+            app.world().get::<Transform>(player).unwrap().translation.x,
+            2.0
+        );
+
+        request(
+            &mut app,
+            PlayModeRequest::Stop {
+                keep_changes: false,
+            },
+        );
+        // The selection is restored once the entity is found again
+        app.update();
+        assert!(!app.world().resource::<PlayMode>().is_active());
+        assert_eq!(bullets(&mut app), 0);
+        let restored = find_entity_by_id(app.world_mut(), id).unwrap();
+        assert_eq!(
+            app.world()
+                .get::<Transform>(restored)
+                .unwrap()
+                .translation
+                .x,
+            0.0
+        );
+
+        let editor_scene = app.world().resource::<EditorScene>();
+        let root = editor_scene.root_entity.unwrap();
+        assert_eq!(app.world().get::<ChildOf>(restored).unwrap().parent(), root);
+        assert!(!editor_scene.is_modified);
+        let selection = app.world().resource::<Selection>();
+        assert_eq!(selection.primary(), Some(restored));
+    }
+
+    #[test]
+    fn kept_entities_keep_their_play_state() {
+        let (mut app, player) = setup();
+        let other = app
+            .world_mut()
+            .spawn((Transform::default(), EditorSceneEntity))
+            .id();
+        app.update();
+        let player_id = *app.world().get::<EditorEntityId>(player).unwrap();
+        let other_id = *app.world().get::<EditorEntityId>(other).unwrap();
+
+        request(&mut app, PlayModeRequest::Play);
+        app.world_mut()
+            .resource_mut::<PlayMode>()
+            .keep(player, true);
+        request(
+            &mut app,
+            PlayModeRequest::Stop {
+                keep_changes: false,
+            },
+        );
+
+        let player = find_entity_by_id(app.world_mut(), player_id).unwrap();
+        let other = find_entity_by_id(app.world_mut(), other_id).unwrap();
+        assert_eq!(
+            app.world().get::<Transform>(player).unwrap().translation.x,
+            1.0
+        );
+        assert_eq!(
+            app.world().get::<Transform>(other).unwrap().translation.x,
+            0.0
+        );
+        assert!(app.world().resource::<EditorScene>().is_modified);
+        assert_eq!(bullets(&mut app), 0);
+    }
+}
@@ -61,13 +61,19 @@ fn main() {
     // Add the editor scene loader plugin (optional, controlled by feature flag)
     #[cfg(feature = "editor-runtime")]
     {
-        app.add_plugins(bevy_editor_runtime::EditorSceneLoaderPlugin);
+        app.add_plugins((
+            bevy_editor_runtime::EditorSceneLoaderPlugin,
+            bevy_editor_runtime::LevelTilemapPlugin,
+        ));
+        app.add_systems(Startup, spawn_level);
         info!("Editor scene loader enabled - set BEVY_EDITOR_SCENE to load a scene");
     }
 
-    // Store level to load
+    // Store level to load, relative to the assets folder
     if let Some(level) = args.level {
-        app.insert_resource(LevelToLoad(level));
+        let project_path = args.project_path.as_deref().unwrap_or(".");
+        let path = std::path::Path::new(project_path).join("assets").join(level);
+        app.insert_resource(LevelToLoad(path));
     }
 
     // Add game systems
@@ -76,9 +82,9 @@ fn main() {
     app.run();
 }
 
-/// Resource to track which level should be loaded
+/// Resource to track which level (.bscene file) should be loaded
 #[derive(Resource)]
-struct LevelToLoad(String);
+struct LevelToLoad(std::path::PathBuf);
 
 /// Initial setup - spawns camera
 fn setup(mut commands: Commands) {
@@ -99,56 +105,28 @@ fn setup(mut commands: Commands) {
 // ============================================================================
 // TILEMAP RENDERING
 // ============================================================================
-//
-// The editor saves tilemap data in .bscene files. To render these in your game:
-//
-// 1. Load the .bscene file using Bevy's asset system
-// 2. Parse the tilemap data from the scene
-// 3. Use bevy_ecs_tilemap to create the tilemap entities
-//
-// Example tilemap rendering system:
-//
-// fn render_tilemap(
-//     mut commands: Commands,
-//     asset_server: Res<AssetServer>,
-//     // Your tilemap data resource here
-// ) {
-//     // Load tileset texture
-//     let texture_handle: Handle<Image> = asset_server.load("tilesets/tileset.png");
-//
-//     // Create tilemap
-//     let map_size = TilemapSize { x: 32, y: 32 };
-//     let tile_size = TilemapTileSize { x: 16.0, y: 16.0 };
-//     let grid_size = TilemapGridSize { x: 16.0, y: 16.0 };
-//
-//     let mut tile_storage = TileStorage::empty(map_size);
-//     let tilemap_entity = commands.spawn_empty().id();
-//
-//     // Spawn tiles
-//     for x in 0..map_size.x {
-//         for y in 0..map_size.y {
-//             let tile_pos = TilePos { x, y };
-//             let tile_entity = commands.spawn(TileBundle {
-//                 position: tile_pos,
-//                 tilemap_id: TilemapId(tilemap_entity),
-//                 texture_index: TileTextureIndex(0), // Your tile index here
-//                 ..default()
-//             }).id();
-//             tile_storage.set(&tile_pos, tile_entity);
-//         }
-//     }
-//
-//     // Add tilemap bundle
-//     commands.entity(tilemap_entity).insert(TilemapBundle {
-//         grid_size,
-//         size: map_size,
-//         storage: tile_storage,
-//         texture: TilemapTexture::Single(texture_handle),
-//         tile_size,
-//         transform: Transform::from_xyz(0.0, 0.0, 0.0),
-//         ..default()
-//     });
-// }
+
+/// Spawn the tilemap of the level passed with `--level`. Each layer and tileset
+/// becomes a bevy_ecs_tilemap tilemap using the tileset's spacing; tilesets with
+/// a margin are cropped by `LevelTilemapPlugin` once their image has loaded.
+#[cfg(feature = "editor-runtime")]
+fn spawn_level(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level: Option<Res<LevelToLoad>>,
+) {
+    let Some(level) = level else {
+        return;
+    };
+    match bevy_editor_runtime::load_level_tilemap(&level.0) {
+        Ok(Some(tilemap)) => {
+            bevy_editor_runtime::spawn_level_tilemap(&mut commands, &asset_server, &tilemap);
+            info!("Loaded level tilemap from {}", level.0.display());
+        }
+        Ok(None) => info!("Level {} has no tilemap", level.0.display()),
+        Err(err) => error!("Failed to load level {}: {}", level.0.display(), err),
+    }
+}
 
 // ============================================================================
 // GAME LOGIC
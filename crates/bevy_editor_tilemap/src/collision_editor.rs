@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use bevy_editor_formats::{CollisionShape, Vector2};
+use bevy_editor_formats::{CollisionShape, TileCollisionData, TileRef, TilesetData, Vector2};
 
 /// Tools available for collision authoring.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -12,17 +12,44 @@ pub enum CollisionTool {
     Point,
 }
 
+/// Snapping applied to points placed or dragged in the collision editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionSnap {
+    None,
+    /// Snap to whole pixels of the tile image
+    Pixel,
+    /// Snap to an N x N grid laid over the tile
+    SubGrid(u32),
+}
+
+/// What a drag in the Select tool is moving.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CollisionDrag {
+    Vertex { shape: usize, vertex: usize },
+    Shape { shape: usize, last: Vec2 },
+}
+
 /// Backend collision authoring state shared with the UI layer.
+///
+/// Shapes are in tile-local pixels with the origin at the tile's top-left
+/// corner and y pointing down, matching `TileCollisionData`.
 #[derive(Resource)]
 pub struct CollisionEditor {
     pub active: bool,
     pub current_tool: CollisionTool,
-    pub current_tile_id: Option<u32>,
+    pub current_tile: Option<TileRef>,
     pub shapes: Vec<CollisionShape>,
     pub selected_shape: Option<usize>,
+    pub selected_vertex: Option<usize>,
+    pub snap: CollisionSnap,
+    /// Shapes copied from a tile, pasted with `paste_shapes`
+    pub clipboard: Vec<CollisionShape>,
+    /// Draw collision shapes over painted tiles in the viewport
+    pub show_overlay: bool,
 
     pub drawing: bool,
     pub drag_start: Option<Vec2>,
+    pub drag: Option<CollisionDrag>,
     pub polygon_points: Vec<Vector2>,
 }
 
@@ -31,12 +58,425 @@ impl Default for CollisionEditor {
         Self {
             active: false,
             current_tool: CollisionTool::Select,
-            current_tile_id: None,
+            current_tile: None,
             shapes: Vec::new(),
             selected_shape: None,
+            selected_vertex: None,
+            snap: CollisionSnap::Pixel,
+            clipboard: Vec::new(),
+            show_overlay: false,
             drawing: false,
             drag_start: None,
+            drag: None,
             polygon_points: Vec::new(),
         }
     }
 }
+
+impl CollisionEditor {
+    /// Start editing a tile, loading its shapes from the tileset.
+    pub fn edit_tile(&mut self, tile: TileRef, tileset: &TilesetData) {
+        self.current_tile = Some(tile);
+        self.shapes = tileset
+            .collision_data
+            .get(&tile.tile_id)
+            .map(|data| data.shapes.clone())
+            .unwrap_or_default();
+        self.clear_selection();
+        self.cancel_drawing();
+    }
+
+    /// Write the current shapes back into the tileset. Tiles without shapes
+    /// are removed from `collision_data`.
+    pub fn store(&self, tileset: &mut TilesetData) {
+        if let Some(tile) = self.current_tile {
+            set_tile_shapes(tileset, tile.tile_id, &self.shapes);
+        }
+    }
+
+    /// Give every tile in `tile_ids` a copy of the current shapes.
+    pub fn apply_to_tiles(&self, tileset: &mut TilesetData, tile_ids: &[u32]) {
+        for &tile_id in tile_ids {
+            set_tile_shapes(tileset, tile_id, &self.shapes);
+        }
+    }
+
+    pub fn copy_shapes(&mut self) {
+        self.clipboard = self.shapes.clone();
+    }
+
+    /// Append the copied shapes to the current tile.
+    pub fn paste_shapes(&mut self) {
+        self.shapes.extend(self.clipboard.iter().cloned());
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected_shape = None;
+        self.selected_vertex = None;
+        self.drag = None;
+    }
+
+    pub fn cancel_drawing(&mut self) {
+        self.drawing = false;
+        self.drag_start = None;
+        self.polygon_points.clear();
+    }
+
+    /// Delete the selected vertex, or the selected shape if no vertex is
+    /// selected or the shape would become degenerate. Returns true if
+    /// anything was removed.
+    pub fn delete_selection(&mut self) -> bool {
+        let Some(shape_idx) = self.selected_shape else {
+            return false;
+        };
+
+        if let Some(vertex) = self.selected_vertex {
+            if let Some(shape) = self.shapes.get_mut(shape_idx) {
+                if remove_vertex(shape, vertex) {
+                    self.selected_vertex = None;
+                    return true;
+                }
+            }
+        }
+
+        if shape_idx < self.shapes.len() {
+            self.shapes.remove(shape_idx);
+            self.clear_selection();
+            return true;
+        }
+        false
+    }
+
+    /// Clamp a point to the tile and apply the current snapping.
+    pub fn snap_point(&self, point: Vec2, tile_size: Vec2) -> Vec2 {
+        let snapped = match self.snap {
+            CollisionSnap::None => point,
+            CollisionSnap::Pixel => point.round(),
+            CollisionSnap::SubGrid(divisions) => {
+                let step = tile_size / divisions.max(1) as f32;
+                (point / step).round() * step
+            }
+        };
+        snapped.clamp(Vec2::ZERO, tile_size)
+    }
+
+    /// Finish the polygon or polyline being drawn. Returns false if it has
+    /// too few points to be kept.
+    pub fn finish_polygon(&mut self) -> bool {
+        let points = std::mem::take(&mut self.polygon_points);
+        let shape = match self.current_tool {
+            CollisionTool::Polygon if points.len() >= 3 => CollisionShape::Polygon { points },
+            CollisionTool::Polyline if points.len() >= 2 => CollisionShape::Polyline { points },
+            _ => return false,
+        };
+        self.shapes.push(shape);
+        self.selected_shape = Some(self.shapes.len() - 1);
+        self.selected_vertex = None;
+        true
+    }
+}
+
+fn set_tile_shapes(tileset: &mut TilesetData, tile_id: u32, shapes: &[CollisionShape]) {
+    if shapes.is_empty() {
+        tileset.collision_data.remove(&tile_id);
+    } else {
+        tileset.collision_data.insert(
+            tile_id,
+            TileCollisionData {
+                tile_id,
+                shapes: shapes.to_vec(),
+            },
+        );
+    }
+}
+
+/// Editable handles of a shape. Rectangles expose their corners, ellipses
+/// their center and the ends of both radii.
+pub fn shape_vertices(shape: &CollisionShape) -> Vec<Vec2> {
+    match shape {
+        CollisionShape::Rectangle {
+            x,
+            y,
+            width,
+            height,
+        } => vec![
+            Vec2::new(*x, *y),
+            Vec2::new(x + width, *y),
+            Vec2::new(x + width, y + height),
+            Vec2::new(*x, y + height),
+        ],
+        CollisionShape::Ellipse { x, y, rx, ry } => vec![
+            Vec2::new(*x, *y),
+            Vec2::new(x + rx, *y),
+            Vec2::new(*x, y + ry),
+        ],
+        CollisionShape::Polygon { points } | CollisionShape::Polyline { points } => {
+            points.iter().map(|p| Vec2::from(*p)).collect()
+        }
+        CollisionShape::Point { x, y } => vec![Vec2::new(*x, *y)],
+    }
+}
+
+/// Move one handle of a shape to `pos`.
+pub fn move_vertex(shape: &mut CollisionShape, vertex: usize, pos: Vec2) {
+    match shape {
+        CollisionShape::Rectangle {
+            x,
+            y,
+            width,
+            height,
+        } => {
+            // The opposite corner stays put
+            let corners = [
+                Vec2::new(*x, *y),
+                Vec2::new(*x + *width, *y),
+                Vec2::new(*x + *width, *y + *height),
+                Vec2::new(*x, *y + *height),
+            ];
+            let Some(opposite) = corners.get((vertex + 2) % 4) else {
+                return;
+            };
+            let min = pos.min(*opposite);
+            let max = pos.max(*opposite);
+            *x = min.x;
+            *y = min.y;
+            *width = max.x - min.x;
+            *height = max.y - min.y;
+        }
+        CollisionShape::Ellipse { x, y, rx, ry } => match vertex {
+            0 => {
+                *x = pos.x;
+                *y = pos.y;
+            }
+            1 => *rx = (pos.x - *x).abs(),
+            2 => *ry = (pos.y - *y).abs(),
+            _ => {}
+        },
+        CollisionShape::Polygon { points } | CollisionShape::Polyline { points } => {
+            if let Some(point) = points.get_mut(vertex) {
+                *point = pos.into();
+            }
+        }
+        CollisionShape::Point { x, y } => {
+            *x = pos.x;
+            *y = pos.y;
+        }
+    }
+}
+
+/// Move a whole shape by `delta`.
+pub fn translate_shape(shape: &mut CollisionShape, delta: Vec2) {
+    match shape {
+        CollisionShape::Rectangle { x, y, .. }
+        | CollisionShape::Ellipse { x, y, .. }
+        | CollisionShape::Point { x, y } => {
+            *x += delta.x;
+            *y += delta.y;
+        }
+        CollisionShape::Polygon { points } | CollisionShape::Polyline { points } => {
+            for point in points {
+                point.x += delta.x;
+                point.y += delta.y;
+            }
+        }
+    }
+}
+
+/// Insert a vertex after `after` (polygons and polylines only).
+/// Returns the new vertex index.
+pub fn insert_vertex(shape: &mut CollisionShape, after: usize, pos: Vec2) -> Option<usize> {
+    match shape {
+        CollisionShape::Polygon { points } | CollisionShape::Polyline { points } => {
+            let index = (after + 1).min(points.len());
+            points.insert(index, pos.into());
+            Some(index)
+        }
+        _ => None,
+    }
+}
+
+/// Remove a vertex from a polygon or polyline, keeping at least 3 or 2
+/// points respectively. Returns false if the vertex can't be removed.
+pub fn remove_vertex(shape: &mut CollisionShape, vertex: usize) -> bool {
+    let (points, min_points) = match shape {
+        CollisionShape::Polygon { points } => (points, 3),
+        CollisionShape::Polyline { points } => (points, 2),
+        _ => return false,
+    };
+    if vertex >= points.len() || points.len() <= min_points {
+        return false;
+    }
+    points.remove(vertex);
+    true
+}
+
+/// Edge of a polygon or polyline closest to `pos` within `max_distance`,
+/// returned as the index of its first vertex.
+pub fn nearest_edge(shape: &CollisionShape, pos: Vec2, max_distance: f32) -> Option<usize> {
+    let (points, closed) = match shape {
+        CollisionShape::Polygon { points } => (points, true),
+        CollisionShape::Polyline { points } => (points, false),
+        _ => return None,
+    };
+    let edge_count = if closed {
+        points.len()
+    } else {
+        points.len().saturating_sub(1)
+    };
+
+    (0..edge_count)
+        .map(|i| {
+            let a = Vec2::from(points[i]);
+            let b = Vec2::from(points[(i + 1) % points.len()]);
+            (i, distance_to_segment(pos, a, b))
+        })
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+/// Whether `pos` lies inside (or, for open shapes, near) a shape.
+pub fn shape_contains(shape: &CollisionShape, pos: Vec2, tolerance: f32) -> bool {
+    match shape {
+        CollisionShape::Rectangle {
+            x,
+            y,
+            width,
+            height,
+        } => Rect::new(*x, *y, x + width, y + height)
+            .inflate(tolerance)
+            .contains(pos),
+        CollisionShape::Ellipse { x, y, rx, ry } => {
+            let d = (pos - Vec2::new(*x, *y)) / Vec2::new(rx + tolerance, ry + tolerance);
+            d.length_squared() <= 1.0
+        }
+        CollisionShape::Polygon { points } => {
+            // Even-odd rule
+            let mut inside = false;
+            for i in 0..points.len() {
+                let a = Vec2::from(points[i]);
+                let b = Vec2::from(points[(i + points.len() - 1) % points.len()]);
+                if (a.y > pos.y) != (b.y > pos.y)
+                    && pos.x < (b.x - a.x) * (pos.y - a.y) / (b.y - a.y) + a.x
+                {
+                    inside = !inside;
+                }
+            }
+            inside || nearest_edge(shape, pos, tolerance).is_some()
+        }
+        CollisionShape::Polyline { .. } => nearest_edge(shape, pos, tolerance).is_some(),
+        CollisionShape::Point { x, y } => Vec2::new(*x, *y).distance(pos) <= tolerance,
+    }
+}
+
+fn distance_to_segment(pos: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let t = if ab.length_squared() > 0.0 {
+        ((pos - a).dot(ab) / ab.length_squared()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    pos.distance(a + ab * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> CollisionShape {
+        CollisionShape::Polygon {
+            points: vec![
+                Vector2::new(0.0, 0.0),
+                Vector2::new(8.0, 0.0),
+                Vector2::new(8.0, 8.0),
+                Vector2::new(0.0, 8.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn snapping_rounds_and_clamps_to_tile() {
+        let tile = Vec2::splat(16.0);
+        let mut editor = CollisionEditor {
+            snap: CollisionSnap::Pixel,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            editor.snap_point(Vec2::new(3.4, 7.6), tile),
+            Vec2::new(3.0, 8.0)
+        );
+        assert_eq!(
+            editor.snap_point(Vec2::new(-2.0, 20.0), tile),
+            Vec2::new(0.0, 16.0)
+        );
+
+        editor.snap = CollisionSnap::SubGrid(4);
+        assert_eq!(
+            editor.snap_point(Vec2::new(5.0, 11.0), tile),
+            Vec2::new(4.0, 12.0)
+        );
+    }
+
+    #[test]
+    fn rectangle_corner_drag_keeps_opposite_corner() {
+        let mut rect = CollisionShape::Rectangle {
+            x: 2.0,
+            y: 2.0,
+            width: 4.0,
+            height: 4.0,
+        };
+        // Drag the top-left corner past the bottom-right one
+        move_vertex(&mut rect, 0, Vec2::new(10.0, 8.0));
+        assert_eq!(
+            rect,
+            CollisionShape::Rectangle {
+                x: 6.0,
+                y: 6.0,
+                width: 4.0,
+                height: 2.0,
+            }
+        );
+    }
+
+    #[test]
+    fn vertices_can_be_inserted_and_removed() {
+        let mut shape = square();
+        assert_eq!(nearest_edge(&shape, Vec2::new(4.0, 0.5), 1.0), Some(0));
+        assert_eq!(insert_vertex(&mut shape, 0, Vec2::new(4.0, -2.0)), Some(1));
+        assert_eq!(shape_vertices(&shape).len(), 5);
+
+        assert!(remove_vertex(&mut shape, 1));
+        assert!(remove_vertex(&mut shape, 0));
+        // A polygon keeps at least three points
+        assert!(!remove_vertex(&mut shape, 0));
+    }
+
+    #[test]
+    fn shapes_round_trip_through_tileset() {
+        let mut tileset = TilesetData::default();
+        let mut editor = CollisionEditor::default();
+        editor.edit_tile(TileRef::new(0, 5), &tileset);
+        editor.shapes.push(square());
+        editor.store(&mut tileset);
+        editor.apply_to_tiles(&mut tileset, &[6, 7]);
+
+        let mut other = CollisionEditor::default();
+        other.edit_tile(TileRef::new(0, 7), &tileset);
+        assert_eq!(other.shapes, vec![square()]);
+
+        // Clearing a tile's shapes removes its entry
+        editor.shapes.clear();
+        editor.store(&mut tileset);
+        assert!(!tileset.collision_data.contains_key(&5));
+        assert_eq!(tileset.collision_data.len(), 2);
+    }
+
+    #[test]
+    fn polygon_hit_testing() {
+        let shape = square();
+        assert!(shape_contains(&shape, Vec2::new(4.0, 4.0), 0.0));
+        assert!(!shape_contains(&shape, Vec2::new(12.0, 4.0), 0.0));
+        assert!(shape_contains(&shape, Vec2::new(8.5, 4.0), 1.0));
+    }
+}
@@ -0,0 +1,168 @@
+//! Undoable layer operations.
+
+use bevy::prelude::*;
+use bevy_editor_commands::{CommandRecord, EditorCommand, SerializableCommand};
+use bevy_editor_formats::{LayerData, LayerMetadata};
+use serde::{Deserialize, Serialize};
+
+use crate::layer_manager::LayerManager;
+
+/// Adding, removing or reordering a layer. Layers keep their id through undo
+/// and redo, so tile strokes recorded against them still apply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LayerCommand {
+    Add {
+        index: usize,
+        layer: LayerData,
+    },
+    Remove {
+        index: usize,
+        layer: LayerData,
+    },
+    /// Move a layer one step towards the front of the list (`up`) or the back.
+    Move {
+        layer_id: u32,
+        up: bool,
+    },
+}
+
+impl LayerCommand {
+    /// Append a new layer described by `metadata`
+    pub fn add(layer_manager: &LayerManager, mut metadata: LayerMetadata) -> Self {
+        metadata.id = layer_manager.next_layer_id();
+        Self::Add {
+            index: layer_manager.layers.len(),
+            layer: LayerData::new(metadata),
+        }
+    }
+
+    /// Remove the layer at `index`, keeping its tiles for undo
+    pub fn remove(layer_manager: &LayerManager, index: usize) -> Option<Self> {
+        let layer = layer_manager.get_layer(index)?.clone();
+        Some(Self::Remove { index, layer })
+    }
+
+    pub fn move_layer(layer_manager: &LayerManager, index: usize, up: bool) -> Option<Self> {
+        let layer_id = layer_manager.get_layer(index)?.metadata.id;
+        Some(Self::Move { layer_id, up })
+    }
+
+    /// Apply the command (`redo`) or its inverse to `layer_manager`
+    pub fn apply(&self, layer_manager: &mut LayerManager, redo: bool) {
+        match self {
+            Self::Add { index, layer } | Self::Remove { index, layer } => {
+                let adding = matches!(self, Self::Add { .. }) == redo;
+                if adding {
+                    layer_manager.insert_layer(*index, layer.clone());
+                } else if let Some(index) = layer_manager.layer_index(layer.metadata.id) {
+                    layer_manager.remove_layer(index);
+                }
+            }
+            Self::Move { layer_id, up } => {
+                let Some(index) = layer_manager.layer_index(*layer_id) else {
+                    return;
+                };
+                if *up == redo {
+                    layer_manager.move_layer_up(index);
+                } else {
+                    layer_manager.move_layer_down(index);
+                }
+            }
+        }
+    }
+}
+
+impl EditorCommand for LayerCommand {
+    fn execute(&mut self, world: &mut World) {
+        if let Some(mut layer_manager) = world.get_resource_mut::<LayerManager>() {
+            self.apply(&mut layer_manager, true);
+        }
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        if let Some(mut layer_manager) = world.get_resource_mut::<LayerManager>() {
+            self.apply(&mut layer_manager, false);
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            Self::Add { layer, .. } => format!("Add Layer '{}'", layer.metadata.identifier),
+            Self::Remove { layer, .. } => format!("Delete Layer '{}'", layer.metadata.identifier),
+            Self::Move { up: true, .. } => "Move Layer Up".to_string(),
+            Self::Move { up: false, .. } => "Move Layer Down".to_string(),
+        }
+    }
+
+    fn size_bytes(&self) -> usize {
+        let tiles = match self {
+            Self::Add { layer, .. } | Self::Remove { layer, .. } => layer.tiles.capacity(),
+            Self::Move { .. } => 0,
+        };
+        std::mem::size_of::<Self>() + tiles * std::mem::size_of::<bevy_editor_formats::TileData>()
+    }
+
+    fn to_record(&self) -> Option<CommandRecord> {
+        CommandRecord::of(self)
+    }
+}
+
+impl SerializableCommand for LayerCommand {
+    const KIND: &'static str = "layer";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer_manager::create_default_layer;
+    use bevy_editor_formats::{LayerType, TileData};
+
+    fn names(layer_manager: &LayerManager) -> Vec<&str> {
+        layer_manager
+            .layers
+            .iter()
+            .map(|layer| layer.metadata.identifier.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn removed_layer_comes_back_with_its_id_and_tiles() {
+        let mut layer_manager = LayerManager::new();
+        layer_manager.add_layer(create_default_layer(LayerType::Tiles, "Ground", 0, None));
+        layer_manager.add_layer(create_default_layer(LayerType::Tiles, "Walls", 1, None));
+        layer_manager.set_active_layer(1);
+        layer_manager.add_tile(TileData {
+            x: 1,
+            y: 2,
+            tileset_id: 0,
+            tile_id: 3,
+            flip_x: false,
+            flip_y: false,
+        });
+        let walls_id = layer_manager.layers[1].metadata.id;
+
+        let remove = LayerCommand::remove(&layer_manager, 1).unwrap();
+        remove.apply(&mut layer_manager, true);
+        assert_eq!(names(&layer_manager), ["Ground"]);
+
+        remove.apply(&mut layer_manager, false);
+        assert_eq!(names(&layer_manager), ["Ground", "Walls"]);
+        assert_eq!(layer_manager.layers[1].metadata.id, walls_id);
+        assert_eq!(layer_manager.layers[1].tiles.len(), 1);
+
+        // New layers never reuse the id of a restored layer
+        let add = LayerCommand::add(
+            &layer_manager,
+            create_default_layer(LayerType::Tiles, "Decor", 2, None),
+        );
+        add.apply(&mut layer_manager, true);
+        assert!(layer_manager.layers[2].metadata.id > walls_id);
+
+        let raise = LayerCommand::move_layer(&layer_manager, 2, true).unwrap();
+        raise.apply(&mut layer_manager, true);
+        assert_eq!(names(&layer_manager), ["Ground", "Decor", "Walls"]);
+        raise.apply(&mut layer_manager, false);
+        add.apply(&mut layer_manager, false);
+        assert_eq!(names(&layer_manager), ["Ground", "Walls"]);
+    }
+}
@@ -68,6 +68,32 @@ impl LayerManager {
         }
     }
 
+    /// Insert a layer keeping its id, e.g. to restore a removed layer
+    pub fn insert_layer(&mut self, index: usize, layer: LayerData) {
+        let index = index.min(self.layers.len());
+        self.next_id = self.next_id.max(layer.metadata.id + 1);
+        self.layer_visibility.insert(layer.metadata.id, true);
+        self.layers.insert(index, layer);
+
+        self.active_layer = match self.active_layer {
+            Some(active) if active >= index => Some(active + 1),
+            Some(active) => Some(active),
+            None => Some(index),
+        };
+    }
+
+    /// Id the next added layer will get
+    pub fn next_layer_id(&self) -> u32 {
+        self.next_id
+    }
+
+    /// Index of the layer with `layer_id`
+    pub fn layer_index(&self, layer_id: u32) -> Option<usize> {
+        self.layers
+            .iter()
+            .position(|layer| layer.metadata.id == layer_id)
+    }
+
     /// Get layer by index
     pub fn get_layer(&self, index: usize) -> Option<&LayerData> {
         self.layers.get(index)
@@ -144,6 +170,21 @@ impl LayerManager {
         }
     }
 
+    /// Place or clear a tile on a specific layer, regardless of which layer is active
+    pub fn set_tile_on_layer(&mut self, layer_id: u32, x: u32, y: u32, tile: Option<TileData>) {
+        let Some(layer) = self
+            .layers
+            .iter_mut()
+            .find(|layer| layer.metadata.id == layer_id)
+        else {
+            return;
+        };
+        layer.tiles.retain(|t| t.x != x || t.y != y);
+        if let Some(tile) = tile {
+            layer.tiles.push(TileData { x, y, ..tile });
+        }
+    }
+
     /// Get tile at position in active layer
     pub fn get_tile_at(&self, x: u32, y: u32) -> Option<&TileData> {
         self.get_active_layer()?
@@ -3,10 +3,12 @@
 //! Tilemap editing functionality for Bevy-based editors.
 //!
 //! This crate provides:
-//! - **Tileset Management**: Load and manage multiple tilesets
-//! - **Layer System**: Multi-layer tilemap editing with z-ordering
-//! - **Painting Tools**: Brush, stamp, fill, line, and rectangle tools
-//! - **Collision Editing**: Per-tile collision shape authoring
+//! - **Tileset Management**: Load and manage multiple tilesets, with spacing/margin detection
+//! - **Layer System**: Multi-layer tilemap editing with z-ordering, undoable add/remove/reorder
+//! - **Painting Tools**: Brush, stamp, fill, line, and rectangle tools, undoable per stroke
+//! - **Map Orientations**: Square, isometric and hexagonal grids per layer
+//! - **Variation Brush**: Weighted random tile picking with optional seeding
+//! - **Collision Editing**: Per-tile collision shapes with vertex editing, stored in the tileset
 //! - **Tilemap Components**: Integration with bevy_ecs_tilemap
 //!
 //! ## Features
@@ -28,19 +30,30 @@
 //! ```
 
 pub mod collision_editor;
+pub mod layer_commands;
 pub mod layer_manager;
 pub mod map_canvas;
+pub mod tile_commands;
+pub mod tile_grid;
 pub mod tile_painter;
 pub mod tilemap_component;
+pub mod tileset_layout;
 pub mod tileset_manager;
+pub mod variation_brush;
 
 // Re-export commonly used types
-pub use collision_editor::{CollisionEditor, CollisionTool};
+pub use collision_editor::{
+    insert_vertex, move_vertex, nearest_edge, remove_vertex, shape_contains, shape_vertices,
+    translate_shape, CollisionDrag, CollisionEditor, CollisionSnap, CollisionTool,
+};
+pub use layer_commands::LayerCommand;
 pub use layer_manager::{create_default_layer, ensure_default_layer_system, LayerManager};
 pub use map_canvas::{
     handle_canvas_click_painting, handle_paint_tile_events, setup_map_canvas,
-    update_map_canvas_on_layer_changes, MapCanvas, MapDimensions, PaintTileEvent,
+    update_map_canvas_on_layer_changes, EraseTileEvent, MapCanvas, MapDimensions, PaintTileEvent,
 };
+pub use tile_commands::{TileChange, TileStroke, TileStrokeCommand};
+pub use tile_grid::{sync_canvas_grid, tilemap_type, update_tile_grid, TileGrid};
 pub use tile_painter::{
     bucket_fill, paint_line, paint_rectangle, paint_single_tile, paint_stamp, PaintMode,
     TilePainter,
@@ -48,14 +61,20 @@ pub use tile_painter::{
 pub use tilemap_component::{
     cleanup_tilemap_entities, sync_tilemap_entities, TilemapComponent, TilemapLayers,
 };
+pub use tileset_layout::{detect_tileset_layout, tileset_render_texture, TilesetLayout};
 pub use tileset_manager::{
     handle_tileset_load_requests, load_tileset, update_tileset_dimensions, LoadTilesetEvent,
     TilesetInfo, TilesetManager,
 };
+pub use variation_brush::{TileBrush, VariationBrush};
 // Re-export CollisionShape from formats crate
-pub use bevy_editor_formats::CollisionShape;
+pub use bevy_editor_formats::{
+    BrushPreset, CollisionShape, MapOrientation, PresetTile, TileCollisionData, TileRef,
+    WeightedTile,
+};
 
 use bevy::prelude::*;
+use bevy_editor_commands::CommandRegistry;
 
 /// Main plugin for tilemap editing functionality
 pub struct TilemapEditorPlugin;
@@ -68,10 +87,13 @@ impl Plugin for TilemapEditorPlugin {
             .init_resource::<LayerManager>()
             .init_resource::<TilePainter>()
             .init_resource::<MapDimensions>()
+            .init_resource::<TileGrid>()
             .init_resource::<CollisionEditor>()
             // Events
             .add_event::<LoadTilesetEvent>()
             .add_event::<PaintTileEvent>()
+            .add_event::<EraseTileEvent>()
+            .init_resource::<CommandRegistry>()
             // Systems
             .add_systems(Startup, ensure_default_layer_system)
             .add_systems(
@@ -79,16 +101,22 @@ impl Plugin for TilemapEditorPlugin {
                 (
                     handle_tileset_load_requests,
                     update_tileset_dimensions,
-                    setup_map_canvas,
+                    update_tile_grid,
+                    setup_map_canvas.after(update_tile_grid),
+                    sync_canvas_grid.after(update_tile_grid),
                     update_map_canvas_on_layer_changes,
                     handle_paint_tile_events,
-                    handle_canvas_click_painting,
                     // Tilemap component systems
                     sync_tilemap_entities,
                     cleanup_tilemap_entities,
                 ),
             );
 
+        app.world_mut()
+            .resource_mut::<CommandRegistry>()
+            .register::<TileStrokeCommand>()
+            .register::<LayerCommand>();
+
         // Register tilemap component for scene serialization (if bevy_ecs_tilemap feature is enabled)
         #[cfg(feature = "tilemap")]
         {
@@ -109,9 +137,12 @@ impl Plugin for TilemapCorePlugin {
             .init_resource::<LayerManager>()
             .init_resource::<TilePainter>()
             .init_resource::<MapDimensions>()
+            .init_resource::<TileGrid>()
             // Events
             .add_event::<LoadTilesetEvent>()
             .add_event::<PaintTileEvent>()
+            .add_event::<EraseTileEvent>()
+            .init_resource::<CommandRegistry>()
             // Systems
             .add_systems(Startup, ensure_default_layer_system)
             .add_systems(
@@ -119,15 +150,21 @@ impl Plugin for TilemapCorePlugin {
                 (
                     handle_tileset_load_requests,
                     update_tileset_dimensions,
-                    setup_map_canvas,
+                    update_tile_grid,
+                    setup_map_canvas.after(update_tile_grid),
+                    sync_canvas_grid.after(update_tile_grid),
                     update_map_canvas_on_layer_changes,
                     handle_paint_tile_events,
-                    handle_canvas_click_painting,
                     sync_tilemap_entities,
                     cleanup_tilemap_entities,
                 ),
             );
 
+        app.world_mut()
+            .resource_mut::<CommandRegistry>()
+            .register::<TileStrokeCommand>()
+            .register::<LayerCommand>();
+
         #[cfg(feature = "tilemap")]
         {
             app.register_type::<TilemapComponent>()
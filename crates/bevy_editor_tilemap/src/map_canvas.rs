@@ -1,11 +1,13 @@
-use crate::{LayerManager, TilesetManager};
+use crate::{tileset_render_texture, LayerManager, TileGrid, TilesetManager};
 use bevy::prelude::*;
 use bevy_ecs_tilemap::prelude::*;
 
-/// Component to mark the tilemap entity for the map canvas
+/// Component to mark a tilemap entity of the map canvas.
+/// The canvas holds one tilemap per tileset so a layer can mix tiles from several tilesets.
 #[derive(Component)]
 pub struct MapCanvas {
     pub layer_id: u32,
+    pub tileset_id: u32,
 }
 
 /// Resource to track the current map dimensions
@@ -24,86 +26,105 @@ impl Default for MapDimensions {
     }
 }
 
-/// Setup the map canvas - creates a simple tilemap for testing
-/// This is a simplified version that creates one tilemap if a tileset is loaded
+/// Setup the map canvas - creates a tilemap for every loaded tileset that
+/// doesn't have one yet
 pub fn setup_map_canvas(
     mut commands: Commands,
-    tileset_manager: Res<TilesetManager>,
+    mut tileset_manager: ResMut<TilesetManager>,
     _layer_manager: Res<LayerManager>,
     map_dimensions: Res<MapDimensions>,
-    existing_canvas: Query<Entity, With<MapCanvas>>,
+    tile_grid: Res<TileGrid>,
+    existing_canvas: Query<&MapCanvas>,
+    mut images: ResMut<Assets<Image>>,
 ) {
-    // Only create if we don't already have a canvas and we have a tileset
-    if !existing_canvas.is_empty() {
-        return;
-    }
+    // Only the render texture cache is written, which isn't a tileset change
+    for (&tileset_id, tileset_info) in tileset_manager
+        .bypass_change_detection()
+        .tilesets
+        .iter_mut()
+    {
+        if existing_canvas
+            .iter()
+            .any(|canvas| canvas.tileset_id == tileset_id)
+        {
+            continue;
+        }
 
-    if let Some(tileset_id) = tileset_manager.selected_tileset_id {
-        if let Some(tileset_info) = tileset_manager.tilesets.get(&tileset_id) {
-            info!(
-                "Creating map canvas with tileset '{}'",
-                tileset_info.data.identifier
-            );
+        // Tilesets with a margin need their image loaded before the canvas can be built
+        let Some(texture) = tileset_render_texture(tileset_info, &mut images) else {
+            continue;
+        };
 
-            let map_size = TilemapSize {
-                x: map_dimensions.width,
-                y: map_dimensions.height,
-            };
+        info!(
+            "Creating map canvas with tileset '{}'",
+            tileset_info.data.identifier
+        );
 
-            let tile_size = TilemapTileSize {
-                x: tileset_info.data.tile_width as f32,
-                y: tileset_info.data.tile_height as f32,
-            };
+        let map_size = TilemapSize {
+            x: map_dimensions.width,
+            y: map_dimensions.height,
+        };
 
-            let grid_size = TilemapGridSize {
-                x: tileset_info.data.tile_width as f32,
-                y: tileset_info.data.tile_height as f32,
-            };
+        let tile_size = TilemapTileSize {
+            x: tileset_info.data.tile_width as f32,
+            y: tileset_info.data.tile_height as f32,
+        };
 
-            let tilemap_entity = commands.spawn_empty().id();
-            let mut tile_storage = TileStorage::empty(map_size);
-
-            // Spawn tiles (initially hidden)
-            for x in 0..map_size.x {
-                for y in 0..map_size.y {
-                    let tile_pos = TilePos { x, y };
-                    let tile_entity = commands
-                        .spawn(TileBundle {
-                            position: tile_pos,
-                            tilemap_id: TilemapId(tilemap_entity),
-                            texture_index: TileTextureIndex(0),
-                            visible: TileVisible(false),
-                            ..Default::default()
-                        })
-                        .id();
-                    tile_storage.set(&tile_pos, tile_entity);
-                }
-            }
+        // Cells are laid out at this tileset's tile size, in the map's orientation
+        let grid = tile_grid.for_tileset(&tileset_info.data);
+        let grid_size = grid.grid_size();
 
-            // Create tilemap with proper type and positioning
-            // Offset by half a tile so tiles are centered on grid intersections
-            let half_tile_x = tileset_info.data.tile_width as f32 / 2.0;
-            let half_tile_y = tileset_info.data.tile_height as f32 / 2.0;
-
-            commands.entity(tilemap_entity).insert((
-                TilemapBundle {
-                    grid_size,
-                    size: map_size,
-                    storage: tile_storage,
-                    texture: TilemapTexture::Single(tileset_info.texture_handle.clone()),
-                    tile_size,
-                    transform: Transform::from_xyz(half_tile_x, half_tile_y, 0.0),
-                    map_type: TilemapType::Square,
-                    ..Default::default()
-                },
-                MapCanvas { layer_id: 0 },
-            ));
-
-            info!(
-                "Created map canvas tilemap: {}x{} tiles",
-                map_size.x, map_size.y
-            );
+        let spacing = TilemapSpacing {
+            x: tileset_info.data.spacing as f32,
+            y: tileset_info.data.spacing as f32,
+        };
+
+        let tilemap_entity = commands.spawn_empty().id();
+        let mut tile_storage = TileStorage::empty(map_size);
+
+        // Spawn tiles (initially hidden)
+        for x in 0..map_size.x {
+            for y in 0..map_size.y {
+                let tile_pos = TilePos { x, y };
+                let tile_entity = commands
+                    .spawn(TileBundle {
+                        position: tile_pos,
+                        tilemap_id: TilemapId(tilemap_entity),
+                        texture_index: TileTextureIndex(0),
+                        visible: TileVisible(false),
+                        ..Default::default()
+                    })
+                    .id();
+                tile_storage.set(&tile_pos, tile_entity);
+            }
         }
+
+        // Create tilemap with proper type and positioning
+        // Offset by half a cell so square cells start on grid intersections
+        let origin = grid.origin();
+
+        commands.entity(tilemap_entity).insert((
+            TilemapBundle {
+                grid_size,
+                size: map_size,
+                storage: tile_storage,
+                texture: TilemapTexture::Single(texture),
+                tile_size,
+                spacing,
+                transform: Transform::from_xyz(origin.x, origin.y, 0.0),
+                map_type: grid.tilemap_type(),
+                ..Default::default()
+            },
+            MapCanvas {
+                layer_id: 0,
+                tileset_id,
+            },
+        ));
+
+        info!(
+            "Created map canvas tilemap: {}x{} tiles",
+            map_size.x, map_size.y
+        );
     }
 }
 
@@ -121,48 +142,77 @@ pub struct PaintTileEvent {
     pub layer_id: u32,
     pub x: u32,
     pub y: u32,
+    pub tileset_id: u32,
     pub tile_id: u32,
 }
 
-/// System to handle tile painting events
+/// Event to clear the tile at a specific position
+#[derive(Event)]
+pub struct EraseTileEvent {
+    pub layer_id: u32,
+    pub x: u32,
+    pub y: u32,
+}
+
+/// System to handle tile painting and erasing events
 pub fn handle_paint_tile_events(
     mut paint_events: EventReader<PaintTileEvent>,
-    tilemap_query: Query<&TileStorage, With<MapCanvas>>,
+    mut erase_events: EventReader<EraseTileEvent>,
+    tilemap_query: Query<(&TileStorage, &MapCanvas)>,
     mut tile_query: Query<(&mut TileTextureIndex, &mut TileVisible)>,
 ) {
+    for event in erase_events.read() {
+        let tile_pos = TilePos {
+            x: event.x,
+            y: event.y,
+        };
+        for (tile_storage, _) in &tilemap_query {
+            if let Some(tile_entity) = tile_storage.get(&tile_pos) {
+                if let Ok((_, mut visible)) = tile_query.get_mut(tile_entity) {
+                    visible.0 = false;
+                }
+            }
+        }
+    }
+
     for event in paint_events.read() {
-        // Find the tilemap
-        for tile_storage in &tilemap_query {
-            let tile_pos = TilePos {
-                x: event.x,
-                y: event.y,
+        let tile_pos = TilePos {
+            x: event.x,
+            y: event.y,
+        };
+
+        for (tile_storage, canvas) in &tilemap_query {
+            let Some(tile_entity) = tile_storage.get(&tile_pos) else {
+                continue;
+            };
+            let Ok((mut texture_index, mut visible)) = tile_query.get_mut(tile_entity) else {
+                continue;
             };
 
-            // Get the tile entity and update it
-            if let Some(tile_entity) = tile_storage.get(&tile_pos) {
-                if let Ok((mut texture_index, mut visible)) = tile_query.get_mut(tile_entity) {
-                    texture_index.0 = event.tile_id;
-                    visible.0 = true;
-                    info!(
-                        "Painted tile {} at ({}, {})",
-                        event.tile_id, event.x, event.y
-                    );
-                }
+            if canvas.tileset_id == event.tileset_id {
+                texture_index.0 = event.tile_id;
+                visible.0 = true;
+                info!(
+                    "Painted tile {} (tileset {}) at ({}, {})",
+                    event.tile_id, event.tileset_id, event.x, event.y
+                );
+            } else {
+                // A cell holds a single tile, so hide whatever another tileset drew there
+                visible.0 = false;
             }
         }
     }
 }
 
-/// System to handle mouse clicks on the map canvas for painting
+/// Minimal click-to-paint for frontends without their own painting input.
+/// Not registered by the plugins: it bypasses `LayerManager` and undo history.
 pub fn handle_canvas_click_painting(
     mouse_button: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window>,
     camera_query: Query<(&Camera, &GlobalTransform)>,
     tileset_manager: Res<TilesetManager>,
-    tilemap_query: Query<
-        (&TileStorage, &TilemapSize, &TilemapGridSize, &Transform),
-        With<MapCanvas>,
-    >,
+    tile_grid: Res<TileGrid>,
+    tilemap_query: Query<&MapCanvas>,
     mut paint_events: EventWriter<PaintTileEvent>,
 ) {
     // Only paint on left click
@@ -171,7 +221,7 @@ pub fn handle_canvas_click_painting(
     }
 
     // Need a selected tile
-    let Some(selected_tile) = tileset_manager.selected_tile_id else {
+    let Some(selected_tile) = tileset_manager.selected_tile_ref() else {
         return;
     };
 
@@ -192,32 +242,25 @@ pub fn handle_canvas_click_painting(
         return;
     };
 
-    // Find which tile was clicked
-    for (_storage, map_size, grid_size, transform) in &tilemap_query {
-        // Convert world position to tile position
-        // The tilemap is offset by half a tile, so we need to account for that
-        let local_pos = world_pos - transform.translation.truncate();
-
-        // Add half tile offset back to properly center the coordinate system
-        let adjusted_x = local_pos.x + (grid_size.x / 2.0);
-        let adjusted_y = local_pos.y + (grid_size.y / 2.0);
-
-        let tile_x = (adjusted_x / grid_size.x).floor() as i32;
-        let tile_y = (adjusted_y / grid_size.y).floor() as i32;
-
-        // Check bounds
-        if tile_x < 0 || tile_y < 0 || tile_x >= map_size.x as i32 || tile_y >= map_size.y as i32 {
-            continue;
-        }
+    // Only paint once the selected tileset has a canvas
+    if !tilemap_query
+        .iter()
+        .any(|canvas| canvas.tileset_id == selected_tile.tileset_id)
+    {
+        return;
+    }
 
-        // Send paint event
-        paint_events.write(PaintTileEvent {
-            layer_id: 0,
-            x: tile_x as u32,
-            y: tile_y as u32,
-            tile_id: selected_tile,
-        });
+    // Convert world position to tile position for the current orientation
+    let Some((tile_x, tile_y)) = tile_grid.world_to_tile(world_pos) else {
+        return;
+    };
 
-        break; // Only paint on first tilemap
-    }
+    // Send paint event
+    paint_events.write(PaintTileEvent {
+        layer_id: 0,
+        x: tile_x,
+        y: tile_y,
+        tileset_id: selected_tile.tileset_id,
+        tile_id: selected_tile.tile_id,
+    });
 }
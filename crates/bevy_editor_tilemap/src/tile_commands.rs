@@ -0,0 +1,316 @@
+//! Undoable tile painting: each stroke or fill is recorded as one history command.
+
+use bevy::prelude::*;
+use bevy_editor_commands::{CommandRecord, EditorCommand, SerializableCommand};
+use bevy_editor_formats::TileData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::layer_manager::LayerManager;
+use crate::map_canvas::{EraseTileEvent, PaintTileEvent};
+
+/// Brush strokes started within this time of the previous one merge into a single undo step
+const BRUSH_MERGE_WINDOW: Duration = Duration::from_millis(750);
+
+type LayerSnapshot = HashMap<u32, HashMap<(u32, u32), TileData>>;
+
+/// Before/after state of one cell on one layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TileChange {
+    pub layer_id: u32,
+    pub x: u32,
+    pub y: u32,
+    pub before: Option<TileData>,
+    pub after: Option<TileData>,
+}
+
+/// A stroke in progress. The layers are snapshotted when it begins and diffed
+/// when it finishes, so painting functions don't need to record anything.
+pub struct TileStroke {
+    description: String,
+    /// Whether the stroke may merge with a brush stroke right before it
+    mergeable: bool,
+    started_at: Instant,
+    before: LayerSnapshot,
+}
+
+impl TileStroke {
+    pub fn begin(description: &str, mergeable: bool, layer_manager: &LayerManager) -> Self {
+        Self {
+            description: description.to_string(),
+            mergeable,
+            started_at: Instant::now(),
+            before: snapshot(layer_manager),
+        }
+    }
+
+    /// Diff the layers against the snapshot. Returns `None` if nothing changed.
+    pub fn finish(self, layer_manager: &LayerManager) -> Option<TileStrokeCommand> {
+        let after = snapshot(layer_manager);
+        let empty = HashMap::new();
+        let mut changes = Vec::new();
+
+        let layer_ids: std::collections::BTreeSet<u32> =
+            self.before.keys().chain(after.keys()).copied().collect();
+        for layer_id in layer_ids {
+            let before_tiles = self.before.get(&layer_id).unwrap_or(&empty);
+            let after_tiles = after.get(&layer_id).unwrap_or(&empty);
+            let positions: std::collections::BTreeSet<(u32, u32)> = before_tiles
+                .keys()
+                .chain(after_tiles.keys())
+                .copied()
+                .collect();
+
+            for (x, y) in positions {
+                let before = before_tiles.get(&(x, y)).copied();
+                let after = after_tiles.get(&(x, y)).copied();
+                if before != after {
+                    changes.push(TileChange {
+                        layer_id,
+                        x,
+                        y,
+                        before,
+                        after,
+                    });
+                }
+            }
+        }
+
+        (!changes.is_empty()).then_some(TileStrokeCommand {
+            description: self.description,
+            mergeable: self.mergeable,
+            started_at: self.started_at,
+            changes,
+        })
+    }
+}
+
+fn snapshot(layer_manager: &LayerManager) -> LayerSnapshot {
+    layer_manager
+        .layers
+        .iter()
+        .map(|layer| {
+            let tiles = layer.tiles.iter().map(|t| ((t.x, t.y), *t)).collect();
+            (layer.metadata.id, tiles)
+        })
+        .collect()
+}
+
+/// History command holding the tiles a stroke changed. The stroke has already
+/// been painted when it is recorded, so it is added with `add_executed`.
+#[derive(Serialize, Deserialize)]
+pub struct TileStrokeCommand {
+    description: String,
+    mergeable: bool,
+    #[serde(skip, default = "Instant::now")]
+    started_at: Instant,
+    changes: Vec<TileChange>,
+}
+
+impl TileStrokeCommand {
+    pub fn changes(&self) -> &[TileChange] {
+        &self.changes
+    }
+
+    /// Write either side of the changes to `LayerManager` and the rendered tilemap
+    fn apply(&self, world: &mut World, redo: bool) {
+        for change in &self.changes {
+            let tile = if redo { change.after } else { change.before };
+
+            if let Some(mut layer_manager) = world.get_resource_mut::<LayerManager>() {
+                layer_manager.set_tile_on_layer(change.layer_id, change.x, change.y, tile);
+            }
+
+            match tile {
+                Some(tile) => {
+                    world.send_event(PaintTileEvent {
+                        layer_id: change.layer_id,
+                        x: change.x,
+                        y: change.y,
+                        tileset_id: tile.tileset_id,
+                        tile_id: tile.tile_id,
+                    });
+                }
+                None => {
+                    world.send_event(EraseTileEvent {
+                        layer_id: change.layer_id,
+                        x: change.x,
+                        y: change.y,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl EditorCommand for TileStrokeCommand {
+    fn execute(&mut self, world: &mut World) {
+        self.apply(world, true);
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        self.apply(world, false);
+    }
+
+    fn description(&self) -> String {
+        format!("{} ({} tiles)", self.description, self.changes.len())
+    }
+
+    fn can_merge_with(&self, other: &dyn EditorCommand) -> bool {
+        let Some(other) = other
+            .as_any()
+            .and_then(|any| any.downcast_ref::<TileStrokeCommand>())
+        else {
+            return false;
+        };
+        self.mergeable
+            && other.mergeable
+            && self.description == other.description
+            && other.started_at.saturating_duration_since(self.started_at) <= BRUSH_MERGE_WINDOW
+    }
+
+    fn merge(&mut self, other: Box<dyn EditorCommand>) {
+        let Some(other) = other
+            .as_any()
+            .and_then(|any| any.downcast_ref::<TileStrokeCommand>())
+        else {
+            return;
+        };
+
+        // Keep the earliest `before` and the latest `after` of every cell
+        for change in &other.changes {
+            match self
+                .changes
+                .iter_mut()
+                .find(|c| (c.layer_id, c.x, c.y) == (change.layer_id, change.x, change.y))
+            {
+                Some(existing) => existing.after = change.after,
+                None => self.changes.push(*change),
+            }
+        }
+        self.changes.retain(|c| c.before != c.after);
+        self.started_at = other.started_at;
+    }
+
+    fn as_any(&self) -> Option<&dyn std::any::Any> {
+        Some(self)
+    }
+
+    fn size_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.description.capacity()
+            + self.changes.capacity() * std::mem::size_of::<TileChange>()
+    }
+
+    fn to_record(&self) -> Option<CommandRecord> {
+        CommandRecord::of(self)
+    }
+}
+
+impl SerializableCommand for TileStrokeCommand {
+    const KIND: &'static str = "tile_stroke";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer_manager::create_default_layer;
+    use bevy_editor_formats::LayerType;
+
+    fn tile(x: u32, y: u32, tile_id: u32) -> TileData {
+        TileData {
+            x,
+            y,
+            tileset_id: 0,
+            tile_id,
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+
+    fn layers() -> LayerManager {
+        let mut layer_manager = LayerManager::new();
+        layer_manager.add_layer(create_default_layer(LayerType::Tiles, "Layer 0", 0, None));
+        layer_manager
+    }
+
+    fn world_with(layer_manager: LayerManager) -> World {
+        let mut world = World::new();
+        world.insert_resource(layer_manager);
+        world.init_resource::<Events<PaintTileEvent>>();
+        world.init_resource::<Events<EraseTileEvent>>();
+        world
+    }
+
+    #[test]
+    fn stroke_records_only_changed_cells() {
+        let mut layer_manager = layers();
+        layer_manager.add_tile(tile(0, 0, 1));
+        layer_manager.add_tile(tile(1, 0, 1));
+
+        let stroke = TileStroke::begin("Paint Tiles", true, &layer_manager);
+        layer_manager.add_tile(tile(0, 0, 2));
+        layer_manager.add_tile(tile(1, 0, 1));
+        layer_manager.remove_tile(5, 5);
+        let command = stroke.finish(&layer_manager).unwrap();
+
+        assert_eq!(command.changes().len(), 1);
+        assert_eq!(command.changes()[0].before, Some(tile(0, 0, 1)));
+        assert_eq!(command.changes()[0].after, Some(tile(0, 0, 2)));
+
+        let stroke = TileStroke::begin("Paint Tiles", true, &layer_manager);
+        assert!(stroke.finish(&layer_manager).is_none());
+    }
+
+    #[test]
+    fn undo_and_redo_restore_layer_tiles() {
+        let mut layer_manager = layers();
+        layer_manager.add_tile(tile(2, 2, 1));
+
+        let stroke = TileStroke::begin("Bucket Fill", false, &layer_manager);
+        layer_manager.add_tile(tile(2, 2, 7));
+        layer_manager.add_tile(tile(3, 2, 7));
+        let mut command = stroke.finish(&layer_manager).unwrap();
+
+        let mut world = world_with(layer_manager);
+        command.undo(&mut world);
+        let layer_manager = world.resource::<LayerManager>();
+        assert_eq!(layer_manager.get_tile_at(2, 2), Some(&tile(2, 2, 1)));
+        assert_eq!(layer_manager.get_tile_at(3, 2), None);
+        assert_eq!(world.resource::<Events<EraseTileEvent>>().len(), 1);
+
+        command.execute(&mut world);
+        let layer_manager = world.resource::<LayerManager>();
+        assert_eq!(layer_manager.get_tile_at(2, 2), Some(&tile(2, 2, 7)));
+        assert_eq!(layer_manager.get_tile_at(3, 2), Some(&tile(3, 2, 7)));
+    }
+
+    #[test]
+    fn consecutive_brush_strokes_merge() {
+        let mut layer_manager = layers();
+
+        let stroke = TileStroke::begin("Paint Tiles", true, &layer_manager);
+        layer_manager.add_tile(tile(0, 0, 1));
+        let mut first = stroke.finish(&layer_manager).unwrap();
+
+        let stroke = TileStroke::begin("Paint Tiles", true, &layer_manager);
+        layer_manager.add_tile(tile(0, 0, 2));
+        layer_manager.add_tile(tile(1, 0, 2));
+        let second = stroke.finish(&layer_manager).unwrap();
+
+        assert!(first.can_merge_with(&second));
+        first.merge(Box::new(second));
+        assert_eq!(first.changes().len(), 2);
+        assert!(first
+            .changes()
+            .iter()
+            .any(|c| c.before.is_none() && c.after == Some(tile(0, 0, 2))));
+
+        // Fills are never merged
+        let stroke = TileStroke::begin("Bucket Fill", false, &layer_manager);
+        layer_manager.add_tile(tile(4, 4, 3));
+        let fill = stroke.finish(&layer_manager).unwrap();
+        assert!(!first.can_merge_with(&fill));
+    }
+}
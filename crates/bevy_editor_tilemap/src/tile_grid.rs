@@ -0,0 +1,285 @@
+//! Grid geometry for square, isometric and hexagonal tile layers.
+//!
+//! Conversions go through bevy_ecs_tilemap's own helpers so picking, grid
+//! drawing and previews always agree with how the tilemap is rendered.
+
+use bevy::prelude::*;
+use bevy_ecs_tilemap::helpers::hex_grid::neighbors::HexNeighbors;
+use bevy_ecs_tilemap::helpers::square_grid::neighbors::Neighbors;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_editor_formats::{MapOrientation, TilesetData};
+
+use crate::{LayerManager, MapCanvas, TilesetManager};
+
+/// bevy_ecs_tilemap map type used to render an orientation
+pub fn tilemap_type(orientation: MapOrientation) -> TilemapType {
+    match orientation {
+        MapOrientation::Square => TilemapType::Square,
+        MapOrientation::IsometricDiamond => TilemapType::Isometric(IsoCoordSystem::Diamond),
+        MapOrientation::IsometricStaggered => TilemapType::Isometric(IsoCoordSystem::Staggered),
+        MapOrientation::HexRow => TilemapType::Hexagon(HexCoordSystem::RowOdd),
+        MapOrientation::HexColumn => TilemapType::Hexagon(HexCoordSystem::ColumnOdd),
+    }
+}
+
+/// Geometry of the grid being edited. The orientation belongs to the whole map;
+/// the cell size is the tile size of the selected tileset, since every map canvas
+/// lays its cells out at its own tileset's tile size (see [`TileGrid::for_tileset`]).
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct TileGrid {
+    /// Orientation of every layer of the map
+    pub orientation: MapOrientation,
+    /// Size of one grid cell in world units
+    pub cell_size: Vec2,
+    /// Map size in tiles
+    pub map_size: UVec2,
+}
+
+impl Default for TileGrid {
+    fn default() -> Self {
+        Self::new(MapOrientation::Square, Vec2::splat(16.0), UVec2::splat(64))
+    }
+}
+
+impl TileGrid {
+    pub fn new(orientation: MapOrientation, cell_size: Vec2, map_size: UVec2) -> Self {
+        Self {
+            orientation,
+            cell_size,
+            map_size,
+        }
+    }
+
+    /// The map's grid as laid out by the canvas of `tileset`
+    pub fn for_tileset(&self, tileset: &TilesetData) -> Self {
+        Self {
+            cell_size: Vec2::new(tileset.tile_width as f32, tileset.tile_height as f32),
+            ..*self
+        }
+    }
+
+    pub fn tilemap_type(&self) -> TilemapType {
+        tilemap_type(self.orientation)
+    }
+
+    pub fn grid_size(&self) -> TilemapGridSize {
+        TilemapGridSize {
+            x: self.cell_size.x,
+            y: self.cell_size.y,
+        }
+    }
+
+    /// World position of the center of tile (0, 0). Map canvases are placed here so
+    /// square cells span `[x * size, (x + 1) * size]`.
+    pub fn origin(&self) -> Vec2 {
+        self.cell_size / 2.0
+    }
+
+    /// Tile under a world position, if it lies on the map
+    pub fn world_to_tile(&self, world_pos: Vec2) -> Option<(u32, u32)> {
+        let local_pos = world_pos - self.origin();
+        TilePos::from_world_pos(
+            &local_pos,
+            &self.tilemap_size(),
+            &self.grid_size(),
+            &self.tile_size(),
+            &self.tilemap_type(),
+            &TilemapAnchor::None,
+        )
+        .map(|pos| (pos.x, pos.y))
+    }
+
+    /// World position of a tile's center
+    pub fn tile_center(&self, x: u32, y: u32) -> Vec2 {
+        self.origin()
+            + TilePos { x, y }.center_in_world(
+                &self.tilemap_size(),
+                &self.grid_size(),
+                &self.tile_size(),
+                &self.tilemap_type(),
+                &TilemapAnchor::None,
+            )
+    }
+
+    /// Corners of a tile's cell in world space, in drawing order
+    pub fn tile_outline(&self, x: u32, y: u32) -> Vec<Vec2> {
+        let center = self.tile_center(x, y);
+        let half = self.cell_size / 2.0;
+        let corners = match self.orientation {
+            MapOrientation::Square => vec![
+                Vec2::new(-half.x, -half.y),
+                Vec2::new(half.x, -half.y),
+                Vec2::new(half.x, half.y),
+                Vec2::new(-half.x, half.y),
+            ],
+            MapOrientation::IsometricDiamond | MapOrientation::IsometricStaggered => vec![
+                Vec2::new(0.0, -half.y),
+                Vec2::new(half.x, 0.0),
+                Vec2::new(0.0, half.y),
+                Vec2::new(-half.x, 0.0),
+            ],
+            // Pointy-top: rows are 3/4 of the cell height apart
+            MapOrientation::HexRow => vec![
+                Vec2::new(0.0, -half.y),
+                Vec2::new(half.x, -half.y / 2.0),
+                Vec2::new(half.x, half.y / 2.0),
+                Vec2::new(0.0, half.y),
+                Vec2::new(-half.x, half.y / 2.0),
+                Vec2::new(-half.x, -half.y / 2.0),
+            ],
+            // Flat-top: columns are 3/4 of the cell width apart
+            MapOrientation::HexColumn => vec![
+                Vec2::new(-half.x, 0.0),
+                Vec2::new(-half.x / 2.0, -half.y),
+                Vec2::new(half.x / 2.0, -half.y),
+                Vec2::new(half.x, 0.0),
+                Vec2::new(half.x / 2.0, half.y),
+                Vec2::new(-half.x / 2.0, half.y),
+            ],
+        };
+        corners.into_iter().map(|corner| center + corner).collect()
+    }
+
+    /// Tiles sharing an edge with the given tile (used for flood fill)
+    pub fn neighbors(&self, x: u32, y: u32) -> Vec<(u32, u32)> {
+        let tile_pos = TilePos { x, y };
+        let map_size = self.tilemap_size();
+        let positions: Vec<TilePos> = match self.tilemap_type() {
+            TilemapType::Square | TilemapType::Isometric(IsoCoordSystem::Diamond) => {
+                Neighbors::get_square_neighboring_positions(&tile_pos, &map_size, false)
+                    .iter()
+                    .copied()
+                    .collect()
+            }
+            TilemapType::Isometric(IsoCoordSystem::Staggered) => {
+                Neighbors::get_staggered_neighboring_positions(&tile_pos, &map_size, false)
+                    .iter()
+                    .copied()
+                    .collect()
+            }
+            TilemapType::Hexagon(coord_system) => {
+                HexNeighbors::get_neighboring_positions(&tile_pos, &map_size, &coord_system)
+                    .iter()
+                    .copied()
+                    .collect()
+            }
+        };
+        positions.into_iter().map(|pos| (pos.x, pos.y)).collect()
+    }
+
+    fn tilemap_size(&self) -> TilemapSize {
+        TilemapSize {
+            x: self.map_size.x,
+            y: self.map_size.y,
+        }
+    }
+
+    fn tile_size(&self) -> TilemapTileSize {
+        TilemapTileSize {
+            x: self.cell_size.x,
+            y: self.cell_size.y,
+        }
+    }
+}
+
+/// System to keep the `TileGrid`'s cell and map size in sync with the selected
+/// tileset and the active layer
+pub fn update_tile_grid(
+    layer_manager: Res<LayerManager>,
+    tileset_manager: Res<TilesetManager>,
+    mut tile_grid: ResMut<TileGrid>,
+) {
+    let Some(layer) = layer_manager.get_active_layer() else {
+        return;
+    };
+
+    let grid = TileGrid::new(
+        tile_grid.orientation,
+        Vec2::splat(layer.metadata.grid_size as f32),
+        UVec2::new(layer.metadata.width, layer.metadata.height),
+    );
+    let grid = match tileset_manager.get_selected_tileset() {
+        Some(info) => grid.for_tileset(&info.data),
+        None => grid,
+    };
+    tile_grid.set_if_neq(grid);
+}
+
+/// System to re-layout map canvases when the orientation or their tileset changes
+pub fn sync_canvas_grid(
+    tile_grid: Res<TileGrid>,
+    tileset_manager: Res<TilesetManager>,
+    mut canvas_query: Query<(
+        &MapCanvas,
+        &mut TilemapType,
+        &mut TilemapGridSize,
+        &mut Transform,
+    )>,
+) {
+    if !tile_grid.is_changed() && !tileset_manager.is_changed() {
+        return;
+    }
+
+    for (canvas, mut map_type, mut grid_size, mut transform) in canvas_query.iter_mut() {
+        let Some(info) = tileset_manager.get_tileset(canvas.tileset_id) else {
+            continue;
+        };
+        let grid = tile_grid.for_tileset(&info.data);
+        let origin = grid.origin();
+        map_type.set_if_neq(grid.tilemap_type());
+        grid_size.set_if_neq(grid.grid_size());
+        transform.translation.x = origin.x;
+        transform.translation.y = origin.y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(orientation: MapOrientation) -> TileGrid {
+        TileGrid::new(orientation, Vec2::new(32.0, 16.0), UVec2::splat(10))
+    }
+
+    #[test]
+    fn picking_round_trips_tile_centers() {
+        for orientation in MapOrientation::ALL {
+            let grid = grid(orientation);
+            for (x, y) in [(0, 0), (3, 4), (9, 9), (5, 2)] {
+                let center = grid.tile_center(x, y);
+                assert_eq!(
+                    grid.world_to_tile(center),
+                    Some((x, y)),
+                    "{orientation:?} ({x}, {y})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn square_cells_start_at_world_origin() {
+        let grid = TileGrid::new(MapOrientation::Square, Vec2::splat(16.0), UVec2::splat(4));
+        assert_eq!(grid.world_to_tile(Vec2::new(0.5, 0.5)), Some((0, 0)));
+        assert_eq!(grid.world_to_tile(Vec2::new(31.5, 16.5)), Some((1, 1)));
+        assert_eq!(grid.world_to_tile(Vec2::new(-0.5, 0.5)), None);
+    }
+
+    #[test]
+    fn neighbor_counts_match_orientation() {
+        assert_eq!(grid(MapOrientation::Square).neighbors(5, 5).len(), 4);
+        assert_eq!(
+            grid(MapOrientation::IsometricDiamond).neighbors(5, 5).len(),
+            4
+        );
+        assert_eq!(
+            grid(MapOrientation::IsometricStaggered)
+                .neighbors(5, 5)
+                .len(),
+            4
+        );
+        assert_eq!(grid(MapOrientation::HexRow).neighbors(5, 5).len(), 6);
+        assert_eq!(grid(MapOrientation::HexColumn).neighbors(5, 5).len(), 6);
+        // Corners are clipped to the map
+        assert_eq!(grid(MapOrientation::Square).neighbors(0, 0).len(), 2);
+    }
+}
@@ -1,9 +1,12 @@
 use bevy::prelude::*;
-use bevy_editor_formats::TileData;
+use bevy_editor_formats::{TileData, TileRef};
 
 use crate::layer_manager::LayerManager;
 use crate::map_canvas::PaintTileEvent;
+use crate::tile_commands::TileStroke;
+use crate::tile_grid::TileGrid;
 use crate::tileset_manager::TilesetManager;
+use crate::variation_brush::{TileBrush, VariationBrush};
 
 /// Tile painting mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -24,6 +27,10 @@ pub struct TilePainter {
     pub drag_start: Option<(u32, u32)>,
     /// Current cursor position (for previews rendered by the UI layer).
     pub current_pos: Option<(u32, u32)>,
+    /// Random/weighted variation brush; overrides the selected tile when active.
+    pub variation: VariationBrush,
+    /// Stroke being recorded for undo while a mouse button is held.
+    pub stroke: Option<TileStroke>,
 }
 
 impl Default for TilePainter {
@@ -34,6 +41,19 @@ impl Default for TilePainter {
             flip_y: false,
             drag_start: None,
             current_pos: None,
+            variation: VariationBrush::default(),
+            stroke: None,
+        }
+    }
+}
+
+impl TilePainter {
+    /// Brush to paint with: the variation set when active, otherwise `selected_tile`.
+    pub fn brush(&mut self, selected_tile: Option<TileRef>) -> Option<TileBrush<'_>> {
+        if self.variation.is_active() {
+            Some(TileBrush::Variation(&mut self.variation))
+        } else {
+            selected_tile.map(TileBrush::Tile)
         }
     }
 }
@@ -43,26 +63,27 @@ impl Default for TilePainter {
 pub fn paint_single_tile(
     x: u32,
     y: u32,
-    tile_id: u32,
+    tile: TileRef,
     flip_x: bool,
     flip_y: bool,
     layer_manager: &mut LayerManager,
     paint_events: &mut EventWriter<PaintTileEvent>,
 ) {
-    let tile = TileData {
+    layer_manager.add_tile(TileData {
         x,
         y,
-        tile_id,
+        tileset_id: tile.tileset_id,
+        tile_id: tile.tile_id,
         flip_x,
         flip_y,
-    };
-    layer_manager.add_tile(tile);
+    });
 
     paint_events.write(PaintTileEvent {
         layer_id: 0,
         x,
         y,
-        tile_id,
+        tileset_id: tile.tileset_id,
+        tile_id: tile.tile_id,
     });
 }
 
@@ -70,54 +91,52 @@ pub fn paint_single_tile(
 pub fn bucket_fill(
     start_x: u32,
     start_y: u32,
-    tile_id: u32,
+    brush: &mut TileBrush,
     flip_x: bool,
     flip_y: bool,
+    tile_grid: &TileGrid,
     layer_manager: &mut LayerManager,
     paint_events: &mut EventWriter<PaintTileEvent>,
 ) {
-    let target_tile = layer_manager.get_tile_at(start_x, start_y).copied();
-    let target_tile_id = target_tile.map(|t| t.tile_id);
+    let target_tile = layer_manager
+        .get_tile_at(start_x, start_y)
+        .map(TileData::tile_ref);
 
-    if target_tile_id == Some(tile_id) {
-        return;
+    if let Some(target_tile) = target_tile {
+        if brush.only_produces(target_tile) {
+            return;
+        }
     }
 
-    let Some(layer) = layer_manager.get_active_layer() else {
-        return;
-    };
-    let width = layer.metadata.width;
-    let height = layer.metadata.height;
+    // Neighbours depend on the map orientation (4 for square/isometric, 6 for hex)
 
+    // Collect the region first so variant tiles painted along the way cannot
+    // leak into (or cut off) the flood.
     let mut stack = vec![(start_x, start_y)];
     let mut visited = std::collections::HashSet::new();
+    let mut region = Vec::new();
 
     while let Some((x, y)) = stack.pop() {
         if !visited.insert((x, y)) {
             continue;
         }
 
-        let current_tile = layer_manager.get_tile_at(x, y).copied();
-        let current_tile_id = current_tile.map(|t| t.tile_id);
+        let current_tile = layer_manager.get_tile_at(x, y).map(TileData::tile_ref);
 
-        if current_tile_id != target_tile_id {
+        if current_tile != target_tile {
             continue;
         }
 
-        paint_single_tile(x, y, tile_id, flip_x, flip_y, layer_manager, paint_events);
+        region.push((x, y));
 
-        if x > 0 {
-            stack.push((x - 1, y));
-        }
-        if x + 1 < width {
-            stack.push((x + 1, y));
-        }
-        if y > 0 {
-            stack.push((x, y - 1));
-        }
-        if y + 1 < height {
-            stack.push((x, y + 1));
-        }
+        stack.extend(tile_grid.neighbors(x, y));
+    }
+
+    for (x, y) in region {
+        let Some(tile) = brush.next_tile() else {
+            return;
+        };
+        paint_single_tile(x, y, tile, flip_x, flip_y, layer_manager, paint_events);
     }
 }
 
@@ -127,7 +146,7 @@ pub fn paint_rectangle(
     start_y: u32,
     end_x: u32,
     end_y: u32,
-    tile_id: u32,
+    brush: &mut TileBrush,
     flip_x: bool,
     flip_y: bool,
     layer_manager: &mut LayerManager,
@@ -140,7 +159,10 @@ pub fn paint_rectangle(
 
     for y in min_y..=max_y {
         for x in min_x..=max_x {
-            paint_single_tile(x, y, tile_id, flip_x, flip_y, layer_manager, paint_events);
+            let Some(tile) = brush.next_tile() else {
+                return;
+            };
+            paint_single_tile(x, y, tile, flip_x, flip_y, layer_manager, paint_events);
         }
     }
 }
@@ -151,7 +173,7 @@ pub fn paint_line(
     start_y: u32,
     end_x: u32,
     end_y: u32,
-    tile_id: u32,
+    brush: &mut TileBrush,
     flip_x: bool,
     flip_y: bool,
     layer_manager: &mut LayerManager,
@@ -167,10 +189,13 @@ pub fn paint_line(
     let mut y = start_y as i32;
 
     loop {
+        let Some(tile) = brush.next_tile() else {
+            return;
+        };
         paint_single_tile(
             x as u32,
             y as u32,
-            tile_id,
+            tile,
             flip_x,
             flip_y,
             layer_manager,
@@ -211,6 +236,10 @@ pub fn paint_stamp(
         return;
     };
 
+    let Some(tileset_id) = tileset_manager.selected_tileset_id else {
+        return;
+    };
+
     for (index, &tile_id) in tileset_manager.selected_tiles.iter().enumerate() {
         let offset_x = (index as u32) % stamp_width;
         let offset_y = (index as u32) / stamp_width;
@@ -228,7 +257,7 @@ pub fn paint_stamp(
         paint_single_tile(
             world_x,
             world_y,
-            tile_id,
+            TileRef::new(tileset_id, tile_id),
             flip_x,
             flip_y,
             layer_manager,
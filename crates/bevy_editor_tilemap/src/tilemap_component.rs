@@ -49,7 +49,9 @@ pub fn sync_tilemap_entities(
         Changed<TilemapComponent>,
     >,
     tilemap_storage_query: Query<Entity, With<TileStorage>>,
-    tileset_manager: Res<crate::TilesetManager>,
+    mut tileset_manager: ResMut<crate::TilesetManager>,
+    tile_grid: Res<crate::TileGrid>,
+    mut images: ResMut<Assets<Image>>,
 ) {
     for (entity, mut tilemap_comp, transform) in tilemap_query.iter_mut() {
         // If tilemap entity already exists, skip
@@ -61,7 +63,15 @@ pub fn sync_tilemap_entities(
 
         // Get tileset info if available
         if let Some(tileset_id) = tilemap_comp.tileset_id {
-            if let Some(tileset_info) = tileset_manager.tilesets.get(&tileset_id) {
+            // Filling the render texture cache doesn't count as a tileset change
+            let tilesets = &mut tileset_manager.bypass_change_detection().tilesets;
+            if let Some(tileset_info) = tilesets.get_mut(&tileset_id) {
+                let Some(texture) = crate::tileset_render_texture(tileset_info, &mut images) else {
+                    // Image still loading; mark changed so we retry next frame
+                    tilemap_comp.set_changed();
+                    continue;
+                };
+
                 info!(
                     "Creating tilemap entity for TilemapComponent on entity {:?}",
                     entity
@@ -77,9 +87,12 @@ pub fn sync_tilemap_entities(
                     y: tileset_info.data.tile_height as f32,
                 };
 
-                let grid_size = TilemapGridSize {
-                    x: tileset_info.data.tile_width as f32,
-                    y: tileset_info.data.tile_height as f32,
+                let grid = tile_grid.for_tileset(&tileset_info.data);
+                let grid_size = grid.grid_size();
+
+                let spacing = TilemapSpacing {
+                    x: tileset_info.data.spacing as f32,
+                    y: tileset_info.data.spacing as f32,
                 };
 
                 // Create the tilemap entity
@@ -103,14 +116,13 @@ pub fn sync_tilemap_entities(
                     }
                 }
 
-                // Offset by half a tile so tiles are centered on grid intersections
-                let half_tile_x = tileset_info.data.tile_width as f32 / 2.0;
-                let half_tile_y = tileset_info.data.tile_height as f32 / 2.0;
+                // Offset by half a cell so square cells start on grid intersections
+                let origin = grid.origin();
 
                 // Use the parent entity's transform
                 let tilemap_transform = Transform::from_xyz(
-                    transform.translation.x + half_tile_x,
-                    transform.translation.y + half_tile_y,
+                    transform.translation.x + origin.x,
+                    transform.translation.y + origin.y,
                     transform.translation.z,
                 );
 
@@ -118,10 +130,11 @@ pub fn sync_tilemap_entities(
                     grid_size,
                     size: map_size,
                     storage: tile_storage,
-                    texture: TilemapTexture::Single(tileset_info.texture_handle.clone()),
+                    texture: TilemapTexture::Single(texture),
                     tile_size,
+                    spacing,
                     transform: tilemap_transform,
-                    map_type: TilemapType::Square,
+                    map_type: grid.tilemap_type(),
                     ..Default::default()
                 });
 
@@ -0,0 +1,226 @@
+//! Tileset image layout: spacing/margin detection and margin-free render textures.
+
+use bevy::prelude::*;
+use bevy_editor_formats::crop_tileset_margin;
+
+use crate::TilesetInfo;
+
+/// Largest spacing or padding (in pixels) considered by auto-detection
+const MAX_GUTTER: u32 = 8;
+
+/// Tile sizes tried when the requested size doesn't fit the image
+const COMMON_TILE_SIZES: [u32; 8] = [8, 12, 16, 24, 32, 48, 64, 128];
+
+/// Tile size, spacing and padding of a tileset image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TilesetLayout {
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub spacing: u32,
+    pub padding: u32,
+}
+
+/// Detect the spacing and padding of a tileset image by looking for empty gutters.
+/// The requested tile size is preferred; common square sizes are tried if it doesn't fit.
+pub fn detect_tileset_layout(image: &Image, tile_width: u32, tile_height: u32) -> TilesetLayout {
+    let width = image.width();
+    let height = image.height();
+
+    // Gutters are either transparent or filled with the colour of the top-left pixel
+    let background = image.get_color_at(0, 0).ok().map(|c| c.to_srgba());
+    let is_background = |x: u32, y: u32| match image.get_color_at(x, y) {
+        Ok(color) => {
+            let color = color.to_srgba();
+            color.alpha < 0.01 || Some(color) == background
+        }
+        Err(_) => false,
+    };
+
+    let empty_columns: Vec<bool> = (0..width)
+        .map(|x| (0..height).all(|y| is_background(x, y)))
+        .collect();
+    let empty_rows: Vec<bool> = (0..height)
+        .map(|y| (0..width).all(|x| is_background(x, y)))
+        .collect();
+
+    detect_from_gutters(&empty_columns, &empty_rows, tile_width, tile_height)
+}
+
+/// Find the smallest spacing/padding for which the tile grid fits the image exactly
+/// and every gutter line is empty.
+fn detect_from_gutters(
+    empty_columns: &[bool],
+    empty_rows: &[bool],
+    tile_width: u32,
+    tile_height: u32,
+) -> TilesetLayout {
+    let requested = std::iter::once((tile_width, tile_height));
+    let common = COMMON_TILE_SIZES.iter().map(|&size| (size, size));
+
+    for (tile_width, tile_height) in requested.chain(common) {
+        for gutter_total in 0..=MAX_GUTTER * 2 {
+            for padding in 0..=gutter_total.min(MAX_GUTTER) {
+                let spacing = gutter_total - padding;
+                if spacing > MAX_GUTTER {
+                    continue;
+                }
+                if axis_matches(empty_columns, tile_width, spacing, padding)
+                    && axis_matches(empty_rows, tile_height, spacing, padding)
+                {
+                    return TilesetLayout {
+                        tile_width,
+                        tile_height,
+                        spacing,
+                        padding,
+                    };
+                }
+            }
+        }
+    }
+
+    TilesetLayout {
+        tile_width,
+        tile_height,
+        spacing: 0,
+        padding: 0,
+    }
+}
+
+/// Whether tiles fill one image axis exactly with only empty lines in the gutters
+fn axis_matches(empty_lines: &[bool], tile_size: u32, spacing: u32, padding: u32) -> bool {
+    let length = empty_lines.len() as u32;
+    let stride = tile_size + spacing;
+    let Some(usable) = length.checked_sub(padding * 2) else {
+        return false;
+    };
+    if tile_size == 0 || usable < tile_size || !(usable + spacing).is_multiple_of(stride) {
+        return false;
+    }
+
+    empty_lines.iter().enumerate().all(|(i, &empty)| {
+        let i = i as u32;
+        let in_margin = i < padding || i >= length - padding;
+        let in_spacing = !in_margin && (i - padding) % stride >= tile_size;
+        empty || !(in_margin || in_spacing)
+    })
+}
+
+/// Texture to render a tileset with. bevy_ecs_tilemap understands spacing but not an
+/// outer margin, so tilesets with padding are rendered from a copy with the top/left
+/// margin cropped off. The copy is cached on the tileset until its spacing or padding
+/// changes. Returns `None` while the image is still loading.
+pub fn tileset_render_texture(
+    tileset_info: &mut TilesetInfo,
+    images: &mut Assets<Image>,
+) -> Option<Handle<Image>> {
+    let padding = tileset_info.data.padding;
+    if padding == 0 {
+        return Some(tileset_info.texture_handle.clone());
+    }
+
+    let layout = (tileset_info.data.spacing, padding);
+    if let Some((cached_layout, handle)) = &tileset_info.render_texture {
+        if *cached_layout == layout {
+            return Some(handle.clone());
+        }
+    }
+
+    let image = images.get(&tileset_info.texture_handle)?;
+    let Some(cropped) = crop_tileset_margin(image, padding) else {
+        warn!(
+            "Tileset '{}': could not crop {}px margin, rendering without it",
+            tileset_info.data.identifier, padding
+        );
+        return Some(tileset_info.texture_handle.clone());
+    };
+    let handle = images.add(cropped);
+    tileset_info.render_texture = Some((layout, handle.clone()));
+    Some(handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::asset::RenderAssetUsages;
+    use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+    /// Empty-line mask for `count` tiles with the given gutters
+    fn gutters(count: u32, tile_size: u32, spacing: u32, padding: u32) -> Vec<bool> {
+        let mut lines = vec![true; padding as usize];
+        for i in 0..count {
+            if i > 0 {
+                lines.extend(std::iter::repeat_n(true, spacing as usize));
+            }
+            lines.extend(std::iter::repeat_n(false, tile_size as usize));
+        }
+        lines.extend(std::iter::repeat_n(true, padding as usize));
+        lines
+    }
+
+    #[test]
+    fn plain_grid_has_no_gutters() {
+        let columns = gutters(8, 16, 0, 0);
+        let rows = gutters(4, 16, 0, 0);
+        let layout = detect_from_gutters(&columns, &rows, 16, 16);
+        assert_eq!((layout.spacing, layout.padding), (0, 0));
+    }
+
+    #[test]
+    fn detects_spacing_and_padding() {
+        let columns = gutters(10, 16, 2, 1);
+        let rows = gutters(6, 16, 2, 1);
+        let layout = detect_from_gutters(&columns, &rows, 16, 16);
+        assert_eq!((layout.spacing, layout.padding), (2, 1));
+    }
+
+    #[test]
+    fn falls_back_to_common_tile_size() {
+        let columns = gutters(5, 32, 1, 0);
+        let rows = gutters(5, 32, 1, 0);
+        let layout = detect_from_gutters(&columns, &rows, 20, 20);
+        assert_eq!(
+            layout,
+            TilesetLayout {
+                tile_width: 32,
+                tile_height: 32,
+                spacing: 1,
+                padding: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn cropped_render_texture_is_reused_until_the_margin_changes() {
+        let mut images = Assets::<Image>::default();
+        let texture = images.add(Image::new_fill(
+            Extent3d {
+                width: 8,
+                height: 8,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[255, 0, 0, 255],
+            bevy::render::render_resource::TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        ));
+        let mut tilesets = crate::TilesetManager::new();
+        let id = tilesets.add_tileset(
+            bevy_editor_formats::TilesetData {
+                padding: 1,
+                ..Default::default()
+            },
+            texture,
+        );
+        let tileset = tilesets.tilesets.get_mut(&id).unwrap();
+
+        let first = tileset_render_texture(tileset, &mut images).unwrap();
+        let second = tileset_render_texture(tileset, &mut images).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(images.len(), 2);
+
+        tileset.data.padding = 2;
+        let recropped = tileset_render_texture(tileset, &mut images).unwrap();
+        assert_ne!(first, recropped);
+        assert_eq!(images.get(&recropped).unwrap().width(), 6);
+    }
+}
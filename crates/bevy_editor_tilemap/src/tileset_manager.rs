@@ -1,6 +1,6 @@
 use bevy::image::ImageSampler;
 use bevy::prelude::*;
-use bevy_editor_formats::TilesetData;
+use bevy_editor_formats::{TileRef, TilesetData};
 use std::collections::HashMap;
 
 /// Manages loaded tilesets for the editor
@@ -28,6 +28,9 @@ pub struct TilesetInfo {
     pub texture_handle: Handle<Image>,
     /// Cached tile count for quick access
     pub tile_count: u32,
+    /// Margin-cropped render texture with the (spacing, padding) it was made
+    /// for, reused by every canvas of this tileset
+    pub(crate) render_texture: Option<((u32, u32), Handle<Image>)>,
 }
 
 impl TilesetManager {
@@ -57,6 +60,7 @@ impl TilesetManager {
                 data,
                 texture_handle,
                 tile_count,
+                render_texture: None,
             },
         );
 
@@ -110,6 +114,40 @@ impl TilesetManager {
         self.selected_tile_id
     }
 
+    /// Get the currently selected tile qualified by its tileset
+    pub fn selected_tile_ref(&self) -> Option<TileRef> {
+        Some(TileRef::new(
+            self.selected_tileset_id?,
+            self.selected_tile_id?,
+        ))
+    }
+
+    /// Select a tile together with the tileset it belongs to (e.g. from the eyedropper)
+    pub fn select_tile_ref(&mut self, tile: TileRef) {
+        if self.selected_tileset_id != Some(tile.tileset_id) {
+            self.select_tileset(tile.tileset_id);
+            self.clear_multi_selection();
+        }
+        self.select_tile(tile.tile_id);
+    }
+
+    /// Find a loaded tileset by the texture path it was loaded from
+    pub fn find_by_texture_path(&self, texture_path: &str) -> Option<u32> {
+        self.tilesets
+            .iter()
+            .find(|(_, info)| info.data.texture_path == texture_path)
+            .map(|(id, _)| *id)
+    }
+
+    /// Find a loaded tileset by its texture path relative to the assets folder,
+    /// the key saved levels and project presets refer to tilesets by
+    pub fn find_by_relative_texture_path(&self, relative_path: &str) -> Option<u32> {
+        self.tilesets
+            .iter()
+            .find(|(_, info)| info.data.relative_texture_path() == relative_path)
+            .map(|(id, _)| *id)
+    }
+
     /// Check if a tile is currently selected
     pub fn is_tile_selected(&self, tile_id: u32) -> bool {
         self.selected_tile_id == Some(tile_id)
@@ -281,39 +319,40 @@ pub fn handle_tileset_load_requests(
             let image_width = image.width();
             let image_height = image.height();
 
-            let columns = image_width / event.tile_width;
-            let rows = image_height / event.tile_height;
+            let mut data = TilesetData {
+                id: 0,
+                identifier: event.identifier.clone(),
+                texture_path: event.path.clone(),
+                tile_width: event.tile_width,
+                tile_height: event.tile_height,
+                columns: 0,
+                rows: 0,
+                spacing: event.spacing,
+                padding: event.padding,
+                collision_data: std::collections::HashMap::new(),
+            };
+            let (columns, rows) = data.grid_for_image(image_width, image_height);
+            data.columns = columns;
+            data.rows = rows;
 
             // Validate dimensions
-            if image_width % event.tile_width != 0 {
+            let (leftover_x, leftover_y) = data.leftover_pixels(image_width, image_height);
+            if leftover_x != 0 {
                 warn!(
-                    "Tileset '{}': Image width ({}) is not evenly divisible by tile width ({})",
-                    event.identifier, image_width, event.tile_width
+                    "Tileset '{}': {}px of image width ({}) not covered by tiles (tile width {}, spacing {}, padding {})",
+                    event.identifier, leftover_x, image_width, event.tile_width, event.spacing, event.padding
                 );
             }
-            if image_height % event.tile_height != 0 {
+            if leftover_y != 0 {
                 warn!(
-                    "Tileset '{}': Image height ({}) is not evenly divisible by tile height ({})",
-                    event.identifier, image_height, event.tile_height
+                    "Tileset '{}': {}px of image height ({}) not covered by tiles (tile height {}, spacing {}, padding {})",
+                    event.identifier, leftover_y, image_height, event.tile_height, event.spacing, event.padding
                 );
             }
 
             // Configure nearest-neighbor sampling for pixel-perfect rendering
             image.sampler = ImageSampler::nearest();
 
-            let data = TilesetData {
-                id: 0,
-                identifier: event.identifier.clone(),
-                texture_path: event.path.clone(),
-                tile_width: event.tile_width,
-                tile_height: event.tile_height,
-                columns,
-                rows,
-                spacing: 0,
-                padding: 0,
-                collision_data: std::collections::HashMap::new(),
-            };
-
             let tileset_id = tileset_manager.add_tileset(data, texture_handle.clone());
             info!(
                 "Loaded tileset '{}' with ID {} ({}x{} tiles, {}x{} image)",
@@ -335,8 +374,8 @@ pub fn handle_tileset_load_requests(
                 tile_height: event.tile_height,
                 columns: 16,
                 rows: 16,
-                spacing: 0,
-                padding: 0,
+                spacing: event.spacing,
+                padding: event.padding,
                 collision_data: std::collections::HashMap::new(),
             };
 
@@ -365,8 +404,7 @@ pub fn update_tileset_dimensions(
                 let image_width = image.width();
                 let image_height = image.height();
 
-                let columns = image_width / tileset_info.data.tile_width;
-                let rows = image_height / tileset_info.data.tile_height;
+                let (columns, rows) = tileset_info.data.grid_for_image(image_width, image_height);
 
                 // Only update if dimensions actually changed
                 if columns != tileset_info.data.columns || rows != tileset_info.data.rows {
@@ -401,6 +439,10 @@ pub struct LoadTilesetEvent {
     pub identifier: String,
     pub tile_width: u32,
     pub tile_height: u32,
+    /// Gap in pixels between neighbouring tiles
+    pub spacing: u32,
+    /// Margin in pixels around the edge of the image
+    pub padding: u32,
 }
 
 impl LoadTilesetEvent {
@@ -410,6 +452,15 @@ impl LoadTilesetEvent {
             identifier: identifier.to_string(),
             tile_width,
             tile_height,
+            spacing: 0,
+            padding: 0,
         }
     }
+
+    /// Set the spacing between tiles and the margin around the image
+    pub fn with_spacing(mut self, spacing: u32, padding: u32) -> Self {
+        self.spacing = spacing;
+        self.padding = padding;
+        self
+    }
 }
@@ -0,0 +1,273 @@
+//! Random/weighted variation brush used to break up tiling repetition.
+
+use crate::TilesetManager;
+use bevy::log::warn;
+use bevy_editor_formats::{BrushPreset, PresetTile, TileRef, WeightedTile};
+
+/// Weighted set of tile ids that the painter picks from for every painted cell.
+#[derive(Debug, Clone)]
+pub struct VariationBrush {
+    pub enabled: bool,
+    pub tiles: Vec<WeightedTile>,
+    /// Fixed seed for reproducible strokes. When `None` each stroke uses a fresh seed.
+    pub seed: Option<u64>,
+    /// Current RNG state (splitmix64).
+    state: u64,
+}
+
+impl Default for VariationBrush {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tiles: Vec::new(),
+            seed: None,
+            state: entropy_seed(),
+        }
+    }
+}
+
+impl VariationBrush {
+    /// Replace the variant set with tiles from one tileset, all weighted equally.
+    /// Typically seeded from `TilesetManager::selected_tiles`.
+    pub fn set_tiles(&mut self, tileset_id: u32, tile_ids: &[u32]) {
+        self.tiles = tile_ids
+            .iter()
+            .map(|&tile_id| WeightedTile::new(TileRef::new(tileset_id, tile_id), 1.0))
+            .collect();
+    }
+
+    /// Whether painting should pick from the variant set.
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.total_weight() > 0.0
+    }
+
+    /// Reset the RNG at the start of a stroke. With a fixed seed the same
+    /// stroke always produces the same tiles.
+    pub fn begin_stroke(&mut self) {
+        self.state = self.seed.unwrap_or_else(entropy_seed);
+    }
+
+    /// Pick the next tile, or `None` if the set is empty or has no weight.
+    pub fn next_tile(&mut self) -> Option<TileRef> {
+        let total = self.total_weight();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = self.next_f32() * total;
+        let mut last = None;
+        for tile in self.tiles.iter().filter(|t| t.weight > 0.0) {
+            if roll < tile.weight {
+                return Some(tile.tile_ref());
+            }
+            roll -= tile.weight;
+            last = Some(tile.tile_ref());
+        }
+
+        // Floating point rounding can leave a tiny remainder
+        last
+    }
+
+    /// Whether every tile this brush can produce is `tile`.
+    pub fn only_produces(&self, tile: TileRef) -> bool {
+        self.tiles
+            .iter()
+            .filter(|t| t.weight > 0.0)
+            .all(|t| t.tile_ref() == tile)
+    }
+
+    /// Build a preset from the current brush settings. Tilesets are stored by
+    /// texture path; variants from tilesets that are no longer loaded are dropped.
+    pub fn to_preset(&self, name: &str, tileset_manager: &TilesetManager) -> BrushPreset {
+        BrushPreset {
+            name: name.to_string(),
+            tiles: self
+                .tiles
+                .iter()
+                .filter_map(|tile| {
+                    let info = tileset_manager.get_tileset(tile.tileset_id)?;
+                    Some(PresetTile {
+                        tileset: info.data.relative_texture_path(),
+                        tile_id: tile.tile_id,
+                        weight: tile.weight,
+                    })
+                })
+                .collect(),
+            seed: self.seed,
+        }
+    }
+
+    /// Load tiles and seed from a saved preset and enable the brush. Variants
+    /// whose tileset isn't loaded are skipped.
+    pub fn apply_preset(&mut self, preset: &BrushPreset, tileset_manager: &TilesetManager) {
+        self.tiles = preset
+            .tiles
+            .iter()
+            .filter_map(|tile| {
+                let Some(tileset_id) = tileset_manager.find_by_relative_texture_path(&tile.tileset)
+                else {
+                    warn!(
+                        "Brush preset '{}' uses tileset '{}' which isn't loaded",
+                        preset.name, tile.tileset
+                    );
+                    return None;
+                };
+                Some(WeightedTile::new(
+                    TileRef::new(tileset_id, tile.tile_id),
+                    tile.weight,
+                ))
+            })
+            .collect();
+        self.seed = preset.seed;
+        self.enabled = true;
+        self.begin_stroke();
+    }
+
+    fn total_weight(&self) -> f32 {
+        self.tiles.iter().map(|t| t.weight.max(0.0)).sum()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in `[0, 1)`.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+fn entropy_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// Source of tiles for a paint operation.
+pub enum TileBrush<'a> {
+    /// Paint every cell with the same tile.
+    Tile(TileRef),
+    /// Pick a weighted random tile for every cell.
+    Variation(&'a mut VariationBrush),
+}
+
+impl TileBrush<'_> {
+    /// Tile for the next painted cell.
+    pub fn next_tile(&mut self) -> Option<TileRef> {
+        match self {
+            TileBrush::Tile(tile) => Some(*tile),
+            TileBrush::Variation(brush) => brush.next_tile(),
+        }
+    }
+
+    /// Whether painting over `tile` with this brush would change nothing.
+    pub fn only_produces(&self, tile: TileRef) -> bool {
+        match self {
+            TileBrush::Tile(brush_tile) => *brush_tile == tile,
+            TileBrush::Variation(brush) => brush.only_produces(tile),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::Handle;
+    use bevy_editor_formats::TilesetData;
+
+    fn brush(tiles: &[(u32, f32)], seed: u64) -> VariationBrush {
+        let mut brush = VariationBrush {
+            enabled: true,
+            tiles: tiles
+                .iter()
+                .map(|&(id, weight)| WeightedTile::new(TileRef::new(0, id), weight))
+                .collect(),
+            seed: Some(seed),
+            ..Default::default()
+        };
+        brush.begin_stroke();
+        brush
+    }
+
+    #[test]
+    fn fixed_seed_is_reproducible() {
+        let mut a = brush(&[(1, 1.0), (2, 1.0), (3, 1.0)], 42);
+        let mut b = brush(&[(1, 1.0), (2, 1.0), (3, 1.0)], 42);
+        let first: Vec<_> = (0..32).map(|_| a.next_tile()).collect();
+        let second: Vec<_> = (0..32).map(|_| b.next_tile()).collect();
+        assert_eq!(first, second);
+
+        a.begin_stroke();
+        let replay: Vec<_> = (0..32).map(|_| a.next_tile()).collect();
+        assert_eq!(first, replay);
+    }
+
+    #[test]
+    fn zero_weight_tiles_are_never_picked() {
+        let mut brush = brush(&[(1, 0.0), (2, 1.0)], 7);
+        assert!((0..100).all(|_| brush.next_tile() == Some(TileRef::new(0, 2))));
+        assert!(brush.only_produces(TileRef::new(0, 2)));
+    }
+
+    #[test]
+    fn weights_bias_the_distribution() {
+        let mut brush = brush(&[(1, 9.0), (2, 1.0)], 1234);
+        let ones = (0..1000)
+            .filter(|_| brush.next_tile() == Some(TileRef::new(0, 1)))
+            .count();
+        assert!(ones > 800, "expected heavy bias towards tile 1, got {ones}");
+    }
+
+    #[test]
+    fn variants_keep_their_tileset() {
+        let mut brush = VariationBrush::default();
+        brush.set_tiles(3, &[5, 6]);
+        brush.begin_stroke();
+        let tile = brush.next_tile().unwrap();
+        assert_eq!(tile.tileset_id, 3);
+        assert!(tile.tile_id == 5 || tile.tile_id == 6);
+    }
+
+    fn tileset(name: &str) -> TilesetData {
+        TilesetData {
+            identifier: name.to_string(),
+            texture_path: format!("/project/assets/tilesets/{name}.png"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn presets_follow_tilesets_across_load_order() {
+        let mut first_session = TilesetManager::new();
+        first_session.add_tileset(tileset("grass"), Handle::default());
+        let rocks = first_session.add_tileset(tileset("rocks"), Handle::default());
+        let mut brush = VariationBrush::default();
+        brush.set_tiles(rocks, &[4]);
+        let preset = brush.to_preset("Rocks", &first_session);
+        assert_eq!(preset.tiles[0].tileset, "tilesets/rocks.png");
+
+        // The next session loads the tilesets the other way round
+        let mut second_session = TilesetManager::new();
+        let rocks = second_session.add_tileset(tileset("rocks"), Handle::default());
+        second_session.add_tileset(tileset("grass"), Handle::default());
+        let mut loaded = VariationBrush::default();
+        loaded.apply_preset(&preset, &second_session);
+
+        assert_eq!(loaded.next_tile(), Some(TileRef::new(rocks, 4)));
+    }
+
+    #[test]
+    fn empty_brush_is_inactive() {
+        let mut brush = VariationBrush {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(!brush.is_active());
+        assert_eq!(brush.next_tile(), None);
+    }
+}
@@ -0,0 +1,282 @@
+//! Align, distribute and match operations on the selected entities
+//!
+//! Operations arrive as [`EditorAction::ArrangeSelection`] and work on the
+//! entities' world-space sprite bounds. Everything one operation moves,
+//! scales or turns is recorded as a single [`TransformEntitiesCommand`].
+
+use bevy::prelude::*;
+use bevy_editor_commands::EditorHistory;
+use bevy_editor_foundation::Selection;
+use bevy_editor_frontend_api::{
+    AlignEdge, ArrangeAxis, ArrangeOperation, DistributeSpacing, EditorAction,
+};
+
+use crate::editor_commands::{TransformChange, TransformEntitiesCommand};
+use crate::entity_bounds::EntityBounds;
+use crate::viewport_selection::{has_selected_ancestor, DraggableQuery};
+
+/// System applying arrange actions to the selection
+pub fn arrange_selection_system(
+    mut actions: EventReader<EditorAction>,
+    selection: Res<Selection>,
+    mut entity_query: DraggableQuery,
+    parent_query: Query<&GlobalTransform>,
+    images: Res<Assets<Image>>,
+    atlas_layouts: Res<Assets<TextureAtlasLayout>>,
+    mut history: ResMut<EditorHistory>,
+) {
+    for action in actions.read() {
+        let EditorAction::ArrangeSelection(operation) = *action else {
+            continue;
+        };
+
+        // Children follow their parents, so only the topmost selected are arranged
+        let selected: Vec<Entity> = selection.iter().collect();
+        let bounds = |entity: Entity| {
+            let (_, global_transform, sprite, ..) = entity_query.get(entity).ok()?;
+            Some(EntityBounds::of_sprite(
+                global_transform,
+                sprite,
+                &images,
+                &atlas_layouts,
+            ))
+        };
+        let arranged: Vec<(Entity, EntityBounds)> = selected
+            .iter()
+            .filter(|&&entity| !has_selected_ancestor(entity, &selected, &entity_query))
+            .filter_map(|&entity| Some((entity, bounds(entity)?)))
+            .collect();
+        let rects: Vec<Rect> = arranged.iter().map(|(_, bounds)| bounds.aabb()).collect();
+        let offsets = layout_offsets(operation, &rects);
+        let primary = selection.primary().and_then(|primary| {
+            let (_, global_transform, ..) = entity_query.get(primary).ok()?;
+            Some((
+                global_transform.compute_transform().rotation,
+                bounds(primary)?.scaled_size(),
+            ))
+        });
+
+        let mut changes = Vec::new();
+        for (index, &(entity, bounds)) in arranged.iter().enumerate() {
+            let Ok((mut transform, _, _, parent, id)) = entity_query.get_mut(entity) else {
+                continue;
+            };
+            let parent_transform =
+                parent.and_then(|child_of| parent_query.get(child_of.parent()).ok());
+            let old = *transform;
+            match operation {
+                ArrangeOperation::Align(_) | ArrangeOperation::Distribute { .. } => {
+                    // Offsets are in world space, translations in the parent's
+                    let offset = parent_transform.map_or(offsets[index].extend(0.0), |parent| {
+                        parent
+                            .affine()
+                            .inverse()
+                            .transform_vector3(offsets[index].extend(0.0))
+                    });
+                    transform.translation += offset;
+                }
+                ArrangeOperation::MatchSize => {
+                    let Some((_, target)) = primary else {
+                        continue;
+                    };
+                    let size = bounds.scaled_size();
+                    let factor = Vec2::new(
+                        if size.x > f32::EPSILON {
+                            target.x / size.x
+                        } else {
+                            1.0
+                        },
+                        if size.y > f32::EPSILON {
+                            target.y / size.y
+                        } else {
+                            1.0
+                        },
+                    );
+                    transform.scale *= factor.extend(1.0);
+                }
+                ArrangeOperation::MatchRotation => {
+                    let Some((target, _)) = primary else {
+                        continue;
+                    };
+                    let parent_rotation = parent_transform
+                        .map_or(Quat::IDENTITY, |parent| parent.compute_transform().rotation);
+                    transform.rotation = parent_rotation.inverse() * target;
+                }
+            }
+            if let (true, Some(&id)) = (*transform != old, id) {
+                changes.push(TransformChange {
+                    entity: id,
+                    old,
+                    new: *transform,
+                });
+            }
+        }
+
+        if changes.is_empty() {
+            continue;
+        }
+        let property = match operation {
+            ArrangeOperation::Align(_) | ArrangeOperation::Distribute { .. } => "Position",
+            ArrangeOperation::MatchSize => "Scale",
+            ArrangeOperation::MatchRotation => "Rotation",
+        };
+        info!("{:?} on {} entities", operation, changes.len());
+        history.add_executed(Box::new(TransformEntitiesCommand::new(changes, property)));
+    }
+}
+
+/// How far each of `rects` moves for an align or distribute operation; other
+/// operations don't move anything
+fn layout_offsets(operation: ArrangeOperation, rects: &[Rect]) -> Vec<Vec2> {
+    let mut offsets = vec![Vec2::ZERO; rects.len()];
+    match operation {
+        ArrangeOperation::Align(edge) => {
+            let union = rects
+                .iter()
+                .fold(Rect::EMPTY, |union, rect| union.union(*rect));
+            for (offset, rect) in offsets.iter_mut().zip(rects) {
+                *offset = match edge {
+                    AlignEdge::Left => Vec2::X * (union.min.x - rect.min.x),
+                    AlignEdge::Center => Vec2::X * (union.center().x - rect.center().x),
+                    AlignEdge::Right => Vec2::X * (union.max.x - rect.max.x),
+                    AlignEdge::Top => Vec2::Y * (union.max.y - rect.max.y),
+                    AlignEdge::Middle => Vec2::Y * (union.center().y - rect.center().y),
+                    AlignEdge::Bottom => Vec2::Y * (union.min.y - rect.min.y),
+                };
+            }
+        }
+        ArrangeOperation::Distribute { axis, spacing } if rects.len() > 2 => {
+            let direction = match axis {
+                ArrangeAxis::Horizontal => Vec2::X,
+                ArrangeAxis::Vertical => Vec2::Y,
+            };
+            let along = |point: Vec2| point.dot(direction);
+            // The outermost entities stay, the rest are spread out between them
+            let mut order: Vec<usize> = (0..rects.len()).collect();
+            order.sort_by(|&a, &b| along(rects[a].center()).total_cmp(&along(rects[b].center())));
+            let first = rects[order[0]];
+            let last = rects[order[order.len() - 1]];
+            let steps = (rects.len() - 1) as f32;
+            match spacing {
+                DistributeSpacing::Centers => {
+                    let start = along(first.center());
+                    let step = (along(last.center()) - start) / steps;
+                    for (position, &index) in order.iter().enumerate() {
+                        let target = start + step * position as f32;
+                        offsets[index] = direction * (target - along(rects[index].center()));
+                    }
+                }
+                DistributeSpacing::Gaps => {
+                    let total: f32 = rects.iter().map(|rect| along(rect.size())).sum();
+                    let gap = (along(last.max) - along(first.min) - total) / steps;
+                    let mut cursor = along(first.min);
+                    for &index in &order {
+                        offsets[index] = direction * (cursor - along(rects[index].min));
+                        cursor += along(rects[index].size()) + gap;
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_editor_scene::{EditorEntityId, EditorSceneEntity};
+    use std::f32::consts::FRAC_PI_4;
+
+    #[test]
+    fn align_lines_bounds_up_with_the_selection() {
+        let rects = [
+            Rect::new(0.0, 0.0, 10.0, 10.0),
+            Rect::new(20.0, 5.0, 40.0, 30.0),
+        ];
+        let offsets = layout_offsets(ArrangeOperation::Align(AlignEdge::Left), &rects);
+        assert_eq!(offsets, vec![Vec2::ZERO, Vec2::new(-20.0, 0.0)]);
+        let offsets = layout_offsets(ArrangeOperation::Align(AlignEdge::Top), &rects);
+        assert_eq!(offsets, vec![Vec2::new(0.0, 20.0), Vec2::ZERO]);
+        let offsets = layout_offsets(ArrangeOperation::Align(AlignEdge::Center), &rects);
+        assert_eq!(offsets, vec![Vec2::new(15.0, 0.0), Vec2::new(-10.0, 0.0)]);
+    }
+
+    #[test]
+    fn distribute_spaces_centers_or_gaps_evenly() {
+        // Out of order, with the middle one closer to the left
+        let rects = [
+            Rect::new(100.0, 0.0, 130.0, 10.0),
+            Rect::new(0.0, 0.0, 10.0, 10.0),
+            Rect::new(20.0, 0.0, 40.0, 10.0),
+        ];
+        let centers = layout_offsets(
+            ArrangeOperation::Distribute {
+                axis: ArrangeAxis::Horizontal,
+                spacing: DistributeSpacing::Centers,
+            },
+            &rects,
+        );
+        // Centers at 5 and 115, so the middle one centers on 60
+        assert_eq!(centers, vec![Vec2::ZERO, Vec2::ZERO, Vec2::new(30.0, 0.0)]);
+
+        let gaps = layout_offsets(
+            ArrangeOperation::Distribute {
+                axis: ArrangeAxis::Horizontal,
+                spacing: DistributeSpacing::Gaps,
+            },
+            &rects,
+        );
+        // 130 wide with 60 covered leaves two gaps of 35
+        assert_eq!(gaps, vec![Vec2::ZERO, Vec2::ZERO, Vec2::new(25.0, 0.0)]);
+
+        // Two entities have nothing to distribute
+        let offsets = layout_offsets(
+            ArrangeOperation::Distribute {
+                axis: ArrangeAxis::Vertical,
+                spacing: DistributeSpacing::Gaps,
+            },
+            &rects[..2],
+        );
+        assert_eq!(offsets, vec![Vec2::ZERO; 2]);
+    }
+
+    #[test]
+    fn match_size_scales_rotated_entities_by_their_sprite_size() {
+        let mut world = World::new();
+        world.init_resource::<Events<EditorAction>>();
+        world.init_resource::<Assets<Image>>();
+        world.init_resource::<Assets<TextureAtlasLayout>>();
+        world.init_resource::<EditorHistory>();
+        let mut spawn = |size: f32, transform: Transform| {
+            world
+                .spawn((
+                    Sprite {
+                        custom_size: Some(Vec2::splat(size)),
+                        ..default()
+                    },
+                    transform,
+                    GlobalTransform::from(transform),
+                    EditorSceneEntity,
+                    EditorEntityId::new(),
+                ))
+                .id()
+        };
+        let rotated = spawn(
+            10.0,
+            Transform::from_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+        );
+        let primary = spawn(20.0, Transform::default());
+        let mut selection = Selection::default();
+        selection.set([rotated, primary]);
+        selection.set_primary(primary);
+        world.insert_resource(selection);
+
+        world.send_event(EditorAction::ArrangeSelection(ArrangeOperation::MatchSize));
+        world.run_system_once(arrange_selection_system).unwrap();
+
+        let scale = world.get::<Transform>(rotated).unwrap().scale;
+        assert!(scale.abs_diff_eq(Vec3::new(2.0, 2.0, 1.0), 1e-4), "{scale}");
+    }
+}
@@ -0,0 +1,224 @@
+//! Copy, paste and duplicate of scene entities
+//!
+//! Copied entities are put on the system clipboard as scene text (see
+//! [`copy_entities`]), so they can be pasted into another open scene or
+//! another editor instance. Pasting and duplicating are recorded as one
+//! [`PasteEntitiesCommand`] each.
+
+use crate::editor_commands::PasteEntitiesCommand;
+use bevy::prelude::*;
+use bevy_editor_commands::EditorHistory;
+use bevy_editor_foundation::{EditorState, Selection};
+use bevy_editor_scene::{
+    copy_entities, is_copied_entities, EditorEntityId, EditorScene, EditorSceneEntity,
+    LoadingSceneRoot, OpenScenes, PlayMode, SceneTabChanged,
+};
+
+/// Requests for the clipboard, handled by [`handle_clipboard_requests`]
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardRequest {
+    /// Copy the selected entities and their children
+    Copy,
+    /// Paste copied entities into the open scene at this index, or the
+    /// active scene
+    Paste { scene: Option<usize> },
+    /// Paste a copy of the selected entities next to them, leaving the
+    /// clipboard as it is
+    Duplicate,
+}
+
+/// Text clipboard shared with other applications, falling back to one private
+/// to the editor where there is no system clipboard
+#[derive(Resource, Default)]
+pub struct SceneClipboard {
+    /// Kept open, as on some platforms the copied text is lost when it closes
+    system: Option<arboard::Clipboard>,
+    local: Option<String>,
+    /// Entities to paste once the scene switched to has loaded
+    pending_paste: Option<(usize, String)>,
+}
+
+impl SceneClipboard {
+    pub fn set_text(&mut self, text: String) {
+        if let Some(clipboard) = self.system_clipboard() {
+            match clipboard.set_text(text.clone()) {
+                Ok(()) => return,
+                Err(err) => warn!("Failed to write the system clipboard: {}", err),
+            }
+        }
+        self.local = Some(text);
+    }
+
+    pub fn text(&mut self) -> Option<String> {
+        if let Some(clipboard) = self.system_clipboard() {
+            if let Ok(text) = clipboard.get_text() {
+                return Some(text);
+            }
+        }
+        self.local.clone()
+    }
+
+    fn system_clipboard(&mut self) -> Option<&mut arboard::Clipboard> {
+        if self.system.is_none() {
+            self.system = arboard::Clipboard::new().ok();
+        }
+        self.system.as_mut()
+    }
+}
+
+/// System handling [`ClipboardRequest`]s and pastes waiting for a scene to load.
+///
+/// Exclusive because entities are copied and pasted through the whole world.
+pub fn handle_clipboard_requests(world: &mut World) {
+    let requests: Vec<ClipboardRequest> = world
+        .get_resource_mut::<Events<ClipboardRequest>>()
+        .map(|mut events| events.drain().collect())
+        .unwrap_or_default();
+
+    for request in requests {
+        match request {
+            ClipboardRequest::Copy => {
+                let Some(text) = copy_selection(world) else {
+                    continue;
+                };
+                world.resource_mut::<SceneClipboard>().set_text(text);
+                info!("Copied selected entities");
+            }
+            ClipboardRequest::Paste { scene } => {
+                let Some(text) = world.resource_mut::<SceneClipboard>().text() else {
+                    continue;
+                };
+                if !is_copied_entities(&text) {
+                    info!("Clipboard holds no entities to paste");
+                    continue;
+                }
+                let active_index = world.resource::<OpenScenes>().active_index;
+                match scene {
+                    Some(index) if index != active_index => {
+                        paste_into_other_scene(world, index, text);
+                    }
+                    _ => {
+                        let parent = paste_parent(world);
+                        paste(world, text, parent, false);
+                    }
+                }
+            }
+            ClipboardRequest::Duplicate => {
+                let Some(text) = copy_selection(world) else {
+                    continue;
+                };
+                let parent = world
+                    .resource::<Selection>()
+                    .primary()
+                    .and_then(|entity| world.get::<ChildOf>(entity))
+                    .and_then(|child_of| world.get::<EditorEntityId>(child_of.parent()))
+                    .copied();
+                paste(world, text, parent, true);
+            }
+        }
+    }
+
+    paste_pending(world);
+}
+
+/// Copy the selected entities, leaving out the scene root
+fn copy_selection(world: &mut World) -> Option<String> {
+    let root = world.resource::<EditorScene>().root_entity;
+    let selection = world.resource::<Selection>();
+    if selection.is_empty() {
+        return None;
+    }
+    let selected: Vec<Entity> = selection
+        .iter()
+        .filter(|entity| Some(*entity) != root)
+        .filter(|entity| world.get::<EditorSceneEntity>(*entity).is_some())
+        .collect();
+    if selected.is_empty() {
+        info!("The scene root can't be copied");
+        return None;
+    }
+    match copy_entities(world, &selected) {
+        Ok(text) => Some(text),
+        Err(err) => {
+            error!("Failed to copy entities {:?}: {}", selected, err);
+            None
+        }
+    }
+}
+
+/// Pasted entities become siblings of the primary selected entity, or
+/// children of the scene root
+fn paste_parent(world: &World) -> Option<EditorEntityId> {
+    let editor_scene = world.resource::<EditorScene>();
+    let parent = world
+        .resource::<Selection>()
+        .primary()
+        .filter(|entity| editor_scene.root_entity != Some(*entity))
+        .and_then(|entity| world.get::<ChildOf>(entity))
+        .map(ChildOf::parent)
+        .or(editor_scene.root_entity)?;
+    world.get::<EditorEntityId>(parent).copied()
+}
+
+fn paste(world: &mut World, text: String, parent: Option<EditorEntityId>, duplicate: bool) {
+    // Copies are moved by one grid cell, so they don't hide the originals
+    let grid_size = world
+        .get_resource::<EditorState>()
+        .map_or(16.0, |state| state.grid_size);
+    let command = PasteEntitiesCommand::new(
+        text,
+        parent,
+        Vec3::new(grid_size, -grid_size, 0.0),
+        duplicate,
+    );
+    world.resource_scope(|world, mut history: Mut<EditorHistory>| {
+        history.execute(Box::new(command), world);
+    });
+}
+
+/// Switch to the scene at `index` and paste once it has loaded
+fn paste_into_other_scene(world: &mut World, index: usize, text: String) {
+    if world.resource::<PlayMode>().is_active() {
+        warn!("Can't switch scenes while playing");
+        return;
+    }
+    let mut open_scenes = world.resource_mut::<OpenScenes>();
+    if index >= open_scenes.scenes.len() {
+        return;
+    }
+    open_scenes.set_active(index);
+    world.send_event(SceneTabChanged { new_index: index });
+    world.resource_mut::<SceneClipboard>().pending_paste = Some((index, text));
+}
+
+fn paste_pending(world: &mut World) {
+    let Some(index) = world
+        .resource::<SceneClipboard>()
+        .pending_paste
+        .as_ref()
+        .map(|(index, _)| *index)
+    else {
+        return;
+    };
+    if world.resource::<OpenScenes>().active_index != index {
+        // Switched to another scene before this one loaded
+        world.resource_mut::<SceneClipboard>().pending_paste = None;
+        return;
+    }
+    let switching = world
+        .get_resource::<Events<SceneTabChanged>>()
+        .is_some_and(|events| !events.is_empty());
+    let loading = world
+        .query_filtered::<(), With<LoadingSceneRoot>>()
+        .iter(world)
+        .next()
+        .is_some();
+    if switching || loading || world.resource::<EditorScene>().root_entity.is_none() {
+        return;
+    }
+    let Some((_, text)) = world.resource_mut::<SceneClipboard>().pending_paste.take() else {
+        return;
+    };
+    let parent = paste_parent(world);
+    paste(world, text, parent, false);
+}
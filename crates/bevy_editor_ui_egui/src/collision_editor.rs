@@ -1,22 +1,70 @@
 use bevy::prelude::*;
 use bevy_editor_formats::{CollisionShape, Vector2};
-use bevy_editor_tilemap::{CollisionEditor, CollisionTool, TilesetManager};
+use bevy_editor_tilemap::{
+    insert_vertex, move_vertex, nearest_edge, shape_contains, shape_vertices, translate_shape,
+    CollisionDrag, CollisionEditor, CollisionSnap, CollisionTool, LayerManager, TileGrid,
+    TilesetManager,
+};
 use bevy_egui::{egui, EguiContexts};
 
+/// Longest side of the tile canvas in the collision editor window
+const CANVAS_SIZE: f32 = 256.0;
+/// Pick radius of vertex handles, in screen pixels
+const HANDLE_RADIUS: f32 = 6.0;
+
+const SHAPE_COLOR: egui::Color32 = egui::Color32::from_rgb(0, 255, 0);
+const SELECTED_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 255, 0);
+const DRAWING_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 128, 0);
+
 /// UI system for collision editor
 pub fn collision_editor_ui(
     mut contexts: EguiContexts,
     mut collision_editor: ResMut<CollisionEditor>,
-    tileset_manager: Res<TilesetManager>,
+    mut tileset_manager: ResMut<TilesetManager>,
+    images: Res<Assets<Image>>,
 ) {
     if !collision_editor.active {
         return;
     }
 
+    // Follow the tile selected in the tileset panel
+    let selected_tile = tileset_manager.selected_tile_ref();
+    if selected_tile != collision_editor.current_tile {
+        match selected_tile
+            .and_then(|tile| Some((tile, tileset_manager.get_tileset(tile.tileset_id)?)))
+        {
+            Some((tile, tileset_info)) => collision_editor.edit_tile(tile, &tileset_info.data),
+            None => {
+                collision_editor.current_tile = None;
+                collision_editor.shapes.clear();
+                collision_editor.clear_selection();
+            }
+        }
+    }
+
+    // Texture and UV rect of the edited tile
+    let tile_texture = collision_editor.current_tile.and_then(|tile| {
+        let tileset_info = tileset_manager.get_tileset(tile.tileset_id)?;
+        let image_size = images.get(&tileset_info.texture_handle)?.size().as_vec2();
+        let (x, y, w, h) = tileset_info.data.tile_rect(tile.tile_id)?;
+        let uv = egui::Rect::from_min_max(
+            egui::pos2(x as f32 / image_size.x, y as f32 / image_size.y),
+            egui::pos2((x + w) as f32 / image_size.x, (y + h) as f32 / image_size.y),
+        );
+        Some((
+            contexts.add_image(tileset_info.texture_handle.clone_weak()),
+            uv,
+        ))
+    });
+
     let Some(ctx) = contexts.try_ctx_mut() else {
         return;
     };
 
+    let editor = &mut *collision_editor;
+    let mut shapes_changed = false;
+    let mut apply_to_selection = false;
+
     // Collision editor window
     let mut open = true;
     egui::Window::new("Collision Editor")
@@ -29,84 +77,99 @@ pub fn collision_editor_ui(
             // Tool selection
             ui.label("Tool:");
             ui.horizontal(|ui| {
+                tool_button(ui, editor, CollisionTool::Select, "Select");
+                tool_button(ui, editor, CollisionTool::Rectangle, "Rect");
+                tool_button(ui, editor, CollisionTool::Ellipse, "Ellipse");
+            });
+            ui.horizontal(|ui| {
+                tool_button(ui, editor, CollisionTool::Polygon, "Polygon");
+                tool_button(ui, editor, CollisionTool::Polyline, "Polyline");
+                tool_button(ui, editor, CollisionTool::Point, "Point");
+            });
+
+            // Snapping
+            ui.horizontal(|ui| {
+                ui.label("Snap:");
+                let is_sub_grid = matches!(editor.snap, CollisionSnap::SubGrid(_));
                 if ui
-                    .selectable_label(
-                        collision_editor.current_tool == CollisionTool::Select,
-                        "Select",
-                    )
+                    .selectable_label(editor.snap == CollisionSnap::None, "Off")
                     .clicked()
                 {
-                    collision_editor.current_tool = CollisionTool::Select;
+                    editor.snap = CollisionSnap::None;
                 }
                 if ui
-                    .selectable_label(
-                        collision_editor.current_tool == CollisionTool::Rectangle,
-                        "Rect",
-                    )
+                    .selectable_label(editor.snap == CollisionSnap::Pixel, "Pixel")
                     .clicked()
                 {
-                    collision_editor.current_tool = CollisionTool::Rectangle;
+                    editor.snap = CollisionSnap::Pixel;
                 }
-                if ui
-                    .selectable_label(
-                        collision_editor.current_tool == CollisionTool::Ellipse,
-                        "Ellipse",
-                    )
-                    .clicked()
-                {
-                    collision_editor.current_tool = CollisionTool::Ellipse;
+                if ui.selectable_label(is_sub_grid, "Sub-grid").clicked() && !is_sub_grid {
+                    editor.snap = CollisionSnap::SubGrid(4);
+                }
+                if let CollisionSnap::SubGrid(divisions) = &mut editor.snap {
+                    ui.add(egui::DragValue::new(divisions).range(1..=32).suffix(" div"));
                 }
             });
+
+            ui.checkbox(&mut editor.show_overlay, "Show collision on map");
+
+            ui.separator();
+
+            // Tile selection
+            let Some(tile) = editor.current_tile else {
+                ui.label("Select a tile to edit collision shapes");
+                return;
+            };
+            let Some(tileset_info) = tileset_manager.get_tileset(tile.tileset_id) else {
+                return;
+            };
+            let tile_size = Vec2::new(
+                tileset_info.data.tile_width as f32,
+                tileset_info.data.tile_height as f32,
+            );
+
+            ui.label(format!(
+                "Editing Tile: {} (Tileset {})",
+                tile.tile_id, tile.tileset_id
+            ));
+
+            shapes_changed |= tile_canvas(ui, editor, tile_size, tile_texture);
+
+            // Copy shapes between tiles
             ui.horizontal(|ui| {
                 if ui
-                    .selectable_label(
-                        collision_editor.current_tool == CollisionTool::Polygon,
-                        "Polygon",
-                    )
+                    .add_enabled(!editor.shapes.is_empty(), egui::Button::new("Copy"))
                     .clicked()
                 {
-                    collision_editor.current_tool = CollisionTool::Polygon;
+                    editor.copy_shapes();
                 }
                 if ui
-                    .selectable_label(
-                        collision_editor.current_tool == CollisionTool::Polyline,
-                        "Polyline",
-                    )
+                    .add_enabled(!editor.clipboard.is_empty(), egui::Button::new("Paste"))
                     .clicked()
                 {
-                    collision_editor.current_tool = CollisionTool::Polyline;
+                    editor.paste_shapes();
+                    shapes_changed = true;
                 }
                 if ui
-                    .selectable_label(
-                        collision_editor.current_tool == CollisionTool::Point,
-                        "Point",
-                    )
+                    .add_enabled(!editor.shapes.is_empty(), egui::Button::new("Clear"))
                     .clicked()
                 {
-                    collision_editor.current_tool = CollisionTool::Point;
+                    editor.shapes.clear();
+                    editor.clear_selection();
+                    shapes_changed = true;
                 }
             });
 
-            ui.separator();
-
-            // Tile selection
-            if let (Some(tileset_id), Some(tile_id)) = (
-                tileset_manager.selected_tileset_id,
-                tileset_manager.selected_tile_id,
-            ) {
-                ui.label(format!(
-                    "Editing Tile: {} (Tileset {})",
-                    tile_id, tileset_id
-                ));
-
-                if collision_editor.current_tile_id != Some(tile_id) {
-                    // Load collision shapes for this tile
-                    collision_editor.current_tile_id = Some(tile_id);
-                    // TODO: Load shapes from tileset collision data
-                    collision_editor.shapes.clear();
-                }
-            } else {
-                ui.label("Select a tile to edit collision shapes");
+            let selection_count = tileset_manager.selected_tiles.len();
+            if ui
+                .add_enabled(
+                    selection_count > 1,
+                    egui::Button::new(format!("Apply to {} Selected Tiles", selection_count)),
+                )
+                .on_hover_text("Replace the shapes of every selected tile with these")
+                .clicked()
+            {
+                apply_to_selection = true;
             }
 
             ui.separator();
@@ -115,23 +178,15 @@ pub fn collision_editor_ui(
             ui.label("Collision Shapes:");
             let mut to_remove = None;
             let mut new_selection = None;
-            let current_selection = collision_editor.selected_shape;
+            let current_selection = editor.selected_shape;
 
             egui::ScrollArea::vertical()
-                .max_height(200.0)
+                .max_height(150.0)
                 .show(ui, |ui| {
-                    for (idx, shape) in collision_editor.shapes.iter().enumerate() {
+                    for (idx, shape) in editor.shapes.iter().enumerate() {
                         ui.horizontal(|ui| {
-                            let shape_name = match shape {
-                                CollisionShape::Rectangle { .. } => "Rectangle",
-                                CollisionShape::Ellipse { .. } => "Ellipse",
-                                CollisionShape::Polygon { .. } => "Polygon",
-                                CollisionShape::Polyline { .. } => "Polyline",
-                                CollisionShape::Point { .. } => "Point",
-                            };
-
                             if ui
-                                .selectable_label(current_selection == Some(idx), shape_name)
+                                .selectable_label(current_selection == Some(idx), shape_name(shape))
                                 .clicked()
                             {
                                 new_selection = Some(idx);
@@ -145,50 +200,22 @@ pub fn collision_editor_ui(
                 });
 
             if let Some(idx) = new_selection {
-                collision_editor.selected_shape = Some(idx);
+                editor.selected_shape = Some(idx);
+                editor.selected_vertex = None;
             }
             if let Some(idx) = to_remove {
-                collision_editor.shapes.remove(idx);
-                if collision_editor.selected_shape == Some(idx) {
-                    collision_editor.selected_shape = None;
-                }
+                editor.shapes.remove(idx);
+                editor.clear_selection();
+                shapes_changed = true;
             }
 
             ui.separator();
 
             // Shape properties
-            if let Some(idx) = collision_editor.selected_shape {
-                if let Some(shape) = collision_editor.shapes.get_mut(idx) {
+            if let Some(idx) = editor.selected_shape {
+                if let Some(shape) = editor.shapes.get_mut(idx) {
                     ui.label("Shape Properties:");
-
-                    match shape {
-                        CollisionShape::Rectangle {
-                            x,
-                            y,
-                            width,
-                            height,
-                        } => {
-                            ui.add(egui::Slider::new(x, 0.0..=100.0).text("X"));
-                            ui.add(egui::Slider::new(y, 0.0..=100.0).text("Y"));
-                            ui.add(egui::Slider::new(width, 1.0..=100.0).text("Width"));
-                            ui.add(egui::Slider::new(height, 1.0..=100.0).text("Height"));
-                        }
-                        CollisionShape::Ellipse { x, y, rx, ry } => {
-                            ui.add(egui::Slider::new(x, 0.0..=100.0).text("X"));
-                            ui.add(egui::Slider::new(y, 0.0..=100.0).text("Y"));
-                            ui.add(egui::Slider::new(rx, 1.0..=50.0).text("Radius X"));
-                            ui.add(egui::Slider::new(ry, 1.0..=50.0).text("Radius Y"));
-                        }
-                        CollisionShape::Point { x, y } => {
-                            ui.add(egui::Slider::new(x, 0.0..=100.0).text("X"));
-                            ui.add(egui::Slider::new(y, 0.0..=100.0).text("Y"));
-                        }
-                        CollisionShape::Polygon { points }
-                        | CollisionShape::Polyline { points } => {
-                            ui.label(format!("Points: {}", points.len()));
-                            // Could add point editing UI here
-                        }
-                    }
+                    shapes_changed |= shape_properties(ui, shape, editor.selected_vertex);
                 }
             }
 
@@ -196,212 +223,504 @@ pub fn collision_editor_ui(
 
             // Instructions
             ui.label("Instructions:");
-            match collision_editor.current_tool {
-                CollisionTool::Select => ui.label("Click to select shapes, drag to move"),
+            match editor.current_tool {
+                CollisionTool::Select => ui.label(
+                    "Drag shapes or points to move them, double-click an edge to add a point, \
+                     Delete removes the selected point or shape",
+                ),
                 CollisionTool::Rectangle | CollisionTool::Ellipse => {
                     ui.label("Click and drag to create shape")
                 }
-                CollisionTool::Polygon | CollisionTool::Polyline => {
-                    ui.label("Click to add points, double-click to finish")
-                }
+                CollisionTool::Polygon | CollisionTool::Polyline => ui.label(
+                    "Click to add points, double-click or right-click to finish, Escape to cancel",
+                ),
                 CollisionTool::Point => ui.label("Click to place point"),
             };
         });
 
-    collision_editor.active = open;
-}
+    editor.active = open;
 
-/// System for handling collision shape input
-pub fn handle_collision_input(
-    mut collision_editor: ResMut<CollisionEditor>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
-    windows: Query<&Window>,
-    camera_query: Query<(&Camera, &GlobalTransform)>,
-) {
-    if !collision_editor.active || collision_editor.current_tile_id.is_none() {
+    // Write edits straight through to the tileset so they are saved with it
+    let Some(tile) = editor.current_tile else {
         return;
+    };
+    let selected_tiles = tileset_manager.selected_tiles.clone();
+    if let Some(tileset_info) = tileset_manager.tilesets.get_mut(&tile.tileset_id) {
+        if shapes_changed {
+            editor.store(&mut tileset_info.data);
+        }
+        if apply_to_selection {
+            editor.apply_to_tiles(&mut tileset_info.data, &selected_tiles);
+        }
     }
+}
 
-    let Some(window) = windows.iter().next() else {
-        return;
-    };
-    let Some((camera, camera_transform)) = camera_query.iter().next() else {
-        return;
-    };
+fn tool_button(ui: &mut egui::Ui, editor: &mut CollisionEditor, tool: CollisionTool, label: &str) {
+    if ui
+        .selectable_label(editor.current_tool == tool, label)
+        .clicked()
+    {
+        editor.current_tool = tool;
+        editor.cancel_drawing();
+    }
+}
 
-    // Get mouse position in world space
-    let Some(cursor_pos) = window.cursor_position() else {
-        return;
-    };
-    let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) else {
-        return;
+fn shape_name(shape: &CollisionShape) -> &'static str {
+    match shape {
+        CollisionShape::Rectangle { .. } => "Rectangle",
+        CollisionShape::Ellipse { .. } => "Ellipse",
+        CollisionShape::Polygon { .. } => "Polygon",
+        CollisionShape::Polyline { .. } => "Polyline",
+        CollisionShape::Point { .. } => "Point",
+    }
+}
+
+/// Numeric fields for the selected shape. Returns true if anything changed.
+fn shape_properties(
+    ui: &mut egui::Ui,
+    shape: &mut CollisionShape,
+    selected_vertex: Option<usize>,
+) -> bool {
+    let mut changed = false;
+    let mut field = |ui: &mut egui::Ui, value: &mut f32, label: &str| {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            changed |= ui.add(egui::DragValue::new(value).speed(0.5)).changed();
+        });
     };
 
-    // Handle mouse input based on current tool
-    match collision_editor.current_tool {
-        CollisionTool::Rectangle | CollisionTool::Ellipse => {
-            if mouse_button.just_pressed(MouseButton::Left) {
-                collision_editor.drawing = true;
-                collision_editor.drag_start = Some(world_pos);
+    match shape {
+        CollisionShape::Rectangle {
+            x,
+            y,
+            width,
+            height,
+        } => {
+            field(ui, x, "X");
+            field(ui, y, "Y");
+            field(ui, width, "Width");
+            field(ui, height, "Height");
+        }
+        CollisionShape::Ellipse { x, y, rx, ry } => {
+            field(ui, x, "X");
+            field(ui, y, "Y");
+            field(ui, rx, "Radius X");
+            field(ui, ry, "Radius Y");
+        }
+        CollisionShape::Point { x, y } => {
+            field(ui, x, "X");
+            field(ui, y, "Y");
+        }
+        CollisionShape::Polygon { points } | CollisionShape::Polyline { points } => {
+            ui.label(format!("Points: {}", points.len()));
+            if let Some(point) = selected_vertex.and_then(|idx| points.get_mut(idx)) {
+                field(ui, &mut point.x, "Point X");
+                field(ui, &mut point.y, "Point Y");
             }
+        }
+    }
+    changed
+}
 
-            if mouse_button.just_released(MouseButton::Left) && collision_editor.drawing {
-                if let Some(start) = collision_editor.drag_start {
-                    let shape = match collision_editor.current_tool {
-                        CollisionTool::Rectangle => {
-                            let min_x = start.x.min(world_pos.x);
-                            let min_y = start.y.min(world_pos.y);
-                            let width = (world_pos.x - start.x).abs();
-                            let height = (world_pos.y - start.y).abs();
-
-                            CollisionShape::Rectangle {
-                                x: min_x,
-                                y: min_y,
-                                width,
-                                height,
+/// Draw the tile with its shapes and handle editing input on it.
+/// Returns true if the shapes changed.
+fn tile_canvas(
+    ui: &mut egui::Ui,
+    editor: &mut CollisionEditor,
+    tile_size: Vec2,
+    tile_texture: Option<(egui::TextureId, egui::Rect)>,
+) -> bool {
+    let scale = CANVAS_SIZE / tile_size.max_element().max(1.0);
+    let (response, painter) = ui.allocate_painter(
+        egui::vec2(tile_size.x * scale, tile_size.y * scale),
+        egui::Sense::click_and_drag(),
+    );
+    let rect = response.rect;
+    let to_screen = |p: Vec2| rect.min + egui::vec2(p.x * scale, p.y * scale);
+    let to_tile = |p: egui::Pos2| Vec2::new((p.x - rect.min.x) / scale, (p.y - rect.min.y) / scale);
+    let handle_radius = HANDLE_RADIUS / scale;
+
+    // Tile image and snapping grid
+    painter.rect_filled(rect, 0.0, egui::Color32::from_gray(40));
+    if let Some((texture_id, uv)) = tile_texture {
+        painter.image(texture_id, rect, uv, egui::Color32::WHITE);
+    }
+    if let CollisionSnap::SubGrid(divisions) = editor.snap {
+        let grid_stroke = egui::Stroke::new(1.0, egui::Color32::from_white_alpha(40));
+        for i in 1..divisions {
+            let t = i as f32 / divisions as f32;
+            painter.line_segment(
+                [
+                    to_screen(tile_size * Vec2::new(t, 0.0)),
+                    to_screen(tile_size * Vec2::new(t, 1.0)),
+                ],
+                grid_stroke,
+            );
+            painter.line_segment(
+                [
+                    to_screen(tile_size * Vec2::new(0.0, t)),
+                    to_screen(tile_size * Vec2::new(1.0, t)),
+                ],
+                grid_stroke,
+            );
+        }
+    }
+    painter.rect_stroke(
+        rect,
+        0.0,
+        egui::Stroke::new(1.0, egui::Color32::GRAY),
+        egui::StrokeKind::Inside,
+    );
+
+    let mut changed = false;
+    let pointer = response.hover_pos().map(to_tile);
+    let press_origin = ui.input(|i| i.pointer.press_origin()).map(to_tile);
+
+    match editor.current_tool {
+        CollisionTool::Select => {
+            if response.drag_started() || response.clicked() {
+                if let Some(pos) = press_origin.or(pointer) {
+                    select_at(editor, pos, handle_radius);
+                    if response.drag_started() {
+                        editor.drag = match (editor.selected_shape, editor.selected_vertex) {
+                            (Some(shape), Some(vertex)) => {
+                                Some(CollisionDrag::Vertex { shape, vertex })
+                            }
+                            (Some(shape), None) => Some(CollisionDrag::Shape {
+                                shape,
+                                last: editor.snap_point(pos, tile_size),
+                            }),
+                            _ => None,
+                        };
+                    }
+                }
+            }
+
+            if response.dragged() {
+                if let (Some(drag), Some(pos)) = (editor.drag, response.interact_pointer_pos()) {
+                    let pos = editor.snap_point(to_tile(pos), tile_size);
+                    match drag {
+                        CollisionDrag::Vertex { shape, vertex } => {
+                            if let Some(shape) = editor.shapes.get_mut(shape) {
+                                move_vertex(shape, vertex, pos);
+                                changed = true;
                             }
                         }
-                        CollisionTool::Ellipse => {
-                            let center_x = (start.x + world_pos.x) / 2.0;
-                            let center_y = (start.y + world_pos.y) / 2.0;
-                            let rx = (world_pos.x - start.x).abs() / 2.0;
-                            let ry = (world_pos.y - start.y).abs() / 2.0;
-
-                            CollisionShape::Ellipse {
-                                x: center_x,
-                                y: center_y,
-                                rx,
-                                ry,
+                        CollisionDrag::Shape { shape, last } => {
+                            if pos != last {
+                                if let Some(shape) = editor.shapes.get_mut(shape) {
+                                    translate_shape(shape, pos - last);
+                                    changed = true;
+                                }
+                                editor.drag = Some(CollisionDrag::Shape { shape, last: pos });
                             }
                         }
-                        _ => unreachable!(),
-                    };
+                    }
+                }
+            }
+            if response.drag_stopped() {
+                editor.drag = None;
+            }
 
-                    collision_editor.shapes.push(shape);
+            // Double-click an edge of the selected polygon to insert a point
+            if response.double_clicked() {
+                if let (Some(idx), Some(pos)) = (editor.selected_shape, pointer) {
+                    let pos = editor.snap_point(pos, tile_size);
+                    if let Some(shape) = editor.shapes.get_mut(idx) {
+                        if let Some(edge) = nearest_edge(shape, pos, handle_radius * 2.0) {
+                            editor.selected_vertex = insert_vertex(shape, edge, pos);
+                            changed = true;
+                        }
+                    }
                 }
+            }
 
-                collision_editor.drawing = false;
-                collision_editor.drag_start = None;
+            if response.hovered()
+                && ui.input(|i| {
+                    i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace)
+                })
+            {
+                changed |= editor.delete_selection();
+            }
+        }
+        CollisionTool::Rectangle | CollisionTool::Ellipse => {
+            if response.drag_started() {
+                if let Some(pos) = press_origin {
+                    editor.drawing = true;
+                    editor.drag_start = Some(editor.snap_point(pos, tile_size));
+                }
+            }
+
+            if response.drag_stopped() && editor.drawing {
+                if let (Some(start), Some(pos)) = (
+                    editor.drag_start,
+                    response.interact_pointer_pos().or(response.hover_pos()),
+                ) {
+                    let end = editor.snap_point(to_tile(pos), tile_size);
+                    if let Some(shape) = drag_shape(editor.current_tool, start, end) {
+                        editor.shapes.push(shape);
+                        editor.selected_shape = Some(editor.shapes.len() - 1);
+                        editor.selected_vertex = None;
+                        changed = true;
+                    }
+                }
+                editor.drawing = false;
+                editor.drag_start = None;
             }
         }
         CollisionTool::Point => {
-            if mouse_button.just_pressed(MouseButton::Left) {
-                collision_editor.shapes.push(CollisionShape::Point {
-                    x: world_pos.x,
-                    y: world_pos.y,
-                });
+            if response.clicked() {
+                if let Some(pos) = pointer {
+                    let pos = editor.snap_point(pos, tile_size);
+                    editor
+                        .shapes
+                        .push(CollisionShape::Point { x: pos.x, y: pos.y });
+                    editor.selected_shape = Some(editor.shapes.len() - 1);
+                    changed = true;
+                }
             }
         }
         CollisionTool::Polygon | CollisionTool::Polyline => {
-            // Single click adds point
-            if mouse_button.just_pressed(MouseButton::Left) {
-                collision_editor
-                    .polygon_points
-                    .push(Vector2::new(world_pos.x, world_pos.y));
+            // The first click of a double-click already added the last point
+            if response.double_clicked() || response.secondary_clicked() {
+                changed |= editor.finish_polygon();
+            } else if response.clicked() {
+                if let Some(pos) = pointer {
+                    let pos = editor.snap_point(pos, tile_size);
+                    editor.polygon_points.push(Vector2::new(pos.x, pos.y));
+                }
             }
+            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                editor.cancel_drawing();
+            }
+        }
+    }
 
-            // Double click (or right click) finishes polygon/polyline
-            if mouse_button.just_pressed(MouseButton::Right)
-                && !collision_editor.polygon_points.is_empty()
-            {
-                let shape = match collision_editor.current_tool {
-                    CollisionTool::Polygon => CollisionShape::Polygon {
-                        points: collision_editor.polygon_points.clone(),
-                    },
-                    CollisionTool::Polyline => CollisionShape::Polyline {
-                        points: collision_editor.polygon_points.clone(),
-                    },
-                    _ => unreachable!(),
-                };
-
-                collision_editor.shapes.push(shape);
-                collision_editor.polygon_points.clear();
+    // Shapes
+    for (idx, shape) in editor.shapes.iter().enumerate() {
+        let color = if editor.selected_shape == Some(idx) {
+            SELECTED_COLOR
+        } else {
+            SHAPE_COLOR
+        };
+        paint_shape(&painter, shape, &to_screen, scale, color);
+    }
+
+    // Handles of the selected shape
+    if let Some(shape) = editor.selected_shape.and_then(|idx| editor.shapes.get(idx)) {
+        for (idx, vertex) in shape_vertices(shape).into_iter().enumerate() {
+            let center = to_screen(vertex);
+            if editor.selected_vertex == Some(idx) {
+                painter.circle_filled(center, HANDLE_RADIUS * 0.75, SELECTED_COLOR);
+            } else {
+                painter.circle_stroke(center, HANDLE_RADIUS * 0.75, (1.5, SELECTED_COLOR));
             }
         }
-        CollisionTool::Select => {
-            // TODO: Implement shape selection and movement
+    }
+
+    // Polygon or polyline in progress
+    if !editor.polygon_points.is_empty() {
+        let mut points: Vec<egui::Pos2> = editor
+            .polygon_points
+            .iter()
+            .map(|p| to_screen(Vec2::from(*p)))
+            .collect();
+        if let Some(pos) = pointer {
+            points.push(to_screen(editor.snap_point(pos, tile_size)));
         }
+        painter.add(egui::Shape::line(points, (1.5, DRAWING_COLOR)));
     }
-}
 
-/// System to render collision shapes for the current tile
-pub fn render_collision_shapes(mut gizmos: Gizmos, collision_editor: Res<CollisionEditor>) {
-    if !collision_editor.active {
-        return;
+    // Rectangle or ellipse being dragged
+    if let (true, Some(start), Some(pos)) = (editor.drawing, editor.drag_start, pointer) {
+        let end = editor.snap_point(pos, tile_size);
+        if let Some(shape) = drag_shape(editor.current_tool, start, end) {
+            paint_shape(&painter, &shape, &to_screen, scale, DRAWING_COLOR);
+        }
     }
 
-    // Render all shapes
-    for (idx, shape) in collision_editor.shapes.iter().enumerate() {
-        let color = if collision_editor.selected_shape == Some(idx) {
-            Color::srgb(1.0, 1.0, 0.0) // Yellow for selected
-        } else {
-            Color::srgb(0.0, 1.0, 0.0) // Green for normal
-        };
+    changed
+}
 
-        match shape {
-            CollisionShape::Rectangle {
-                x,
-                y,
-                width,
-                height,
-            } => {
-                gizmos.rect_2d(
-                    Isometry2d::new(
-                        Vec2::new(*x + width / 2.0, *y + height / 2.0),
-                        Rot2::default(),
-                    ),
-                    Vec2::new(*width, *height),
-                    color,
-                );
-            }
-            CollisionShape::Ellipse { x, y, rx, ry } => {
-                gizmos.ellipse_2d(
-                    Isometry2d::new(Vec2::new(*x, *y), Rot2::default()),
-                    Vec2::new(*rx, *ry),
-                    color,
-                );
-            }
-            CollisionShape::Polygon { points } => {
-                for i in 0..points.len() {
-                    let start = points[i];
-                    let end = points[(i + 1) % points.len()];
-                    gizmos.line_2d(Vec2::new(start.x, start.y), Vec2::new(end.x, end.y), color);
-                }
-            }
-            CollisionShape::Polyline { points } => {
-                for i in 0..points.len().saturating_sub(1) {
-                    let start = points[i];
-                    let end = points[i + 1];
-                    gizmos.line_2d(Vec2::new(start.x, start.y), Vec2::new(end.x, end.y), color);
-                }
-            }
-            CollisionShape::Point { x, y } => {
-                gizmos.circle_2d(Vec2::new(*x, *y), 3.0, color);
-            }
+/// Select the vertex or shape under `pos`, preferring handles of the
+/// already selected shape.
+fn select_at(editor: &mut CollisionEditor, pos: Vec2, handle_radius: f32) {
+    let hit_vertex = |shape: &CollisionShape| {
+        shape_vertices(shape)
+            .iter()
+            .position(|vertex| vertex.distance(pos) <= handle_radius)
+    };
+
+    if let Some(idx) = editor.selected_shape {
+        if let Some(vertex) = editor.shapes.get(idx).and_then(hit_vertex) {
+            editor.selected_vertex = Some(vertex);
+            return;
+        }
+    }
+
+    // Topmost (last drawn) shapes first
+    for (idx, shape) in editor.shapes.iter().enumerate().rev() {
+        if let Some(vertex) = hit_vertex(shape) {
+            editor.selected_shape = Some(idx);
+            editor.selected_vertex = Some(vertex);
+            return;
         }
     }
+    let hit_shape = editor
+        .shapes
+        .iter()
+        .rposition(|shape| shape_contains(shape, pos, handle_radius));
+    editor.selected_shape = hit_shape;
+    editor.selected_vertex = None;
+}
+
+/// Shape spanned by a rectangle or ellipse drag, if it isn't degenerate
+fn drag_shape(tool: CollisionTool, start: Vec2, end: Vec2) -> Option<CollisionShape> {
+    let min = start.min(end);
+    let size = (end - start).abs();
+    if size.x <= 0.0 || size.y <= 0.0 {
+        return None;
+    }
+    match tool {
+        CollisionTool::Rectangle => Some(CollisionShape::Rectangle {
+            x: min.x,
+            y: min.y,
+            width: size.x,
+            height: size.y,
+        }),
+        CollisionTool::Ellipse => Some(CollisionShape::Ellipse {
+            x: min.x + size.x / 2.0,
+            y: min.y + size.y / 2.0,
+            rx: size.x / 2.0,
+            ry: size.y / 2.0,
+        }),
+        _ => None,
+    }
+}
 
-    // Render current polygon/polyline being drawn
-    if matches!(
-        collision_editor.current_tool,
-        CollisionTool::Polygon | CollisionTool::Polyline
-    ) {
-        for i in 0..collision_editor.polygon_points.len().saturating_sub(1) {
-            let start = collision_editor.polygon_points[i];
-            let end = collision_editor.polygon_points[i + 1];
-            gizmos.line_2d(
-                Vec2::new(start.x, start.y),
-                Vec2::new(end.x, end.y),
-                Color::srgb(1.0, 0.5, 0.0), // Orange for in-progress
+fn paint_shape(
+    painter: &egui::Painter,
+    shape: &CollisionShape,
+    to_screen: &impl Fn(Vec2) -> egui::Pos2,
+    scale: f32,
+    color: egui::Color32,
+) {
+    let stroke = egui::Stroke::new(1.5, color);
+    match shape {
+        CollisionShape::Rectangle {
+            x,
+            y,
+            width,
+            height,
+        } => {
+            painter.rect_stroke(
+                egui::Rect::from_two_pos(
+                    to_screen(Vec2::new(*x, *y)),
+                    to_screen(Vec2::new(x + width, y + height)),
+                ),
+                0.0,
+                stroke,
+                egui::StrokeKind::Middle,
             );
         }
+        CollisionShape::Ellipse { x, y, rx, ry } => {
+            painter.add(egui::Shape::ellipse_stroke(
+                to_screen(Vec2::new(*x, *y)),
+                egui::vec2(rx * scale, ry * scale),
+                stroke,
+            ));
+        }
+        CollisionShape::Polygon { points } => {
+            let points = points.iter().map(|p| to_screen(Vec2::from(*p))).collect();
+            painter.add(egui::Shape::closed_line(points, stroke));
+        }
+        CollisionShape::Polyline { points } => {
+            let points = points.iter().map(|p| to_screen(Vec2::from(*p))).collect();
+            painter.add(egui::Shape::line(points, stroke));
+        }
+        CollisionShape::Point { x, y } => {
+            painter.circle_stroke(to_screen(Vec2::new(*x, *y)), 3.0, stroke);
+        }
+    }
+}
+
+/// System to draw the collision shapes of painted tiles over the map
+pub fn render_collision_shapes(
+    mut gizmos: Gizmos,
+    collision_editor: Res<CollisionEditor>,
+    layer_manager: Res<LayerManager>,
+    tileset_manager: Res<TilesetManager>,
+    tile_grid: Res<TileGrid>,
+) {
+    if !collision_editor.show_overlay {
+        return;
     }
 
-    // Render drag preview for rectangle/ellipse
-    if collision_editor.drawing {
-        if let Some(_start) = collision_editor.drag_start {
-            // This would need actual mouse position - simplified for now
-            // gizmos.rect_2d(...)
+    let color = Color::srgba(0.0, 1.0, 0.0, 0.8);
+    for layer in &layer_manager.layers {
+        if !layer_manager.is_layer_visible(layer.metadata.id) {
+            continue;
+        }
+
+        for tile in &layer.tiles {
+            let Some(tileset_info) = tileset_manager.get_tileset(tile.tileset_id) else {
+                continue;
+            };
+            let Some(collision) = tileset_info.data.collision_data.get(&tile.tile_id) else {
+                continue;
+            };
+
+            // Tile pixels have y pointing down from the top-left of the tile image,
+            // which is drawn centered on the cell
+            let tile_size = Vec2::new(
+                tileset_info.data.tile_width as f32,
+                tileset_info.data.tile_height as f32,
+            );
+            let top_left =
+                tile_grid.tile_center(tile.x, tile.y) + Vec2::new(-tile_size.x, tile_size.y) / 2.0;
+            let to_world = |x: f32, y: f32| top_left + Vec2::new(x, -y);
+
+            for shape in &collision.shapes {
+                match shape {
+                    CollisionShape::Rectangle {
+                        x,
+                        y,
+                        width,
+                        height,
+                    } => {
+                        gizmos.rect_2d(
+                            Isometry2d::from_translation(to_world(
+                                x + width / 2.0,
+                                y + height / 2.0,
+                            )),
+                            Vec2::new(*width, *height),
+                            color,
+                        );
+                    }
+                    CollisionShape::Ellipse { x, y, rx, ry } => {
+                        gizmos.ellipse_2d(
+                            Isometry2d::from_translation(to_world(*x, *y)),
+                            Vec2::new(*rx, *ry),
+                            color,
+                        );
+                    }
+                    CollisionShape::Polygon { points } => {
+                        gizmos.linestrip_2d(
+                            points
+                                .iter()
+                                .chain(points.first())
+                                .map(|p| to_world(p.x, p.y)),
+                            color,
+                        );
+                    }
+                    CollisionShape::Polyline { points } => {
+                        gizmos.linestrip_2d(points.iter().map(|p| to_world(p.x, p.y)), color);
+                    }
+                    CollisionShape::Point { x, y } => {
+                        gizmos.circle_2d(to_world(*x, *y), 2.0, color);
+                    }
+                }
+            }
         }
     }
 }
@@ -1,32 +1,55 @@
-//! Registry of all Bevy 2D components that can be added in the editor
+//! Registry of the components that can be added in the editor
+//!
+//! Built from the [`AppTypeRegistry`]: every reflected component that can be
+//! constructed from `ReflectDefault` or `ReflectFromWorld` is listed, so game
+//! components show up as soon as they're registered. Components the game
+//! exported with a default value ([`ImportedTypes`]) are listed as well, and are
+//! added as [`bevy_editor_formats::GameComponents`] entries. Components are
+//! grouped by their [`EditorCategory`] attribute, or by module path when they
+//! have none.
 
+use bevy::ecs::reflect::ReflectFromWorld;
 use bevy::prelude::*;
-
-/// Categories of components available in the editor
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ComponentCategory {
-    Core,      // Transform, Name, Visibility
-    Rendering, // Sprite, Camera2D, etc.
-    Audio,     // AudioSource, etc.
-    UI,        // Node, Button, Text, etc.
-    Physics,   // Avian physics components
-    Animation, // Animation components
-    Custom,    // User-defined components
-}
+use bevy::reflect::attributes::CustomAttributes;
+use bevy::reflect::{TypeInfo, TypeRegistry};
+use bevy::render::sync_world::SyncToRenderWorld;
+use bevy::render::view::VisibilityClass;
+use bevy_editor_core::EditorCategory;
+use bevy_editor_formats::{GameComponents, TypeSchemas};
+use bevy_editor_scene::{EditorEntityId, EditorSceneEntity, EditorViewState, ImportedTypes};
+use std::any::TypeId;
+use std::collections::{BTreeMap, HashSet};
 
 /// Information about a component type that can be added in the editor
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct ComponentInfo {
-    pub name: &'static str,
-    pub category: ComponentCategory,
-    pub description: &'static str,
-    /// Function to spawn a default instance of this component
-    pub spawn_fn: fn(&mut EntityWorldMut),
+    /// [`GameComponents`] for imported components
+    pub type_id: TypeId,
+    pub name: String,
+    pub type_path: String,
+    /// Menu group, nested groups separated by `::`
+    pub category: String,
+    /// Component of a game type the editor doesn't register
+    pub imported: bool,
+}
+
+/// Menu groups of the Add Component menu
+#[derive(Debug, Default)]
+pub struct CategoryTree {
+    pub children: BTreeMap<String, CategoryTree>,
+    /// Indices into [`ComponentRegistry::components`]
+    pub components: Vec<usize>,
 }
 
 /// Registry of all available component types
 pub struct ComponentRegistry {
     components: Vec<ComponentInfo>,
+    /// Components exported by the game, see [`Self::set_imported`]
+    imported: Vec<ComponentInfo>,
+    tree: CategoryTree,
+    excluded: HashSet<TypeId>,
+    /// Number of registered types when the list was last built
+    registered_types: Option<usize>,
 }
 
 impl Default for ComponentRegistry {
@@ -39,158 +62,194 @@ impl ComponentRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             components: Vec::new(),
+            imported: Vec::new(),
+            tree: CategoryTree::default(),
+            excluded: HashSet::new(),
+            registered_types: None,
         };
 
-        // Register all built-in Bevy 2D components
-        registry.register_core_components();
-        registry.register_rendering_components();
-        registry.register_audio_components();
-        registry.register_ui_components();
+        // Editor bookkeeping, hierarchy links and state derived by the engine
+        registry
+            .exclude::<EditorSceneEntity>()
+            .exclude::<EditorEntityId>()
+            .exclude::<EditorViewState>()
+            .exclude::<ChildOf>()
+            .exclude::<Children>()
+            .exclude::<GlobalTransform>()
+            .exclude::<InheritedVisibility>()
+            .exclude::<ViewVisibility>()
+            .exclude::<VisibilityClass>()
+            .exclude::<SyncToRenderWorld>()
+            .exclude::<GameComponents>();
 
         registry
     }
 
-    /// Get all components in a category
-    pub fn get_by_category(&self, category: ComponentCategory) -> Vec<&ComponentInfo> {
-        self.components
-            .iter()
-            .filter(|c| c.category == category)
-            .collect()
+    /// Keep components of type `T` out of the menu
+    pub fn exclude<T: 'static>(&mut self) -> &mut Self {
+        self.excluded.insert(TypeId::of::<T>());
+        self.registered_types = None;
+        self
     }
 
-    /// Get all categories
-    pub fn categories(&self) -> Vec<ComponentCategory> {
-        vec![
-            ComponentCategory::Core,
-            ComponentCategory::Rendering,
-            ComponentCategory::Audio,
-            ComponentCategory::UI,
-            ComponentCategory::Physics,
-            ComponentCategory::Animation,
-            ComponentCategory::Custom,
-        ]
-    }
-
-    /// Get category display name
-    pub fn category_name(category: ComponentCategory) -> &'static str {
-        match category {
-            ComponentCategory::Core => "Core",
-            ComponentCategory::Rendering => "Rendering",
-            ComponentCategory::Audio => "Audio",
-            ComponentCategory::UI => "UI",
-            ComponentCategory::Physics => "Physics",
-            ComponentCategory::Animation => "Animation",
-            ComponentCategory::Custom => "Custom",
-        }
+    /// List the components among the game's exported types that have a
+    /// default value. Takes effect on the next [`Self::refresh`].
+    pub fn set_imported(&mut self, schemas: &TypeSchemas) {
+        self.imported = schemas
+            .iter()
+            .filter(|schema| schema.component && schema.default.is_some())
+            .map(|schema| ComponentInfo {
+                type_id: TypeId::of::<GameComponents>(),
+                name: schema.short_path.clone(),
+                type_path: schema.type_path.clone(),
+                category: schema
+                    .category
+                    .clone()
+                    .or_else(|| schema.module_path.clone())
+                    .unwrap_or_else(|| "Other".to_string()),
+                imported: true,
+            })
+            .collect();
+        self.registered_types = None;
     }
 
-    fn register_core_components(&mut self) {
-        self.components.push(ComponentInfo {
-            name: "Transform",
-            category: ComponentCategory::Core,
-            description: "Position, rotation, and scale",
-            spawn_fn: |entity| {
-                entity.insert(Transform::default());
-            },
-        });
+    /// Rebuild the list if types were registered since the last call.
+    /// Returns whether it was rebuilt.
+    pub fn refresh(&mut self, type_registry: &TypeRegistry) -> bool {
+        if !self.is_stale(type_registry) {
+            return false;
+        }
+        self.registered_types = Some(type_registry.iter().count());
 
-        self.components.push(ComponentInfo {
-            name: "Name",
-            category: ComponentCategory::Core,
-            description: "Entity name for identification",
-            spawn_fn: |entity| {
-                entity.insert(Name::new("New Entity"));
-            },
-        });
+        self.components = type_registry
+            .iter()
+            .filter(|registration| {
+                registration.data::<ReflectComponent>().is_some()
+                    && (registration.data::<ReflectDefault>().is_some()
+                        || registration.data::<ReflectFromWorld>().is_some())
+                    && !self.excluded.contains(&registration.type_id())
+            })
+            .map(|registration| {
+                let info = registration.type_info();
+                let table = info.type_path_table();
+                let category = custom_attributes(info)
+                    .and_then(|attributes| attributes.get::<EditorCategory>())
+                    .map(|category| category.0.to_string())
+                    .or_else(|| table.module_path().map(str::to_string))
+                    .unwrap_or_else(|| "Other".to_string());
+                ComponentInfo {
+                    type_id: registration.type_id(),
+                    name: table.short_path().to_string(),
+                    type_path: table.path().to_string(),
+                    category,
+                    imported: false,
+                }
+            })
+            .collect();
+        // Game types the editor also registers are edited directly
+        self.components.extend(
+            self.imported
+                .iter()
+                .filter(|component| {
+                    type_registry
+                        .get_with_type_path(&component.type_path)
+                        .is_none()
+                })
+                .cloned(),
+        );
+        self.components
+            .sort_by(|a, b| (&a.category, &a.name).cmp(&(&b.category, &b.name)));
 
-        self.components.push(ComponentInfo {
-            name: "Visibility",
-            category: ComponentCategory::Core,
-            description: "Controls visibility in rendering",
-            spawn_fn: |entity| {
-                entity.insert(Visibility::default());
-            },
-        });
+        self.tree = CategoryTree::default();
+        for (index, component) in self.components.iter().enumerate() {
+            let mut node = &mut self.tree;
+            for group in component.category.split("::") {
+                node = node.children.entry(group.to_string()).or_default();
+            }
+            node.components.push(index);
+        }
+        true
     }
 
-    fn register_rendering_components(&mut self) {
-        self.components.push(ComponentInfo {
-            name: "Sprite",
-            category: ComponentCategory::Rendering,
-            description: "2D sprite rendering",
-            spawn_fn: |entity| {
-                entity.insert(Sprite {
-                    color: Color::WHITE,
-                    custom_size: Some(Vec2::new(64.0, 64.0)),
-                    ..default()
-                });
-            },
-        });
-
-        self.components.push(ComponentInfo {
-            name: "Camera2D",
-            category: ComponentCategory::Rendering,
-            description: "2D camera",
-            spawn_fn: |entity| {
-                entity.insert(Camera2d);
-            },
-        });
-
-        self.components.push(ComponentInfo {
-            name: "Tilemap",
-            category: ComponentCategory::Rendering,
-            description: "Tile-based map rendering",
-            spawn_fn: |entity| {
-                entity.insert(bevy_editor_tilemap::TilemapComponent::default());
-                entity.insert(bevy_editor_tilemap::TilemapLayers::default());
-            },
-        });
+    /// Whether types were registered since the list was built
+    pub fn is_stale(&self, type_registry: &TypeRegistry) -> bool {
+        self.registered_types != Some(type_registry.iter().count())
+    }
 
-        // Note: TextureAtlas requires TextureAtlasLayout handle, skip for now
+    pub fn components(&self) -> &[ComponentInfo] {
+        &self.components
     }
 
-    fn register_audio_components(&mut self) {
-        // Note: Audio components will be added once we figure out the correct API
-        // The audio system in Bevy 0.16 uses AudioPlayer with AudioSource
+    pub fn get(&self, type_path: &str) -> Option<&ComponentInfo> {
+        self.components
+            .iter()
+            .find(|component| component.type_path == type_path)
     }
 
-    fn register_ui_components(&mut self) {
-        self.components.push(ComponentInfo {
-            name: "Node",
-            category: ComponentCategory::UI,
-            description: "UI container element",
-            spawn_fn: |entity| {
-                entity.insert(Node::default());
-            },
-        });
+    /// Components grouped by category
+    pub fn tree(&self) -> &CategoryTree {
+        &self.tree
+    }
 
-        self.components.push(ComponentInfo {
-            name: "Button",
-            category: ComponentCategory::UI,
-            description: "Interactive button",
-            spawn_fn: |entity| {
-                entity.insert(Button);
-            },
+    /// Components fuzzy-matching `query`, best match first
+    pub fn search(&self, query: &str) -> Vec<&ComponentInfo> {
+        let mut matches: Vec<(i32, &ComponentInfo)> = self
+            .components
+            .iter()
+            .filter_map(|component| {
+                // Names rank above matches that need the full path
+                let score = fuzzy_score(query, &component.name)
+                    .map(|score| score * 2)
+                    .or_else(|| fuzzy_score(query, &component.type_path))?;
+                Some((score, component))
+            })
+            .collect();
+        matches.sort_by(|(a_score, a), (b_score, b)| {
+            b_score.cmp(a_score).then_with(|| a.name.cmp(&b.name))
         });
+        matches
+            .into_iter()
+            .map(|(_, component)| component)
+            .collect()
+    }
+}
 
-        self.components.push(ComponentInfo {
-            name: "Text",
-            category: ComponentCategory::UI,
-            description: "Text rendering",
-            spawn_fn: |entity| {
-                entity.insert(Text::new("New Text"));
-            },
-        });
+fn custom_attributes(info: &TypeInfo) -> Option<&CustomAttributes> {
+    match info {
+        TypeInfo::Struct(info) => Some(info.custom_attributes()),
+        TypeInfo::TupleStruct(info) => Some(info.custom_attributes()),
+        TypeInfo::Enum(info) => Some(info.custom_attributes()),
+        _ => None,
+    }
+}
 
-        self.components.push(ComponentInfo {
-            name: "BackgroundColor",
-            category: ComponentCategory::UI,
-            description: "UI element background color",
-            spawn_fn: |entity| {
-                entity.insert(BackgroundColor(Color::WHITE));
-            },
-        });
+/// Score of `candidate` for a fuzzy `query`: every query character must appear
+/// in order (case-insensitive). Consecutive characters and characters starting
+/// a word score higher; `None` if it doesn't match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        let query_char = query_char.to_ascii_lowercase();
+        let index = (position..candidate.len())
+            .find(|&index| candidate[index].to_ascii_lowercase() == query_char)?;
+        score += 1;
+        if index > 0 && previous == Some(index - 1) {
+            score += 5;
+        }
+        let word_start = index == 0
+            || !candidate[index - 1].is_alphanumeric()
+            || (candidate[index].is_uppercase() && candidate[index - 1].is_lowercase());
+        if word_start {
+            score += 10;
+        }
+        previous = Some(index);
+        position = index + 1;
     }
+    // Prefer shorter candidates among equal matches
+    Some(score * 8 - candidate.len() as i32)
 }
 
 /// Resource for the component registry
@@ -198,3 +257,98 @@ impl ComponentRegistry {
 pub struct EditorComponentRegistry {
     pub registry: ComponentRegistry,
 }
+
+/// Pick up components registered or exported by the game since the last frame
+pub fn refresh_component_registry(
+    type_registry: Res<AppTypeRegistry>,
+    imported: Option<Res<ImportedTypes>>,
+    mut components: ResMut<EditorComponentRegistry>,
+) {
+    if let Some(imported) = imported.filter(|imported| imported.is_changed()) {
+        components.registry.set_imported(&imported.schemas);
+    }
+    let type_registry = type_registry.read();
+    // Only take the resource mutably when something changed
+    if components.registry.is_stale(&type_registry) {
+        components.registry.refresh(&type_registry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_editor_formats::TypeSchemaFile;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component, Default, @EditorCategory("Gameplay::Combat"))]
+    struct Health(f32);
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct NoDefault;
+
+    #[test]
+    fn registered_components_are_listed_by_category() {
+        let mut type_registry = TypeRegistry::default();
+        type_registry.register::<Health>();
+        type_registry.register::<NoDefault>();
+        type_registry.register::<Sprite>();
+        type_registry.register::<GlobalTransform>();
+
+        let mut registry = ComponentRegistry::new();
+        assert!(registry.refresh(&type_registry));
+        assert!(!registry.refresh(&type_registry));
+
+        let names: Vec<&str> = registry
+            .components()
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert!(names.contains(&"Health"));
+        assert!(names.contains(&"Sprite"));
+        assert!(!names.contains(&"NoDefault"));
+        assert!(!names.contains(&"GlobalTransform"));
+
+        let combat = &registry.tree().children["Gameplay"].children["Combat"];
+        assert_eq!(registry.components()[combat.components[0]].name, "Health");
+        let sprite = registry.get(Sprite::type_path()).unwrap();
+        assert_eq!(sprite.category, "bevy_sprite::sprite");
+    }
+
+    #[test]
+    fn exported_game_components_are_listed() {
+        #[derive(Component, Reflect, Default)]
+        #[reflect(Component, Default)]
+        struct Mana(f32);
+
+        let mut game_registry = TypeRegistry::default();
+        game_registry.register::<Mana>();
+        game_registry.register::<NoDefault>();
+        game_registry.register::<Sprite>();
+        let schemas = TypeSchemas::from(TypeSchemaFile::from_registry(&game_registry));
+
+        let mut type_registry = TypeRegistry::default();
+        type_registry.register::<Sprite>();
+        let mut registry = ComponentRegistry::new();
+        registry.set_imported(&schemas);
+        registry.refresh(&type_registry);
+
+        let mana = registry.get(Mana::type_path()).unwrap();
+        assert!(mana.imported);
+        assert_eq!(mana.type_id, TypeId::of::<GameComponents>());
+        assert!(registry.get(NoDefault::type_path()).is_none());
+        // Engine components the game exported too stay regular components
+        let sprites: Vec<_> = registry.search("Sprite");
+        assert!(sprites.iter().all(|sprite| !sprite.imported));
+    }
+
+    #[test]
+    fn fuzzy_search_prefers_word_starts() {
+        assert!(fuzzy_score("xyz", "Sprite").is_none());
+        assert!(fuzzy_score("rb", "RigidBody").unwrap() > fuzzy_score("rb", "Rubble").unwrap());
+        assert!(
+            fuzzy_score("vis", "Visibility").unwrap()
+                > fuzzy_score("vis", "ViewVisibility").unwrap()
+        );
+    }
+}
@@ -1,19 +1,36 @@
 //! Concrete implementations of editor commands for undo/redo
+//!
+//! Commands don't mark the scene modified; that follows the history save point.
+//! They address entities by [`EditorEntityId`] so they still apply after the
+//! scene has been despawned and reloaded (tab switches, undo of a delete), and
+//! are serializable so macros and the crash journal can replay them.
 
 use bevy::prelude::*;
-use bevy_editor_commands::EditorCommand;
+use bevy_editor_commands::{CommandRecord, EditorCommand, SerializableCommand};
+use bevy_editor_formats::EntityFields;
+use bevy_editor_foundation::Selection;
 use bevy_editor_frontend_api::scene_tree::SceneEntityTemplate;
-use bevy_editor_scene::EditorScene;
+use bevy_editor_scene::{
+    copy_entities, find_entity_by_id, paste_entities, restore_entities, EditorEntityId, EditorScene,
+};
+use serde::{Deserialize, Serialize};
+use std::any::TypeId;
+use std::time::{Duration, Instant};
+
+/// Edits of the same field closer together than this are undone as one step
+const FIELD_EDIT_MERGE_WINDOW: Duration = Duration::from_secs(1);
 
 /// Command to create a new entity
+#[derive(Serialize, Deserialize)]
 pub struct CreateEntityCommand {
-    /// The entity that was/will be created
-    entity: Option<Entity>,
+    /// The entity that was/will be created; redo recreates it with the same id
+    entity: EditorEntityId,
     /// The template to use for creation
     template: SceneEntityTemplate,
-    /// The parent entity (if any)
-    parent: Option<Entity>,
+    /// The parent entity, `None` for the scene root
+    parent: Option<EditorEntityId>,
     /// Saved component data for redo
+    #[serde(skip)]
     saved_components: Option<SavedEntityData>,
 }
 
@@ -26,9 +43,9 @@ struct SavedEntityData {
 }
 
 impl CreateEntityCommand {
-    pub fn new(template: SceneEntityTemplate, parent: Option<Entity>) -> Self {
+    pub fn new(template: SceneEntityTemplate, parent: Option<EditorEntityId>) -> Self {
         Self {
-            entity: None,
+            entity: EditorEntityId::new(),
             template,
             parent,
             saved_components: None,
@@ -39,18 +56,23 @@ impl CreateEntityCommand {
 impl EditorCommand for CreateEntityCommand {
     fn execute(&mut self, world: &mut World) {
         // Spawn the entity using the template
+        let parent = match self.parent {
+            Some(id) => find_entity_by_id(world, id),
+            None => world
+                .get_resource::<EditorScene>()
+                .and_then(|editor_scene| editor_scene.root_entity),
+        };
         let entity = crate::entity_templates::spawn_from_template(
             &mut world.commands(),
             self.template,
-            self.parent,
+            parent,
         );
+        world.commands().entity(entity).insert(self.entity);
+        world.flush();
 
-        self.entity = Some(entity);
-
-        // Update editor scene selection
-        if let Some(mut editor_scene) = world.get_resource_mut::<EditorScene>() {
-            editor_scene.select_entity(entity);
-            editor_scene.mark_modified();
+        // Select the new entity
+        if let Some(mut selection) = world.get_resource_mut::<Selection>() {
+            selection.select(entity);
         }
 
         info!(
@@ -60,7 +82,7 @@ impl EditorCommand for CreateEntityCommand {
     }
 
     fn undo(&mut self, world: &mut World) {
-        if let Some(entity) = self.entity {
+        if let Some(entity) = find_entity_by_id(world, self.entity) {
             // Save component data before deleting (for potential redo)
             if let Ok(entity_ref) = world.get_entity(entity) {
                 if let Some(name) = entity_ref.get::<Name>() {
@@ -79,12 +101,9 @@ impl EditorCommand for CreateEntityCommand {
                 info!("Undid entity creation - despawned {:?}", entity);
             }
 
-            // Clear selection if this was the selected entity
-            if let Some(mut editor_scene) = world.get_resource_mut::<EditorScene>() {
-                if editor_scene.selected_entity == Some(entity) {
-                    editor_scene.select_entity(Entity::PLACEHOLDER);
-                }
-                editor_scene.mark_modified();
+            // Deselect the despawned entity
+            if let Some(mut selection) = world.get_resource_mut::<Selection>() {
+                selection.remove(entity);
             }
         }
     }
@@ -92,135 +111,158 @@ impl EditorCommand for CreateEntityCommand {
     fn description(&self) -> String {
         format!("Create {}", self.template.display_name())
     }
+
+    fn to_record(&self) -> Option<CommandRecord> {
+        CommandRecord::of(self)
+    }
 }
 
-/// Command to delete an entity
-pub struct DeleteEntityCommand {
-    /// The entity that was/will be deleted
-    entity: Entity,
-    /// Saved data to restore the entity on undo
-    saved_data: Option<SavedDeletedEntity>,
+impl SerializableCommand for CreateEntityCommand {
+    const KIND: &'static str = "create_entity";
 }
 
-#[derive(Clone)]
-struct SavedDeletedEntity {
-    name: String,
-    transform: Transform,
-    visibility: Visibility,
-    template: SceneEntityTemplate,
-    parent: Option<Entity>,
-    // TODO: Save all components via reflection/serialization
+/// Command to delete an entity with its children
+#[derive(Serialize, Deserialize)]
+pub struct DeleteEntityCommand {
+    /// The entity that was/will be deleted; undo recreates it with the same id
+    entity: EditorEntityId,
+    /// Parent to restore the entity under, the scene root if `None`
+    #[serde(default)]
+    parent: Option<EditorEntityId>,
+    /// Name, for the history description
+    #[serde(default)]
+    name: Option<String>,
+    /// The deleted subtree, as written by `copy_entities`
+    #[serde(default)]
+    scene: Option<String>,
 }
 
 impl DeleteEntityCommand {
-    pub fn new(entity: Entity) -> Self {
+    pub fn new(entity: EditorEntityId) -> Self {
         Self {
             entity,
-            saved_data: None,
+            parent: None,
+            name: None,
+            scene: None,
         }
     }
 }
 
 impl EditorCommand for DeleteEntityCommand {
     fn execute(&mut self, world: &mut World) {
-        // Save entity data before deleting
-        if let Ok(entity_ref) = world.get_entity(self.entity) {
-            let name = entity_ref
-                .get::<Name>()
-                .map(|n| n.to_string())
-                .unwrap_or_default();
-            let transform = entity_ref.get::<Transform>().copied().unwrap_or_default();
-            let visibility = entity_ref.get::<Visibility>().copied().unwrap_or_default();
-            let parent = entity_ref.get::<ChildOf>().map(|p| p.0);
-
-            // Determine template type from components
-            let template = if entity_ref.contains::<Sprite>() {
-                SceneEntityTemplate::Sprite
-            } else if entity_ref.contains::<Node>() {
-                if entity_ref.contains::<Button>() {
-                    SceneEntityTemplate::Button
-                } else {
-                    SceneEntityTemplate::UiNode
-                }
-            } else if entity_ref.contains::<Text>() {
-                SceneEntityTemplate::Text
-            } else {
-                SceneEntityTemplate::Empty
-            };
+        let Some(entity) = find_entity_by_id(world, self.entity) else {
+            return;
+        };
 
-            self.saved_data = Some(SavedDeletedEntity {
-                name,
-                transform,
-                visibility,
-                template,
-                parent,
-            });
+        // Snapshot the whole subtree so undo brings back every component
+        match copy_entities(world, &[entity]) {
+            Ok(scene) => self.scene = Some(scene),
+            Err(err) => {
+                error!(
+                    "Not deleting {:?}, it can't be saved for undo: {}",
+                    entity, err
+                );
+                return;
+            }
         }
+        self.name = world.get::<Name>(entity).map(|name| name.to_string());
+        self.parent = world
+            .get::<ChildOf>(entity)
+            .and_then(|child_of| world.get::<EditorEntityId>(child_of.parent()).copied());
 
-        // Despawn the entity
-        if let Ok(entity_commands) = world.get_entity_mut(self.entity) {
-            entity_commands.despawn();
-            info!("Deleted entity {:?}", self.entity);
-        }
+        world.entity_mut(entity).despawn();
+        info!("Deleted entity {:?}", entity);
 
-        // Clear selection
-        if let Some(mut editor_scene) = world.get_resource_mut::<EditorScene>() {
-            if editor_scene.selected_entity == Some(self.entity) {
-                editor_scene.select_entity(Entity::PLACEHOLDER);
+        // Deselect the despawned entity and its children
+        let despawned: Vec<Entity> = world
+            .get_resource::<Selection>()
+            .map(|selection| {
+                selection
+                    .iter()
+                    .filter(|entity| world.get_entity(*entity).is_err())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(mut selection) = world.get_resource_mut::<Selection>() {
+            for entity in despawned {
+                selection.remove(entity);
             }
-            editor_scene.mark_modified();
         }
     }
 
     fn undo(&mut self, world: &mut World) {
-        if let Some(ref data) = self.saved_data {
-            // Recreate the entity
-            let entity = crate::entity_templates::spawn_from_template(
-                &mut world.commands(),
-                data.template,
-                data.parent,
-            );
-
-            // Restore components
-            if let Ok(mut entity_commands) = world.get_entity_mut(entity) {
-                entity_commands.insert(Name::new(data.name.clone()));
-                entity_commands.insert(data.transform);
-                entity_commands.insert(data.visibility);
-            }
-
-            // Update stored entity ID
-            self.entity = entity;
-
-            // Update editor scene
-            if let Some(mut editor_scene) = world.get_resource_mut::<EditorScene>() {
-                editor_scene.select_entity(entity);
-                editor_scene.mark_modified();
+        let Some(scene) = &self.scene else {
+            return;
+        };
+        let parent = match self.parent {
+            Some(id) => find_entity_by_id(world, id),
+            None => world
+                .get_resource::<EditorScene>()
+                .and_then(|editor_scene| editor_scene.root_entity),
+        };
+        let roots = match restore_entities(world, scene, parent) {
+            Ok(roots) => roots,
+            Err(err) => {
+                error!("Failed to restore deleted entity: {}", err);
+                return;
             }
+        };
 
-            info!("Undid entity deletion - recreated {:?}", entity);
+        // Select the recreated entity
+        if let Some(mut selection) = world.get_resource_mut::<Selection>() {
+            selection.set(roots);
         }
+        info!("Undid entity deletion - recreated {:?}", self.entity);
     }
 
     fn description(&self) -> String {
-        if let Some(ref data) = self.saved_data {
-            format!("Delete '{}'", data.name)
+        if let Some(name) = &self.name {
+            format!("Delete '{}'", name)
         } else {
             format!("Delete entity {:?}", self.entity)
         }
     }
+
+    fn to_record(&self) -> Option<CommandRecord> {
+        CommandRecord::of(self)
+    }
+}
+
+impl SerializableCommand for DeleteEntityCommand {
+    const KIND: &'static str = "delete_entity";
 }
 
 /// Command to modify an entity's transform
+#[derive(Serialize, Deserialize)]
 pub struct TransformCommand {
-    entity: Entity,
+    entity: EditorEntityId,
+    #[serde(with = "transform_serde")]
     old_transform: Transform,
+    #[serde(with = "transform_serde")]
     new_transform: Transform,
     property_name: String, // "Position", "Rotation", or "Scale"
+    #[serde(skip, default = "Instant::now")]
+    edited_at: Instant,
 }
 
 impl TransformCommand {
+    pub fn new(
+        entity: EditorEntityId,
+        old_transform: Transform,
+        new_transform: Transform,
+        property_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            entity,
+            old_transform,
+            new_transform,
+            property_name: property_name.into(),
+            edited_at: Instant::now(),
+        }
+    }
+
     pub fn new_position(
-        entity: Entity,
+        entity: EditorEntityId,
         old_pos: Vec2,
         new_pos: Vec2,
         current_transform: Transform,
@@ -233,16 +275,11 @@ impl TransformCommand {
         new_transform.translation.x = new_pos.x;
         new_transform.translation.y = new_pos.y;
 
-        Self {
-            entity,
-            old_transform,
-            new_transform,
-            property_name: "Position".to_string(),
-        }
+        Self::new(entity, old_transform, new_transform, "Position")
     }
 
     pub fn new_rotation(
-        entity: Entity,
+        entity: EditorEntityId,
         old_rot: f32,
         new_rot: f32,
         current_transform: Transform,
@@ -253,16 +290,11 @@ impl TransformCommand {
         let mut new_transform = current_transform;
         new_transform.rotation = Quat::from_rotation_z(new_rot);
 
-        Self {
-            entity,
-            old_transform,
-            new_transform,
-            property_name: "Rotation".to_string(),
-        }
+        Self::new(entity, old_transform, new_transform, "Rotation")
     }
 
     pub fn new_scale(
-        entity: Entity,
+        entity: EditorEntityId,
         old_scale: Vec2,
         new_scale: Vec2,
         current_transform: Transform,
@@ -275,56 +307,191 @@ impl TransformCommand {
         new_transform.scale.x = new_scale.x;
         new_transform.scale.y = new_scale.y;
 
-        Self {
-            entity,
-            old_transform,
-            new_transform,
-            property_name: "Scale".to_string(),
-        }
+        Self::new(entity, old_transform, new_transform, "Scale")
     }
 }
 
 impl EditorCommand for TransformCommand {
     fn execute(&mut self, world: &mut World) {
-        if let Ok(mut entity_commands) = world.get_entity_mut(self.entity) {
-            entity_commands.insert(self.new_transform);
+        if let Some(entity) = find_entity_by_id(world, self.entity) {
+            world.entity_mut(entity).insert(self.new_transform);
         }
+    }
 
-        if let Some(mut editor_scene) = world.get_resource_mut::<EditorScene>() {
-            editor_scene.mark_modified();
+    fn undo(&mut self, world: &mut World) {
+        if let Some(entity) = find_entity_by_id(world, self.entity) {
+            world.entity_mut(entity).insert(self.old_transform);
         }
     }
 
-    fn undo(&mut self, world: &mut World) {
-        if let Ok(mut entity_commands) = world.get_entity_mut(self.entity) {
-            entity_commands.insert(self.old_transform);
+    fn description(&self) -> String {
+        format!("Change {}", self.property_name)
+    }
+
+    fn can_merge_with(&self, other: &dyn EditorCommand) -> bool {
+        let Some(other) = other
+            .as_any()
+            .and_then(|any| any.downcast_ref::<TransformCommand>())
+        else {
+            return false;
+        };
+        // Consecutive edits of one entity's property become a single step
+        self.entity == other.entity
+            && self.property_name == other.property_name
+            && other.edited_at.saturating_duration_since(self.edited_at) <= FIELD_EDIT_MERGE_WINDOW
+    }
+
+    fn merge(&mut self, other: Box<dyn EditorCommand>) {
+        let Some(other) = other
+            .as_any()
+            .and_then(|any| any.downcast_ref::<TransformCommand>())
+        else {
+            return;
+        };
+        self.new_transform = other.new_transform;
+        self.edited_at = other.edited_at;
+    }
+
+    fn as_any(&self) -> Option<&dyn std::any::Any> {
+        Some(self)
+    }
+
+    fn to_record(&self) -> Option<CommandRecord> {
+        CommandRecord::of(self)
+    }
+}
+
+impl SerializableCommand for TransformCommand {
+    const KIND: &'static str = "transform";
+}
+
+/// One entity's transform before and after a [`TransformEntitiesCommand`]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TransformChange {
+    pub entity: EditorEntityId,
+    #[serde(with = "transform_serde")]
+    pub old: Transform,
+    #[serde(with = "transform_serde")]
+    pub new: Transform,
+}
+
+/// Command to change the transforms of several entities as one step, like a
+/// group drag in the viewport or an inspector edit of the whole selection
+#[derive(Serialize, Deserialize)]
+pub struct TransformEntitiesCommand {
+    changes: Vec<TransformChange>,
+    property_name: String, // "Position", "Rotation", or "Scale"
+    /// Continuous edits (inspector drags) merge; separate gizmo drags don't
+    #[serde(skip)]
+    mergeable: bool,
+    #[serde(skip, default = "Instant::now")]
+    edited_at: Instant,
+}
+
+impl TransformEntitiesCommand {
+    pub fn new(changes: Vec<TransformChange>, property_name: impl Into<String>) -> Self {
+        Self {
+            changes,
+            property_name: property_name.into(),
+            mergeable: false,
+            edited_at: Instant::now(),
         }
+    }
+
+    /// Merge with the next edit of the same entities and property
+    pub fn mergeable(mut self) -> Self {
+        self.mergeable = true;
+        self
+    }
+
+    fn same_entities(&self, other: &Self) -> bool {
+        self.changes.len() == other.changes.len()
+            && self
+                .changes
+                .iter()
+                .zip(&other.changes)
+                .all(|(a, b)| a.entity == b.entity)
+    }
+}
+
+impl EditorCommand for TransformEntitiesCommand {
+    fn execute(&mut self, world: &mut World) {
+        for change in &self.changes {
+            if let Some(entity) = find_entity_by_id(world, change.entity) {
+                world.entity_mut(entity).insert(change.new);
+            }
+        }
+    }
 
-        if let Some(mut editor_scene) = world.get_resource_mut::<EditorScene>() {
-            editor_scene.mark_modified();
+    fn undo(&mut self, world: &mut World) {
+        for change in &self.changes {
+            if let Some(entity) = find_entity_by_id(world, change.entity) {
+                world.entity_mut(entity).insert(change.old);
+            }
         }
     }
 
     fn description(&self) -> String {
-        format!("Change {}", self.property_name)
+        match self.changes.len() {
+            1 => format!("Change {}", self.property_name),
+            count => format!("Change {} of {} entities", self.property_name, count),
+        }
+    }
+
+    fn can_merge_with(&self, other: &dyn EditorCommand) -> bool {
+        let Some(other) = other
+            .as_any()
+            .and_then(|any| any.downcast_ref::<TransformEntitiesCommand>())
+        else {
+            return false;
+        };
+        self.mergeable
+            && other.mergeable
+            && self.property_name == other.property_name
+            && self.same_entities(other)
+            && other.edited_at.saturating_duration_since(self.edited_at) <= FIELD_EDIT_MERGE_WINDOW
+    }
+
+    fn merge(&mut self, other: Box<dyn EditorCommand>) {
+        let Some(other) = other
+            .as_any()
+            .and_then(|any| any.downcast_ref::<TransformEntitiesCommand>())
+        else {
+            return;
+        };
+        for (change, other) in self.changes.iter_mut().zip(&other.changes) {
+            change.new = other.new;
+        }
+        self.edited_at = other.edited_at;
     }
 
-    fn can_merge_with(&self, _other: &dyn EditorCommand) -> bool {
-        // Try to downcast to TransformCommand
-        // This is a simplified version - in practice, you'd want better type checking
-        false // For now, don't merge transform commands
+    fn as_any(&self) -> Option<&dyn std::any::Any> {
+        Some(self)
+    }
+
+    fn size_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.changes.len() * std::mem::size_of::<TransformChange>()
+    }
+
+    fn to_record(&self) -> Option<CommandRecord> {
+        CommandRecord::of(self)
     }
 }
 
+impl SerializableCommand for TransformEntitiesCommand {
+    const KIND: &'static str = "transform_entities";
+}
+
 /// Command to rename an entity
+#[derive(Serialize, Deserialize)]
 pub struct RenameEntityCommand {
-    entity: Entity,
+    entity: EditorEntityId,
     old_name: String,
     new_name: String,
 }
 
 impl RenameEntityCommand {
-    pub fn new(entity: Entity, old_name: String, new_name: String) -> Self {
+    pub fn new(entity: EditorEntityId, old_name: String, new_name: String) -> Self {
         Self {
             entity,
             old_name,
@@ -335,26 +502,600 @@ impl RenameEntityCommand {
 
 impl EditorCommand for RenameEntityCommand {
     fn execute(&mut self, world: &mut World) {
-        if let Ok(mut entity_commands) = world.get_entity_mut(self.entity) {
-            entity_commands.insert(Name::new(self.new_name.clone()));
+        if let Some(entity) = find_entity_by_id(world, self.entity) {
+            world
+                .entity_mut(entity)
+                .insert(Name::new(self.new_name.clone()));
+        }
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        if let Some(entity) = find_entity_by_id(world, self.entity) {
+            world
+                .entity_mut(entity)
+                .insert(Name::new(self.old_name.clone()));
+        }
+    }
+
+    fn description(&self) -> String {
+        format!("Rename to '{}'", self.new_name)
+    }
+    fn to_record(&self) -> Option<CommandRecord> {
+        CommandRecord::of(self)
+    }
+}
+
+impl SerializableCommand for RenameEntityCommand {
+    const KIND: &'static str = "rename_entity";
+}
+
+/// Command to move an entity under another parent
+#[derive(Serialize, Deserialize)]
+pub struct ReparentEntityCommand {
+    entity: EditorEntityId,
+    /// `None` when the entity had no parent
+    old_parent: Option<EditorEntityId>,
+    /// `None` detaches the entity from its parent
+    new_parent: Option<EditorEntityId>,
+}
+
+impl ReparentEntityCommand {
+    pub fn new(
+        entity: EditorEntityId,
+        old_parent: Option<EditorEntityId>,
+        new_parent: Option<EditorEntityId>,
+    ) -> Self {
+        Self {
+            entity,
+            old_parent,
+            new_parent,
+        }
+    }
+
+    fn set_parent(&self, world: &mut World, parent: Option<EditorEntityId>) {
+        let Some(entity) = find_entity_by_id(world, self.entity) else {
+            return;
+        };
+        match parent.map(|id| find_entity_by_id(world, id)) {
+            Some(Some(parent)) => {
+                world.entity_mut(entity).insert(ChildOf(parent));
+            }
+            Some(None) => warn!("Parent {:?} of {:?} no longer exists", parent, entity),
+            None => {
+                world.entity_mut(entity).remove::<ChildOf>();
+            }
+        }
+    }
+}
+
+impl EditorCommand for ReparentEntityCommand {
+    fn execute(&mut self, world: &mut World) {
+        self.set_parent(world, self.new_parent);
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        self.set_parent(world, self.old_parent);
+    }
+
+    fn description(&self) -> String {
+        "Reparent entity".to_string()
+    }
+
+    fn to_record(&self) -> Option<CommandRecord> {
+        CommandRecord::of(self)
+    }
+}
+
+impl SerializableCommand for ReparentEntityCommand {
+    const KIND: &'static str = "reparent_entity";
+}
+
+/// Command to attach, edit or remove an entity's custom fields
+#[derive(Serialize, Deserialize)]
+pub struct SetEntityFieldsCommand {
+    entity: EditorEntityId,
+    old_fields: Option<EntityFields>,
+    new_fields: Option<EntityFields>,
+    /// The single field edited, `None` when the whole component is added or removed
+    field: Option<String>,
+    #[serde(skip, default = "Instant::now")]
+    edited_at: Instant,
+}
+
+impl SetEntityFieldsCommand {
+    pub fn new(
+        entity: EditorEntityId,
+        old_fields: Option<EntityFields>,
+        new_fields: Option<EntityFields>,
+        field: Option<String>,
+    ) -> Self {
+        Self {
+            entity,
+            old_fields,
+            new_fields,
+            field,
+            edited_at: Instant::now(),
+        }
+    }
+
+    fn apply(&self, world: &mut World, fields: &Option<EntityFields>) {
+        let Some(entity) = find_entity_by_id(world, self.entity) else {
+            return;
+        };
+        match fields {
+            Some(fields) => {
+                world.entity_mut(entity).insert(fields.clone());
+            }
+            None => {
+                world.entity_mut(entity).remove::<EntityFields>();
+            }
+        }
+    }
+}
+
+impl EditorCommand for SetEntityFieldsCommand {
+    fn execute(&mut self, world: &mut World) {
+        self.apply(world, &self.new_fields);
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        self.apply(world, &self.old_fields);
+    }
+
+    fn description(&self) -> String {
+        match (&self.field, &self.old_fields, &self.new_fields) {
+            (Some(field), _, _) => format!("Set Field '{}'", field),
+            (None, None, Some(_)) => "Add Entity Fields".to_string(),
+            (None, Some(_), None) => "Remove Entity Fields".to_string(),
+            (None, _, _) => "Change Entity Fields".to_string(),
         }
+    }
+
+    fn can_merge_with(&self, other: &dyn EditorCommand) -> bool {
+        let Some(other) = other
+            .as_any()
+            .and_then(|any| any.downcast_ref::<SetEntityFieldsCommand>())
+        else {
+            return false;
+        };
+        self.field.is_some()
+            && self.entity == other.entity
+            && self.field == other.field
+            && other.edited_at.saturating_duration_since(self.edited_at) <= FIELD_EDIT_MERGE_WINDOW
+    }
+
+    fn merge(&mut self, other: Box<dyn EditorCommand>) {
+        let Some(other) = other
+            .as_any()
+            .and_then(|any| any.downcast_ref::<SetEntityFieldsCommand>())
+        else {
+            return;
+        };
+        self.new_fields = other.new_fields.clone();
+        self.edited_at = other.edited_at;
+    }
 
-        if let Some(mut editor_scene) = world.get_resource_mut::<EditorScene>() {
-            editor_scene.mark_modified();
+    fn as_any(&self) -> Option<&dyn std::any::Any> {
+        Some(self)
+    }
+
+    fn to_record(&self) -> Option<CommandRecord> {
+        CommandRecord::of(self)
+    }
+}
+
+impl SerializableCommand for SetEntityFieldsCommand {
+    const KIND: &'static str = "entity_fields";
+}
+
+/// Command to paste or duplicate copied entities, see [`paste_entities`]
+#[derive(Serialize, Deserialize)]
+pub struct PasteEntitiesCommand {
+    /// Copied entities, as written by `copy_entities`
+    scene: String,
+    /// Parent of the pasted entities, the scene root if `None`
+    parent: Option<EditorEntityId>,
+    offset: [f32; 3],
+    duplicate: bool,
+    /// Ids of the pasted entities; redo recreates them with the same ids
+    ids: Vec<EditorEntityId>,
+    /// Ids of the pasted entities that aren't children of other pasted ones
+    roots: Vec<EditorEntityId>,
+}
+
+impl PasteEntitiesCommand {
+    pub fn new(
+        scene: String,
+        parent: Option<EditorEntityId>,
+        offset: Vec3,
+        duplicate: bool,
+    ) -> Self {
+        Self {
+            scene,
+            parent,
+            offset: offset.to_array(),
+            duplicate,
+            ids: Vec::new(),
+            roots: Vec::new(),
         }
     }
+}
+
+impl EditorCommand for PasteEntitiesCommand {
+    fn execute(&mut self, world: &mut World) {
+        let parent = match self.parent {
+            Some(id) => find_entity_by_id(world, id),
+            None => world.resource::<EditorScene>().root_entity,
+        };
+        let roots = match paste_entities(
+            world,
+            &self.scene,
+            parent,
+            Vec3::from_array(self.offset),
+            &mut self.ids,
+        ) {
+            Ok(roots) => roots,
+            Err(err) => {
+                error!("Failed to paste entities: {}", err);
+                return;
+            }
+        };
+        self.roots = roots
+            .iter()
+            .filter_map(|entity| world.get::<EditorEntityId>(*entity).copied())
+            .collect();
+
+        if let Some(mut selection) = world.get_resource_mut::<Selection>() {
+            selection.set(roots);
+        }
+        info!("Pasted {} entities", self.ids.len());
+    }
 
     fn undo(&mut self, world: &mut World) {
-        if let Ok(mut entity_commands) = world.get_entity_mut(self.entity) {
-            entity_commands.insert(Name::new(self.old_name.clone()));
+        for id in &self.roots {
+            let Some(entity) = find_entity_by_id(world, *id) else {
+                continue;
+            };
+            world.entity_mut(entity).despawn();
+            if let Some(mut selection) = world.get_resource_mut::<Selection>() {
+                selection.remove(entity);
+            }
+        }
+    }
+
+    fn description(&self) -> String {
+        let action = if self.duplicate { "Duplicate" } else { "Paste" };
+        match self.roots.len() {
+            1 => format!("{} entity", action),
+            count => format!("{} {} entities", action, count),
+        }
+    }
+
+    fn to_record(&self) -> Option<CommandRecord> {
+        CommandRecord::of(self)
+    }
+}
+
+impl SerializableCommand for PasteEntitiesCommand {
+    const KIND: &'static str = "paste_entities";
+}
+
+/// Command adding, replacing or removing any reflected component, used by the
+/// reflection inspector. Values may hold asset handles, so it isn't recorded
+/// in macros or the crash journal.
+pub struct ReflectComponentCommand {
+    component: TypeId,
+    /// Short type name, for the history description
+    name: String,
+    changes: Vec<ReflectComponentChange>,
+    edited_at: Instant,
+}
+
+/// One entity's part of a [`ReflectComponentCommand`]
+pub struct ReflectComponentChange {
+    pub entity: EditorEntityId,
+    /// `None` when the entity didn't have the component
+    pub old_value: Option<Box<dyn Reflect>>,
+    /// `None` to remove the component
+    pub new_value: Option<Box<dyn Reflect>>,
+}
+
+impl ReflectComponentCommand {
+    pub fn new(
+        entity: EditorEntityId,
+        component: TypeId,
+        name: impl Into<String>,
+        old_value: Option<Box<dyn Reflect>>,
+        new_value: Option<Box<dyn Reflect>>,
+    ) -> Self {
+        Self::for_entities(
+            component,
+            name,
+            vec![ReflectComponentChange {
+                entity,
+                old_value,
+                new_value,
+            }],
+        )
+    }
+
+    /// The same component changed on several entities, as one undo step
+    pub fn for_entities(
+        component: TypeId,
+        name: impl Into<String>,
+        changes: Vec<ReflectComponentChange>,
+    ) -> Self {
+        Self {
+            component,
+            name: name.into(),
+            changes,
+            edited_at: Instant::now(),
+        }
+    }
+
+    fn apply(&self, world: &mut World, entity: EditorEntityId, value: Option<&dyn Reflect>) {
+        let Some(entity) = find_entity_by_id(world, entity) else {
+            return;
+        };
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+        let Some(reflect_component) = registry.get_type_data::<ReflectComponent>(self.component)
+        else {
+            return;
+        };
+        let mut entity_mut = world.entity_mut(entity);
+        match value {
+            Some(value) if entity_mut.contains_type_id(self.component) => {
+                reflect_component.apply(&mut entity_mut, value.as_partial_reflect());
+            }
+            Some(value) => {
+                reflect_component.insert(&mut entity_mut, value.as_partial_reflect(), &registry);
+            }
+            None => reflect_component.remove(&mut entity_mut),
+        }
+    }
+
+    fn is_edit(&self) -> bool {
+        self.changes
+            .iter()
+            .all(|change| change.old_value.is_some() && change.new_value.is_some())
+    }
+}
+
+impl EditorCommand for ReflectComponentCommand {
+    fn execute(&mut self, world: &mut World) {
+        for change in &self.changes {
+            self.apply(world, change.entity, change.new_value.as_deref());
         }
+    }
 
-        if let Some(mut editor_scene) = world.get_resource_mut::<EditorScene>() {
-            editor_scene.mark_modified();
+    fn undo(&mut self, world: &mut World) {
+        for change in self.changes.iter().rev() {
+            self.apply(world, change.entity, change.old_value.as_deref());
         }
     }
 
     fn description(&self) -> String {
-        format!("Rename to '{}'", self.new_name)
+        let verb = match self
+            .changes
+            .first()
+            .map(|change| (&change.old_value, &change.new_value))
+        {
+            Some((None, _)) => "Add",
+            Some((Some(_), None)) => "Remove",
+            _ => "Edit",
+        };
+        match self.changes.len() {
+            1 => format!("{verb} {}", self.name),
+            count => format!("{verb} {} of {count} entities", self.name),
+        }
+    }
+
+    fn can_merge_with(&self, other: &dyn EditorCommand) -> bool {
+        let Some(other) = other
+            .as_any()
+            .and_then(|any| any.downcast_ref::<ReflectComponentCommand>())
+        else {
+            return false;
+        };
+        self.is_edit()
+            && other.is_edit()
+            && self.component == other.component
+            && self.changes.len() == other.changes.len()
+            && self
+                .changes
+                .iter()
+                .zip(&other.changes)
+                .all(|(a, b)| a.entity == b.entity)
+            && other.edited_at.saturating_duration_since(self.edited_at) <= FIELD_EDIT_MERGE_WINDOW
+    }
+
+    fn merge(&mut self, other: Box<dyn EditorCommand>) {
+        let Some(other) = other
+            .as_any()
+            .and_then(|any| any.downcast_ref::<ReflectComponentCommand>())
+        else {
+            return;
+        };
+        for (change, other) in self.changes.iter_mut().zip(&other.changes) {
+            if let Some(Ok(new_value)) = other.new_value.as_ref().map(|value| value.reflect_clone())
+            {
+                change.new_value = Some(new_value);
+            }
+        }
+        self.edited_at = other.edited_at;
+    }
+
+    fn as_any(&self) -> Option<&dyn std::any::Any> {
+        Some(self)
+    }
+}
+
+// Bevy is built without its `serialize` feature, so transforms are written
+// through a plain mirror of their fields.
+
+mod transform_serde {
+    use bevy::prelude::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct TransformData {
+        translation: [f32; 3],
+        rotation: [f32; 4],
+        scale: [f32; 3],
+    }
+
+    pub fn serialize<S: Serializer>(
+        transform: &Transform,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        TransformData {
+            translation: transform.translation.to_array(),
+            rotation: transform.rotation.to_array(),
+            scale: transform.scale.to_array(),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Transform, D::Error> {
+        let data = TransformData::deserialize(deserializer)?;
+        Ok(Transform {
+            translation: Vec3::from_array(data.translation),
+            rotation: Quat::from_array(data.rotation),
+            scale: Vec3::from_array(data.scale),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inspector_panel::{apply_inspector_transform_edits, InspectorTransformEdit};
+    use crate::scene_tree_panel::handle_scene_tree_commands;
+    use bevy_editor_commands::{
+        flush_command_journal, replay_in_group, CommandRegistry, EditorHistory, MacroRecorder,
+    };
+    use bevy_editor_frontend_api::scene_tree::SceneTreeCommand;
+    use bevy_editor_scene::{EditorSceneEntity, SceneEditorPlugin};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(SceneEditorPlugin)
+            .register_type::<ChildOf>()
+            .register_type::<Children>()
+            .init_resource::<EditorHistory>()
+            .init_resource::<Selection>();
+        app.world_mut().insert_resource(Assets::<Image>::default());
+        app.update();
+        app
+    }
+
+    /// An app running the scene tree and inspector edits, with a macro recorder
+    fn editing_app() -> App {
+        let mut app = app();
+        app.add_event::<SceneTreeCommand>()
+            .add_event::<InspectorTransformEdit>()
+            .init_resource::<MacroRecorder>()
+            .init_resource::<CommandRegistry>()
+            .add_systems(
+                Update,
+                (
+                    handle_scene_tree_commands,
+                    apply_inspector_transform_edits,
+                    flush_command_journal,
+                )
+                    .chain(),
+            );
+        app.world_mut()
+            .resource_mut::<CommandRegistry>()
+            .register::<CreateEntityCommand>()
+            .register::<RenameEntityCommand>()
+            .register::<TransformEntitiesCommand>();
+        app
+    }
+
+    fn execute(world: &mut World, command: impl EditorCommand + 'static) {
+        world.resource_scope(|world, mut history: Mut<EditorHistory>| {
+            history.execute(Box::new(command), world);
+        });
+    }
+
+    fn undo(world: &mut World) {
+        world.resource_scope(|world, mut history: Mut<EditorHistory>| {
+            history.undo(world);
+        });
+    }
+
+    #[test]
+    fn undoing_a_delete_restores_the_subtree() {
+        let mut app = app();
+        let world = app.world_mut();
+        let parent_id = EditorEntityId::new();
+        let parent = world
+            .spawn((
+                Name::new("Parent"),
+                Transform::from_xyz(4.0, 0.0, 0.0),
+                Visibility::Hidden,
+                EditorSceneEntity,
+                parent_id,
+            ))
+            .id();
+        let child_id = EditorEntityId::new();
+        world.spawn((
+            Name::new("Child"),
+            Transform::default(),
+            EditorSceneEntity,
+            child_id,
+            ChildOf(parent),
+        ));
+
+        execute(world, DeleteEntityCommand::new(parent_id));
+        assert!(find_entity_by_id(world, parent_id).is_none());
+        assert!(find_entity_by_id(world, child_id).is_none());
+
+        undo(world);
+        let parent = find_entity_by_id(world, parent_id).unwrap();
+        let child = find_entity_by_id(world, child_id).unwrap();
+        assert_eq!(world.get::<ChildOf>(child).unwrap().parent(), parent);
+        assert_eq!(world.get::<Visibility>(parent), Some(&Visibility::Hidden));
+        assert_eq!(world.get::<Name>(child).unwrap().as_str(), "Child");
+    }
+
+    #[test]
+    fn recorded_macro_replays_onto_a_fresh_scene() {
+        let mut app = editing_app();
+        app.world_mut().resource_mut::<MacroRecorder>().start();
+
+        app.world_mut()
+            .send_event(SceneTreeCommand::AddTemplateEntity {
+                template: SceneEntityTemplate::Sprite,
+                parent: None,
+            });
+        app.update();
+        let entity = app.world().resource::<Selection>().primary().unwrap();
+        let id = *app.world().get::<EditorEntityId>(entity).unwrap();
+        app.world_mut().send_event(SceneTreeCommand::RenameEntity {
+            entity,
+            new_name: "Player".to_string(),
+        });
+        app.world_mut().send_event(InspectorTransformEdit {
+            transforms: vec![(entity, Transform::from_xyz(3.0, 5.0, 0.0))],
+            property: "Position",
+        });
+        app.update();
+        let entries = app.world_mut().resource_mut::<MacroRecorder>().stop();
+        assert_eq!(entries.len(), 3);
+
+        let mut fresh = editing_app();
+        let summary = replay_in_group(fresh.world_mut(), "Play Macro", &entries);
+        assert_eq!((summary.applied, summary.skipped), (3, 0));
+        let world = fresh.world_mut();
+        let entity = find_entity_by_id(world, id).unwrap();
+        assert_eq!(world.get::<Name>(entity).unwrap().as_str(), "Player");
+        assert_eq!(
+            world.get::<Transform>(entity).unwrap().translation,
+            Vec3::new(3.0, 5.0, 0.0)
+        );
     }
 }
@@ -0,0 +1,196 @@
+//! World-space bounds of scene entities, for viewport picking and outlines
+//!
+//! Bounds follow the entity's global transform, so they are rotated and
+//! scaled with it, and are placed around the sprite's anchor rather than
+//! centered on the entity.
+
+use bevy::math::Affine3A;
+use bevy::prelude::*;
+
+/// Size of entities without a sprite, or whose image hasn't loaded, so they
+/// can still be clicked
+pub const DEFAULT_ENTITY_SIZE: Vec2 = Vec2::splat(32.0);
+
+/// A rectangle in an entity's local space, placed in the world by its
+/// global transform
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityBounds {
+    transform: Affine3A,
+    rect: Rect,
+}
+
+impl EntityBounds {
+    /// Bounds of `size` around `anchor`, given as by [`bevy::sprite::Anchor::as_vec`]
+    pub fn new(transform: &GlobalTransform, size: Vec2, anchor: Vec2) -> Self {
+        let min = (Vec2::splat(-0.5) - anchor) * size;
+        Self {
+            transform: transform.affine(),
+            rect: Rect::from_corners(min, min + size),
+        }
+    }
+
+    /// Bounds of the sprite as drawn, or [`DEFAULT_ENTITY_SIZE`] without one
+    pub fn of_sprite(
+        transform: &GlobalTransform,
+        sprite: Option<&Sprite>,
+        images: &Assets<Image>,
+        atlas_layouts: &Assets<TextureAtlasLayout>,
+    ) -> Self {
+        let Some(sprite) = sprite else {
+            return Self::new(transform, DEFAULT_ENTITY_SIZE, Vec2::ZERO);
+        };
+        let size = sprite
+            .custom_size
+            .or_else(|| sprite.rect.map(|rect| rect.size()))
+            .or_else(|| {
+                sprite
+                    .texture_atlas
+                    .as_ref()
+                    .and_then(|atlas| atlas.texture_rect(atlas_layouts))
+                    .map(|rect| rect.size().as_vec2())
+            })
+            .or_else(|| images.get(&sprite.image).map(|image| image.size_f32()))
+            .unwrap_or(DEFAULT_ENTITY_SIZE);
+        Self::new(transform, size, sprite.anchor.as_vec())
+    }
+
+    /// Corners in world space, counter-clockwise from the local bottom left
+    pub fn corners(&self) -> [Vec2; 4] {
+        let Rect { min, max } = self.rect;
+        [
+            Vec2::new(min.x, min.y),
+            Vec2::new(max.x, min.y),
+            Vec2::new(max.x, max.y),
+            Vec2::new(min.x, max.y),
+        ]
+        .map(|corner| {
+            self.transform
+                .transform_point3(corner.extend(0.0))
+                .truncate()
+        })
+    }
+
+    /// The entity's origin in world space
+    pub fn origin(&self) -> Vec2 {
+        self.transform.translation.truncate()
+    }
+
+    /// Center in world space
+    pub fn center(&self) -> Vec2 {
+        self.transform
+            .transform_point3(self.rect.center().extend(0.0))
+            .truncate()
+    }
+
+    /// Lengths of the bottom and left edges in world space, the size the
+    /// sprite is drawn at
+    pub fn size(&self) -> Vec2 {
+        let [a, b, _, d] = self.corners();
+        Vec2::new(a.distance(b), a.distance(d))
+    }
+
+    /// Local size times the world scale, the sprite's size without the shear
+    /// a rotated, non-uniformly scaled parent adds
+    pub fn scaled_size(&self) -> Vec2 {
+        let (scale, _, _) = self.transform.to_scale_rotation_translation();
+        self.rect.size() * scale.truncate().abs()
+    }
+
+    /// Smallest axis-aligned world-space rectangle holding the bounds
+    pub fn aabb(&self) -> Rect {
+        self.corners()
+            .into_iter()
+            .fold(Rect::EMPTY, |rect, corner| rect.union_point(corner))
+    }
+
+    /// Area in world space
+    pub fn area(&self) -> f32 {
+        let [a, b, _, d] = self.corners();
+        (b - a).perp_dot(d - a).abs()
+    }
+
+    /// Whether the world point lies inside the bounds
+    pub fn contains(&self, point: Vec2) -> bool {
+        let inverse = self.transform.inverse();
+        if !inverse.is_finite() {
+            // Scaled to nothing
+            return false;
+        }
+        let local = inverse
+            .transform_point3(point.extend(self.transform.translation.z))
+            .truncate();
+        self.rect.contains(local)
+    }
+
+    /// Whether the bounds overlap the world-space rectangle `rect`
+    pub fn intersects(&self, rect: Rect) -> bool {
+        let corners = self.corners();
+        let rect_corners = [
+            rect.min,
+            Vec2::new(rect.max.x, rect.min.y),
+            rect.max,
+            Vec2::new(rect.min.x, rect.max.y),
+        ];
+        // Separating axis test: both shapes are convex quads, so the edge
+        // normals of each are the only axes to check
+        let axes = [
+            Vec2::X,
+            Vec2::Y,
+            (corners[1] - corners[0]).perp(),
+            (corners[3] - corners[0]).perp(),
+        ];
+        axes.into_iter()
+            .filter(|axis| *axis != Vec2::ZERO)
+            .all(|axis| {
+                let (min_a, max_a) = project(&corners, axis);
+                let (min_b, max_b) = project(&rect_corners, axis);
+                min_a <= max_b && min_b <= max_a
+            })
+    }
+}
+
+fn project(points: &[Vec2; 4], axis: Vec2) -> (f32, f32) {
+    points
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(min, max), point| {
+            let distance = point.dot(axis);
+            (min.min(distance), max.max(distance))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_4;
+
+    #[test]
+    fn bounds_follow_anchor_scale_and_rotation() {
+        // Anchored at the bottom left and scaled up, so the sprite covers (0, 0) to (20, 10)
+        let transform = GlobalTransform::from(Transform::from_scale(Vec3::new(2.0, 1.0, 1.0)));
+        let bounds = EntityBounds::new(&transform, Vec2::splat(10.0), Vec2::splat(-0.5));
+        assert!(bounds.contains(Vec2::new(19.0, 9.0)));
+        assert!(!bounds.contains(Vec2::new(-1.0, 5.0)));
+        assert_eq!(bounds.center(), Vec2::new(10.0, 5.0));
+        assert!((bounds.area() - 200.0).abs() < 1e-3);
+
+        // A diamond reaches further along the axes than its square did
+        let transform =
+            GlobalTransform::from(Transform::from_rotation(Quat::from_rotation_z(FRAC_PI_4)));
+        let bounds = EntityBounds::new(&transform, Vec2::splat(10.0), Vec2::ZERO);
+        assert!(bounds.contains(Vec2::new(6.5, 0.0)));
+        assert!(!bounds.contains(Vec2::new(4.5, 4.5)));
+    }
+
+    #[test]
+    fn intersection_uses_rotated_edges() {
+        let transform =
+            GlobalTransform::from(Transform::from_rotation(Quat::from_rotation_z(FRAC_PI_4)));
+        let bounds = EntityBounds::new(&transform, Vec2::splat(10.0), Vec2::ZERO);
+        // Overlaps the diamond's tip
+        assert!(bounds.intersects(Rect::new(6.0, -1.0, 10.0, 1.0)));
+        // Inside the square's corner but outside the diamond
+        assert!(!bounds.intersects(Rect::new(4.0, 4.0, 5.0, 5.0)));
+        // Enclosing the whole entity
+        assert!(bounds.intersects(Rect::new(-20.0, -20.0, 20.0, 20.0)));
+    }
+}
@@ -0,0 +1,627 @@
+//! Custom entity fields from the project's entity definitions
+//!
+//! The inspector edits [`EntityFields`] through undoable commands. `EntityRef`
+//! fields are assigned by picking the target in the viewport, links are drawn
+//! as arrows, and references left dangling by a delete are reported.
+
+use bevy::asset::uuid::Uuid;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_editor_commands::EditorHistory;
+use bevy_editor_formats::{
+    check_entity_refs, EntityFields, FieldType, FieldValue, ReferenceIssue, ReferenceProblem,
+};
+use bevy_editor_foundation::Selection;
+use bevy_editor_project::CurrentProject;
+use bevy_editor_scene::{EditorEntityId, EditorEntityIndex};
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::editor_commands::SetEntityFieldsCommand;
+use crate::icons::Icons;
+
+/// The `EntityRef` field waiting for an entity to be clicked in the viewport
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickTarget {
+    pub entity: Entity,
+    pub field: String,
+    /// Element index when the field is an array of references
+    pub index: Option<usize>,
+}
+
+/// Viewport pick mode for `EntityRef` fields. While a target is set, clicking
+/// an entity in the viewport assigns it to the field instead of selecting it.
+#[derive(Resource, Default)]
+pub struct EntityPickState {
+    target: Option<PickTarget>,
+    picked: Option<Entity>,
+}
+
+impl EntityPickState {
+    pub fn start(&mut self, target: PickTarget) {
+        self.target = Some(target);
+        self.picked = None;
+    }
+
+    pub fn cancel(&mut self) {
+        self.target = None;
+        self.picked = None;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.target.is_some()
+    }
+
+    pub fn target(&self) -> Option<&PickTarget> {
+        self.target.as_ref()
+    }
+
+    /// Called by the viewport when an entity is clicked in pick mode
+    pub fn pick(&mut self, entity: Entity) {
+        if self.target.is_some() {
+            self.picked = Some(entity);
+        }
+    }
+
+    fn take(&mut self) -> Option<(PickTarget, Entity)> {
+        let picked = self.picked.take()?;
+        Some((self.target.take()?, picked))
+    }
+}
+
+/// Request to replace an entity's custom fields, applied as an undoable command
+#[derive(Event, Debug, Clone)]
+pub struct EntityFieldsEdit {
+    pub entity: Entity,
+    pub fields: Option<EntityFields>,
+    /// The single field edited, if any; consecutive edits of one field merge
+    pub field: Option<String>,
+}
+
+/// `EntityRef` problems in the current scene
+#[derive(Resource, Default)]
+pub struct EntityReferenceIssues {
+    pub issues: Vec<ReferenceIssue>,
+    /// References whose target was deleted, kept until dismissed or resolved
+    pub dangling: Vec<ReferenceIssue>,
+}
+
+impl EntityReferenceIssues {
+    /// Issues of one field of `source`
+    pub fn for_field<'a>(
+        &'a self,
+        source: EditorEntityId,
+        field: &'a str,
+    ) -> impl Iterator<Item = &'a ReferenceIssue> + 'a {
+        self.issues
+            .iter()
+            .filter(move |issue| issue.source == source.0 && issue.field == field)
+    }
+}
+
+/// Apply [`EntityFieldsEdit`] events and viewport picks through the history
+pub fn apply_entity_field_edits(world: &mut World) {
+    if world
+        .get_resource::<ButtonInput<KeyCode>>()
+        .is_some_and(|keys| keys.just_pressed(KeyCode::Escape))
+    {
+        world.resource_mut::<EntityPickState>().cancel();
+    }
+
+    let mut edits: Vec<EntityFieldsEdit> = world
+        .resource_mut::<Events<EntityFieldsEdit>>()
+        .drain()
+        .collect();
+    let picked = world.resource_mut::<EntityPickState>().take();
+    if let Some(edit) = picked.and_then(|(target, entity)| picked_edit(world, target, entity)) {
+        edits.push(edit);
+    }
+
+    if edits.is_empty() || !world.contains_resource::<EditorHistory>() {
+        return;
+    }
+
+    world.resource_scope(|world, mut history: Mut<EditorHistory>| {
+        for edit in edits {
+            let Some(&id) = world.get::<EditorEntityId>(edit.entity) else {
+                continue;
+            };
+            let old_fields = world.get::<EntityFields>(edit.entity).cloned();
+            if old_fields == edit.fields {
+                continue;
+            }
+            history.execute(
+                Box::new(SetEntityFieldsCommand::new(
+                    id,
+                    old_fields,
+                    edit.fields,
+                    edit.field,
+                )),
+                world,
+            );
+        }
+    });
+}
+
+/// The edit assigning `picked` to the field described by `target`
+fn picked_edit(world: &World, target: PickTarget, picked: Entity) -> Option<EntityFieldsEdit> {
+    let picked_id = *world.get::<EditorEntityId>(picked)?;
+    let mut fields = world.get::<EntityFields>(target.entity)?.clone();
+    let value = fields.values.get_mut(&target.field)?;
+    let slot = match (value, target.index) {
+        (FieldValue::Array(values), Some(index)) => values.get_mut(index)?,
+        (value, _) => value,
+    };
+    *slot = FieldValue::EntityRef(Some(picked_id.0));
+    Some(EntityFieldsEdit {
+        entity: target.entity,
+        fields: Some(fields),
+        field: Some(target.field),
+    })
+}
+
+/// Re-validate `EntityRef` fields when fields, entities or definitions change.
+/// References whose target existed on the previous check are reported as
+/// dangling, so loading a scene with broken links doesn't raise the report.
+pub fn validate_entity_references(
+    project: Option<Res<CurrentProject>>,
+    index: Res<EditorEntityIndex>,
+    sources: Query<(&EditorEntityId, Ref<EntityFields>)>,
+    targets: Query<Option<&EntityFields>>,
+    mut removed: RemovedComponents<EntityFields>,
+    mut issues: ResMut<EntityReferenceIssues>,
+    mut known_ids: Local<HashSet<Uuid>>,
+) {
+    let fields_removed = removed.read().count() > 0;
+    let fields_changed = fields_removed || sources.iter().any(|(_, fields)| fields.is_changed());
+    let project_changed = project.as_ref().is_some_and(|project| project.is_changed());
+    if !(fields_changed || project_changed || index.is_changed()) {
+        return;
+    }
+
+    let definitions = project
+        .as_ref()
+        .map(|project| project.metadata.config.entity_definitions.as_slice())
+        .unwrap_or_default();
+    let target_definition = |id: Uuid| {
+        let entity = index.get(EditorEntityId(id))?;
+        Some(
+            targets
+                .get(entity)
+                .ok()
+                .flatten()
+                .map(|fields| fields.definition_id),
+        )
+    };
+    let current: Vec<ReferenceIssue> = sources
+        .iter()
+        .flat_map(|(id, fields)| {
+            definitions
+                .iter()
+                .find(|definition| definition.id == fields.definition_id)
+                .map(|definition| {
+                    check_entity_refs(id.0, definition, &fields.values, target_definition)
+                })
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let issues = &mut *issues;
+    for issue in &current {
+        let deleted =
+            issue.problem == ReferenceProblem::Missing && known_ids.contains(&issue.target);
+        if deleted && !issues.dangling.contains(issue) {
+            warn!(
+                "Field '{}' of entity {} points at deleted entity {}",
+                issue.field, issue.source, issue.target
+            );
+            issues.dangling.push(issue.clone());
+        }
+    }
+    // Undoing the delete or reassigning the field resolves the report
+    issues.dangling.retain(|issue| current.contains(issue));
+    issues.issues = current;
+
+    *known_ids = index.iter().map(|(id, _)| id.0).collect();
+}
+
+/// Window listing references left dangling by deleted entities
+pub fn dangling_references_ui(
+    mut contexts: EguiContexts,
+    mut issues: ResMut<EntityReferenceIssues>,
+    mut selection: ResMut<Selection>,
+    index: Res<EditorEntityIndex>,
+    names: Query<&Name>,
+) {
+    if issues.dangling.is_empty() {
+        return;
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut dismiss = false;
+    egui::Window::new(format!("{} Dangling References", Icons::WARNING))
+        .collapsible(true)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label("These fields point at entities that were deleted:");
+            for issue in &issues.dangling {
+                let source = index.get(EditorEntityId(issue.source));
+                let source_name = source
+                    .and_then(|entity| names.get(entity).ok())
+                    .map_or_else(|| issue.source.to_string(), |name| name.to_string());
+                ui.horizontal(|ui| {
+                    ui.label(format!("'{}' . {}", source_name, issue.field));
+                    if let Some(source) = source {
+                        if ui.small_button("Select").clicked() {
+                            selection.select(source);
+                        }
+                    }
+                });
+            }
+            if ui.button("Dismiss").clicked() {
+                dismiss = true;
+            }
+        });
+
+    if dismiss {
+        issues.dangling.clear();
+    }
+}
+
+/// Draw an arrow from every entity to the entities its `EntityRef` fields point at
+pub fn draw_entity_reference_arrows(
+    mut gizmos: Gizmos,
+    selection: Res<Selection>,
+    index: Res<EditorEntityIndex>,
+    sources: Query<(Entity, &GlobalTransform, &EntityFields)>,
+    transforms: Query<&GlobalTransform>,
+) {
+    for (entity, transform, fields) in sources.iter() {
+        let start = transform.translation().truncate();
+        let color = if selection.is_selected(entity) {
+            Color::srgb(0.3, 0.9, 1.0)
+        } else {
+            Color::srgba(0.3, 0.9, 1.0, 0.35)
+        };
+        for target in fields.values.values().flat_map(FieldValue::entity_refs) {
+            let Some(end) = index
+                .get(EditorEntityId(target))
+                .and_then(|target| transforms.get(target).ok())
+            else {
+                continue;
+            };
+            let end = end.translation().truncate();
+            if start.distance(end) > f32::EPSILON {
+                gizmos.arrow_2d(start, end, color);
+            }
+        }
+    }
+}
+
+type FieldEntities<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static Name>,
+        Option<&'static EditorEntityId>,
+        Option<&'static EntityFields>,
+    ),
+>;
+
+/// Inspector section for [`EntityFields`]
+#[derive(SystemParam)]
+pub struct EntityFieldsInspector<'w, 's> {
+    project: Option<Res<'w, CurrentProject>>,
+    index: Res<'w, EditorEntityIndex>,
+    issues: Res<'w, EntityReferenceIssues>,
+    pick: ResMut<'w, EntityPickState>,
+    edits: EventWriter<'w, EntityFieldsEdit>,
+    entities: FieldEntities<'w, 's>,
+}
+
+impl EntityFieldsInspector<'_, '_> {
+    /// Show the custom fields of `entity`, or a menu to attach them
+    pub fn show(&mut self, ui: &mut egui::Ui, entity: Entity) {
+        let Self {
+            project,
+            index,
+            issues,
+            pick,
+            edits,
+            entities,
+        } = self;
+        let (index, entities) = (&**index, &*entities);
+        let Ok((_, Some(&id), fields)) = entities.get(entity) else {
+            return;
+        };
+        let definitions = project
+            .as_ref()
+            .map(|project| project.metadata.config.entity_definitions.as_slice())
+            .unwrap_or_default();
+        let assets_path = project
+            .as_ref()
+            .map(|project| project.metadata.assets_path.as_path());
+
+        let Some(fields) = fields else {
+            if !definitions.is_empty() {
+                ui.menu_button(format!("{} Add Entity Fields", Icons::NEW), |ui| {
+                    for definition in definitions {
+                        if ui.button(&definition.identifier).clicked() {
+                            edits.write(EntityFieldsEdit {
+                                entity,
+                                fields: Some(EntityFields::from_definition(definition)),
+                                field: None,
+                            });
+                            ui.close_menu();
+                        }
+                    }
+                });
+            }
+            return;
+        };
+
+        let definition = definitions
+            .iter()
+            .find(|definition| definition.id == fields.definition_id);
+        let title = definition.map_or_else(
+            || format!("Fields (definition {})", fields.definition_id),
+            |definition| format!("{} Fields", definition.identifier),
+        );
+
+        egui::CollapsingHeader::new(title)
+            .default_open(true)
+            .show(ui, |ui| {
+                if let Some(definition) = definition {
+                    let mut edited = fields.clone();
+                    for field in &definition.field_definitions {
+                        let value = edited
+                            .values
+                            .entry(field.name.clone())
+                            .or_insert_with(|| field.default_value.clone());
+                        let mut context = FieldContext {
+                            entity,
+                            field: &field.name,
+                            pick,
+                            index,
+                            entities,
+                            assets_path,
+                        };
+                        let changed = ui
+                            .horizontal(|ui| {
+                                let label = ui.label(&field.name);
+                                if let Some(description) = &field.description {
+                                    label.on_hover_text(description);
+                                }
+                                edit_field_value(ui, &field.field_type, value, &mut context, None)
+                            })
+                            .inner;
+                        for issue in issues.for_field(id, &field.name) {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(230, 90, 90),
+                                describe_issue(issue),
+                            );
+                        }
+                        if changed {
+                            edits.write(EntityFieldsEdit {
+                                entity,
+                                fields: Some(edited.clone()),
+                                field: Some(field.name.clone()),
+                            });
+                        }
+                    }
+                } else {
+                    ui.label("Definition not found in the project");
+                }
+
+                if ui.button("Remove Fields").clicked() {
+                    edits.write(EntityFieldsEdit {
+                        entity,
+                        fields: None,
+                        field: None,
+                    });
+                }
+            });
+    }
+}
+
+/// What a field widget needs besides the value itself
+struct FieldContext<'a, 'w, 's> {
+    entity: Entity,
+    field: &'a str,
+    pick: &'a mut EntityPickState,
+    index: &'a EditorEntityIndex,
+    entities: &'a FieldEntities<'w, 's>,
+    assets_path: Option<&'a Path>,
+}
+
+/// Widget for one field value; returns whether the value changed
+fn edit_field_value(
+    ui: &mut egui::Ui,
+    field_type: &FieldType,
+    value: &mut FieldValue,
+    context: &mut FieldContext,
+    element: Option<usize>,
+) -> bool {
+    match (field_type, value) {
+        (FieldType::Int { min, max }, FieldValue::Int(value)) => ui
+            .add(
+                egui::DragValue::new(value)
+                    .range(min.unwrap_or(i32::MIN)..=max.unwrap_or(i32::MAX)),
+            )
+            .changed(),
+        (FieldType::Float { min, max }, FieldValue::Float(value)) => ui
+            .add(
+                egui::DragValue::new(value)
+                    .speed(0.1)
+                    .range(min.unwrap_or(f32::MIN)..=max.unwrap_or(f32::MAX)),
+            )
+            .changed(),
+        (FieldType::String { max_length }, FieldValue::String(text)) => {
+            let changed = ui.text_edit_singleline(text).changed();
+            if let Some(max_length) = *max_length {
+                if text.chars().count() > max_length {
+                    *text = text.chars().take(max_length).collect();
+                }
+            }
+            changed
+        }
+        (FieldType::Bool, FieldValue::Bool(value)) => ui.checkbox(value, "").changed(),
+        (FieldType::Enum { .. }, value @ FieldValue::Null) => {
+            if ui.small_button("Set").clicked() {
+                *value = FieldValue::Enum(String::new());
+                return true;
+            }
+            false
+        }
+        (FieldType::Enum { .. }, FieldValue::Enum(text))
+        | (FieldType::Color, FieldValue::Color(text)) => ui.text_edit_singleline(text).changed(),
+        (FieldType::Point, FieldValue::Point { x, y }) => {
+            let x_changed = ui.add(egui::DragValue::new(x).prefix("x: ")).changed();
+            let y_changed = ui.add(egui::DragValue::new(y).prefix("y: ")).changed();
+            x_changed || y_changed
+        }
+        (FieldType::Array { element_type }, FieldValue::Array(values)) => {
+            edit_array(ui, element_type, values, context)
+        }
+        (FieldType::EntityRef { .. }, FieldValue::EntityRef(target)) => {
+            edit_entity_ref(ui, target, context, element)
+        }
+        (FieldType::AssetPath { extensions }, FieldValue::AssetPath(path)) => {
+            let mut changed = ui.text_edit_singleline(path).changed();
+            if ui.small_button(Icons::FOLDER_OPEN).clicked() {
+                if let Some(picked) = pick_asset_path(extensions, context.assets_path) {
+                    *path = picked;
+                    changed = true;
+                }
+            }
+            if !path.is_empty() && !field_type.accepts_asset_path(path) {
+                ui.colored_label(
+                    egui::Color32::from_rgb(230, 90, 90),
+                    format!("expected .{}", extensions.join(", .")),
+                );
+            }
+            changed
+        }
+        // The definition changed since the value was stored
+        (field_type, value) => {
+            ui.label(format!("{:?}", value));
+            if ui.small_button("Reset").clicked() {
+                *value = FieldValue::default_for_type(field_type);
+                return true;
+            }
+            false
+        }
+    }
+}
+
+fn edit_array(
+    ui: &mut egui::Ui,
+    element_type: &FieldType,
+    values: &mut Vec<FieldValue>,
+    context: &mut FieldContext,
+) -> bool {
+    let mut changed = false;
+    let mut remove = None;
+    ui.vertical(|ui| {
+        for (index, value) in values.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                changed |= edit_field_value(ui, element_type, value, context, Some(index));
+                if ui.small_button(Icons::CLOSE).clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        if ui.small_button(Icons::NEW).clicked() {
+            values.push(FieldValue::default_for_type(element_type));
+            changed = true;
+        }
+    });
+    if let Some(index) = remove {
+        values.remove(index);
+        // A pick aimed at a shifted element would land in the wrong slot
+        context.pick.cancel();
+        changed = true;
+    }
+    changed
+}
+
+fn edit_entity_ref(
+    ui: &mut egui::Ui,
+    target: &mut Option<Uuid>,
+    context: &mut FieldContext,
+    element: Option<usize>,
+) -> bool {
+    let label = match *target {
+        None => "None".to_string(),
+        Some(id) => match context.index.get(EditorEntityId(id)) {
+            Some(entity) => context
+                .entities
+                .get(entity)
+                .ok()
+                .and_then(|(name, _, _)| name)
+                .map_or_else(|| format!("{:?}", entity), |name| name.to_string()),
+            None => format!("{} Missing", Icons::WARNING),
+        },
+    };
+    ui.label(label);
+
+    let pick_target = PickTarget {
+        entity: context.entity,
+        field: context.field.to_string(),
+        index: element,
+    };
+    let picking = context.pick.target() == Some(&pick_target);
+    if ui
+        .selectable_label(picking, "Pick")
+        .on_hover_text("Click an entity in the viewport (Esc cancels)")
+        .clicked()
+    {
+        if picking {
+            context.pick.cancel();
+        } else {
+            context.pick.start(pick_target);
+        }
+    }
+
+    if target.is_some() && ui.small_button(Icons::CLOSE).clicked() {
+        *target = None;
+        return true;
+    }
+    false
+}
+
+/// Pick a file below the assets folder, returning its path relative to it
+fn pick_asset_path(extensions: &[String], assets_path: Option<&Path>) -> Option<String> {
+    let mut dialog = rfd::FileDialog::new();
+    if let Some(assets_path) = assets_path {
+        dialog = dialog.set_directory(assets_path);
+    }
+    if !extensions.is_empty() {
+        dialog = dialog.add_filter("Asset", extensions);
+    }
+    let path = dialog.pick_file()?;
+    let relative = match assets_path {
+        Some(assets_path) => match path.strip_prefix(assets_path) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => {
+                warn!("{} is outside the project assets folder", path.display());
+                return None;
+            }
+        },
+        None => path,
+    };
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
+
+fn describe_issue(issue: &ReferenceIssue) -> String {
+    match issue.problem {
+        ReferenceProblem::Missing => format!("{} Target no longer exists", Icons::WARNING),
+        ReferenceProblem::WrongDefinition { .. } => {
+            format!("{} Target has the wrong entity definition", Icons::WARNING)
+        }
+    }
+}
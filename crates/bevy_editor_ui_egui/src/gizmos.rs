@@ -1,10 +1,12 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
-use bevy_editor_core::{GizmoMode, GizmoState};
-use bevy_editor_foundation::{EditorState, EditorTool};
-use bevy_editor_scene::{EditorScene, EditorSceneEntity};
-use bevy_editor_tilemap::{PaintMode, TilePainter};
+use crate::entity_bounds::EntityBounds;
+use crate::viewport_selection::{GizmoDragState, MarqueeSelection, SelectionGizmo};
+use bevy_editor_core::{GizmoMode, GizmoState, PivotMode, SnapGuide, SnapSettings};
+use bevy_editor_foundation::{EditorState, EditorTool, Selection};
+use bevy_editor_scene::EditorSceneEntity;
+use bevy_editor_tilemap::{MapOrientation, PaintMode, TileGrid, TilePainter};
 
 fn gizmo_mode_display_name(mode: GizmoMode) -> &'static str {
     match mode {
@@ -26,6 +28,7 @@ fn gizmo_mode_icon(mode: GizmoMode) -> &'static str {
 pub fn draw_grid(
     mut gizmos: Gizmos,
     editor_state: Res<EditorState>,
+    tile_grid: Res<TileGrid>,
     camera_q: Query<&Transform, With<Camera2d>>,
 ) {
     if !editor_state.grid_snap_enabled {
@@ -42,6 +45,15 @@ pub fn draw_grid(
     // Draw grid lines in view
     let grid_extent = 2000.0; // How far to draw grid
 
+    // Isometric and hex layers get their cell outlines instead of square lines
+    // while painting, so the grid matches where tiles will land.
+    if editor_state.current_tool == EditorTool::Platform
+        && tile_grid.orientation != MapOrientation::Square
+    {
+        draw_tile_grid(&mut gizmos, &tile_grid, camera_pos, grid_extent);
+        return;
+    }
+
     // Vertical lines
     let start_x = ((camera_pos.x - grid_extent) / grid_size).floor() * grid_size;
     let end_x = camera_pos.x + grid_extent;
@@ -81,72 +93,119 @@ pub fn draw_grid(
     );
 }
 
-/// Draw selection highlights and move handles
+/// Draw the cell outlines of a non-square tile grid around the camera
+fn draw_tile_grid(gizmos: &mut Gizmos, tile_grid: &TileGrid, camera_pos: Vec2, extent: f32) {
+    let color = Color::srgba(1.0, 1.0, 1.0, 0.1);
+    for y in 0..tile_grid.map_size.y {
+        for x in 0..tile_grid.map_size.x {
+            let center = tile_grid.tile_center(x, y);
+            if (center - camera_pos).abs().max_element() > extent {
+                continue;
+            }
+            let corners = tile_grid.tile_outline(x, y);
+            for i in 0..corners.len() {
+                gizmos.line_2d(corners[i], corners[(i + 1) % corners.len()], color);
+            }
+        }
+    }
+}
+
+/// Draw the box being dragged to select entities
+pub fn draw_marquee_selection(mut gizmos: Gizmos, marquee: Res<MarqueeSelection>) {
+    if let Some(rect) = marquee.rect() {
+        gizmos.rect_2d(
+            Isometry2d::from_translation(rect.center()),
+            rect.size(),
+            Color::srgb(0.3, 0.7, 1.0),
+        );
+    }
+}
+
+/// Draw what the current gizmo drag snapped to
+pub fn draw_snap_guides(mut gizmos: Gizmos, drag_state: Res<GizmoDragState>) {
+    let color = Color::srgb(0.2, 1.0, 0.9);
+    for guide in &drag_state.guides {
+        match *guide {
+            SnapGuide::Line { from, to } => gizmos.line_2d(from, to, color),
+            SnapGuide::Point(point) => {
+                gizmos.circle_2d(point, 3.0, color);
+                draw_cross(&mut gizmos, point, 6.0, color);
+            }
+        }
+    }
+}
+
+/// Draw selection outlines, the gizmo handles of the selection and its pivot
 pub fn draw_selection_gizmos(
     mut gizmos: Gizmos,
-    editor_scene: Res<EditorScene>,
-    scene_entities: Query<
-        (&Transform, Option<&Sprite>, Option<&Node>, Option<&Text>),
-        With<EditorSceneEntity>,
-    >,
+    selection: Res<Selection>,
+    scene_entities: Query<(&GlobalTransform, Option<&Sprite>), With<EditorSceneEntity>>,
     images: Res<Assets<Image>>,
+    atlas_layouts: Res<Assets<TextureAtlasLayout>>,
     gizmo_state: Res<GizmoState>,
 ) {
-    // Draw gizmos for new scene editor entities
-    if let Some(selected_entity) = editor_scene.selected_entity {
-        if let Ok((transform, sprite, node, text)) = scene_entities.get(selected_entity) {
-            let pos = transform.translation.xy();
-            let scale = transform.scale.xy();
-
-            // Calculate bounds based on component type
-            let bounds = if let Some(sprite) = sprite {
-                // Get sprite size from custom_size or texture dimensions
-                let base_size = if let Some(custom_size) = sprite.custom_size {
-                    custom_size
-                } else if let Some(image) = images.get(&sprite.image) {
-                    image.size().as_vec2()
-                } else {
-                    // Default size for sprites without texture
-                    Vec2::new(64.0, 64.0)
-                };
-
-                // Apply scale to base size
-                base_size * scale
-            } else if let Some(_node) = node {
-                // UI Node size - use fixed size for now
-                Vec2::new(100.0, 100.0) * scale
-            } else if let Some(_text) = text {
-                // Text size - use fixed size for now
-                Vec2::new(100.0, 32.0) * scale
-            } else {
-                // Default bounds for empty entities
-                Vec2::new(32.0, 32.0) * scale
-            };
+    let pivot_color = Color::srgb(1.0, 0.3, 0.8);
+    if gizmo_state.pivot == PivotMode::Cursor {
+        gizmos.circle_2d(gizmo_state.cursor, 6.0, pivot_color);
+        draw_cross(&mut gizmos, gizmo_state.cursor, 10.0, pivot_color);
+    }
 
-            // Draw bounds rectangle
-            let half_size = bounds / 2.0;
-            let corners = [
-                pos + Vec2::new(-half_size.x, -half_size.y),
-                pos + Vec2::new(half_size.x, -half_size.y),
-                pos + Vec2::new(half_size.x, half_size.y),
-                pos + Vec2::new(-half_size.x, half_size.y),
-            ];
+    let bounds = |entity: Entity| {
+        let (transform, sprite) = scene_entities.get(entity).ok()?;
+        Some(EntityBounds::of_sprite(
+            transform,
+            sprite,
+            &images,
+            &atlas_layouts,
+        ))
+    };
 
-            // Draw selection rectangle
-            for i in 0..4 {
-                gizmos.line_2d(corners[i], corners[(i + 1) % 4], Color::srgb(1.0, 1.0, 0.0));
-            }
+    // Outline the bounds as drawn, following anchor and rotation; the
+    // primary entity is brighter
+    for selected_entity in selection.iter() {
+        let Some(corners) = bounds(selected_entity).map(|bounds| bounds.corners()) else {
+            continue;
+        };
+        let color = if selection.primary() == Some(selected_entity) {
+            Color::srgb(1.0, 1.0, 0.0)
+        } else {
+            Color::srgb(1.0, 0.6, 0.0)
+        };
+        for i in 0..4 {
+            gizmos.line_2d(corners[i], corners[(i + 1) % 4], color);
+        }
+    }
 
-            // Draw appropriate gizmo handles based on mode
-            match gizmo_state.mode {
-                GizmoMode::Move => draw_move_handles(&mut gizmos, pos),
-                GizmoMode::Rotate => draw_rotation_handles(&mut gizmos, pos, bounds),
-                GizmoMode::Scale => draw_scale_handles(&mut gizmos, pos, bounds),
-            }
+    let Some(gizmo) = SelectionGizmo::new(&selection, &gizmo_state, bounds) else {
+        return;
+    };
+    if selection.len() > 1 {
+        // Box around the whole selection
+        gizmos.rect_2d(
+            Isometry2d::from_translation(gizmo.center),
+            gizmo.size + Vec2::splat(4.0),
+            Color::srgba(1.0, 0.6, 0.0, 0.4),
+        );
+    }
+
+    // Draw appropriate gizmo handles based on mode
+    match gizmo_state.mode {
+        GizmoMode::Move => draw_move_handles(&mut gizmos, gizmo.center),
+        GizmoMode::Rotate => draw_rotation_handles(&mut gizmos, gizmo.center, gizmo.size),
+        GizmoMode::Scale => draw_scale_handles(&mut gizmos, gizmo.center, gizmo.size),
+    }
+    if gizmo_state.mode != GizmoMode::Move && gizmo_state.pivot != PivotMode::Cursor {
+        if let Some(pivot) = gizmo.pivot {
+            draw_cross(&mut gizmos, pivot, 6.0, pivot_color);
         }
     }
 }
 
+fn draw_cross(gizmos: &mut Gizmos, position: Vec2, size: f32, color: Color) {
+    gizmos.line_2d(position - Vec2::X * size, position + Vec2::X * size, color);
+    gizmos.line_2d(position - Vec2::Y * size, position + Vec2::Y * size, color);
+}
+
 fn draw_move_handles(gizmos: &mut Gizmos, position: Vec2) {
     let handle_size = 8.0;
 
@@ -257,13 +316,14 @@ pub fn draw_tile_tool_preview(
     editor_state: Res<EditorState>,
     tile_painter: Res<TilePainter>,
     tileset_manager: Res<bevy_editor_tilemap::TilesetManager>,
+    tile_grid: Res<TileGrid>,
 ) {
     // Only show preview for tile painting tools
     if editor_state.current_tool != EditorTool::Platform {
         return;
     }
 
-    let grid_size = editor_state.grid_size;
+    let preview_color = Color::srgba(0.0, 1.0, 1.0, 0.5); // Cyan semi-transparent
 
     // Draw stamp preview if in stamp mode (multi-tile selection)
     // Only show when NOT actively dragging (Rectangle/Line tools use drag_start)
@@ -271,29 +331,18 @@ pub fn draw_tile_tool_preview(
         && tileset_manager.selected_tiles.len() > 1
         && tile_painter.drag_start.is_none()
     {
-        // Prevent conflict with drag tools
-
         if let Some((cursor_x, cursor_y)) = tile_painter.current_pos {
             if let Some((stamp_width, stamp_height)) = tileset_manager.get_selection_dimensions() {
-                let preview_color = Color::srgba(0.0, 1.0, 1.0, 0.5); // Cyan semi-transparent
-
                 // Draw preview for each tile in the stamp
                 for offset_y in 0..stamp_height {
                     for offset_x in 0..stamp_width {
-                        let world_x = (cursor_x + offset_x) as f32 * grid_size;
-                        let world_y = (cursor_y + offset_y) as f32 * grid_size;
-
-                        // Draw tile outline
-                        let corners = [
-                            Vec2::new(world_x, world_y),
-                            Vec2::new(world_x + grid_size, world_y),
-                            Vec2::new(world_x + grid_size, world_y + grid_size),
-                            Vec2::new(world_x, world_y + grid_size),
-                        ];
-
-                        for i in 0..4 {
-                            gizmos.line_2d(corners[i], corners[(i + 1) % 4], preview_color);
-                        }
+                        draw_tile_outline(
+                            &mut gizmos,
+                            &tile_grid,
+                            cursor_x + offset_x,
+                            cursor_y + offset_y,
+                            preview_color,
+                        );
                     }
                 }
             }
@@ -301,72 +350,43 @@ pub fn draw_tile_tool_preview(
     }
 
     // Draw rectangle/line preview ONLY when in those modes AND actively dragging
-    match tile_painter.mode {
-        PaintMode::Rectangle | PaintMode::Line => {
-            // Show preview when dragging
-            if let (Some((start_x, start_y)), Some((end_x, end_y))) =
-                (tile_painter.drag_start, tile_painter.current_pos)
-            {
-                // Convert tile coords to world coords
-                let start_world = Vec2::new(start_x as f32 * grid_size, start_y as f32 * grid_size);
-                let end_world = Vec2::new(end_x as f32 * grid_size, end_y as f32 * grid_size);
-
-                let preview_color = Color::srgba(0.0, 1.0, 1.0, 0.5); // Cyan semi-transparent
-
-                match tile_painter.mode {
-                    PaintMode::Rectangle => {
-                        // Draw rectangle preview
-                        let min_x = start_world.x.min(end_world.x);
-                        let max_x = start_world.x.max(end_world.x) + grid_size;
-                        let min_y = start_world.y.min(end_world.y);
-                        let max_y = start_world.y.max(end_world.y) + grid_size;
-
-                        // Draw filled rectangle using lines
-                        let corners = [
-                            Vec2::new(min_x, min_y),
-                            Vec2::new(max_x, min_y),
-                            Vec2::new(max_x, max_y),
-                            Vec2::new(min_x, max_y),
-                        ];
-
-                        // Draw outline
-                        for i in 0..4 {
-                            gizmos.line_2d(corners[i], corners[(i + 1) % 4], preview_color);
-                        }
+    let (Some((start_x, start_y)), Some((end_x, end_y))) =
+        (tile_painter.drag_start, tile_painter.current_pos)
+    else {
+        return;
+    };
 
-                        // Draw diagonals to show it's filled
-                        gizmos.line_2d(corners[0], corners[2], Color::srgba(0.0, 1.0, 1.0, 0.2));
-                        gizmos.line_2d(corners[1], corners[3], Color::srgba(0.0, 1.0, 1.0, 0.2));
-                    }
-                    PaintMode::Line => {
-                        // Draw line preview using Bresenham
-                        let tiles = calculate_line_tiles(start_x, start_y, end_x, end_y);
-
-                        for (tile_x, tile_y) in tiles {
-                            let world_x = tile_x as f32 * grid_size;
-                            let world_y = tile_y as f32 * grid_size;
-
-                            // Draw tile outline
-                            let corners = [
-                                Vec2::new(world_x, world_y),
-                                Vec2::new(world_x + grid_size, world_y),
-                                Vec2::new(world_x + grid_size, world_y + grid_size),
-                                Vec2::new(world_x, world_y + grid_size),
-                            ];
-
-                            for i in 0..4 {
-                                gizmos.line_2d(corners[i], corners[(i + 1) % 4], preview_color);
-                            }
-                        }
-                    }
-                    _ => {}
+    match tile_painter.mode {
+        PaintMode::Rectangle => {
+            // Outline every cell, since isometric and hex rectangles aren't axis-aligned
+            for tile_y in start_y.min(end_y)..=start_y.max(end_y) {
+                for tile_x in start_x.min(end_x)..=start_x.max(end_x) {
+                    draw_tile_outline(&mut gizmos, &tile_grid, tile_x, tile_y, preview_color);
                 }
             }
         }
+        PaintMode::Line => {
+            // Draw line preview using Bresenham
+            for (tile_x, tile_y) in calculate_line_tiles(start_x, start_y, end_x, end_y) {
+                draw_tile_outline(&mut gizmos, &tile_grid, tile_x, tile_y, preview_color);
+            }
+        }
         _ => {}
     }
 }
 
+/// Draw the outline of one tile cell, skipping cells outside the map
+fn draw_tile_outline(gizmos: &mut Gizmos, tile_grid: &TileGrid, x: u32, y: u32, color: Color) {
+    if x >= tile_grid.map_size.x || y >= tile_grid.map_size.y {
+        return;
+    }
+
+    let corners = tile_grid.tile_outline(x, y);
+    for i in 0..corners.len() {
+        gizmos.line_2d(corners[i], corners[(i + 1) % corners.len()], color);
+    }
+}
+
 /// Calculate tiles along a line using Bresenham's algorithm
 fn calculate_line_tiles(start_x: u32, start_y: u32, end_x: u32, end_y: u32) -> Vec<(u32, u32)> {
     let mut tiles = Vec::new();
@@ -404,15 +424,15 @@ fn calculate_line_tiles(start_x: u32, start_y: u32, end_x: u32, end_y: u32) -> V
 /// Draw selection highlights for EditorSceneEntity (new scene editor system)
 pub fn draw_scene_entity_gizmos(
     mut gizmos: Gizmos,
-    editor_scene: Res<EditorScene>,
+    selection: Res<Selection>,
     scene_entities: Query<
-        (&GlobalTransform, Option<&Sprite>, Option<&Name>),
+        (Entity, &GlobalTransform, Option<&Sprite>, Option<&Name>),
         With<EditorSceneEntity>,
     >,
 ) {
-    // Draw highlight for selected entity
-    if let Some(selected_entity) = editor_scene.selected_entity {
-        if let Ok((transform, sprite, name)) = scene_entities.get(selected_entity) {
+    // Draw highlight for selected entities
+    for selected_entity in selection.iter() {
+        if let Ok((_, transform, sprite, name)) = scene_entities.get(selected_entity) {
             let pos = transform.translation().truncate();
 
             // Calculate bounds based on sprite size or default
@@ -451,11 +471,11 @@ pub fn draw_scene_entity_gizmos(
     }
 
     // Draw subtle outlines for all scene entities (not selected)
-    for (transform, sprite, _) in scene_entities.iter() {
+    for (entity, transform, sprite, _) in scene_entities.iter() {
         let pos = transform.translation().truncate();
 
-        // Skip if this is the selected entity
-        if Some(Entity::PLACEHOLDER) != editor_scene.selected_entity {
+        // Skip selected entities
+        if !selection.is_selected(entity) {
             // Calculate bounds
             let size = if let Some(sprite_comp) = sprite {
                 sprite_comp.custom_size.unwrap_or(Vec2::new(32.0, 32.0))
@@ -518,7 +538,11 @@ fn draw_scene_move_handles(gizmos: &mut Gizmos, position: Vec2) {
 }
 
 /// Draw gizmo mode indicator overlay in viewport
-pub fn draw_gizmo_mode_indicator(mut contexts: EguiContexts, gizmo_state: Res<GizmoState>) {
+pub fn draw_gizmo_mode_indicator(
+    mut contexts: EguiContexts,
+    mut gizmo_state: ResMut<GizmoState>,
+    mut snap_settings: ResMut<SnapSettings>,
+) {
     let Some(ctx) = contexts.try_ctx_mut() else {
         return;
     };
@@ -570,7 +594,63 @@ pub fn draw_gizmo_mode_indicator(mut contexts: EguiContexts, gizmo_state: Res<Gi
                                 });
                             }
                         }
+
+                        // Pivot the selection rotates and scales around
+                        ui.add_space(4.0);
+                        let pivot = gizmo_state.pivot;
+                        egui::ComboBox::from_id_salt("gizmo_pivot")
+                            .selected_text(
+                                egui::RichText::new(format!("Pivot: {}", pivot.display_name()))
+                                    .size(10.0),
+                            )
+                            .show_ui(ui, |ui| {
+                                for mode in PivotMode::ALL {
+                                    ui.selectable_value(
+                                        &mut gizmo_state.pivot,
+                                        mode,
+                                        mode.display_name(),
+                                    );
+                                }
+                            })
+                            .response
+                            .on_hover_text("Shift + right click places the 2D cursor");
+
+                        ui.menu_button(egui::RichText::new("Snapping").size(10.0), |ui| {
+                            render_snap_settings(ui, &mut snap_settings);
+                        })
+                        .response
+                        .on_hover_text(
+                            "G toggles grid snapping; hold Ctrl while dragging to invert snapping",
+                        );
                     });
                 });
         });
 }
+
+fn render_snap_settings(ui: &mut egui::Ui, settings: &mut SnapSettings) {
+    ui.checkbox(&mut settings.to_entities, "Entity edges and centers");
+    ui.add_enabled(
+        settings.to_entities,
+        egui::Slider::new(&mut settings.entity_distance, 2.0..=32.0).text("Distance (px)"),
+    );
+    ui.checkbox(&mut settings.to_tiles, "Tile cells");
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut settings.rotation, "Rotation");
+        ui.add_enabled(
+            settings.rotation,
+            egui::DragValue::new(&mut settings.rotation_step)
+                .range(1.0..=180.0)
+                .suffix("°"),
+        );
+    });
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut settings.scale, "Scale");
+        ui.add_enabled(
+            settings.scale,
+            egui::DragValue::new(&mut settings.scale_step)
+                .range(0.01..=10.0)
+                .speed(0.01),
+        );
+    });
+}
@@ -0,0 +1,239 @@
+use bevy::prelude::*;
+use bevy_editor_commands::{
+    CrashRecovery, EditorHistory, HistoryBudget, HistoryNodeId, HistoryRequest, MacroRecorder,
+    MacroRequest,
+};
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+/// UI state for the undo history window
+#[derive(Resource, Default)]
+pub struct HistoryPanel {
+    pub open: bool,
+}
+
+/// UI system showing the undo tree. Clicking a node jumps to it, switching
+/// branches if needed.
+pub fn history_panel_ui(
+    mut contexts: EguiContexts,
+    mut panel: ResMut<HistoryPanel>,
+    mut history: ResMut<EditorHistory>,
+    mut requests: EventWriter<HistoryRequest>,
+    recorder: Res<MacroRecorder>,
+    mut macro_requests: EventWriter<MacroRequest>,
+) {
+    if !panel.open {
+        return;
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    egui::Window::new("History")
+        .open(&mut open)
+        .default_width(280.0)
+        .default_height(360.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(history.can_undo(), egui::Button::new("Undo"))
+                    .clicked()
+                {
+                    requests.write(HistoryRequest::Undo);
+                }
+                if ui
+                    .add_enabled(history.can_redo(), egui::Button::new("Redo"))
+                    .clicked()
+                {
+                    requests.write(HistoryRequest::Redo);
+                }
+            });
+
+            budget_controls(ui, &mut history);
+            macro_controls(ui, &recorder, &mut macro_requests);
+            ui.separator();
+
+            // Branches are indented one step from the node they fork off
+            let entries = history.entries();
+            let mut indents: HashMap<HistoryNodeId, usize> = HashMap::new();
+            let now = SystemTime::now();
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    let mut previous = None;
+                    for entry in &entries {
+                        let indent = match entry.parent {
+                            Some(parent) if previous == Some(parent) => indents[&parent],
+                            Some(parent) => indents[&parent] + 1,
+                            None => 0,
+                        };
+                        indents.insert(entry.id, indent);
+                        previous = Some(entry.id);
+
+                        ui.horizontal(|ui| {
+                            ui.add_space(indent as f32 * 12.0);
+
+                            let mut text = egui::RichText::new(&entry.description);
+                            if !entry.is_applied {
+                                text = text.weak();
+                            }
+                            let age = now
+                                .duration_since(entry.timestamp)
+                                .map(|d| format_age(d.as_secs()))
+                                .unwrap_or_default();
+
+                            if ui
+                                .selectable_label(entry.is_current, text)
+                                .on_hover_text(age)
+                                .clicked()
+                                && !entry.is_current
+                            {
+                                requests.write(HistoryRequest::JumpTo(entry.id));
+                            }
+                            if entry.branch_count > 1 {
+                                ui.weak(format!("⑂{}", entry.branch_count));
+                            }
+                        });
+                    }
+                });
+        });
+
+    if !open {
+        panel.open = false;
+    }
+}
+
+/// Limit the history by command count or by memory
+fn budget_controls(ui: &mut egui::Ui, history: &mut EditorHistory) {
+    let stats = history.stats();
+    ui.label(format!(
+        "{} commands, {:.1} KB",
+        stats.node_count,
+        stats.memory_bytes as f64 / 1024.0
+    ));
+
+    let mut budget = history.budget();
+    ui.horizontal(|ui| {
+        ui.label("Keep");
+        match &mut budget {
+            HistoryBudget::Commands(count) => {
+                ui.add(egui::DragValue::new(count).range(1..=10_000));
+            }
+            HistoryBudget::Bytes(bytes) => {
+                let mut megabytes = *bytes / (1024 * 1024);
+                if ui
+                    .add(egui::DragValue::new(&mut megabytes).range(1..=4096))
+                    .changed()
+                {
+                    *bytes = megabytes * 1024 * 1024;
+                }
+            }
+        }
+
+        egui::ComboBox::from_id_salt("history_budget_kind")
+            .selected_text(match budget {
+                HistoryBudget::Commands(_) => "commands",
+                HistoryBudget::Bytes(_) => "MB",
+            })
+            .show_ui(ui, |ui| {
+                let by_count = matches!(budget, HistoryBudget::Commands(_));
+                if ui.selectable_label(by_count, "commands").clicked() && !by_count {
+                    budget = HistoryBudget::Commands(100);
+                }
+                if ui.selectable_label(!by_count, "MB").clicked() && by_count {
+                    budget = HistoryBudget::Bytes(64 * 1024 * 1024);
+                }
+            });
+    });
+
+    if budget != history.budget() {
+        history.set_budget(budget);
+    }
+}
+
+/// Record the steps applied to the scene into a macro file, or replay one
+fn macro_controls(
+    ui: &mut egui::Ui,
+    recorder: &MacroRecorder,
+    requests: &mut EventWriter<MacroRequest>,
+) {
+    ui.horizontal(|ui| {
+        if recorder.is_recording() {
+            ui.colored_label(
+                egui::Color32::LIGHT_RED,
+                format!("Recording ({} steps)", recorder.len()),
+            );
+            if ui.button("Stop && Save...").clicked() {
+                let save_to = rfd::FileDialog::new()
+                    .add_filter("Editor Macro", &["jsonl"])
+                    .set_file_name("macro.jsonl")
+                    .save_file();
+                requests.write(MacroRequest::StopRecording { save_to });
+            }
+            if ui.button("Discard").clicked() {
+                requests.write(MacroRequest::StopRecording { save_to: None });
+            }
+        } else {
+            if ui.button("Record Macro").clicked() {
+                requests.write(MacroRequest::StartRecording);
+            }
+            if ui.button("Play Macro...").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Editor Macro", &["jsonl"])
+                    .pick_file()
+                {
+                    requests.write(MacroRequest::Play(path));
+                }
+            }
+        }
+    });
+}
+
+/// Offers to replay a journal left behind by a session that ended without
+/// saving. The replay itself waits until the scene has loaded.
+pub fn crash_recovery_prompt_ui(mut contexts: EguiContexts, mut recovery: ResMut<CrashRecovery>) {
+    let Some(pending) = recovery
+        .pending
+        .as_mut()
+        .filter(|pending| pending.decision.is_none())
+    else {
+        return;
+    };
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    egui::Window::new("Recover Unsaved Changes")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "'{}' has {} unsaved edits from a session that didn't close cleanly.",
+                pending.scene_name,
+                pending.entries.len()
+            ));
+            ui.label("Replay them onto the last saved version of the scene?");
+            ui.horizontal(|ui| {
+                if ui.button("Recover").clicked() {
+                    pending.decision = Some(true);
+                }
+                if ui.button("Discard").clicked() {
+                    pending.decision = Some(false);
+                }
+            });
+        });
+}
+
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => format!("{seconds}s ago"),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        _ => format!("{}h ago", seconds / 3600),
+    }
+}
@@ -5,6 +5,7 @@ pub struct Icons;
 impl Icons {
     // Toolbar actions
     pub const PLAY: &'static str = "�-";
+    pub const PAUSE: &'static str = "||";
     pub const BUILD: &'static str = "[B]";
     pub const STOP: &'static str = "�-�";
     pub const SAVE: &'static str = "[S]";
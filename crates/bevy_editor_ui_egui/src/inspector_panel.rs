@@ -1,20 +1,131 @@
 //! Inspector panel for viewing and editing entity components
 
 use bevy::prelude::*;
+use bevy_editor_commands::EditorHistory;
+use bevy_editor_foundation::Selection;
 use bevy_editor_frontend_api::EntityComponentData;
-use bevy_editor_scene::{EditorScene, NameEditEvent, SpriteTextureEvent, TransformEditEvent};
+use bevy_editor_scene::{
+    sprite_with_texture, EditorEntityId, EditorSceneEntity, NameEditEvent, SpriteTextureEvent,
+};
 use bevy_egui::egui;
-
-use crate::component_registry::ComponentRegistry;
+use std::any::TypeId;
+
+use crate::component_registry::{CategoryTree, ComponentInfo, ComponentRegistry};
+use crate::editor_commands::{
+    ReflectComponentChange, ReflectComponentCommand, RenameEntityCommand, TransformChange,
+    TransformEntitiesCommand,
+};
+use crate::entity_fields::EntityFieldsInspector;
 use crate::icons::{IconLabel, Icons};
+use crate::reflect_inspector::ReflectInspector;
+
+/// Transform edit of the selected entities, applied as one undoable command
+#[derive(Event, Debug, Clone)]
+pub struct InspectorTransformEdit {
+    /// New transform of every edited entity
+    pub transforms: Vec<(Entity, Transform)>,
+    /// "Position", "Rotation" or "Scale"
+    pub property: &'static str,
+}
+
+/// System executing [`InspectorTransformEdit`]s through the history.
+/// Consecutive edits of one property merge, so a drag is one undo step.
+pub fn apply_inspector_transform_edits(world: &mut World) {
+    let edits: Vec<InspectorTransformEdit> = world
+        .resource_mut::<Events<InspectorTransformEdit>>()
+        .drain()
+        .collect();
+    if edits.is_empty() || !world.contains_resource::<EditorHistory>() {
+        return;
+    }
+
+    world.resource_scope(|world, mut history: Mut<EditorHistory>| {
+        for edit in edits {
+            let changes: Vec<TransformChange> = edit
+                .transforms
+                .into_iter()
+                .filter_map(|(entity, new)| {
+                    let entity = world.get_entity(entity).ok()?;
+                    Some(TransformChange {
+                        entity: *entity.get::<EditorEntityId>()?,
+                        old: *entity.get::<Transform>()?,
+                        new,
+                    })
+                })
+                .filter(|change| change.old != change.new)
+                .collect();
+            if changes.is_empty() {
+                continue;
+            }
+            history.execute(
+                Box::new(TransformEntitiesCommand::new(changes, edit.property).mergeable()),
+                world,
+            );
+        }
+    });
+}
+
+/// System recording inspector renames in the history. Runs BEFORE
+/// handle_name_edit_events, so the current name is the one to undo to.
+pub fn record_name_edits(
+    mut events: EventReader<NameEditEvent>,
+    names: Query<(&Name, &EditorEntityId), With<EditorSceneEntity>>,
+    mut history: ResMut<EditorHistory>,
+) {
+    for event in events.read() {
+        let Ok((name, id)) = names.get(event.entity) else {
+            continue;
+        };
+        // The name field reports every lost focus, edited or not
+        if name.as_str() == event.new_name {
+            continue;
+        }
+        history.add_executed(Box::new(RenameEntityCommand::new(
+            *id,
+            name.to_string(),
+            event.new_name.clone(),
+        )));
+    }
+}
+
+/// System recording texture assignments in the history, one undo step per
+/// frame. Runs BEFORE handle_sprite_texture_events.
+pub fn record_sprite_texture_edits(
+    mut events: EventReader<SpriteTextureEvent>,
+    sprites: Query<(&Sprite, &EditorEntityId), With<EditorSceneEntity>>,
+    images: Res<Assets<Image>>,
+    mut history: ResMut<EditorHistory>,
+) {
+    let changes: Vec<ReflectComponentChange> = events
+        .read()
+        .filter_map(|event| {
+            let (sprite, id) = sprites.get(event.entity).ok()?;
+            let new = sprite_with_texture(sprite, &event.texture_handle, &images);
+            Some(ReflectComponentChange {
+                entity: *id,
+                old_value: Some(Box::new(sprite.clone())),
+                new_value: Some(Box::new(new)),
+            })
+        })
+        .collect();
+    if changes.is_empty() {
+        return;
+    }
+    history.add_executed(Box::new(ReflectComponentCommand::for_entities(
+        TypeId::of::<Sprite>(),
+        "Sprite",
+        changes,
+    )));
+}
 
 /// Render the inspector panel content
 pub fn render_inspector_panel(
     ui: &mut egui::Ui,
-    editor_scene: &EditorScene,
+    selection: &Selection,
     component_data: Option<&EntityComponentData>,
+    selected_transforms: &[(Entity, Transform)],
     component_registry: &ComponentRegistry,
-    transform_events: &mut EventWriter<TransformEditEvent>,
+    transform_edits: &mut EventWriter<InspectorTransformEdit>,
     name_events: &mut EventWriter<NameEditEvent>,
     name_edit_buffer: &mut String,
     project_root: Option<&std::path::PathBuf>,
@@ -22,15 +133,24 @@ pub fn render_inspector_panel(
     texture_events: &mut EventWriter<SpriteTextureEvent>,
     sprite_texture_id: Option<egui::TextureId>,
     images: &Assets<Image>,
+    entity_fields: &mut EntityFieldsInspector,
+    reflected: &mut ReflectInspector,
 ) {
     ui.heading("Inspector");
     ui.separator();
 
-    // Check if an entity is selected
-    let Some(selected_entity) = editor_scene.selected_entity else {
+    // Check if an entity is selected; the primary one is shown
+    let Some(selected_entity) = selection.primary() else {
         ui.label("No entity selected");
         return;
     };
+    if selection.len() > 1 {
+        ui.label(format!(
+            "{} entities selected; shared fields edit all of them",
+            selection.len()
+        ))
+        .on_hover_text("Fields marked with a dash differ between the selected entities");
+    }
 
     // Check if we have component data
     let Some(data) = component_data else {
@@ -70,7 +190,8 @@ pub fn render_inspector_panel(
                 ui,
                 data,
                 selected_entity,
-                transform_events,
+                selected_transforms,
+                transform_edits,
                 project_root,
                 asset_server,
                 texture_events,
@@ -78,10 +199,16 @@ pub fn render_inspector_panel(
                 images,
             );
 
+            // Every other reflected component
+            reflected.show(ui, selected_entity);
+
+            // Custom fields from the project's entity definitions
+            entity_fields.show(ui, selected_entity);
+
             ui.separator();
 
             // Add Component button
-            render_add_component_menu(ui, component_registry);
+            render_add_component_menu(ui, component_registry, reflected);
         });
 }
 
@@ -90,7 +217,8 @@ fn render_existing_components(
     ui: &mut egui::Ui,
     data: &EntityComponentData,
     entity: Entity,
-    transform_events: &mut EventWriter<TransformEditEvent>,
+    selected_transforms: &[(Entity, Transform)],
+    transform_edits: &mut EventWriter<InspectorTransformEdit>,
     project_root: Option<&std::path::PathBuf>,
     asset_server: &AssetServer,
     texture_events: &mut EventWriter<SpriteTextureEvent>,
@@ -99,7 +227,7 @@ fn render_existing_components(
 ) {
     // Transform component
     if let Some(transform) = &data.transform {
-        render_transform_component(ui, transform, entity, transform_events);
+        render_transform_component(ui, transform, selected_transforms, transform_edits);
     }
 
     // Name component
@@ -107,11 +235,6 @@ fn render_existing_components(
         render_name_component(ui, name);
     }
 
-    // Visibility component
-    if let Some(visibility) = &data.visibility {
-        render_visibility_component(ui, visibility);
-    }
-
     // Sprite component
     if let Some(sprite) = &data.sprite {
         render_sprite_component(
@@ -125,94 +248,119 @@ fn render_existing_components(
             images,
         );
     }
-
-    // Camera2d component
-    if data.has_camera2d {
-        render_camera2d_component(ui);
-    }
-
-    // UI Node component
-    if let Some(node) = &data.node {
-        render_node_component(ui, node);
-    }
-
-    // Button component
-    if data.has_button {
-        render_button_component(ui);
-    }
-
-    // Text component
-    if let Some(text) = &data.text {
-        render_text_component(ui, text);
-    }
 }
 
+/// Edit the transform shown, the primary entity's, and the same fields of
+/// every other selected entity
 fn render_transform_component(
     ui: &mut egui::Ui,
     transform: &Transform,
-    entity: Entity,
-    transform_events: &mut EventWriter<TransformEditEvent>,
+    selected_transforms: &[(Entity, Transform)],
+    transform_edits: &mut EventWriter<InspectorTransformEdit>,
 ) {
+    let euler_degrees = |transform: &Transform| {
+        let (x, y, z) = transform.rotation.to_euler(EulerRot::XYZ);
+        Vec3::new(x.to_degrees(), y.to_degrees(), z.to_degrees())
+    };
+    // Axes whose value differs across the selection
+    let mixed = |value: Vec3, of: &dyn Fn(&Transform) -> Vec3| {
+        let mut mixed = [false; 3];
+        for (_, other) in selected_transforms {
+            let other = of(other);
+            for axis in 0..3 {
+                mixed[axis] |= (other[axis] - value[axis]).abs() > 1e-4;
+            }
+        }
+        mixed
+    };
+
     egui::CollapsingHeader::new(Icons::TRANSFORM.with_icon("Transform"))
         .default_open(true)
         .show(ui, |ui| {
             let mut translation = transform.translation;
-            let rotation = transform.rotation.to_euler(EulerRot::XYZ);
-            let mut rotation_deg = Vec3::new(
-                rotation.0.to_degrees(),
-                rotation.1.to_degrees(),
-                rotation.2.to_degrees(),
-            );
+            let mut rotation_deg = euler_degrees(transform);
             let mut scale = transform.scale;
+            let mixed_translation = mixed(translation, &|transform| transform.translation);
+            let mixed_rotation = mixed(rotation_deg, &euler_degrees);
+            let mixed_scale = mixed(scale, &|transform| transform.scale);
 
             // Translation
             ui.label(Icons::ARROW_UP.with_icon("Translation"));
-            let changed_translation = edit_vec3(ui, &mut translation);
+            let changed_translation = edit_vec3(ui, &mut translation, mixed_translation);
 
             // Rotation (convert to degrees for UI)
             ui.label(Icons::ARROW_RIGHT.with_icon("Rotation (deg)"));
-            let changed_rotation = edit_vec3(ui, &mut rotation_deg);
+            let changed_rotation = edit_vec3(ui, &mut rotation_deg, mixed_rotation);
 
             // Scale
             ui.label(Icons::ARROW_UP.with_icon("Scale"));
-            let changed_scale = edit_vec3(ui, &mut scale);
-
-            if changed_translation {
-                transform_events.write(TransformEditEvent::SetPosition {
-                    entity,
-                    position: Vec2::new(translation.x, translation.y),
+            let changed_scale = edit_vec3(ui, &mut scale, mixed_scale);
+
+            // Only the edited axes change, the others keep each entity's value
+            let edit = |changed: [bool; 3], apply: &dyn Fn(&mut Transform, usize)| {
+                selected_transforms
+                    .iter()
+                    .map(|(entity, transform)| {
+                        let mut transform = *transform;
+                        for axis in (0..3).filter(|axis| changed[*axis]) {
+                            apply(&mut transform, axis);
+                        }
+                        (*entity, transform)
+                    })
+                    .collect()
+            };
+
+            if changed_translation.contains(&true) {
+                transform_edits.write(InspectorTransformEdit {
+                    transforms: edit(changed_translation, &|transform, axis| {
+                        transform.translation[axis] = translation[axis];
+                    }),
+                    property: "Position",
                 });
             }
 
-            if changed_rotation {
-                transform_events.write(TransformEditEvent::SetRotation {
-                    entity,
-                    rotation: rotation_deg.z.to_radians(),
+            if changed_rotation.contains(&true) {
+                transform_edits.write(InspectorTransformEdit {
+                    transforms: edit(changed_rotation, &|transform, axis| {
+                        let mut degrees = euler_degrees(transform);
+                        degrees[axis] = rotation_deg[axis];
+                        transform.rotation = Quat::from_euler(
+                            EulerRot::XYZ,
+                            degrees.x.to_radians(),
+                            degrees.y.to_radians(),
+                            degrees.z.to_radians(),
+                        );
+                    }),
+                    property: "Rotation",
                 });
             }
 
-            if changed_scale {
-                transform_events.write(TransformEditEvent::SetScale {
-                    entity,
-                    scale: Vec2::new(scale.x, scale.y),
+            if changed_scale.contains(&true) {
+                transform_edits.write(InspectorTransformEdit {
+                    transforms: edit(changed_scale, &|transform, axis| {
+                        transform.scale[axis] = scale[axis];
+                    }),
+                    property: "Scale",
                 });
             }
         });
 }
 
-fn edit_vec3(ui: &mut egui::Ui, value: &mut Vec3) -> bool {
-    let mut changed = false;
+/// Edit the axes of `value`; `mixed` axes show a dash instead of their value.
+/// Returns which axes changed.
+fn edit_vec3(ui: &mut egui::Ui, value: &mut Vec3, mixed: [bool; 3]) -> [bool; 3] {
+    let mut changed = [false; 3];
 
     ui.horizontal(|ui| {
-        changed |= ui
-            .add(egui::DragValue::new(&mut value.x).speed(0.1).prefix("X: "))
-            .changed();
-        changed |= ui
-            .add(egui::DragValue::new(&mut value.y).speed(0.1).prefix("Y: "))
-            .changed();
-        changed |= ui
-            .add(egui::DragValue::new(&mut value.z).speed(0.1).prefix("Z: "))
-            .changed();
+        for (axis, prefix) in ["X: ", "Y: ", "Z: "].into_iter().enumerate() {
+            let mut drag = egui::DragValue::new(&mut value[axis])
+                .speed(0.1)
+                .prefix(prefix);
+            if mixed[axis] {
+                drag = drag.custom_formatter(|_, _| "—".to_string());
+            }
+            changed[axis] = ui.add(drag).changed();
+        }
     });
 
     changed
@@ -227,17 +375,9 @@ fn render_name_component(ui: &mut egui::Ui, name: &str) {
         });
 }
 
-fn render_visibility_component(ui: &mut egui::Ui, visibility: &Visibility) {
-    egui::CollapsingHeader::new(Icons::EYE.with_icon("Visibility"))
-        .default_open(true)
-        .show(ui, |ui| {
-            ui.label(format!("Visible: {}", visibility == &Visibility::Visible));
-        });
-}
-
 fn render_sprite_component(
     ui: &mut egui::Ui,
-    sprite: &Sprite,
+    _sprite: &Sprite,
     entity: Entity,
     project_root: Option<&std::path::PathBuf>,
     asset_server: &AssetServer,
@@ -245,7 +385,8 @@ fn render_sprite_component(
     sprite_texture_id: Option<egui::TextureId>,
     _images: &Assets<Image>,
 ) {
-    egui::CollapsingHeader::new(Icons::SPRITE.with_icon("Sprite"))
+    // The sprite's other fields are edited by the reflection inspector
+    egui::CollapsingHeader::new(Icons::SPRITE.with_icon("Sprite Texture"))
         .default_open(true)
         .show(ui, |ui| {
             if let Some(texture_id) = sprite_texture_id {
@@ -277,72 +418,91 @@ fn render_sprite_component(
                     ui.label("No project loaded");
                 }
             }
-
-            ui.separator();
-
-            ui.label(format!("Color: {:?}", sprite.color));
-            ui.label(format!("Custom Size: {:?}", sprite.custom_size));
         });
 }
 
-/// Render Camera2d component editor
-fn render_camera2d_component(ui: &mut egui::Ui) {
-    egui::CollapsingHeader::new(format!("{} Camera2D", Icons::CAMERA))
-        .default_open(true)
-        .show(ui, |ui| {
-            ui.label("2D Camera");
-            ui.label("(Editing coming soon)");
-        });
-}
+/// Render "Add Component" menu: a fuzzy search box over the registry's
+/// category tree
+fn render_add_component_menu(
+    ui: &mut egui::Ui,
+    component_registry: &ComponentRegistry,
+    reflected: &mut ReflectInspector,
+) {
+    ui.menu_button(format!("{} Add Component", Icons::NEW), |ui| {
+        let search_id = ui.make_persistent_id("add_component_search");
+        let mut query: String = ui.data(|data| data.get_temp(search_id)).unwrap_or_default();
+        let response = ui.add(egui::TextEdit::singleline(&mut query).hint_text("Search..."));
+        if response.changed() {
+            ui.data_mut(|data| data.insert_temp(search_id, query.clone()));
+        }
+        ui.separator();
 
-/// Render Node component editor
-fn render_node_component(ui: &mut egui::Ui, _node: &Node) {
-    egui::CollapsingHeader::new("Node")
-        .default_open(true)
-        .show(ui, |ui| {
-            ui.label("UI Node");
-            ui.label("(Editing coming soon)");
-        });
-}
+        let mut chosen = None;
+        if query.trim().is_empty() {
+            render_category(
+                ui,
+                component_registry,
+                component_registry.tree(),
+                reflected,
+                &mut chosen,
+            );
+        } else {
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    let matches = component_registry.search(&query);
+                    if matches.is_empty() {
+                        ui.weak("No matching components");
+                    }
+                    for component_info in matches {
+                        render_component_button(ui, component_info, reflected, &mut chosen);
+                    }
+                });
+        }
 
-/// Render Button component editor
-fn render_button_component(ui: &mut egui::Ui) {
-    egui::CollapsingHeader::new("Button")
-        .default_open(true)
-        .show(ui, |ui| {
-            ui.label("UI Button");
-            ui.label("(Editing coming soon)");
-        });
+        if let Some(component) = chosen {
+            reflected.add_component(&component);
+            ui.data_mut(|data| data.remove::<String>(search_id));
+            ui.close_menu();
+        }
+    });
 }
 
-/// Render Text component editor
-fn render_text_component(ui: &mut egui::Ui, text: &Text) {
-    egui::CollapsingHeader::new("Text")
-        .default_open(true)
-        .show(ui, |ui| {
-            // In Bevy 0.16, Text is a simple wrapper around String
-            ui.label(format!("Text: {}", text.0));
-            ui.label("(Editing coming soon)");
+fn render_category(
+    ui: &mut egui::Ui,
+    component_registry: &ComponentRegistry,
+    category: &CategoryTree,
+    reflected: &ReflectInspector,
+    chosen: &mut Option<ComponentInfo>,
+) {
+    for (name, child) in &category.children {
+        ui.menu_button(name, |ui| {
+            render_category(ui, component_registry, child, reflected, chosen);
         });
+    }
+    for &index in &category.components {
+        render_component_button(
+            ui,
+            &component_registry.components()[index],
+            reflected,
+            chosen,
+        );
+    }
 }
 
-/// Render "Add Component" menu
-fn render_add_component_menu(ui: &mut egui::Ui, component_registry: &ComponentRegistry) {
-    ui.menu_button(format!("{} Add Component", Icons::NEW), |ui| {
-        for category in component_registry.categories() {
-            let components = component_registry.get_by_category(category);
-            if components.is_empty() {
-                continue;
-            }
-
-            ui.menu_button(ComponentRegistry::category_name(category), |ui| {
-                for component_info in components {
-                    if ui.button(component_info.name).clicked() {
-                        info!("Add component: {}", component_info.name);
-                        ui.close_menu();
-                    }
-                }
-            });
-        }
-    });
+fn render_component_button(
+    ui: &mut egui::Ui,
+    component_info: &ComponentInfo,
+    reflected: &ReflectInspector,
+    chosen: &mut Option<ComponentInfo>,
+) {
+    // Components already on the entity can't be added twice
+    let present = reflected.has_component(component_info);
+    if ui
+        .add_enabled(!present, egui::Button::new(&component_info.name))
+        .on_hover_text(&component_info.type_path)
+        .clicked()
+    {
+        *chosen = Some(component_info.clone());
+    }
 }
@@ -1,10 +1,15 @@
 use bevy::prelude::*;
+use bevy_editor_commands::EditorHistory;
 use bevy_editor_formats::LayerType;
-use bevy_editor_tilemap::{create_default_layer, LayerManager};
+use bevy_editor_tilemap::{create_default_layer, LayerCommand, LayerManager};
 use bevy_egui::{egui, EguiContexts};
 
 /// Layer panel system - shows layer list with controls
-pub fn layer_panel_ui(mut contexts: EguiContexts, mut layer_manager: ResMut<LayerManager>) {
+pub fn layer_panel_ui(
+    mut contexts: EguiContexts,
+    mut layer_manager: ResMut<LayerManager>,
+    mut history: ResMut<EditorHistory>,
+) {
     let Some(ctx) = contexts.try_ctx_mut() else {
         return;
     };
@@ -26,7 +31,9 @@ pub fn layer_panel_ui(mut contexts: EguiContexts, mut layer_manager: ResMut<Laye
                         layer_manager.layers.len() as i32,
                         None,
                     );
-                    layer_manager.add_layer(new_layer);
+                    let command = LayerCommand::add(&layer_manager, new_layer);
+                    command.apply(&mut layer_manager, true);
+                    history.add_executed(Box::new(command));
                 }
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -35,8 +42,12 @@ pub fn layer_panel_ui(mut contexts: EguiContexts, mut layer_manager: ResMut<Laye
                         .on_hover_text("Delete Selected Layer")
                         .clicked()
                     {
-                        if let Some(active_idx) = layer_manager.active_layer {
-                            layer_manager.remove_layer(active_idx);
+                        if let Some(command) = layer_manager
+                            .active_layer
+                            .and_then(|idx| LayerCommand::remove(&layer_manager, idx))
+                        {
+                            command.apply(&mut layer_manager, true);
+                            history.add_executed(Box::new(command));
                         }
                     }
                 });
@@ -132,11 +143,14 @@ pub fn layer_panel_ui(mut contexts: EguiContexts, mut layer_manager: ResMut<Laye
                         if let Some(idx) = new_active_layer {
                             layer_manager.set_active_layer(idx);
                         }
-                        if let Some(idx) = move_up_idx {
-                            layer_manager.move_layer_up(idx);
-                        }
-                        if let Some(idx) = move_down_idx {
-                            layer_manager.move_layer_down(idx);
+                        let moves = [(move_up_idx, true), (move_down_idx, false)];
+                        for (idx, up) in moves {
+                            if let Some(command) = idx
+                                .and_then(|idx| LayerCommand::move_layer(&layer_manager, idx, up))
+                            {
+                                command.apply(&mut layer_manager, true);
+                                history.add_executed(Box::new(command));
+                            }
                         }
                     });
 
@@ -1,19 +1,26 @@
+pub mod arrange;
 pub mod asset_browser_panel;
 pub mod build_progress_ui;
 pub mod cli_output_panel;
+pub mod clipboard;
 pub mod collision_editor;
 pub mod component_registry;
 pub mod current_level;
 pub mod editor_commands;
+pub mod entity_bounds;
+pub mod entity_fields;
 pub mod entity_templates;
 pub mod frontend;
 pub mod gizmos;
+pub mod history_panel;
 pub mod icons;
 pub mod inspector_panel;
 pub mod layer_panel;
+pub mod live_link_panel;
 pub mod panel_manager;
 pub mod project_browser;
 pub mod project_browser_panel;
+pub mod reflect_inspector;
 pub mod scene_tabs;
 pub mod scene_tree_panel;
 pub mod shortcuts;
@@ -21,15 +28,21 @@ pub mod tilemap_ui;
 pub mod tileset_panel;
 pub mod toolbar;
 pub mod ui;
+pub mod variation_brush_panel;
 pub mod viewport_selection;
 
 use bevy::prelude::*;
 use bevy_editor_assets::AssetBrowserSet;
+use bevy_editor_commands::{CommandRegistry, CrashRecovery, MacroRecorder, MacroRequest};
 use bevy_editor_project::ProjectManagerSet;
-use bevy_editor_scene::SceneTabSystemSet;
+use bevy_editor_scene::{
+    handle_name_edit_events, handle_sprite_texture_events, handle_transform_edit_events,
+    SceneTabSystemSet,
+};
 
+pub use arrange::arrange_selection_system;
 pub use asset_browser_panel::asset_browser_panel_ui;
-pub use bevy_editor_core::{GizmoMode, GizmoState};
+pub use bevy_editor_core::{GizmoMode, GizmoState, PivotMode, SnapGuide, SnapSettings};
 pub use bevy_editor_frontend_api::AssetBrowserPanelState as AssetBrowserPanel;
 pub use bevy_editor_frontend_api::ProjectBrowserPanelState as ProjectBrowserPanel;
 pub use bevy_editor_frontend_api::{
@@ -39,35 +52,62 @@ pub use bevy_editor_frontend_api::{
 };
 pub use build_progress_ui::build_progress_overlay_ui;
 pub use cli_output_panel::{render_cli_output_content, should_show_cli_output};
-pub use collision_editor::{collision_editor_ui, handle_collision_input, render_collision_shapes};
+pub use clipboard::{handle_clipboard_requests, ClipboardRequest, SceneClipboard};
+pub use collision_editor::{collision_editor_ui, render_collision_shapes};
 pub use component_registry::{
-    ComponentCategory, ComponentInfo, ComponentRegistry, EditorComponentRegistry,
+    fuzzy_score, refresh_component_registry, CategoryTree, ComponentInfo, ComponentRegistry,
+    EditorComponentRegistry,
 };
 pub use current_level::CurrentLevel;
-pub use editor_commands::{CreateEntityCommand, TransformCommand};
+pub use editor_commands::{
+    CreateEntityCommand, DeleteEntityCommand, PasteEntitiesCommand, ReflectComponentChange,
+    ReflectComponentCommand, RenameEntityCommand, ReparentEntityCommand, SetEntityFieldsCommand,
+    TransformChange, TransformCommand, TransformEntitiesCommand,
+};
+pub use entity_bounds::EntityBounds;
+pub use entity_fields::{
+    apply_entity_field_edits, dangling_references_ui, draw_entity_reference_arrows,
+    validate_entity_references, EntityFieldsEdit, EntityFieldsInspector, EntityPickState,
+    EntityReferenceIssues, PickTarget,
+};
 pub use entity_templates::spawn_from_template;
 pub use frontend::EguiFrontend;
-pub use gizmos::{draw_gizmo_mode_indicator, draw_grid, draw_selection_gizmos};
-pub use inspector_panel::render_inspector_panel;
+pub use gizmos::{
+    draw_gizmo_mode_indicator, draw_grid, draw_marquee_selection, draw_selection_gizmos,
+    draw_snap_guides, draw_tile_tool_preview,
+};
+pub use history_panel::{crash_recovery_prompt_ui, history_panel_ui, HistoryPanel};
+pub use inspector_panel::{
+    apply_inspector_transform_edits, record_name_edits, record_sprite_texture_edits,
+    render_inspector_panel, InspectorTransformEdit,
+};
 pub use layer_panel::{layer_panel_ui, CreateLayerEvent, DeleteLayerEvent, ReorderLayerEvent};
+pub use live_link_panel::{live_link_ui, LiveLinkWindow};
 pub use panel_manager::{render_left_panel, render_right_panel, NameEditBuffer, PanelManager};
 pub use project_browser::{
     clear_panel_state_on_project_switch, refresh_project_browser_system, sync_asset_browser_root,
     sync_project_browser_root, FileEntry, FileType, ProjectBrowser,
 };
 pub use project_browser_panel::project_browser_panel_ui;
+pub use reflect_inspector::{
+    apply_inspector_edits, snapshot_inspected_components, InspectorWidgets, ReflectInspector,
+    ReflectInspectorState,
+};
 pub use scene_tabs::render_scene_tabs_content;
 pub use scene_tree_panel::handle_scene_tree_commands;
 pub use shortcuts::handle_global_shortcuts;
-pub use tilemap_ui::{handle_eyedropper, handle_tile_painting};
+pub use tilemap_ui::{handle_eyedropper, handle_tile_painting, record_tile_strokes};
 pub use tileset_panel::{
-    handle_tile_selection_events, SelectTileEvent, SelectTilesetEvent, TilesetZoom,
+    handle_tile_selection_events, tileset_import_dialog_ui, SelectTileEvent, SelectTilesetEvent,
+    TilesetImportDialog, TilesetZoom,
 };
 pub use toolbar::render_toolbar_content;
 pub use ui::ui_system;
+pub use variation_brush_panel::{variation_brush_ui, VariationBrushWindow};
 pub use viewport_selection::{
-    gizmo_drag_interaction_system, transform_with_undo_system, viewport_entity_selection_system,
-    GizmoDragState,
+    gizmo_drag_interaction_system, place_pivot_cursor_system, transform_with_undo_system,
+    viewport_entity_selection_system, DraggedEntity, GizmoDragState, MarqueeSelection,
+    SelectionGizmo, ViewportPickState,
 };
 
 /// System ordering buckets for the egui UI layer.
@@ -88,6 +128,7 @@ impl Plugin for EditorUiEguiPlugin {
             .init_resource::<PanelManager>()
             .init_resource::<NameEditBuffer>()
             .init_resource::<TilesetZoom>()
+            .init_resource::<TilesetImportDialog>()
             .init_resource::<AssetBrowserPanel>()
             .init_resource::<ProjectBrowser>()
             .init_resource::<ProjectBrowserPanelState>()
@@ -95,8 +136,25 @@ impl Plugin for EditorUiEguiPlugin {
             .init_resource::<SceneTreePanelState>()
             .init_resource::<InspectorPanelState>()
             .init_resource::<GizmoDragState>()
+            .init_resource::<ViewportPickState>()
+            .init_resource::<MarqueeSelection>()
             .init_resource::<CurrentLevel>()
+            .init_resource::<VariationBrushWindow>()
+            .init_resource::<HistoryPanel>()
+            .init_resource::<LiveLinkWindow>()
+            .init_resource::<CommandRegistry>()
+            .init_resource::<MacroRecorder>()
+            .init_resource::<CrashRecovery>()
+            .init_resource::<EntityPickState>()
+            .init_resource::<EntityReferenceIssues>()
+            .init_resource::<InspectorWidgets>()
+            .init_resource::<ReflectInspectorState>()
+            .init_resource::<SceneClipboard>()
+            .add_event::<MacroRequest>()
+            .add_event::<EntityFieldsEdit>()
+            .add_event::<InspectorTransformEdit>()
             .add_event::<SceneTreeCommand>()
+            .add_event::<ClipboardRequest>()
             .add_event::<SelectTileEvent>()
             .add_event::<SelectTilesetEvent>()
             .configure_sets(
@@ -130,7 +188,12 @@ impl Plugin for EditorUiEguiPlugin {
                         .before(AssetBrowserSet),
                     refresh_project_browser_system.after(ProjectManagerSet),
                     build_progress_overlay_ui.after(ProjectManagerSet),
-                    (ui_system, render_left_panel, render_right_panel)
+                    (
+                        (refresh_component_registry, snapshot_inspected_components),
+                        ui_system,
+                        render_left_panel,
+                        render_right_panel,
+                    )
                         .chain()
                         .after(ProjectManagerSet)
                         .after(AssetBrowserSet),
@@ -143,17 +206,47 @@ impl Plugin for EditorUiEguiPlugin {
                 (
                     viewport_entity_selection_system,
                     gizmo_drag_interaction_system.after(viewport_entity_selection_system),
-                    transform_with_undo_system.after(gizmo_drag_interaction_system),
+                    transform_with_undo_system
+                        .after(gizmo_drag_interaction_system)
+                        .before(handle_transform_edit_events),
+                    record_name_edits.before(handle_name_edit_events),
+                    record_sprite_texture_edits.before(handle_sprite_texture_events),
+                    (
+                        place_pivot_cursor_system,
+                        arrange_selection_system.after(gizmo_drag_interaction_system),
+                    ),
                     handle_tile_selection_events.after(ProjectManagerSet),
                     handle_scene_tree_commands,
-                    handle_tile_painting,
+                    handle_clipboard_requests,
+                    (
+                        (
+                            apply_inspector_edits,
+                            apply_inspector_transform_edits,
+                            apply_entity_field_edits,
+                            validate_entity_references,
+                        )
+                            .chain()
+                            .after(gizmo_drag_interaction_system),
+                        dangling_references_ui,
+                        draw_entity_reference_arrows,
+                    ),
+                    (handle_tile_painting, record_tile_strokes).chain(),
                     handle_eyedropper,
+                    variation_brush_ui,
+                    history_panel_ui,
+                    live_link_ui,
+                    crash_recovery_prompt_ui,
+                    tileset_import_dialog_ui,
                     collision_editor_ui,
-                    handle_collision_input,
                     render_collision_shapes,
-                    draw_grid,
-                    draw_selection_gizmos,
-                    draw_gizmo_mode_indicator,
+                    (
+                        draw_grid,
+                        draw_selection_gizmos,
+                        draw_marquee_selection,
+                        draw_snap_guides,
+                        draw_tile_tool_preview,
+                        draw_gizmo_mode_indicator,
+                    ),
                 )
                     .in_set(EditorUiSet::Interaction)
                     .after(EditorUiSet::Panels),
@@ -164,5 +257,16 @@ impl Plugin for EditorUiEguiPlugin {
                     .after(ProjectManagerSet)
                     .in_set(SceneTabSystemSet::Apply),
             );
+
+        app.world_mut()
+            .resource_mut::<CommandRegistry>()
+            .register::<CreateEntityCommand>()
+            .register::<DeleteEntityCommand>()
+            .register::<TransformCommand>()
+            .register::<TransformEntitiesCommand>()
+            .register::<RenameEntityCommand>()
+            .register::<ReparentEntityCommand>()
+            .register::<SetEntityFieldsCommand>()
+            .register::<PasteEntitiesCommand>();
     }
 }
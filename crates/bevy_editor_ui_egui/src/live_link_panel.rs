@@ -0,0 +1,210 @@
+//! Window showing the running game through the [`LiveLink`]
+//!
+//! Lists the game's entities as a tree and the components of the selected one.
+//! Values are read through the editor's type registry or the game's exported
+//! types ([`ImportedTypes`]), edited with the reflection inspector's widgets
+//! and sent straight back to the game.
+
+use bevy::prelude::*;
+use bevy::reflect::TypeRegistry;
+use bevy_editor_project::{scene_asset_path, CurrentProject, LiveEntity, LiveLink, LiveLinkStatus};
+use bevy_editor_scene::{ImportedTypes, OpenScenes};
+use bevy_egui::{egui, EguiContexts};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::reflect_inspector::{edit_detached_value, short_type_name, InspectorWidgets};
+
+/// UI state for the live game window
+#[derive(Resource, Default)]
+pub struct LiveLinkWindow {
+    pub open: bool,
+}
+
+/// UI system for inspecting and editing the running game
+pub fn live_link_ui(
+    mut contexts: EguiContexts,
+    mut window: ResMut<LiveLinkWindow>,
+    mut live_link: ResMut<LiveLink>,
+    type_registry: Res<AppTypeRegistry>,
+    imported: Res<ImportedTypes>,
+    widgets: Res<InspectorWidgets>,
+    open_scenes: Res<OpenScenes>,
+    project: Option<Res<CurrentProject>>,
+) {
+    if !window.open {
+        return;
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    egui::Window::new("Live Game")
+        .open(&mut open)
+        .default_width(480.0)
+        .default_height(420.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                match live_link.status {
+                    LiveLinkStatus::Disconnected => {
+                        ui.weak("Not running - start the game with Run")
+                    }
+                    LiveLinkStatus::Connecting => ui.label("Waiting for the game..."),
+                    LiveLinkStatus::Connected => ui.label(format!(
+                        "Connected on port {} - {} entities",
+                        live_link.port,
+                        live_link.entities.len()
+                    )),
+                };
+
+                let scene_path = open_scenes
+                    .active_scene()
+                    .and_then(|scene| scene.file_path.as_deref())
+                    .zip(project.as_deref())
+                    .map(|(file_path, project)| {
+                        scene_asset_path(&project.metadata.assets_path, file_path)
+                    });
+                if ui
+                    .add_enabled(
+                        live_link.is_connected() && scene_path.is_some(),
+                        egui::Button::new("Push Scene"),
+                    )
+                    .on_hover_text("Reload the active scene's saved file in the game")
+                    .clicked()
+                {
+                    if let Some(path) = scene_path {
+                        live_link.reload_scene(path);
+                    }
+                }
+            });
+            ui.separator();
+
+            if !live_link.is_connected() {
+                return;
+            }
+
+            let registry = type_registry.read();
+            ui.columns(2, |columns| {
+                egui::ScrollArea::vertical()
+                    .id_salt("live_entities")
+                    .auto_shrink([false, false])
+                    .show(&mut columns[0], |ui| entity_tree(ui, &mut live_link));
+                egui::ScrollArea::vertical()
+                    .id_salt("live_components")
+                    .auto_shrink([false, false])
+                    .show(&mut columns[1], |ui| {
+                        components(ui, &mut live_link, &registry, &imported, &widgets);
+                    });
+            });
+        });
+
+    if !open {
+        window.open = false;
+    }
+}
+
+/// The game's entities, nested under their parents
+fn entity_tree(ui: &mut egui::Ui, live_link: &mut LiveLink) {
+    let mut children: HashMap<Option<u64>, Vec<&LiveEntity>> = HashMap::new();
+    for entity in &live_link.entities {
+        children.entry(entity.parent).or_default().push(entity);
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_by_key(|entity| (entity.name.is_none(), entity.label()));
+    }
+
+    let mut selected = live_link.selected;
+    for root in children.get(&None).into_iter().flatten() {
+        entity_node(ui, root, &children, &mut selected);
+    }
+    if selected != live_link.selected {
+        live_link.select(selected);
+    }
+}
+
+fn entity_node(
+    ui: &mut egui::Ui,
+    entity: &LiveEntity,
+    children: &HashMap<Option<u64>, Vec<&LiveEntity>>,
+    selected: &mut Option<u64>,
+) {
+    let is_selected = *selected == Some(entity.entity);
+    match children.get(&Some(entity.entity)) {
+        Some(entity_children) => {
+            let id = ui.make_persistent_id(("live_entity", entity.entity));
+            egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false)
+                .show_header(ui, |ui| {
+                    if ui.selectable_label(is_selected, entity.label()).clicked() {
+                        *selected = Some(entity.entity);
+                    }
+                })
+                .body(|ui| {
+                    for child in entity_children {
+                        entity_node(ui, child, children, selected);
+                    }
+                });
+        }
+        None => {
+            if ui.selectable_label(is_selected, entity.label()).clicked() {
+                *selected = Some(entity.entity);
+            }
+        }
+    }
+}
+
+/// Components of the selected entity. Edits are sent to the game right away.
+fn components(
+    ui: &mut egui::Ui,
+    live_link: &mut LiveLink,
+    registry: &TypeRegistry,
+    imported: &ImportedTypes,
+    widgets: &InspectorWidgets,
+) {
+    let Some(entity) = live_link.selected else {
+        ui.weak("Select an entity");
+        return;
+    };
+    if live_link.components.is_empty() {
+        ui.weak("Loading components...");
+        return;
+    }
+
+    let mut edits: Vec<(String, Value)> = Vec::new();
+    for (type_path, json) in &live_link.components {
+        let name = imported.schemas.get(type_path).map_or_else(
+            || short_type_name(type_path),
+            |schema| schema.short_path.clone(),
+        );
+        ui.push_id(type_path, |ui| {
+            egui::CollapsingHeader::new(name)
+                .default_open(false)
+                .show(ui, |ui| {
+                    match imported.schemas.read_json_value(type_path, json, registry) {
+                        Ok(mut value) => {
+                            if edit_detached_value(ui, value.as_mut(), widgets, registry) {
+                                match imported.schemas.write_json_value(
+                                    type_path,
+                                    value.as_ref(),
+                                    registry,
+                                ) {
+                                    Ok(json) => edits.push((type_path.clone(), json)),
+                                    Err(err) => warn!("Could not write {}: {}", type_path, err),
+                                }
+                            }
+                        }
+                        // Run the game once to export its types
+                        Err(_) => {
+                            ui.weak(json.to_string());
+                        }
+                    }
+                })
+                .header_response
+                .on_hover_text(type_path);
+        });
+    }
+    for (type_path, json) in edits {
+        live_link.insert_component(entity, &type_path, json);
+    }
+}
@@ -4,17 +4,21 @@ use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
 use crate::component_registry::EditorComponentRegistry;
-use crate::inspector_panel::render_inspector_panel;
+use crate::entity_fields::EntityFieldsInspector;
+use crate::inspector_panel::{render_inspector_panel, InspectorTransformEdit};
+use crate::reflect_inspector::ReflectInspector;
 use crate::scene_tree_panel::render_scene_tree_panel;
+use bevy_editor_commands::EditorHistory;
+use bevy_editor_foundation::Selection;
 use bevy_editor_frontend_api::{
     scene_tree::{SceneTreeCommand, SceneTreeNode},
     EntityComponentData, ProjectBrowserPanelState,
 };
 use bevy_editor_scene::{
-    EditorScene, EditorSceneEntity, NameEditEvent, SpriteTextureEvent, TransformEditEvent,
+    EditorScene, EditorSceneEntity, EditorViewState, NameEditEvent, SpriteTextureEvent,
 };
-use bevy_editor_tilemap::LayerManager;
 use bevy_editor_tilemap::TilesetManager;
+use bevy_editor_tilemap::{LayerCommand, LayerManager, TileGrid};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LeftPanelTab {
@@ -58,10 +62,21 @@ pub struct NameEditBuffer {
 pub fn render_left_panel(
     mut contexts: EguiContexts,
     mut panel_manager: ResMut<PanelManager>,
-    mut editor_scene: ResMut<EditorScene>,
+    editor_scene: Res<EditorScene>,
+    mut selection: ResMut<Selection>,
     mut layer_manager: ResMut<LayerManager>,
+    mut tile_grid: ResMut<TileGrid>,
+    mut history: ResMut<EditorHistory>,
     mut scene_tree_events: EventWriter<SceneTreeCommand>,
-    scene_entity_query: Query<(Entity, Option<&Name>, Option<&Children>), With<EditorSceneEntity>>,
+    scene_entity_query: Query<
+        (
+            Entity,
+            Option<&Name>,
+            Option<&Children>,
+            Option<&EditorViewState>,
+        ),
+        With<EditorSceneEntity>,
+    >,
 ) {
     let Some(ctx) = contexts.try_ctx_mut() else {
         return;
@@ -95,26 +110,32 @@ pub fn render_left_panel(
                     // Extract entity data from queries
                     let entity_data: Vec<SceneTreeNode> = scene_entity_query
                         .iter()
-                        .map(|(entity, name, children)| {
-                            SceneTreeNode::new(
-                                entity,
-                                name.map(|n| n.to_string())
-                                    .unwrap_or_else(|| "Unnamed".to_string()),
-                                children.is_some_and(|c| !c.is_empty()),
-                                children.map_or_else(Vec::new, |c| c.iter().collect()),
-                            )
+                        .map(|(entity, name, children, view_state)| {
+                            let view_state = view_state.copied().unwrap_or_default();
+                            SceneTreeNode {
+                                locked: view_state.locked,
+                                hidden: view_state.hidden,
+                                ..SceneTreeNode::new(
+                                    entity,
+                                    name.map(|n| n.to_string())
+                                        .unwrap_or_else(|| "Unnamed".to_string()),
+                                    children.is_some_and(|c| !c.is_empty()),
+                                    children.map_or_else(Vec::new, |c| c.iter().collect()),
+                                )
+                            }
                         })
                         .collect();
 
                     render_scene_tree_panel(
                         ui,
-                        &mut editor_scene,
+                        &editor_scene,
+                        &mut selection,
                         &entity_data,
                         &mut scene_tree_events,
                     );
                 }
                 LeftPanelTab::Layers => {
-                    render_layers_tab(ui, &mut layer_manager);
+                    render_layers_tab(ui, &mut layer_manager, &mut tile_grid, &mut history);
                 }
             }
         });
@@ -124,11 +145,14 @@ pub fn render_left_panel(
 pub fn render_right_panel(
     mut contexts: EguiContexts,
     mut panel_manager: ResMut<PanelManager>,
-    editor_scene: Res<EditorScene>,
+    selection: Res<Selection>,
     component_registry: Res<EditorComponentRegistry>,
     tileset_manager: Res<TilesetManager>,
-    mut tileset_zoom: ResMut<crate::tileset_panel::TilesetZoom>,
-    mut transform_events: EventWriter<TransformEditEvent>,
+    (mut tileset_zoom, mut import_dialog): (
+        ResMut<crate::tileset_panel::TilesetZoom>,
+        ResMut<crate::tileset_panel::TilesetImportDialog>,
+    ),
+    mut transform_edits: EventWriter<InspectorTransformEdit>,
     mut name_events: EventWriter<NameEditEvent>,
     mut name_edit_buffer: ResMut<NameEditBuffer>,
     mut project_browser: ResMut<crate::project_browser::ProjectBrowser>,
@@ -147,10 +171,11 @@ pub fn render_right_panel(
         Has<Button>,
         Option<&Text>,
     )>,
+    (mut entity_fields, mut reflected): (EntityFieldsInspector, ReflectInspector),
 ) {
     // Pre-register sprite texture with egui if inspector tab is active
     let sprite_texture_id = if panel_manager.right_tab == RightPanelTab::Inspector {
-        if let Some(selected_entity) = editor_scene.selected_entity {
+        if let Some(selected_entity) = selection.primary() {
             if let Ok((_, _, _, _, sprite, _, _, _, _)) = entity_query.get(selected_entity) {
                 if let Some(sprite) = sprite {
                     if sprite.image.is_strong() {
@@ -206,43 +231,51 @@ pub fn render_right_panel(
             match panel_manager.right_tab {
                 RightPanelTab::Inspector => {
                     // Get selected entity component data
-                    let selected_entity_data =
-                        if let Some(selected_entity) = editor_scene.selected_entity {
-                            entity_query.get(selected_entity).ok().map(
-                                |(
+                    let selected_entity_data = if let Some(selected_entity) = selection.primary() {
+                        entity_query.get(selected_entity).ok().map(
+                            |(
+                                entity,
+                                name,
+                                transform,
+                                visibility,
+                                sprite,
+                                camera2d,
+                                node,
+                                has_button,
+                                text,
+                            )| {
+                                EntityComponentData {
                                     entity,
-                                    name,
-                                    transform,
-                                    visibility,
-                                    sprite,
-                                    camera2d,
-                                    node,
+                                    name: name.map(|n| n.to_string()),
+                                    transform: transform.copied(),
+                                    visibility: visibility.copied(),
+                                    sprite: sprite.cloned(),
+                                    has_camera2d: camera2d.is_some(),
+                                    node: node.cloned(),
                                     has_button,
-                                    text,
-                                )| {
-                                    EntityComponentData {
-                                        entity,
-                                        name: name.map(|n| n.to_string()),
-                                        transform: transform.copied(),
-                                        visibility: visibility.copied(),
-                                        sprite: sprite.cloned(),
-                                        has_camera2d: camera2d.is_some(),
-                                        node: node.cloned(),
-                                        has_button,
-                                        text: text.cloned(),
-                                    }
-                                },
-                            )
-                        } else {
-                            None
-                        };
+                                    text: text.cloned(),
+                                }
+                            },
+                        )
+                    } else {
+                        None
+                    };
+
+                    let selected_transforms: Vec<(Entity, Transform)> = selection
+                        .iter()
+                        .filter_map(|entity| {
+                            let (_, _, transform, ..) = entity_query.get(entity).ok()?;
+                            Some((entity, *transform?))
+                        })
+                        .collect();
 
                     render_inspector_panel(
                         ui,
-                        &editor_scene,
+                        &selection,
                         selected_entity_data.as_ref(),
+                        &selected_transforms,
                         &component_registry.registry,
-                        &mut transform_events,
+                        &mut transform_edits,
                         &mut name_events,
                         &mut name_edit_buffer.buffer,
                         project_browser.project_root.as_ref(),
@@ -250,17 +283,24 @@ pub fn render_right_panel(
                         &mut texture_events,
                         sprite_texture_id,
                         &images,
+                        &mut entity_fields,
+                        &mut reflected,
                     );
                 }
                 RightPanelTab::Tilesets => {
-                    render_tilesets_tab(ui, &tileset_manager, &mut tileset_zoom);
+                    render_tilesets_tab(
+                        ui,
+                        &tileset_manager,
+                        &mut tileset_zoom,
+                        &mut import_dialog,
+                    );
                 }
                 RightPanelTab::Assets => {
                     crate::project_browser_panel::project_browser_panel_ui(
                         ui,
                         &mut project_browser,
                         &mut project_browser_panel,
-                        &editor_scene,
+                        &selection,
                         &asset_server,
                         &mut texture_events,
                     );
@@ -270,7 +310,12 @@ pub fn render_right_panel(
 }
 
 /// Render layers tab content
-fn render_layers_tab(ui: &mut egui::Ui, layer_manager: &mut LayerManager) {
+fn render_layers_tab(
+    ui: &mut egui::Ui,
+    layer_manager: &mut LayerManager,
+    tile_grid: &mut TileGrid,
+    history: &mut EditorHistory,
+) {
     use crate::icons::Icons;
 
     ui.heading("Layers");
@@ -284,7 +329,9 @@ fn render_layers_tab(ui: &mut egui::Ui, layer_manager: &mut LayerManager) {
                 layer_manager.layers.len() as i32,
                 None,
             );
-            layer_manager.add_layer(new_layer);
+            let command = LayerCommand::add(layer_manager, new_layer);
+            command.apply(layer_manager, true);
+            history.add_executed(Box::new(command));
         }
     });
 
@@ -297,6 +344,19 @@ fn render_layers_tab(ui: &mut egui::Ui, layer_manager: &mut LayerManager) {
         }
     }
 
+    // Grid orientation of the map, shared by every layer
+    let mut orientation = tile_grid.orientation;
+    egui::ComboBox::from_label("Orientation")
+        .selected_text(orientation.as_str())
+        .show_ui(ui, |ui| {
+            for option in bevy_editor_tilemap::MapOrientation::ALL {
+                ui.selectable_value(&mut orientation, option, option.as_str());
+            }
+        });
+    if orientation != tile_grid.orientation {
+        tile_grid.orientation = orientation;
+    }
+
     ui.separator();
 
     // Layer list
@@ -335,6 +395,7 @@ fn render_tilesets_tab(
     ui: &mut egui::Ui,
     tileset_manager: &TilesetManager,
     tileset_zoom: &mut crate::tileset_panel::TilesetZoom,
+    import_dialog: &mut crate::tileset_panel::TilesetImportDialog,
 ) {
     use crate::icons::Icons;
 
@@ -345,8 +406,18 @@ fn render_tilesets_tab(
         .button(format!("{} Load Tileset", Icons::FOLDER_OPEN))
         .clicked()
     {
-        info!("Load tileset clicked");
-        // This will be handled by the existing tileset loading system
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Image Files", &["png", "jpg", "jpeg", "bmp"])
+            .set_title("Select Tileset Image")
+            .pick_file()
+        {
+            let identifier = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("Untitled Tileset")
+                .to_string();
+            import_dialog.open_for(path.to_string_lossy().to_string(), identifier);
+        }
     }
 
     ui.separator();
@@ -359,6 +430,12 @@ fn render_tilesets_tab(
             "Size: {}x{}",
             tileset.data.tile_width, tileset.data.tile_height
         ));
+        if tileset.data.spacing > 0 || tileset.data.padding > 0 {
+            ui.label(format!(
+                "Spacing: {}px, Margin: {}px",
+                tileset.data.spacing, tileset.data.padding
+            ));
+        }
 
         ui.separator();
 
@@ -3,8 +3,9 @@
 use crate::icons::Icons;
 use crate::project_browser::{FileEntry, FileType, ProjectBrowser};
 use bevy::prelude::*;
+use bevy_editor_foundation::Selection;
 use bevy_editor_frontend_api::ProjectBrowserPanelState;
-use bevy_editor_scene::{EditorScene, SpriteTextureEvent};
+use bevy_editor_scene::SpriteTextureEvent;
 use bevy_egui::egui;
 
 /// Render the project browser panel
@@ -12,7 +13,7 @@ pub fn project_browser_panel_ui(
     ui: &mut egui::Ui,
     browser: &mut ProjectBrowser,
     panel: &mut ProjectBrowserPanelState,
-    editor_scene: &EditorScene,
+    selection: &Selection,
     asset_server: &AssetServer,
     texture_events: &mut bevy::ecs::event::EventWriter<SpriteTextureEvent>,
 ) {
@@ -87,7 +88,7 @@ pub fn project_browser_panel_ui(
                 browser,
                 &entries_clone,
                 0,
-                editor_scene,
+                selection,
                 asset_server,
                 texture_events,
             );
@@ -112,7 +113,7 @@ fn render_file_tree(
     browser: &mut ProjectBrowser,
     entries: &[FileEntry],
     depth: usize,
-    editor_scene: &EditorScene,
+    selection: &Selection,
     asset_server: &AssetServer,
     texture_events: &mut bevy::ecs::event::EventWriter<SpriteTextureEvent>,
 ) {
@@ -122,7 +123,7 @@ fn render_file_tree(
             browser,
             entry,
             depth,
-            editor_scene,
+            selection,
             asset_server,
             texture_events,
         );
@@ -135,7 +136,7 @@ fn render_file_entry(
     browser: &mut ProjectBrowser,
     entry: &FileEntry,
     depth: usize,
-    editor_scene: &EditorScene,
+    selection: &Selection,
     asset_server: &AssetServer,
     texture_events: &mut bevy::ecs::event::EventWriter<SpriteTextureEvent>,
 ) {
@@ -195,27 +196,25 @@ fn render_file_entry(
             info!("Selected: {:?}", entry.path);
         }
 
-        // Double-click handler for images - assign to selected sprite
+        // Double-click handler for images - assign to selected sprites
         if name_response.double_clicked() && entry.file_type == FileType::Image {
-            if let Some(selected_entity) = editor_scene.selected_entity {
+            if selection.is_empty() {
+                warn!("Double-clicked image but no entity selected");
+            } else {
                 // Load texture using absolute path since the AssetServer needs full path for user project assets
                 let texture_path = entry.path.to_string_lossy().to_string().replace('\\', "/");
 
                 info!("Loading texture from absolute path: '{}'", texture_path);
                 let texture_handle: Handle<Image> = asset_server.load(&texture_path);
 
-                // Send event to assign texture to sprite
-                texture_events.write(SpriteTextureEvent {
-                    entity: selected_entity,
-                    texture_handle,
-                });
-
-                info!(
-                    "Assigning texture '{}' to sprite {:?}",
-                    entry.name, selected_entity
-                );
-            } else {
-                warn!("Double-clicked image but no entity selected");
+                // Send events to assign texture to sprites
+                for entity in selection.iter() {
+                    texture_events.write(SpriteTextureEvent {
+                        entity,
+                        texture_handle: texture_handle.clone(),
+                    });
+                    info!("Assigning texture '{}' to sprite {:?}", entry.name, entity);
+                }
             }
         }
 
@@ -244,7 +243,7 @@ fn render_file_entry(
             browser,
             &entry.children,
             depth + 1,
-            editor_scene,
+            selection,
             asset_server,
             texture_events,
         );
@@ -0,0 +1,1463 @@
+//! Reflection-driven component inspector
+//!
+//! Every component of the selected entity is looked up in the
+//! [`AppTypeRegistry`] and edited through its reflected fields. Each frame the
+//! components are cloned into [`ReflectInspectorState`], the inspector edits
+//! the clones, and changed ones are written back as [`ReflectComponentCommand`]s
+//! so they can be undone. [`InspectorWidgets`] overrides the editor of a type
+//! (or hides it) and ships widgets for vectors, rotations and colors.
+//!
+//! With several entities selected, only the components they all share are
+//! shown, fields whose values differ are marked, and an edited field is
+//! written to every selected entity as one undo step.
+//!
+//! Components of the game's own types live in [`GameComponents`] as scene RON;
+//! they are read through the types the game exported ([`ImportedTypes`]) into
+//! dynamic values, edited the same way, and written back as RON.
+
+use bevy::asset::{LoadedUntypedAsset, ReflectHandle, UntypedHandle};
+use bevy::ecs::component::ComponentId;
+use bevy::ecs::reflect::ReflectFromWorld;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::reflect::{
+    DynamicEnum, DynamicStruct, DynamicTuple, DynamicVariant, Enum, ReflectMut, ReflectRef,
+    TypeInfo, TypeRegistry, VariantInfo,
+};
+use bevy::render::sync_world::SyncToRenderWorld;
+use bevy::render::view::VisibilityClass;
+use bevy_editor_commands::EditorHistory;
+use bevy_editor_formats::{EntityFields, GameComponents, TypeSchemas};
+use bevy_editor_foundation::Selection;
+use bevy_editor_scene::{EditorEntityId, EditorSceneEntity, EditorViewState, ImportedTypes};
+use bevy_egui::egui;
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+
+use crate::component_registry::ComponentInfo;
+use crate::editor_commands::{ReflectComponentChange, ReflectComponentCommand};
+use crate::icons::Icons;
+
+/// Editor for one type, given the value as a reflected reference
+type WidgetFn = Box<dyn Fn(&mut egui::Ui, &mut dyn PartialReflect) -> bool + Send + Sync>;
+
+/// Per-type widget overrides for the reflection inspector
+#[derive(Resource)]
+pub struct InspectorWidgets {
+    widgets: HashMap<TypeId, WidgetFn>,
+    /// Components left out of the inspector, e.g. those with a dedicated editor
+    hidden: HashSet<TypeId>,
+}
+
+impl Default for InspectorWidgets {
+    fn default() -> Self {
+        let mut widgets = Self {
+            widgets: HashMap::new(),
+            hidden: HashSet::new(),
+        };
+        widgets
+            .register::<Vec2>(|ui, value| drag_floats(ui, AsMut::<[f32; 2]>::as_mut(value)))
+            .register::<Vec3>(|ui, value| drag_floats(ui, AsMut::<[f32; 3]>::as_mut(value)))
+            .register::<Vec4>(|ui, value| drag_floats(ui, AsMut::<[f32; 4]>::as_mut(value)))
+            .register::<Quat>(edit_rotation)
+            .register::<Color>(edit_color);
+
+        // Edited by the dedicated inspector sections
+        widgets
+            .hide::<Transform>()
+            .hide::<Name>()
+            .hide::<EntityFields>();
+        // Shown as the game components it holds
+        widgets.hide::<GameComponents>();
+        // Derived or bookkeeping state that isn't meant to be edited
+        widgets
+            .hide::<GlobalTransform>()
+            .hide::<InheritedVisibility>()
+            .hide::<ViewVisibility>()
+            .hide::<VisibilityClass>()
+            .hide::<SyncToRenderWorld>()
+            .hide::<ChildOf>()
+            .hide::<Children>()
+            .hide::<EditorSceneEntity>()
+            .hide::<EditorEntityId>()
+            .hide::<EditorViewState>();
+        widgets
+    }
+}
+
+impl InspectorWidgets {
+    /// Edit values of type `T` with `widget`, which returns whether it changed the value
+    pub fn register<T: FromReflect>(
+        &mut self,
+        widget: impl Fn(&mut egui::Ui, &mut T) -> bool + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.widgets.insert(
+            TypeId::of::<T>(),
+            Box::new(move |ui, value| {
+                if let Some(value) = value.try_downcast_mut::<T>() {
+                    return widget(ui, value);
+                }
+                // Dynamic representations are edited through a concrete copy
+                let Some(mut typed) = T::from_reflect(value) else {
+                    return false;
+                };
+                let changed = widget(ui, &mut typed);
+                if changed {
+                    value.apply(&typed);
+                }
+                changed
+            }),
+        );
+        self
+    }
+
+    /// Leave components of type `T` out of the inspector
+    pub fn hide<T: 'static>(&mut self) -> &mut Self {
+        self.hidden.insert(TypeId::of::<T>());
+        self
+    }
+
+    pub fn is_hidden(&self, type_id: TypeId) -> bool {
+        self.hidden.contains(&type_id)
+    }
+
+    fn get(&self, type_id: TypeId) -> Option<&WidgetFn> {
+        self.widgets.get(&type_id)
+    }
+}
+
+/// One component of the inspected entity
+pub struct InspectedComponent {
+    pub type_id: TypeId,
+    pub name: String,
+    /// Immutable components are shown but can't be edited
+    pub mutable: bool,
+    /// Working copy, `None` when the type isn't reflected or can't be cloned
+    pub value: Option<Box<dyn Reflect>>,
+    /// Set by the inspector when the working copy was edited this frame
+    pub changed: bool,
+    /// Reflect paths of the fields whose values differ between the selected
+    /// entities
+    pub mixed: HashSet<String>,
+}
+
+/// Component of a game type, kept in [`GameComponents`]
+pub struct InspectedGameComponent {
+    pub type_path: String,
+    pub name: String,
+    /// Working copy read through the game's exported types, or why it can't be
+    pub value: Result<Box<dyn PartialReflect>, String>,
+    /// Set by the inspector when the working copy was edited this frame
+    pub changed: bool,
+}
+
+/// Handle field the user pointed at a new asset path
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandleRequest {
+    pub entity: Entity,
+    pub component: TypeId,
+    /// Reflect path of the handle inside the component
+    pub field_path: String,
+    pub asset_path: String,
+}
+
+/// Working copies of the selected entity's reflected components
+#[derive(Resource, Default)]
+pub struct ReflectInspectorState {
+    pub entity: Option<Entity>,
+    /// The rest of the selection, edited along with `entity`
+    pub others: Vec<Entity>,
+    pub components: Vec<InspectedComponent>,
+    pub game_components: Vec<InspectedGameComponent>,
+    /// Every component type all selected entities have, including hidden ones
+    pub present: HashSet<TypeId>,
+    handle_requests: Vec<HandleRequest>,
+    /// Components to add or remove, as (entities, component)
+    additions: Vec<(Vec<Entity>, TypeId)>,
+    removals: Vec<(Vec<Entity>, TypeId)>,
+    /// Game components to add or remove, as (entity, type path)
+    game_additions: Vec<(Entity, String)>,
+    game_removals: Vec<(Entity, String)>,
+    /// Handle assignments waiting for their asset to load
+    pending_handles: Vec<(HandleRequest, Handle<LoadedUntypedAsset>)>,
+}
+
+/// Clone the primary selected entity's components into [`ReflectInspectorState`]
+pub fn snapshot_inspected_components(world: &mut World) {
+    let entity = world
+        .get_resource::<Selection>()
+        .and_then(Selection::primary)
+        .filter(|&entity| world.get_entity(entity).is_ok());
+    let others: Vec<Entity> = world
+        .get_resource::<Selection>()
+        .map(|selection| {
+            selection
+                .iter()
+                .filter(|&other| Some(other) != entity && world.get_entity(other).is_ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    let components = entity
+        .map(|entity| inspected_components(world, entity, &others))
+        .unwrap_or_default();
+    // Game components are edited one entity at a time
+    let game_components = entity
+        .filter(|_| others.is_empty())
+        .map(|entity| inspected_game_components(world, entity))
+        .unwrap_or_default();
+    let present = entity
+        .map(|entity| {
+            world
+                .entity(entity)
+                .archetype()
+                .components()
+                .filter_map(|id| world.components().get_info(id)?.type_id())
+                .filter(|&type_id| {
+                    others
+                        .iter()
+                        .all(|&other| world.entity(other).contains_type_id(type_id))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut state = world.resource_mut::<ReflectInspectorState>();
+    state.entity = entity;
+    state.others = others;
+    state.components = components;
+    state.game_components = game_components;
+    state.present = present;
+}
+
+/// Components of `entity` that all of `others` have too
+fn inspected_components(
+    world: &World,
+    entity: Entity,
+    others: &[Entity],
+) -> Vec<InspectedComponent> {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let widgets = world.resource::<InspectorWidgets>();
+    let entity_ref = world.entity(entity);
+    let component_ids: Vec<ComponentId> = entity_ref.archetype().components().collect();
+
+    let mut components: Vec<InspectedComponent> = component_ids
+        .into_iter()
+        .filter_map(|component_id| {
+            let info = world.components().get_info(component_id)?;
+            let type_id = info.type_id()?;
+            if widgets.is_hidden(type_id)
+                || !others
+                    .iter()
+                    .all(|&other| world.entity(other).contains_type_id(type_id))
+            {
+                return None;
+            }
+            let registration = registry.get(type_id);
+            let name = registration.map_or_else(
+                || short_type_name(info.name()),
+                |registration| {
+                    registration
+                        .type_info()
+                        .type_path_table()
+                        .short_path()
+                        .to_string()
+                },
+            );
+            let value = registration
+                .and_then(|registration| registration.data::<ReflectComponent>())
+                .and_then(|reflect_component| reflect_component.reflect(entity_ref))
+                .and_then(|value| value.reflect_clone().ok());
+            let mut mixed = HashSet::new();
+            if let Some(value) = &value {
+                for &other in others {
+                    if let Some(other_value) = component_value(world, &registry, other, type_id) {
+                        differing_paths(
+                            value.as_partial_reflect(),
+                            other_value.as_partial_reflect(),
+                            String::new(),
+                            &mut mixed,
+                        );
+                    }
+                }
+            }
+            Some(InspectedComponent {
+                type_id,
+                name,
+                mutable: info.mutable(),
+                value,
+                changed: false,
+                mixed,
+            })
+        })
+        .collect();
+    components.sort_by(|a, b| a.name.cmp(&b.name));
+    components
+}
+
+fn inspected_game_components(world: &World, entity: Entity) -> Vec<InspectedGameComponent> {
+    let Some(components) = world.get::<GameComponents>(entity) else {
+        return Vec::new();
+    };
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let schemas = world
+        .get_resource::<ImportedTypes>()
+        .map(|imported| &imported.schemas)
+        .filter(|schemas| !schemas.is_empty());
+
+    let mut inspected: Vec<InspectedGameComponent> = components
+        .0
+        .iter()
+        .map(|component| {
+            let value = match schemas {
+                Some(schemas) if schemas.get(&component.type_path).is_some() => schemas
+                    .read_value(&component.type_path, &component.ron, &registry)
+                    .map_err(|error| format!("Can't read the value: {error}")),
+                Some(_) => Err("Not among the game's exported types".to_string()),
+                None => Err("Run the game to export its types".to_string()),
+            };
+            InspectedGameComponent {
+                type_path: component.type_path.clone(),
+                name: game_component_name(schemas, &component.type_path),
+                value,
+                changed: false,
+            }
+        })
+        .collect();
+    inspected.sort_by(|a, b| a.name.cmp(&b.name));
+    inspected
+}
+
+fn game_component_name(schemas: Option<&TypeSchemas>, type_path: &str) -> String {
+    schemas
+        .and_then(|schemas| schemas.get(type_path))
+        .map_or_else(
+            || short_type_name(type_path),
+            |schema| schema.short_path.clone(),
+        )
+}
+
+/// Last path segment of a type name, for components without type info
+pub(crate) fn short_type_name(name: &str) -> String {
+    let (base, generics) = name.split_at(name.find('<').unwrap_or(name.len()));
+    let base = base.rsplit("::").next().unwrap_or(base);
+    if generics.is_empty() {
+        base.to_string()
+    } else {
+        format!("{base}<..>")
+    }
+}
+
+/// A component change waiting to be executed as a command, on one or more
+/// entities
+struct ComponentEdit {
+    component: TypeId,
+    name: String,
+    /// New value per entity, `None` removes the component
+    values: Vec<(Entity, Option<Box<dyn Reflect>>)>,
+}
+
+/// Write edited, added and removed components back through the history and
+/// resolve handle fields whose new asset finished loading
+pub fn apply_inspector_edits(world: &mut World) {
+    let mut edits = game_component_edits(world);
+    let changed: Vec<(TypeId, String, Box<dyn Reflect>)> = {
+        let mut state = world.resource_mut::<ReflectInspectorState>();
+        state
+            .components
+            .iter_mut()
+            .filter_map(|component| {
+                if !std::mem::take(&mut component.changed) {
+                    return None;
+                }
+                let value = component.value.as_ref()?.reflect_clone().ok()?;
+                Some((component.type_id, component.name.clone(), value))
+            })
+            .collect()
+    };
+    let state = world.resource::<ReflectInspectorState>();
+    if let Some(entity) = state.entity {
+        let others = state.others.clone();
+        let registry = world.resource::<AppTypeRegistry>().read();
+        for (component, name, value) in changed {
+            let mut values =
+                shared_edit_values(world, &registry, entity, &others, component, &*value);
+            values.insert(0, (entity, Some(value)));
+            edits.push(ComponentEdit {
+                component,
+                name,
+                values,
+            });
+        }
+    }
+    let mut state = world.resource_mut::<ReflectInspectorState>();
+    let requests = std::mem::take(&mut state.handle_requests);
+    let additions = std::mem::take(&mut state.additions);
+    let removals = std::mem::take(&mut state.removals);
+
+    if !requests.is_empty() {
+        let asset_server = world.resource::<AssetServer>().clone();
+        let mut state = world.resource_mut::<ReflectInspectorState>();
+        for request in requests {
+            let handle = asset_server.load_untyped(&request.asset_path);
+            state.pending_handles.push((request, handle));
+        }
+    }
+    edits.extend(resolve_pending_handles(world));
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    for (entities, component) in additions {
+        let values: Vec<_> = entities
+            .into_iter()
+            .filter_map(|entity| {
+                Some((
+                    entity,
+                    Some(default_component(world, &registry, component)?),
+                ))
+            })
+            .collect();
+        if values.is_empty() {
+            warn!("Component {:?} can't be default-constructed", component);
+            continue;
+        }
+        edits.push(ComponentEdit {
+            component,
+            name: short_name(&registry.read(), component),
+            values,
+        });
+    }
+    edits.extend(
+        removals
+            .into_iter()
+            .map(|(entities, component)| ComponentEdit {
+                component,
+                name: short_name(&registry.read(), component),
+                values: entities.into_iter().map(|entity| (entity, None)).collect(),
+            }),
+    );
+
+    if edits.is_empty() || !world.contains_resource::<EditorHistory>() {
+        return;
+    }
+
+    world.resource_scope(|world, mut history: Mut<EditorHistory>| {
+        for edit in edits {
+            let changes: Vec<ReflectComponentChange> = edit
+                .values
+                .into_iter()
+                .filter_map(|(entity, new_value)| {
+                    let id = *world.get::<EditorEntityId>(entity)?;
+                    let old_value =
+                        component_value(world, &registry.read(), entity, edit.component);
+                    let unchanged = match (&old_value, &new_value) {
+                        (Some(old), Some(new)) => {
+                            old.reflect_partial_eq(new.as_partial_reflect()) == Some(true)
+                        }
+                        (old, new) => old.is_none() && new.is_none(),
+                    };
+                    (!unchanged).then_some(ReflectComponentChange {
+                        entity: id,
+                        old_value,
+                        new_value,
+                    })
+                })
+                .collect();
+            if changes.is_empty() {
+                continue;
+            }
+            history.execute(
+                Box::new(ReflectComponentCommand::for_entities(
+                    edit.component,
+                    edit.name,
+                    changes,
+                )),
+                world,
+            );
+        }
+    });
+}
+
+/// New values of `component` on `others`, with the fields that `value`
+/// changed on `entity` copied over and the rest left as they are
+fn shared_edit_values(
+    world: &World,
+    registry: &TypeRegistry,
+    entity: Entity,
+    others: &[Entity],
+    component: TypeId,
+    value: &dyn Reflect,
+) -> Vec<(Entity, Option<Box<dyn Reflect>>)> {
+    let Some(old) = component_value(world, registry, entity, component) else {
+        return Vec::new();
+    };
+    let mut changed_paths = HashSet::new();
+    differing_paths(
+        old.as_partial_reflect(),
+        value.as_partial_reflect(),
+        String::new(),
+        &mut changed_paths,
+    );
+    others
+        .iter()
+        .filter_map(|&other| {
+            let mut other_value = component_value(world, registry, other, component)?;
+            if changed_paths.contains("") {
+                // The component differs as a whole, e.g. an opaque value
+                if let Err(error) = other_value.try_apply(value.as_partial_reflect()) {
+                    warn!("Could not copy the component to {:?}: {}", other, error);
+                }
+                return Some((other, Some(other_value)));
+            }
+            for path in &changed_paths {
+                let Ok(field) = value.reflect_path(path.as_str()) else {
+                    continue;
+                };
+                if let Ok(target) = other_value.reflect_path_mut(path.as_str()) {
+                    if let Err(error) = target.try_apply(field) {
+                        warn!("Could not copy {} to {:?}: {}", path, other, error);
+                    }
+                }
+            }
+            Some((other, Some(other_value)))
+        })
+        .collect()
+}
+
+/// Collect the reflect paths, below `path`, of the leaf fields that differ
+/// between `a` and `b`. Values whose shapes differ (another enum variant, a
+/// list of another length) count as one differing field.
+fn differing_paths(
+    a: &dyn PartialReflect,
+    b: &dyn PartialReflect,
+    path: String,
+    paths: &mut HashSet<String>,
+) {
+    if a.reflect_partial_eq(b) == Some(true) {
+        return;
+    }
+    match (a.reflect_ref(), b.reflect_ref()) {
+        (ReflectRef::Struct(a), ReflectRef::Struct(b)) => {
+            for (index, field) in a.iter_fields().enumerate() {
+                let Some(name) = a.name_at(index) else {
+                    continue;
+                };
+                if let Some(other) = b.field(name) {
+                    differing_paths(field, other, format!("{path}.{name}"), paths);
+                }
+            }
+        }
+        (ReflectRef::TupleStruct(a), ReflectRef::TupleStruct(b)) => {
+            for (index, (field, other)) in a.iter_fields().zip(b.iter_fields()).enumerate() {
+                differing_paths(field, other, format!("{path}.{index}"), paths);
+            }
+        }
+        (ReflectRef::Tuple(a), ReflectRef::Tuple(b)) => {
+            for (index, (field, other)) in a.iter_fields().zip(b.iter_fields()).enumerate() {
+                differing_paths(field, other, format!("{path}.{index}"), paths);
+            }
+        }
+        (ReflectRef::Array(a), ReflectRef::Array(b)) if a.len() == b.len() => {
+            for (index, (item, other)) in a.iter().zip(b.iter()).enumerate() {
+                differing_paths(item, other, format!("{path}[{index}]"), paths);
+            }
+        }
+        (ReflectRef::List(a), ReflectRef::List(b)) if a.len() == b.len() => {
+            for (index, (item, other)) in a.iter().zip(b.iter()).enumerate() {
+                differing_paths(item, other, format!("{path}[{index}]"), paths);
+            }
+        }
+        (ReflectRef::Enum(a), ReflectRef::Enum(b))
+            if a.variant_name() == b.variant_name() && a.field_len() > 0 =>
+        {
+            for (index, (field, other)) in a.iter_fields().zip(b.iter_fields()).enumerate() {
+                let field_path = match field.name() {
+                    Some(name) => format!("{path}.{name}"),
+                    None => format!("{path}.{index}"),
+                };
+                differing_paths(field.value(), other.value(), field_path, paths);
+            }
+        }
+        _ => {
+            paths.insert(path);
+        }
+    }
+}
+
+/// Edited, added and removed game components, as one [`GameComponents`] edit
+/// per entity
+fn game_component_edits(world: &mut World) -> Vec<ComponentEdit> {
+    world.resource_scope(|world, mut state: Mut<ReflectInspectorState>| {
+        let registry = world.resource::<AppTypeRegistry>().read();
+        let schemas = world
+            .get_resource::<ImportedTypes>()
+            .map(|imported| &imported.schemas);
+
+        // New RON per (entity, type path), `None` to remove the component
+        let mut changes: Vec<(Entity, String, Option<String>)> = Vec::new();
+        if let (Some(entity), Some(schemas)) = (state.entity, schemas) {
+            for component in &mut state.game_components {
+                if !std::mem::take(&mut component.changed) {
+                    continue;
+                }
+                let Ok(value) = &component.value else {
+                    continue;
+                };
+                match schemas.write_value(&component.type_path, value.as_ref(), &registry) {
+                    Ok(ron) => changes.push((entity, component.type_path.clone(), Some(ron))),
+                    Err(error) => warn!("Could not write {}: {}", component.type_path, error),
+                }
+            }
+        }
+        for (entity, type_path) in std::mem::take(&mut state.game_additions) {
+            let default = schemas
+                .and_then(|schemas| schemas.get(&type_path))
+                .and_then(|schema| schema.default.clone());
+            match default {
+                Some(ron) => changes.push((entity, type_path, Some(ron))),
+                None => warn!("Component {} has no default value", type_path),
+            }
+        }
+        for (entity, type_path) in std::mem::take(&mut state.game_removals) {
+            changes.push((entity, type_path, None));
+        }
+
+        let mut edits: Vec<ComponentEdit> = Vec::new();
+        for (entity, type_path, ron) in changes {
+            let name = game_component_name(schemas, &type_path);
+            let index = match edits.iter().position(|edit| edit.values[0].0 == entity) {
+                Some(index) => {
+                    // Several game components of one entity changed at once
+                    edits[index].name = "Game Components".to_string();
+                    index
+                }
+                None => {
+                    let current = world.get::<GameComponents>(entity).cloned();
+                    edits.push(ComponentEdit {
+                        component: TypeId::of::<GameComponents>(),
+                        name,
+                        values: vec![(entity, Some(Box::new(current.unwrap_or_default())))],
+                    });
+                    edits.len() - 1
+                }
+            };
+            let Some(components) = edits[index].values[0]
+                .1
+                .as_mut()
+                .and_then(|value| value.downcast_mut::<GameComponents>())
+            else {
+                continue;
+            };
+            match ron {
+                Some(ron) => components.set(&type_path, ron),
+                None => {
+                    components.remove(&type_path);
+                }
+            }
+        }
+        // Entities left without game components lose the component
+        for (_, value) in edits.iter_mut().flat_map(|edit| &mut edit.values) {
+            let empty = value
+                .as_ref()
+                .and_then(|value| value.downcast_ref::<GameComponents>())
+                .is_some_and(|components| components.0.is_empty());
+            if empty {
+                *value = None;
+            }
+        }
+        edits
+    })
+}
+
+/// A new value of `component` from its `ReflectDefault` or `ReflectFromWorld`
+fn default_component(
+    world: &mut World,
+    registry: &AppTypeRegistry,
+    component: TypeId,
+) -> Option<Box<dyn Reflect>> {
+    let (default, from_world) = {
+        let registry = registry.read();
+        let registration = registry.get(component)?;
+        (
+            registration.data::<ReflectDefault>().cloned(),
+            registration.data::<ReflectFromWorld>().cloned(),
+        )
+    };
+    default
+        .map(|default| default.default())
+        .or_else(|| from_world.map(|from_world| from_world.from_world(world)))
+}
+
+fn short_name(registry: &TypeRegistry, type_id: TypeId) -> String {
+    registry
+        .get(type_id)
+        .map(|registration| registration.type_info().type_path_table().short_path())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Component edits for the pending handle assignments whose asset has loaded
+fn resolve_pending_handles(world: &mut World) -> Vec<ComponentEdit> {
+    let pending = std::mem::take(
+        &mut world
+            .resource_mut::<ReflectInspectorState>()
+            .pending_handles,
+    );
+    if pending.is_empty() {
+        return Vec::new();
+    }
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let mut edits = Vec::new();
+    let mut still_pending = Vec::new();
+    for (request, handle) in pending {
+        let loaded = world
+            .resource::<Assets<LoadedUntypedAsset>>()
+            .get(&handle)
+            .map(|loaded| loaded.handle.clone());
+        let Some(loaded) = loaded else {
+            if world
+                .resource::<AssetServer>()
+                .load_state(&handle)
+                .is_failed()
+            {
+                warn!("Could not load asset '{}'", request.asset_path);
+            } else {
+                still_pending.push((request, handle));
+            }
+            continue;
+        };
+        match assign_handle(world, &registry, &request, loaded) {
+            Ok(value) => edits.push(ComponentEdit {
+                component: request.component,
+                name: short_name(&registry, request.component),
+                values: vec![(request.entity, Some(value))],
+            }),
+            Err(message) => warn!("Could not assign '{}': {}", request.asset_path, message),
+        }
+    }
+    world
+        .resource_mut::<ReflectInspectorState>()
+        .pending_handles
+        .extend(still_pending);
+    edits
+}
+
+/// The component value with the handle at `request.field_path` replaced by `loaded`
+fn assign_handle(
+    world: &World,
+    registry: &TypeRegistry,
+    request: &HandleRequest,
+    loaded: UntypedHandle,
+) -> Result<Box<dyn Reflect>, String> {
+    let mut value = component_value(world, registry, request.entity, request.component)
+        .ok_or("the component is gone")?;
+    let field = value
+        .reflect_path_mut(request.field_path.as_str())
+        .map_err(|error| error.to_string())?;
+    let handle_type = field
+        .get_represented_type_info()
+        .map(TypeInfo::type_id)
+        .ok_or("the field has no type info")?;
+    let reflect_handle = registry
+        .get_type_data::<ReflectHandle>(handle_type)
+        .ok_or("the field is not a handle")?;
+    if loaded.type_id() != reflect_handle.asset_type_id() {
+        return Err("the asset has the wrong type".to_string());
+    }
+    field.apply(reflect_handle.typed(loaded).as_partial_reflect());
+    Ok(value)
+}
+
+/// Clone of a reflected component of `entity`
+fn component_value(
+    world: &World,
+    registry: &TypeRegistry,
+    entity: Entity,
+    type_id: TypeId,
+) -> Option<Box<dyn Reflect>> {
+    let reflect_component = registry.get_type_data::<ReflectComponent>(type_id)?;
+    reflect_component
+        .reflect(world.get_entity(entity).ok()?)?
+        .reflect_clone()
+        .ok()
+}
+
+/// Inspector section listing the reflected components of the selected entity
+#[derive(SystemParam)]
+pub struct ReflectInspector<'w> {
+    state: ResMut<'w, ReflectInspectorState>,
+    widgets: Res<'w, InspectorWidgets>,
+    registry: Res<'w, AppTypeRegistry>,
+}
+
+impl ReflectInspector<'_> {
+    /// Add a default `component` to the inspected entities that lack it.
+    /// Game components are only added to a single selected entity.
+    pub fn add_component(&mut self, component: &ComponentInfo) {
+        let Some(entity) = self.state.entity else {
+            return;
+        };
+        if component.imported {
+            if self.state.others.is_empty() {
+                self.state
+                    .game_additions
+                    .push((entity, component.type_path.clone()));
+            }
+        } else {
+            let entities = self.selected().collect();
+            self.state.additions.push((entities, component.type_id));
+        }
+    }
+
+    /// The primary entity followed by the rest of the selection
+    fn selected(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.state
+            .entity
+            .into_iter()
+            .chain(self.state.others.iter().copied())
+    }
+
+    pub fn has_component(&self, component: &ComponentInfo) -> bool {
+        if component.imported {
+            self.state
+                .game_components
+                .iter()
+                .any(|game_component| game_component.type_path == component.type_path)
+        } else {
+            self.state.present.contains(&component.type_id)
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, entity: Entity) {
+        if self.state.entity != Some(entity) {
+            return;
+        }
+        let selected: Vec<Entity> = self.selected().collect();
+        let registry = self.registry.read();
+        let state = &mut *self.state;
+        for component in &mut state.components {
+            ui.push_id(&component.name, |ui| {
+                egui::CollapsingHeader::new(&component.name)
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        let Some(value) = component.value.as_mut() else {
+                            ui.weak("Not reflected");
+                            return;
+                        };
+                        let mut context = WalkContext {
+                            widgets: &self.widgets,
+                            registry: &registry,
+                            handle_requests: Vec::new(),
+                            mixed: Some(&component.mixed),
+                        };
+                        let changed = ui
+                            .add_enabled_ui(component.mutable, |ui| {
+                                edit_reflect(
+                                    ui,
+                                    value.as_partial_reflect_mut(),
+                                    &mut context,
+                                    Some(""),
+                                )
+                            })
+                            .inner;
+                        component.changed |= changed;
+                        state
+                            .handle_requests
+                            .extend(context.handle_requests.into_iter().map(
+                                |(field_path, asset_path)| HandleRequest {
+                                    entity,
+                                    component: component.type_id,
+                                    field_path,
+                                    asset_path,
+                                },
+                            ));
+                    })
+                    .header_response
+                    .context_menu(|ui| {
+                        let removable = component.value.is_some() && component.mutable;
+                        if ui
+                            .add_enabled(removable, egui::Button::new("Remove Component"))
+                            .clicked()
+                        {
+                            state.removals.push((selected.clone(), component.type_id));
+                            ui.close_menu();
+                        }
+                    });
+            });
+        }
+
+        for component in &mut state.game_components {
+            ui.push_id(&component.type_path, |ui| {
+                egui::CollapsingHeader::new(&component.name)
+                    .default_open(true)
+                    .show(ui, |ui| match &mut component.value {
+                        Ok(value) => {
+                            let mut context = WalkContext {
+                                widgets: &self.widgets,
+                                registry: &registry,
+                                handle_requests: Vec::new(),
+                                mixed: None,
+                            };
+                            component.changed |=
+                                edit_reflect(ui, value.as_mut(), &mut context, None);
+                        }
+                        Err(message) => {
+                            ui.weak(message.as_str());
+                        }
+                    })
+                    .header_response
+                    .on_hover_text(&component.type_path)
+                    .context_menu(|ui| {
+                        if ui.button("Remove Component").clicked() {
+                            state
+                                .game_removals
+                                .push((entity, component.type_path.clone()));
+                            ui.close_menu();
+                        }
+                    });
+            });
+        }
+    }
+}
+
+/// What the editors need besides the value being edited
+struct WalkContext<'a> {
+    widgets: &'a InspectorWidgets,
+    registry: &'a TypeRegistry,
+    /// Handle fields given a new asset path, as (reflect path, asset path)
+    handle_requests: Vec<(String, String)>,
+    /// Reflect paths of fields that differ between the selected entities
+    mixed: Option<&'a HashSet<String>>,
+}
+
+impl WalkContext<'_> {
+    /// Whether the field at `path`, or any field inside it, differs between
+    /// the selected entities
+    fn is_mixed(&self, path: Option<&str>) -> bool {
+        let (Some(mixed), Some(path)) = (self.mixed, path) else {
+            return false;
+        };
+        mixed.iter().any(|mixed| {
+            mixed
+                .strip_prefix(path)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+        })
+    }
+}
+
+/// Edit a value that isn't part of a local component, like a component of the
+/// running game. Handles are read-only. Returns whether the value changed.
+pub(crate) fn edit_detached_value(
+    ui: &mut egui::Ui,
+    value: &mut dyn PartialReflect,
+    widgets: &InspectorWidgets,
+    registry: &TypeRegistry,
+) -> bool {
+    let mut context = WalkContext {
+        widgets,
+        registry,
+        handle_requests: Vec::new(),
+        mixed: None,
+    };
+    edit_reflect(ui, value, &mut context, None)
+}
+
+/// Edit any reflected value. `path` is the value's reflect path inside its
+/// component, `None` where none exists (map values), which makes handles
+/// read-only. Returns whether the value changed.
+fn edit_reflect(
+    ui: &mut egui::Ui,
+    value: &mut dyn PartialReflect,
+    context: &mut WalkContext,
+    path: Option<&str>,
+) -> bool {
+    if let Some(type_id) = value.get_represented_type_info().map(TypeInfo::type_id) {
+        if let Some(widget) = context.widgets.get(type_id) {
+            return widget(ui, value);
+        }
+        if let Some(reflect_handle) = context.registry.get_type_data::<ReflectHandle>(type_id) {
+            edit_handle(ui, value, reflect_handle, context, path);
+            return false;
+        }
+    }
+
+    let mut changed = false;
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            for index in 0..value.field_len() {
+                let name = value.name_at(index).unwrap_or_default().to_string();
+                if let Some(field) = value.field_at_mut(index) {
+                    let field_path = path.map(|path| format!("{path}.{name}"));
+                    changed |= edit_field(ui, &name, field, context, field_path.as_deref());
+                }
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_mut(index) {
+                    let field_path = path.map(|path| format!("{path}.{index}"));
+                    let label = index.to_string();
+                    changed |= edit_field(ui, &label, field, context, field_path.as_deref());
+                }
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_mut(index) {
+                    let field_path = path.map(|path| format!("{path}.{index}"));
+                    let label = index.to_string();
+                    changed |= edit_field(ui, &label, field, context, field_path.as_deref());
+                }
+            }
+        }
+        ReflectMut::List(list) => {
+            let mut remove = None;
+            for index in 0..list.len() {
+                let Some(item) = list.get_mut(index) else {
+                    continue;
+                };
+                let item_path = path.map(|path| format!("{path}[{index}]"));
+                ui.horizontal(|ui| {
+                    if ui
+                        .small_button(Icons::CLOSE)
+                        .on_hover_text("Remove")
+                        .clicked()
+                    {
+                        remove = Some(index);
+                    }
+                    changed |=
+                        edit_field(ui, &index.to_string(), item, context, item_path.as_deref());
+                });
+            }
+            if let Some(index) = remove {
+                list.remove(index);
+                changed = true;
+            }
+            let item_default = match list.get_represented_type_info() {
+                Some(TypeInfo::List(info)) => context
+                    .registry
+                    .get_type_data::<ReflectDefault>(info.item_ty().id()),
+                _ => None,
+            };
+            if let Some(item_default) = item_default {
+                if ui.small_button(Icons::NEW).on_hover_text("Add").clicked() {
+                    list.push(item_default.default().into_partial_reflect());
+                    changed = true;
+                }
+            }
+        }
+        ReflectMut::Array(array) => {
+            for index in 0..array.len() {
+                if let Some(item) = array.get_mut(index) {
+                    let item_path = path.map(|path| format!("{path}[{index}]"));
+                    changed |=
+                        edit_field(ui, &index.to_string(), item, context, item_path.as_deref());
+                }
+            }
+        }
+        ReflectMut::Map(map) => {
+            for index in 0..map.len() {
+                if let Some((key, value)) = map.get_at_mut(index) {
+                    changed |= edit_field(ui, &format!("{key:?}"), value, context, None);
+                }
+            }
+        }
+        ReflectMut::Set(set) => {
+            for value in set.iter() {
+                ui.label(format!("{value:?}"));
+            }
+        }
+        ReflectMut::Enum(value) => {
+            changed |= edit_enum(ui, value, context, path);
+        }
+        ReflectMut::Opaque(value) => {
+            changed |= edit_opaque(ui, value);
+        }
+    }
+    changed
+}
+
+/// A labelled field: leaf values sit next to their label, nested values
+/// get a collapsing header
+fn edit_field(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut dyn PartialReflect,
+    context: &mut WalkContext,
+    path: Option<&str>,
+) -> bool {
+    // Fields that differ between the selected entities are marked with a dash
+    let mixed = context.is_mixed(path);
+    let text = if mixed {
+        format!("{label} —")
+    } else {
+        label.to_string()
+    };
+    if is_leaf(value, context) {
+        ui.horizontal(|ui| {
+            let response = ui.label(text);
+            if mixed {
+                response.on_hover_text("Differs between the selected entities");
+            }
+            edit_reflect(ui, value, context, path)
+        })
+        .inner
+    } else {
+        egui::CollapsingHeader::new(text)
+            .id_salt((label, path))
+            .show(ui, |ui| edit_reflect(ui, value, context, path))
+            .body_returned
+            .unwrap_or(false)
+    }
+}
+
+fn is_leaf(value: &dyn PartialReflect, context: &WalkContext) -> bool {
+    let has_widget = value
+        .get_represented_type_info()
+        .map(TypeInfo::type_id)
+        .is_some_and(|type_id| {
+            context.widgets.get(type_id).is_some()
+                || context
+                    .registry
+                    .get_type_data::<ReflectHandle>(type_id)
+                    .is_some()
+        });
+    has_widget
+        || match value.reflect_ref() {
+            ReflectRef::Opaque(_) => true,
+            ReflectRef::Enum(value) => value.field_len() == 0,
+            _ => false,
+        }
+}
+
+/// Variant picker plus the fields of the current variant
+fn edit_enum(
+    ui: &mut egui::Ui,
+    value: &mut dyn Enum,
+    context: &mut WalkContext,
+    path: Option<&str>,
+) -> bool {
+    let mut changed = false;
+    let current = value.variant_name().to_string();
+    if let Some(TypeInfo::Enum(info)) = value.get_represented_type_info() {
+        let mut selected = None;
+        egui::ComboBox::from_id_salt(("variant", path, info.type_path()))
+            .selected_text(&current)
+            .show_ui(ui, |ui| {
+                for variant in info.iter() {
+                    // Variants whose fields have no default can't be switched to
+                    let default = default_variant(variant, context.registry);
+                    let label =
+                        egui::SelectableLabel::new(variant.name() == current, variant.name());
+                    if ui.add_enabled(default.is_some(), label).clicked() {
+                        selected = default;
+                    }
+                }
+            });
+        if let Some(variant) = selected.filter(|variant| variant.variant_name() != current) {
+            value.apply(&variant);
+            changed = true;
+        }
+    } else {
+        ui.label(&current);
+    }
+
+    for index in 0..value.field_len() {
+        let name = value
+            .name_at(index)
+            .map_or_else(|| index.to_string(), str::to_string);
+        if let Some(field) = value.field_at_mut(index) {
+            let field_path = path.map(|path| format!("{path}.{name}"));
+            changed |= edit_field(ui, &name, field, context, field_path.as_deref());
+        }
+    }
+    changed
+}
+
+/// `variant` with every field set to its type's default
+fn default_variant(variant: &VariantInfo, registry: &TypeRegistry) -> Option<DynamicEnum> {
+    let default_of = |type_id: TypeId| {
+        registry
+            .get_type_data::<ReflectDefault>(type_id)
+            .map(|default| default.default().into_partial_reflect())
+    };
+    let dynamic_variant = match variant {
+        VariantInfo::Unit(_) => DynamicVariant::Unit,
+        VariantInfo::Tuple(info) => {
+            let mut tuple = DynamicTuple::default();
+            for field in info.iter() {
+                tuple.insert_boxed(default_of(field.type_id())?);
+            }
+            DynamicVariant::Tuple(tuple)
+        }
+        VariantInfo::Struct(info) => {
+            let mut fields = DynamicStruct::default();
+            for field in info.iter() {
+                fields.insert_boxed(field.name(), default_of(field.type_id())?);
+            }
+            DynamicVariant::Struct(fields)
+        }
+    };
+    Some(DynamicEnum::new(variant.name(), dynamic_variant))
+}
+
+/// Primitives, strings and a read-only fallback for other opaque types
+fn edit_opaque(ui: &mut egui::Ui, value: &mut dyn PartialReflect) -> bool {
+    macro_rules! drag_value {
+        ($($ty:ty),*) => {$(
+            if let Some(value) = value.try_downcast_mut::<$ty>() {
+                return ui.add(egui::DragValue::new(value)).changed();
+            }
+        )*};
+    }
+    drag_value!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+    if let Some(value) = value.try_downcast_mut::<bool>() {
+        return ui.checkbox(value, "").changed();
+    }
+    if let Some(value) = value.try_downcast_mut::<String>() {
+        return ui.text_edit_singleline(value).changed();
+    }
+    ui.weak(format!("{value:?}"));
+    false
+}
+
+/// Asset path of a handle; entering a new path loads that asset and assigns
+/// it once loaded
+fn edit_handle(
+    ui: &mut egui::Ui,
+    value: &mut dyn PartialReflect,
+    reflect_handle: &ReflectHandle,
+    context: &mut WalkContext,
+    path: Option<&str>,
+) {
+    let current = value
+        .try_as_reflect()
+        .and_then(|value| reflect_handle.downcast_handle_untyped(value.as_any()))
+        .and_then(|handle| handle.path().map(ToString::to_string));
+    let Some(path) = path else {
+        ui.label(current.as_deref().unwrap_or("<unnamed asset>"));
+        return;
+    };
+
+    // The typed text lives in egui memory while the field has focus
+    let buffer_id = ui.make_persistent_id(("handle", path));
+    let mut text = ui
+        .data(|data| data.get_temp::<String>(buffer_id))
+        .or_else(|| current.clone())
+        .unwrap_or_default();
+    let response = ui
+        .text_edit_singleline(&mut text)
+        .on_hover_text("Asset path, press Enter to load");
+    if response.has_focus() {
+        ui.data_mut(|data| data.insert_temp(buffer_id, text.clone()));
+    } else {
+        ui.data_mut(|data| data.remove::<String>(buffer_id));
+    }
+    let submitted = response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+    if submitted && !text.is_empty() && current.as_ref() != Some(&text) {
+        context.handle_requests.push((path.to_string(), text));
+    }
+}
+
+fn drag_floats(ui: &mut egui::Ui, values: &mut [f32]) -> bool {
+    const AXES: [&str; 4] = ["X: ", "Y: ", "Z: ", "W: "];
+    let mut changed = false;
+    for (value, axis) in values.iter_mut().zip(AXES) {
+        changed |= ui
+            .add(egui::DragValue::new(value).speed(0.1).prefix(axis))
+            .changed();
+    }
+    changed
+}
+
+/// Rotations are edited as XYZ euler angles in degrees
+fn edit_rotation(ui: &mut egui::Ui, rotation: &mut Quat) -> bool {
+    let (x, y, z) = rotation.to_euler(EulerRot::XYZ);
+    let mut degrees = [x.to_degrees(), y.to_degrees(), z.to_degrees()];
+    let changed = drag_floats(ui, &mut degrees);
+    if changed {
+        let [x, y, z] = degrees.map(f32::to_radians);
+        *rotation = Quat::from_euler(EulerRot::XYZ, x, y, z);
+    }
+    changed
+}
+
+fn edit_color(ui: &mut egui::Ui, color: &mut Color) -> bool {
+    let mut rgba = color.to_srgba().to_f32_array();
+    let changed = ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed();
+    if changed {
+        *color = Color::srgba(rgba[0], rgba[1], rgba[2], rgba[3]);
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_editor_commands::EditorHistory;
+    use bevy_editor_scene::EditorScene;
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Component, Default)]
+    struct Health {
+        current: f32,
+        regenerates: bool,
+    }
+
+    #[test]
+    fn inspector_edits_are_undoable() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Health>();
+        world.init_resource::<InspectorWidgets>();
+        world.init_resource::<ReflectInspectorState>();
+        world.init_resource::<EditorHistory>();
+        world.init_resource::<EditorScene>();
+        world.init_resource::<Selection>();
+        let entity = world
+            .spawn((
+                EditorEntityId::new(),
+                Health {
+                    current: 10.0,
+                    regenerates: false,
+                },
+            ))
+            .id();
+        world.resource_mut::<Selection>().select(entity);
+
+        snapshot_inspected_components(&mut world);
+        {
+            let mut state = world.resource_mut::<ReflectInspectorState>();
+            // EditorEntityId is hidden, so only Health is inspected
+            assert_eq!(state.components.len(), 1);
+            let component = &mut state.components[0];
+            let health = component
+                .value
+                .as_mut()
+                .and_then(|value| value.downcast_mut::<Health>())
+                .expect("health is reflected");
+            health.current = 4.0;
+            component.changed = true;
+        }
+        apply_inspector_edits(&mut world);
+        assert_eq!(world.get::<Health>(entity).unwrap().current, 4.0);
+
+        world.resource_scope(|world, mut history: Mut<EditorHistory>| history.undo(world));
+        assert_eq!(world.get::<Health>(entity).unwrap().current, 10.0);
+    }
+
+    #[test]
+    fn added_components_are_removed_on_undo() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Health>();
+        world.init_resource::<InspectorWidgets>();
+        world.init_resource::<ReflectInspectorState>();
+        world.init_resource::<EditorHistory>();
+        world.init_resource::<EditorScene>();
+        world.init_resource::<Selection>();
+        let entity = world.spawn(EditorEntityId::new()).id();
+        world.resource_mut::<Selection>().select(entity);
+
+        snapshot_inspected_components(&mut world);
+        let mut state = world.resource_mut::<ReflectInspectorState>();
+        state.additions.push((vec![entity], TypeId::of::<Health>()));
+        apply_inspector_edits(&mut world);
+        assert_eq!(world.get::<Health>(entity), Some(&Health::default()));
+
+        world.resource_scope(|world, mut history: Mut<EditorHistory>| history.undo(world));
+        assert!(world.get::<Health>(entity).is_none());
+    }
+
+    #[test]
+    fn shared_fields_edit_the_whole_selection_as_one_step() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Health>();
+        world.init_resource::<InspectorWidgets>();
+        world.init_resource::<ReflectInspectorState>();
+        world.init_resource::<EditorHistory>();
+        world.init_resource::<EditorScene>();
+        world.init_resource::<Selection>();
+        let first = world
+            .spawn((
+                EditorEntityId::new(),
+                Health {
+                    current: 10.0,
+                    regenerates: false,
+                },
+            ))
+            .id();
+        let second = world
+            .spawn((
+                EditorEntityId::new(),
+                Health {
+                    current: 7.0,
+                    regenerates: false,
+                },
+            ))
+            .id();
+        world.resource_mut::<Selection>().set([first, second]);
+
+        snapshot_inspected_components(&mut world);
+        {
+            let mut state = world.resource_mut::<ReflectInspectorState>();
+            let component = &mut state.components[0];
+            assert_eq!(component.mixed, HashSet::from([".current".to_string()]));
+            let health = component
+                .value
+                .as_mut()
+                .and_then(|value| value.downcast_mut::<Health>())
+                .expect("health is reflected");
+            health.regenerates = true;
+            component.changed = true;
+        }
+        apply_inspector_edits(&mut world);
+        // Only the edited field is shared, the differing one is kept
+        assert_eq!(
+            world.get::<Health>(second),
+            Some(&Health {
+                current: 7.0,
+                regenerates: true,
+            })
+        );
+        assert!(world.get::<Health>(first).unwrap().regenerates);
+
+        world.resource_scope(|world, mut history: Mut<EditorHistory>| history.undo(world));
+        assert!(!world.get::<Health>(first).unwrap().regenerates);
+        assert!(!world.get::<Health>(second).unwrap().regenerates);
+    }
+
+    #[test]
+    fn variants_without_defaultable_fields_are_not_offered() {
+        #[derive(Reflect)]
+        enum Shape {
+            Point,
+            Circle(f32),
+            Custom(Entity),
+        }
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<Shape>();
+        registry.register::<f32>();
+        let TypeInfo::Enum(info) = Shape::Point.get_represented_type_info().unwrap() else {
+            panic!("Shape is an enum");
+        };
+        let variant = |name| default_variant(info.variant(name).unwrap(), &registry);
+
+        let mut shape = Shape::Point;
+        shape.apply(&variant("Circle").unwrap());
+        assert!(matches!(shape, Shape::Circle(radius) if radius == 0.0));
+        assert!(variant("Custom").is_none());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::icons::Icons;
 use bevy::prelude::*;
 use bevy_editor_formats::LevelData;
+use bevy_editor_foundation::Selection;
 use bevy_editor_scene::{
     sync_active_scene, EditorScene, EditorSceneEntity, OpenScene, OpenScenes, SceneTabChanged,
 };
@@ -60,27 +61,74 @@ pub fn render_scene_tabs_content(
             }
         }
 
+        // Scenes with unsaved changes are only closed after confirmation
+        let pending_close = egui::Id::new("scene_tab_pending_close");
         if let Some(index) = scene_to_close {
             if open_scenes.scenes[index].is_modified {
-                info!("Scene has unsaved changes, close anyway? (dialog not yet implemented)");
+                ui.data_mut(|data| data.insert_temp(pending_close, index));
+            } else {
+                close_scene_tab(open_scenes, index, tab_changed_events);
             }
-            open_scenes.close_scene(index);
-            tab_changed_events.write(SceneTabChanged {
-                new_index: open_scenes.active_index,
+        }
+
+        let Some(index) = ui.data(|data| data.get_temp::<usize>(pending_close)) else {
+            return;
+        };
+        let Some(name) = open_scenes
+            .scenes
+            .get(index)
+            .map(|scene| scene.name.clone())
+        else {
+            ui.data_mut(|data| data.remove::<usize>(pending_close));
+            return;
+        };
+
+        let mut decision = None;
+        egui::Window::new("Unsaved Changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ui.ctx(), |ui| {
+                ui.label(format!("'{}' has unsaved changes. Close it anyway?", name));
+                ui.horizontal(|ui| {
+                    if ui.button("Close Without Saving").clicked() {
+                        decision = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        decision = Some(false);
+                    }
+                });
             });
+
+        if let Some(close) = decision {
+            ui.data_mut(|data| data.remove::<usize>(pending_close));
+            if close {
+                close_scene_tab(open_scenes, index, tab_changed_events);
+            }
         }
     });
 }
 
+fn close_scene_tab(
+    open_scenes: &mut OpenScenes,
+    index: usize,
+    tab_changed_events: &mut EventWriter<SceneTabChanged>,
+) {
+    open_scenes.close_scene(index);
+    tab_changed_events.write(SceneTabChanged {
+        new_index: open_scenes.active_index,
+    });
+}
+
 /// System to sync [`EditorScene`] with [`OpenScenes`] when tabs change.
 pub fn sync_editor_scene_on_tab_change(
     mut tab_events: EventReader<SceneTabChanged>,
     mut commands: Commands,
     mut editor_scene: ResMut<EditorScene>,
+    mut selection: ResMut<Selection>,
     scene_entities: Query<(Entity, Option<&ChildOf>), With<EditorSceneEntity>>,
     mut name_buffer: ResMut<crate::panel_manager::NameEditBuffer>,
     open_scenes: Res<OpenScenes>,
-    asset_server: Res<AssetServer>,
 ) {
     for event in tab_events.read() {
         info!(
@@ -95,8 +143,8 @@ pub fn sync_editor_scene_on_tab_change(
         sync_active_scene(
             &mut commands,
             &mut editor_scene,
+            &mut selection,
             &open_scenes,
-            &asset_server,
             event.new_index,
             existing,
         );
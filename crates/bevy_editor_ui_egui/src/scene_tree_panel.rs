@@ -1,17 +1,23 @@
 //! Scene tree panel for viewing and editing the entity hierarchy
 
+use crate::editor_commands::{
+    CreateEntityCommand, DeleteEntityCommand, RenameEntityCommand, ReparentEntityCommand,
+};
 use crate::icons::Icons;
 use bevy::prelude::*;
+use bevy_editor_commands::{EditorCommand, EditorHistory};
+use bevy_editor_foundation::{SelectMode, Selection};
 use bevy_editor_frontend_api::scene_tree::{SceneEntityTemplate, SceneTreeCommand, SceneTreeNode};
-use bevy_editor_scene::{EditorScene, EditorSceneEntity};
+use bevy_editor_scene::{EditorEntityId, EditorScene, EditorViewState};
 use bevy_egui::egui;
 
 /// Render the scene tree panel content
 pub fn render_scene_tree_panel(
     ui: &mut egui::Ui,
-    editor_scene: &mut EditorScene,
+    editor_scene: &EditorScene,
+    selection: &mut Selection,
     entity_nodes: &[SceneTreeNode],
-    events: &mut bevy::prelude::EventWriter<SceneTreeCommand>,
+    events: &mut EventWriter<SceneTreeCommand>,
 ) {
     ui.heading("Scene Tree");
     ui.separator();
@@ -32,7 +38,7 @@ pub fn render_scene_tree_panel(
                 if ui.button(template.display_name()).clicked() {
                     events.write(SceneTreeCommand::AddTemplateEntity {
                         template,
-                        parent: editor_scene.selected_entity,
+                        parent: selection.primary(),
                     });
                     info!("Add {} entity command sent", template.display_name());
                     ui.close_menu();
@@ -40,10 +46,10 @@ pub fn render_scene_tree_panel(
             }
         });
 
-        if let Some(selected) = editor_scene.selected_entity {
-            if ui.button(format!("{} Delete", Icons::CLOSE)).clicked() {
-                events.write(SceneTreeCommand::DeleteEntity { entity: selected });
-                info!("Delete entity command sent: {:?}", selected);
+        if !selection.is_empty() && ui.button(format!("{} Delete", Icons::CLOSE)).clicked() {
+            for entity in selection.iter() {
+                events.write(SceneTreeCommand::DeleteEntity { entity });
+                info!("Delete entity command sent: {:?}", entity);
             }
         }
     });
@@ -57,7 +63,7 @@ pub fn render_scene_tree_panel(
             if let Some(root_entity) = editor_scene.root_entity {
                 // Debug: show entity count
                 ui.label(format!("Entities: {}", entity_nodes.len()));
-                render_entity_node(ui, root_entity, editor_scene, entity_nodes, 0);
+                render_entity_node(ui, root_entity, selection, entity_nodes, events, 0);
 
                 // Debug: Show all entities (temporary for debugging)
                 ui.separator();
@@ -82,8 +88,9 @@ pub fn render_scene_tree_panel(
 fn render_entity_node(
     ui: &mut egui::Ui,
     entity: Entity,
-    editor_scene: &mut EditorScene,
+    selection: &mut Selection,
     entity_nodes: &[SceneTreeNode],
+    events: &mut EventWriter<SceneTreeCommand>,
     depth: usize,
 ) {
     // Find this entity's data
@@ -104,15 +111,59 @@ fn render_entity_node(
             ui.add_space(12.0); // Space for alignment
         }
 
+        // Eye and lock toggles, which cascade to the children
+        let eye_icon = if data.hidden {
+            Icons::EYE_CLOSED
+        } else {
+            Icons::EYE
+        };
+        if ui
+            .small_button(eye_icon)
+            .on_hover_text(if data.hidden {
+                "Show in the editor"
+            } else {
+                "Hide in the editor"
+            })
+            .clicked()
+        {
+            events.write(SceneTreeCommand::SetHidden {
+                entity,
+                hidden: !data.hidden,
+            });
+        }
+        let lock_icon = if data.locked {
+            Icons::LOCK
+        } else {
+            Icons::UNLOCK
+        };
+        if ui
+            .small_button(lock_icon)
+            .on_hover_text(if data.locked {
+                "Unlock for viewport picking"
+            } else {
+                "Lock against viewport picking"
+            })
+            .clicked()
+        {
+            events.write(SceneTreeCommand::SetLocked {
+                entity,
+                locked: !data.locked,
+            });
+        }
+
         // Entity icon
         ui.label(Icons::NODE);
 
-        // Entity name (selectable)
-        let is_selected = editor_scene.is_selected(entity);
+        // Entity name (selectable, Shift adds, Ctrl toggles and Alt removes)
+        let is_selected = selection.is_selected(entity);
         let response = ui.selectable_label(is_selected, &data.name);
 
         if response.clicked() {
-            editor_scene.select_entity(entity);
+            let modifiers = ui.input(|input| input.modifiers);
+            selection.click(
+                entity,
+                SelectMode::from_modifiers(modifiers.shift, modifiers.command, modifiers.alt),
+            );
             info!("Selected entity: {} ({:?})", data.name, entity);
         }
 
@@ -123,100 +174,196 @@ fn render_entity_node(
     // Render children recursively
     if data.has_children {
         for child in &data.children {
-            render_entity_node(ui, *child, editor_scene, entity_nodes, depth + 1);
+            render_entity_node(ui, *child, selection, entity_nodes, events, depth + 1);
         }
     }
 }
 
-/// System to handle scene tree commands
-pub fn handle_scene_tree_commands(
-    mut commands: Commands,
-    mut events: EventReader<SceneTreeCommand>,
-    mut editor_scene: ResMut<EditorScene>,
-) {
-    for event in events.read() {
+/// System handling scene tree commands. Scene edits go through the history so
+/// they can be undone; lock and hide are editor-only view state and apply directly.
+pub fn handle_scene_tree_commands(world: &mut World) {
+    let events: Vec<SceneTreeCommand> = world
+        .get_resource_mut::<Events<SceneTreeCommand>>()
+        .map(|mut events| events.drain().collect())
+        .unwrap_or_default();
+
+    for event in events {
         match event {
             SceneTreeCommand::AddTemplateEntity { template, parent } => {
-                let entity =
-                    crate::entity_templates::spawn_from_template(&mut commands, *template, *parent);
-
-                // Set parent if not already done by template
-                if parent.is_none() {
-                    if let Some(root) = editor_scene.root_entity {
-                        info!("Parenting new entity {:?} to scene root {:?}", entity, root);
-                        commands.entity(entity).insert(ChildOf(root));
-                    } else {
-                        warn!(
-                            "No parent for new entity {:?} - root_entity is None!",
-                            entity
-                        );
-                    }
-                }
-
-                editor_scene.select_entity(entity);
-                editor_scene.mark_modified();
-                info!("Added new {:?} entity: {:?}", template, entity);
+                add_entity(world, template, parent);
             }
 
             SceneTreeCommand::AddEntity { parent } => {
-                let entity = commands
-                    .spawn((
-                        Name::new("New Entity"),
-                        Transform::default(),
-                        Visibility::default(),
-                        Sprite {
-                            color: Color::srgba(0.7, 0.7, 0.7, 0.8), // Gray semi-transparent
-                            custom_size: Some(Vec2::new(64.0, 64.0)),
-                            ..default()
-                        },
-                        EditorSceneEntity,
-                    ))
-                    .id();
-
-                // Set parent if specified
-                if let Some(parent_entity) = parent {
-                    info!(
-                        "Parenting new entity {:?} to selected parent {:?}",
-                        entity, parent_entity
-                    );
-                    commands.entity(entity).insert(ChildOf(*parent_entity));
-                } else if let Some(root) = editor_scene.root_entity {
-                    info!("Parenting new entity {:?} to scene root {:?}", entity, root);
-                    commands.entity(entity).insert(ChildOf(root));
-                } else {
-                    warn!(
-                        "No parent for new entity {:?} - root_entity is None!",
-                        entity
-                    );
-                }
-
-                editor_scene.select_entity(entity);
-                editor_scene.mark_modified();
-                info!("Added new entity: {:?}", entity);
+                add_entity(world, SceneEntityTemplate::Sprite, parent);
             }
 
             SceneTreeCommand::DeleteEntity { entity } => {
-                commands.entity(*entity).despawn();
-                editor_scene.clear_selection();
-                editor_scene.mark_modified();
+                // A child may go with its parent deleted just before
+                let Some(id) = world.get::<EditorEntityId>(entity).copied() else {
+                    continue;
+                };
+                execute(world, DeleteEntityCommand::new(id));
                 info!("Deleted entity: {:?}", entity);
             }
 
             SceneTreeCommand::RenameEntity { entity, new_name } => {
-                commands.entity(*entity).insert(Name::new(new_name.clone()));
-                editor_scene.mark_modified();
+                let Ok(entity_ref) = world.get_entity(entity) else {
+                    continue;
+                };
+                let Some(&id) = entity_ref.get::<EditorEntityId>() else {
+                    continue;
+                };
+                let old_name = entity_ref
+                    .get::<Name>()
+                    .map(|name| name.to_string())
+                    .unwrap_or_default();
+                if old_name == new_name {
+                    continue;
+                }
                 info!("Renamed entity {:?} to: {}", entity, new_name);
+                execute(world, RenameEntityCommand::new(id, old_name, new_name));
             }
 
             SceneTreeCommand::ReparentEntity { entity, new_parent } => {
-                if let Some(parent) = new_parent {
-                    commands.entity(*entity).insert(ChildOf(*parent));
-                } else {
-                    commands.entity(*entity).remove::<ChildOf>();
+                let Some(&id) = world.get::<EditorEntityId>(entity) else {
+                    continue;
+                };
+                let old_parent = world
+                    .get::<ChildOf>(entity)
+                    .and_then(|child_of| world.get::<EditorEntityId>(child_of.parent()))
+                    .copied();
+                let new_parent_id = match new_parent {
+                    Some(parent) => match world.get::<EditorEntityId>(parent) {
+                        Some(&parent_id) => Some(parent_id),
+                        None => continue,
+                    },
+                    None => None,
+                };
+                if new_parent_id == old_parent {
+                    continue;
                 }
-                editor_scene.mark_modified();
+                execute(
+                    world,
+                    ReparentEntityCommand::new(id, old_parent, new_parent_id),
+                );
                 info!("Reparented entity {:?} to {:?}", entity, new_parent);
             }
+
+            // View state is editor-only, so it doesn't modify the scene
+            SceneTreeCommand::SetLocked { entity, locked } => {
+                for entity in subtree(world, entity) {
+                    set_view_state(world, entity, |view_state| view_state.locked = locked);
+                }
+                info!("Set locked to {} for {:?} and its children", locked, entity);
+            }
+
+            SceneTreeCommand::SetHidden { entity, hidden } => {
+                for entity in subtree(world, entity) {
+                    if !set_view_state(world, entity, |view_state| view_state.hidden = hidden) {
+                        continue;
+                    }
+                    // Hidden entities can't be seen, so they shouldn't stay selected
+                    if hidden {
+                        world.resource_mut::<Selection>().remove(entity);
+                    }
+                }
+                info!("Set hidden to {} for {:?} and its children", hidden, entity);
+            }
+        }
+    }
+}
+
+fn add_entity(world: &mut World, template: SceneEntityTemplate, parent: Option<Entity>) {
+    // `None` places the entity under the scene root
+    let parent_id = parent.and_then(|parent| world.get::<EditorEntityId>(parent).copied());
+    execute(world, CreateEntityCommand::new(template, parent_id));
+    info!("Added new {:?} entity", template);
+}
+
+fn execute(world: &mut World, command: impl EditorCommand + 'static) {
+    world.resource_scope(|world, mut history: Mut<EditorHistory>| {
+        history.execute(Box::new(command), world);
+    });
+}
+
+/// `entity` followed by all its descendants
+fn subtree(world: &World, entity: Entity) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    let mut stack = vec![entity];
+    while let Some(entity) = stack.pop() {
+        entities.push(entity);
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter());
         }
     }
+    entities
+}
+
+/// Update the view state of `entity`, returning false if it no longer exists
+fn set_view_state(
+    world: &mut World,
+    entity: Entity,
+    edit: impl FnOnce(&mut EditorViewState),
+) -> bool {
+    let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+        return false;
+    };
+    let mut view_state = entity_mut
+        .get::<EditorViewState>()
+        .copied()
+        .unwrap_or_default();
+    edit(&mut view_state);
+    entity_mut.insert(view_state);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_editor_scene::SceneEditorPlugin;
+
+    #[test]
+    fn scene_tree_edits_are_undoable() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(SceneEditorPlugin)
+            .add_event::<SceneTreeCommand>()
+            .init_resource::<EditorHistory>()
+            .init_resource::<Selection>()
+            .add_systems(Update, handle_scene_tree_commands);
+        app.world_mut().insert_resource(Assets::<Image>::default());
+        app.update();
+
+        app.world_mut()
+            .send_event(SceneTreeCommand::AddTemplateEntity {
+                template: SceneEntityTemplate::Empty,
+                parent: None,
+            });
+        app.update();
+        let entity = app.world().resource::<Selection>().primary().unwrap();
+        app.world_mut().send_event(SceneTreeCommand::RenameEntity {
+            entity,
+            new_name: "Player".to_string(),
+        });
+        app.update();
+        assert_eq!(app.world().get::<Name>(entity).unwrap().as_str(), "Player");
+        assert_eq!(
+            app.world()
+                .resource::<EditorHistory>()
+                .get_undo_history()
+                .len(),
+            2
+        );
+
+        let world = app.world_mut();
+        world.resource_scope(|world, mut history: Mut<EditorHistory>| {
+            history.undo(world);
+            assert_eq!(
+                world.get::<Name>(entity).unwrap().as_str(),
+                SceneEntityTemplate::Empty.default_name()
+            );
+            history.undo(world);
+        });
+        assert!(world.get_entity(entity).is_err());
+    }
 }
@@ -1,3 +1,4 @@
+use crate::clipboard::ClipboardRequest;
 use bevy::prelude::*;
 use bevy_editor_foundation::{EditorState, EditorTool};
 use bevy_editor_frontend_api::EditorAction;
@@ -11,6 +12,7 @@ pub fn handle_global_shortcuts(
     mut tile_painter: ResMut<TilePainter>,
     mut contexts: bevy_egui::EguiContexts,
     mut editor_actions: EventWriter<EditorAction>,
+    mut clipboard_requests: EventWriter<ClipboardRequest>,
 ) {
     // Don't process shortcuts if typing in a text field
     let Some(ctx) = contexts.try_ctx_mut() else {
@@ -77,6 +79,17 @@ pub fn handle_global_shortcuts(
         }
     }
 
+    // Clipboard shortcuts for scene entities
+    if keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        if keyboard.just_pressed(KeyCode::KeyC) {
+            clipboard_requests.write(ClipboardRequest::Copy);
+        } else if keyboard.just_pressed(KeyCode::KeyV) {
+            clipboard_requests.write(ClipboardRequest::Paste { scene: None });
+        } else if keyboard.just_pressed(KeyCode::KeyD) {
+            clipboard_requests.write(ClipboardRequest::Duplicate);
+        }
+    }
+
     // Alt key for temporary eyedropper (handled in tile painting system)
     // Space key for pan camera (handled in camera system)
 }
@@ -1,10 +1,11 @@
 use bevy::prelude::*;
+use bevy_editor_commands::EditorHistory;
 use bevy_editor_foundation::EditorTool;
-use bevy_editor_tilemap::PaintTileEvent;
 use bevy_editor_tilemap::{
     bucket_fill, paint_line, paint_rectangle, paint_single_tile, paint_stamp, LayerManager,
-    PaintMode, TilePainter, TilesetManager,
+    PaintMode, TileGrid, TilePainter, TileStroke, TilesetManager,
 };
+use bevy_editor_tilemap::{EraseTileEvent, PaintTileEvent};
 use bevy_egui::EguiContexts;
 
 /// System to handle tile painting. This remains in the UI crate because it
@@ -15,11 +16,13 @@ pub fn handle_tile_painting(
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
     tileset_manager: Res<TilesetManager>,
+    tile_grid: Res<TileGrid>,
     mut layer_manager: ResMut<LayerManager>,
     mut tile_painter: ResMut<TilePainter>,
     editor_state: Res<bevy_editor_foundation::EditorState>,
     mut contexts: EguiContexts,
     mut paint_events: EventWriter<PaintTileEvent>,
+    mut erase_events: EventWriter<EraseTileEvent>,
 ) {
     if editor_state.current_tool != EditorTool::Platform {
         tile_painter.current_pos = None;
@@ -40,58 +43,86 @@ pub fn handle_tile_painting(
         return;
     };
 
-    let Some(selected_tile_id) = tileset_manager.get_selected_tile() else {
+    let selected_tile = tileset_manager.selected_tile_ref();
+    if selected_tile.is_none() && !tile_painter.variation.is_active() {
         tile_painter.current_pos = None;
         return;
-    };
+    }
 
-    let Some(active_layer) = layer_manager.get_active_layer() else {
+    if layer_manager.get_active_layer().is_none() {
+        tile_painter.current_pos = None;
+        return;
+    }
+
+    let Some((tile_x, tile_y)) = tile_grid.world_to_tile(mouse_world_pos) else {
         tile_painter.current_pos = None;
         return;
     };
 
-    let grid_size = active_layer.metadata.grid_size as f32;
+    // Only repaint a cell when the cursor enters it, otherwise the variation
+    // brush would re-roll the tile under a held mouse every frame.
+    let entered_cell = tile_painter.current_pos != Some((tile_x, tile_y));
+    tile_painter.current_pos = Some((tile_x, tile_y));
 
-    let tile_x = (mouse_world_pos.x / grid_size).floor() as i32;
-    let tile_y = (mouse_world_pos.y / grid_size).floor() as i32;
+    let flip_x = tile_painter.flip_x;
+    let flip_y = tile_painter.flip_y;
 
-    if tile_x < 0
-        || tile_y < 0
-        || tile_x >= active_layer.metadata.width as i32
-        || tile_y >= active_layer.metadata.height as i32
-    {
-        tile_painter.current_pos = None;
-        return;
+    if mouse_button.just_pressed(MouseButton::Left) {
+        tile_painter.variation.begin_stroke();
     }
 
-    let tile_x = tile_x as u32;
-    let tile_y = tile_y as u32;
-
-    tile_painter.current_pos = Some((tile_x, tile_y));
+    // Everything painted until the buttons are released becomes one undo step
+    let pressed_left = mouse_button.just_pressed(MouseButton::Left);
+    if tile_painter.stroke.is_none()
+        && (pressed_left || mouse_button.just_pressed(MouseButton::Right))
+    {
+        let (description, mergeable) = if !pressed_left {
+            ("Erase Tiles", false)
+        } else {
+            match tile_painter.mode {
+                PaintMode::Single
+                    if !tile_painter.variation.is_active()
+                        && tileset_manager.selected_tiles.len() > 1 =>
+                {
+                    ("Paint Stamp", false)
+                }
+                PaintMode::Single => ("Paint Tiles", true),
+                PaintMode::Rectangle => ("Paint Rectangle", false),
+                PaintMode::Line => ("Paint Line", false),
+                PaintMode::BucketFill => ("Bucket Fill", false),
+            }
+        };
+        tile_painter.stroke = Some(TileStroke::begin(description, mergeable, &layer_manager));
+    }
 
     if mouse_button.pressed(MouseButton::Left) {
         match tile_painter.mode {
             PaintMode::Single => {
-                if tileset_manager.selected_tiles.len() > 1 {
+                if !tile_painter.variation.is_active() && tileset_manager.selected_tiles.len() > 1 {
                     paint_stamp(
                         tile_x,
                         tile_y,
                         &tileset_manager,
-                        tile_painter.flip_x,
-                        tile_painter.flip_y,
-                        &mut layer_manager,
-                        &mut paint_events,
-                    );
-                } else {
-                    paint_single_tile(
-                        tile_x,
-                        tile_y,
-                        selected_tile_id,
-                        tile_painter.flip_x,
-                        tile_painter.flip_y,
+                        flip_x,
+                        flip_y,
                         &mut layer_manager,
                         &mut paint_events,
                     );
+                } else if entered_cell || mouse_button.just_pressed(MouseButton::Left) {
+                    if let Some(tile) = tile_painter
+                        .brush(selected_tile)
+                        .and_then(|mut brush| brush.next_tile())
+                    {
+                        paint_single_tile(
+                            tile_x,
+                            tile_y,
+                            tile,
+                            flip_x,
+                            flip_y,
+                            &mut layer_manager,
+                            &mut paint_events,
+                        );
+                    }
                 }
             }
             PaintMode::Rectangle => {
@@ -106,60 +137,71 @@ pub fn handle_tile_painting(
             }
             PaintMode::BucketFill => {
                 if mouse_button.just_pressed(MouseButton::Left) {
-                    bucket_fill(
-                        tile_x,
-                        tile_y,
-                        selected_tile_id,
-                        tile_painter.flip_x,
-                        tile_painter.flip_y,
-                        &mut layer_manager,
-                        &mut paint_events,
-                    );
+                    if let Some(mut brush) = tile_painter.brush(selected_tile) {
+                        bucket_fill(
+                            tile_x,
+                            tile_y,
+                            &mut brush,
+                            flip_x,
+                            flip_y,
+                            &tile_grid,
+                            &mut layer_manager,
+                            &mut paint_events,
+                        );
+                    }
                 }
             }
         }
     }
 
     if mouse_button.just_released(MouseButton::Left) {
-        if let Some((start_x, start_y)) = tile_painter.drag_start {
-            match tile_painter.mode {
-                PaintMode::Rectangle => {
-                    paint_rectangle(
-                        start_x,
-                        start_y,
-                        tile_x,
-                        tile_y,
-                        selected_tile_id,
-                        tile_painter.flip_x,
-                        tile_painter.flip_y,
-                        &mut layer_manager,
-                        &mut paint_events,
-                    );
-                }
-                PaintMode::Line => {
-                    paint_line(
-                        start_x,
-                        start_y,
-                        tile_x,
-                        tile_y,
-                        selected_tile_id,
-                        tile_painter.flip_x,
-                        tile_painter.flip_y,
-                        &mut layer_manager,
-                        &mut paint_events,
-                    );
+        if let Some((start_x, start_y)) = tile_painter.drag_start.take() {
+            let mode = tile_painter.mode;
+            if let Some(mut brush) = tile_painter.brush(selected_tile) {
+                match mode {
+                    PaintMode::Rectangle => {
+                        paint_rectangle(
+                            start_x,
+                            start_y,
+                            tile_x,
+                            tile_y,
+                            &mut brush,
+                            flip_x,
+                            flip_y,
+                            &mut layer_manager,
+                            &mut paint_events,
+                        );
+                    }
+                    PaintMode::Line => {
+                        paint_line(
+                            start_x,
+                            start_y,
+                            tile_x,
+                            tile_y,
+                            &mut brush,
+                            flip_x,
+                            flip_y,
+                            &mut layer_manager,
+                            &mut paint_events,
+                        );
+                    }
+                    _ => {}
                 }
-                _ => {}
             }
-
-            tile_painter.drag_start = None;
-        } else {
-            tile_painter.drag_start = None;
         }
     }
 
-    if mouse_button.pressed(MouseButton::Right) {
+    if mouse_button.pressed(MouseButton::Right)
+        && layer_manager.get_tile_at(tile_x, tile_y).is_some()
+    {
         layer_manager.remove_tile(tile_x, tile_y);
+        if let Some(layer_id) = layer_manager.get_active_layer().map(|l| l.metadata.id) {
+            erase_events.write(EraseTileEvent {
+                layer_id,
+                x: tile_x,
+                y: tile_y,
+            });
+        }
     }
 
     if keyboard.just_pressed(KeyCode::KeyX) {
@@ -171,6 +213,30 @@ pub fn handle_tile_painting(
     }
 }
 
+/// Close the stroke started by `handle_tile_painting` once no mouse button is
+/// held and record what it changed in the editor history.
+pub fn record_tile_strokes(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    layer_manager: Res<LayerManager>,
+    mut tile_painter: ResMut<TilePainter>,
+    mut history: ResMut<EditorHistory>,
+) {
+    if tile_painter.stroke.is_none()
+        || mouse_button.pressed(MouseButton::Left)
+        || mouse_button.pressed(MouseButton::Right)
+    {
+        return;
+    }
+
+    if let Some(command) = tile_painter
+        .stroke
+        .take()
+        .and_then(|stroke| stroke.finish(&layer_manager))
+    {
+        history.add_executed(Box::new(command));
+    }
+}
+
 /// System to handle the temporary eyedropper tool.
 pub fn handle_eyedropper(
     mouse_button: Res<ButtonInput<MouseButton>>,
@@ -179,6 +245,7 @@ pub fn handle_eyedropper(
     camera_q: Query<(&Camera, &GlobalTransform)>,
     mut tileset_manager: ResMut<TilesetManager>,
     layer_manager: Res<LayerManager>,
+    tile_grid: Res<TileGrid>,
     mut editor_state: ResMut<bevy_editor_foundation::EditorState>,
     mut tile_painter: ResMut<TilePainter>,
     mut contexts: EguiContexts,
@@ -201,29 +268,20 @@ pub fn handle_eyedropper(
         return;
     };
 
-    let Some(active_layer) = layer_manager.get_active_layer() else {
-        return;
-    };
-
-    let grid_size = active_layer.metadata.grid_size as f32;
-
-    let tile_x = (mouse_world_pos.x / grid_size).floor() as i32;
-    let tile_y = (mouse_world_pos.y / grid_size).floor() as i32;
-
-    if tile_x < 0
-        || tile_y < 0
-        || tile_x >= active_layer.metadata.width as i32
-        || tile_y >= active_layer.metadata.height as i32
-    {
+    if layer_manager.get_active_layer().is_none() {
         return;
     }
 
-    let tile_x = tile_x as u32;
-    let tile_y = tile_y as u32;
+    let Some((tile_x, tile_y)) = tile_grid.world_to_tile(mouse_world_pos) else {
+        return;
+    };
 
     if mouse_button.just_pressed(MouseButton::Left) {
         if let Some(tile_data) = layer_manager.get_tile_at(tile_x, tile_y) {
-            tileset_manager.selected_tile_id = Some(tile_data.tile_id);
+            // Switch to the tile's own tileset so painting reproduces it exactly
+            tileset_manager.select_tile_ref(tile_data.tile_ref());
+            // A picked tile should paint as-is rather than being replaced by variants
+            tile_painter.variation.enabled = false;
 
             if is_alt_held && editor_state.current_tool != EditorTool::Eyedropper {
                 // temporary eyedropper, keep current tool
@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use bevy_editor_tilemap::{LoadTilesetEvent, TilesetManager};
+use bevy_editor_tilemap::{detect_tileset_layout, LoadTilesetEvent, TilesetManager};
 use bevy_egui::egui::TextureId;
 use bevy_egui::{egui, EguiContexts};
 
@@ -20,7 +20,7 @@ pub fn tileset_panel_ui(
     mut contexts: EguiContexts,
     mut tileset_manager: ResMut<TilesetManager>,
     mut tileset_zoom: ResMut<TilesetZoom>,
-    mut load_events: EventWriter<LoadTilesetEvent>,
+    mut import_dialog: ResMut<TilesetImportDialog>,
     mut select_tile_events: EventWriter<SelectTileEvent>,
     mut select_tileset_events: EventWriter<SelectTilesetEvent>,
     images: Res<Assets<Image>>,
@@ -63,14 +63,8 @@ pub fn tileset_panel_ui(
                         .unwrap_or("Untitled Tileset")
                         .to_string();
 
-                    // TODO: In a real implementation, load image to detect dimensions
-                    // For now, use default 16x16 tiles
-                    load_events.write(LoadTilesetEvent {
-                        path: path.to_string_lossy().to_string(),
-                        identifier,
-                        tile_width: 16,
-                        tile_height: 16,
-                    });
+                    // Let the user confirm tile size, spacing and margin before loading
+                    import_dialog.open_for(path.to_string_lossy().to_string(), identifier);
                 }
             }
 
@@ -95,10 +89,17 @@ pub fn tileset_panel_ui(
                         let identifier = tileset_info.data.identifier.clone();
                         let tile_width = tileset_info.data.tile_width;
                         let tile_height = tileset_info.data.tile_height;
+                        let spacing = tileset_info.data.spacing;
+                        let padding = tileset_info.data.padding;
                         let columns = tileset_info.data.columns;
                         let rows = tileset_info.data.rows;
                         let tile_count = tileset_info.tile_count;
                         let texture_handle = tileset_info.texture_handle.clone();
+                        let leftover = images.get(&texture_handle).map(|image| {
+                            tileset_info
+                                .data
+                                .leftover_pixels(image.width(), image.height())
+                        });
 
                         ui.push_id(id, |ui| {
                             if ui.selectable_label(is_selected, &identifier).clicked() {
@@ -110,6 +111,12 @@ pub fn tileset_panel_ui(
 
                                 // Show tileset properties
                                 ui.label(format!("Tile Size: {}x{}px", tile_width, tile_height));
+                                if spacing > 0 || padding > 0 {
+                                    ui.label(format!(
+                                        "Spacing: {}px, Margin: {}px",
+                                        spacing, padding
+                                    ));
+                                }
                                 ui.label(format!("Grid: {}x{} tiles", columns, rows));
                                 ui.label(format!("Total Tiles: {}", tile_count));
 
@@ -122,15 +129,14 @@ pub fn tileset_panel_ui(
                                     ));
 
                                     // Validation warnings
-                                    let image_width = image.width();
-                                    let image_height = image.height();
-                                    if image_width % tile_width != 0 {
+                                    let (leftover_x, leftover_y) = leftover.unwrap_or_default();
+                                    if leftover_x != 0 {
                                         ui.colored_label(
                                             egui::Color32::YELLOW,
                                             "⚠ Width not evenly divisible",
                                         );
                                     }
-                                    if image_height % tile_height != 0 {
+                                    if leftover_y != 0 {
                                         ui.colored_label(
                                             egui::Color32::YELLOW,
                                             "⚠ Height not evenly divisible",
@@ -240,21 +246,15 @@ fn render_tileset_grid(
     ui.label("Click a tile to select:");
 
     // Get actual texture dimensions
-    let _texture_width = image.width() as f32;
-    let _texture_height = image.height() as f32;
+    let texture_width = image.width() as f32;
+    let texture_height = image.height() as f32;
 
-    let tile_width = tileset_info.data.tile_width as f32;
-    let tile_height = tileset_info.data.tile_height as f32;
     let columns = tileset_info.data.columns;
     let rows = tileset_info.data.rows;
 
-    // Apply zoom to tile dimensions
-    let display_tile_width = tile_width * zoom;
-    let display_tile_height = tile_height * zoom;
-
-    // Calculate full image display size
-    let display_width = display_tile_width * columns as f32;
-    let display_height = display_tile_height * rows as f32;
+    // Display the whole image (including spacing and margin) at the zoom level
+    let display_width = texture_width * zoom;
+    let display_height = texture_height * zoom;
 
     // Allocate space for the full tileset image (enable drag for multi-select)
     let (image_rect, response) = ui.allocate_exact_size(
@@ -278,13 +278,16 @@ fn render_tileset_grid(
         for col in 0..columns {
             let tile_id = row * columns + col;
 
-            // Calculate tile rectangle in screen space
+            // Calculate tile rectangle in screen space, skipping spacing and margin
+            let Some((x, y, width, height)) = tileset_info.data.tile_rect(tile_id) else {
+                continue;
+            };
             let tile_rect = egui::Rect::from_min_size(
                 egui::pos2(
-                    image_rect.min.x + col as f32 * display_tile_width,
-                    image_rect.min.y + row as f32 * display_tile_height,
+                    image_rect.min.x + x as f32 * zoom,
+                    image_rect.min.y + y as f32 * zoom,
                 ),
-                egui::vec2(display_tile_width, display_tile_height),
+                egui::vec2(width as f32 * zoom, height as f32 * zoom),
             );
 
             // Check if this tile is hovered
@@ -448,3 +451,183 @@ pub fn handle_tile_selection_events(
         tileset_manager.select_tile(event.tile_id);
     }
 }
+
+/// State of the tileset import dialog shown after picking a tileset image
+#[derive(Resource, Default)]
+pub struct TilesetImportDialog {
+    pub open: bool,
+    pub path: String,
+    pub identifier: String,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub spacing: u32,
+    pub padding: u32,
+    /// Image being imported, loaded to detect its layout
+    image: Option<Handle<Image>>,
+    /// Whether the layout has been auto-detected for the current image
+    detected: bool,
+}
+
+impl TilesetImportDialog {
+    /// Open the dialog for an image file with default 16x16 tiles
+    pub fn open_for(&mut self, path: String, identifier: String) {
+        *self = Self {
+            open: true,
+            path,
+            identifier,
+            tile_width: 16,
+            tile_height: 16,
+            ..Default::default()
+        };
+    }
+}
+
+/// Tileset import dialog - auto-detects spacing and margin, then loads the tileset
+pub fn tileset_import_dialog_ui(
+    mut contexts: EguiContexts,
+    mut dialog: ResMut<TilesetImportDialog>,
+    mut load_events: EventWriter<LoadTilesetEvent>,
+    asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
+) {
+    if !dialog.open {
+        return;
+    }
+
+    let image_handle = match &dialog.image {
+        Some(handle) => handle.clone(),
+        None => {
+            let handle: Handle<Image> = asset_server.load(dialog.path.clone());
+            dialog.image = Some(handle.clone());
+            handle
+        }
+    };
+    let image = images.get(&image_handle);
+
+    // Detect once the image is available; the user can re-run it after changing tile size
+    if let Some(image) = image {
+        if !dialog.detected {
+            apply_detected_layout(&mut dialog, image);
+        }
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut import = false;
+    let mut cancel = false;
+    egui::Window::new("Import Tileset")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(&dialog.path);
+            ui.horizontal(|ui| {
+                ui.label("Name:");
+                ui.text_edit_singleline(&mut dialog.identifier);
+            });
+
+            ui.separator();
+
+            egui::Grid::new("tileset_import_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Tile Width:");
+                    ui.add(egui::DragValue::new(&mut dialog.tile_width).range(1..=1024));
+                    ui.end_row();
+
+                    ui.label("Tile Height:");
+                    ui.add(egui::DragValue::new(&mut dialog.tile_height).range(1..=1024));
+                    ui.end_row();
+
+                    ui.label("Spacing:");
+                    ui.add(egui::DragValue::new(&mut dialog.spacing).range(0..=64));
+                    ui.end_row();
+
+                    ui.label("Margin:");
+                    ui.add(egui::DragValue::new(&mut dialog.padding).range(0..=64));
+                    ui.end_row();
+                });
+
+            match image {
+                Some(image) => {
+                    if ui
+                        .button("🔍 Auto-detect")
+                        .on_hover_text("Detect spacing and margin from empty gutters")
+                        .clicked()
+                    {
+                        apply_detected_layout(&mut dialog, image);
+                    }
+
+                    // Preview the resulting grid
+                    let data = bevy_editor_formats::TilesetData {
+                        tile_width: dialog.tile_width,
+                        tile_height: dialog.tile_height,
+                        spacing: dialog.spacing,
+                        padding: dialog.padding,
+                        ..Default::default()
+                    };
+                    let (columns, rows) = data.grid_for_image(image.width(), image.height());
+                    let (leftover_x, leftover_y) =
+                        data.leftover_pixels(image.width(), image.height());
+                    ui.label(format!(
+                        "Image: {}x{}px → {}x{} tiles",
+                        image.width(),
+                        image.height(),
+                        columns,
+                        rows
+                    ));
+                    if leftover_x != 0 || leftover_y != 0 {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!("⚠ {}x{}px not covered by tiles", leftover_x, leftover_y),
+                        );
+                    }
+                }
+                None => {
+                    ui.colored_label(egui::Color32::GRAY, "Loading texture...");
+                }
+            }
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui.button("Import").clicked() {
+                    import = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    if import {
+        load_events.write(
+            LoadTilesetEvent::new(
+                &dialog.path,
+                &dialog.identifier,
+                dialog.tile_width,
+                dialog.tile_height,
+            )
+            .with_spacing(dialog.spacing, dialog.padding),
+        );
+    }
+    if import || cancel || !open {
+        *dialog = TilesetImportDialog::default();
+    }
+}
+
+fn apply_detected_layout(dialog: &mut TilesetImportDialog, image: &Image) {
+    let layout = detect_tileset_layout(image, dialog.tile_width, dialog.tile_height);
+    dialog.tile_width = layout.tile_width;
+    dialog.tile_height = layout.tile_height;
+    dialog.spacing = layout.spacing;
+    dialog.padding = layout.padding;
+    dialog.detected = true;
+    info!(
+        "Detected tileset layout: {}x{} tiles, spacing {}, margin {}",
+        layout.tile_width, layout.tile_height, layout.spacing, layout.padding
+    );
+}
@@ -1,8 +1,11 @@
 use crate::icons::Icons;
 use bevy::prelude::*;
-use bevy_editor_foundation::{EditorState, EditorTool};
-use bevy_editor_frontend_api::{EditorAction, ProjectCommand};
+use bevy_editor_foundation::{EditorState, EditorTool, Selection};
+use bevy_editor_frontend_api::{
+    AlignEdge, ArrangeAxis, ArrangeOperation, DistributeSpacing, EditorAction, ProjectCommand,
+};
 use bevy_editor_project::BevyCLIRunner;
+use bevy_editor_scene::{PlayMode, PlayState};
 use bevy_editor_tilemap::{PaintMode, TilePainter};
 use bevy_egui::egui;
 
@@ -12,6 +15,8 @@ pub fn render_toolbar_content(
     editor_state: &mut EditorState,
     tile_painter: &mut TilePainter,
     cli_runner: &BevyCLIRunner,
+    play_mode: &mut PlayMode,
+    selection: &Selection,
     editor_actions: &mut EventWriter<EditorAction>,
 ) {
     ui.horizontal(|ui| {
@@ -147,6 +152,13 @@ pub fn render_toolbar_content(
             );
         }
 
+        // Align, distribute and match the selection
+        render_arrange_menu(ui, selection, editor_actions);
+
+        // Play-in-editor
+        ui.separator();
+        render_play_controls(ui, play_mode, selection, editor_actions);
+
         // Bevy CLI buttons (right side)
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             let has_project = cli_runner.project_path.is_some();
@@ -229,3 +241,130 @@ pub fn render_toolbar_content(
         });
     });
 }
+
+/// Menu laying out the selected entities by their bounds
+fn render_arrange_menu(
+    ui: &mut egui::Ui,
+    selection: &Selection,
+    editor_actions: &mut EventWriter<EditorAction>,
+) {
+    ui.add_enabled_ui(selection.len() > 1, |ui| {
+        ui.menu_button("Arrange", |ui| {
+            let mut arrange = |ui: &mut egui::Ui, label: &str, operation: ArrangeOperation| {
+                if ui.button(label).clicked() {
+                    editor_actions.write(EditorAction::ArrangeSelection(operation));
+                    ui.close_menu();
+                }
+            };
+
+            for (label, edge) in [
+                ("Align Left", AlignEdge::Left),
+                ("Align Center", AlignEdge::Center),
+                ("Align Right", AlignEdge::Right),
+                ("Align Top", AlignEdge::Top),
+                ("Align Middle", AlignEdge::Middle),
+                ("Align Bottom", AlignEdge::Bottom),
+            ] {
+                arrange(ui, label, ArrangeOperation::Align(edge));
+            }
+
+            ui.separator();
+            ui.add_enabled_ui(selection.len() > 2, |ui| {
+                for (label, axis, spacing) in [
+                    (
+                        "Distribute Centers Horizontally",
+                        ArrangeAxis::Horizontal,
+                        DistributeSpacing::Centers,
+                    ),
+                    (
+                        "Distribute Gaps Horizontally",
+                        ArrangeAxis::Horizontal,
+                        DistributeSpacing::Gaps,
+                    ),
+                    (
+                        "Distribute Centers Vertically",
+                        ArrangeAxis::Vertical,
+                        DistributeSpacing::Centers,
+                    ),
+                    (
+                        "Distribute Gaps Vertically",
+                        ArrangeAxis::Vertical,
+                        DistributeSpacing::Gaps,
+                    ),
+                ] {
+                    arrange(ui, label, ArrangeOperation::Distribute { axis, spacing });
+                }
+            })
+            .response
+            .on_disabled_hover_text("Select at least three entities");
+
+            ui.separator();
+            arrange(ui, "Match Size", ArrangeOperation::MatchSize);
+            arrange(ui, "Match Rotation", ArrangeOperation::MatchRotation);
+            ui.weak("Sizes and rotations follow the primary entity");
+        });
+    })
+    .response
+    .on_disabled_hover_text("Select at least two entities");
+}
+
+/// Play/Pause/Stop buttons for playing the scene inside the editor
+fn render_play_controls(
+    ui: &mut egui::Ui,
+    play_mode: &mut PlayMode,
+    selection: &Selection,
+    editor_actions: &mut EventWriter<EditorAction>,
+) {
+    if play_mode.state() == PlayState::Playing {
+        if ui
+            .button(format!("{} Pause", Icons::PAUSE))
+            .on_hover_text("Pause gameplay systems")
+            .clicked()
+        {
+            editor_actions.write(EditorAction::PausePlay);
+        }
+    } else {
+        let (label, hover) = if play_mode.is_active() {
+            ("Resume", "Resume gameplay systems")
+        } else {
+            ("Play", "Play the scene in the editor. Stop restores it.")
+        };
+        if ui
+            .button(format!("{} {}", Icons::PLAY, label))
+            .on_hover_text(hover)
+            .clicked()
+        {
+            editor_actions.write(EditorAction::StartPlay);
+        }
+    }
+
+    ui.add_enabled_ui(play_mode.is_active(), |ui| {
+        let stop = ui
+            .button(format!("{} Stop", Icons::STOP))
+            .on_hover_text("Stop playing and restore the scene\nRight-click to keep changes");
+        if stop.clicked() {
+            editor_actions.write(EditorAction::StopPlay {
+                keep_changes: false,
+            });
+        }
+        stop.context_menu(|ui| {
+            if ui.button("Stop and Keep All Changes").clicked() {
+                editor_actions.write(EditorAction::StopPlay { keep_changes: true });
+                ui.close_menu();
+            }
+        });
+
+        if !selection.is_empty() {
+            let mut keep = selection.iter().all(|entity| play_mode.is_kept(entity));
+            if ui
+                .checkbox(&mut keep, "Keep Selected")
+                .on_hover_text("Keep the selected entities' changes when play stops")
+                .changed()
+            {
+                for entity in selection.iter() {
+                    play_mode.keep(entity, keep);
+                }
+            }
+        }
+    });
+}
@@ -2,34 +2,43 @@ use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
 use crate::cli_output_panel::{render_cli_output_content, should_show_cli_output};
+use crate::clipboard::ClipboardRequest;
+use crate::history_panel::HistoryPanel;
+use crate::live_link_panel::LiveLinkWindow;
 use crate::scene_tabs::render_scene_tabs_content;
 use crate::toolbar::render_toolbar_content;
-use crate::CurrentLevel;
-use bevy_editor_foundation::EditorState;
+use crate::variation_brush_panel::VariationBrushWindow;
+use bevy_editor_commands::HistoryRequest;
+use bevy_editor_foundation::{EditorState, Selection};
 use bevy_editor_frontend_api::CliOutputPanelState;
 use bevy_editor_frontend_api::EditorAction;
 use bevy_editor_project::{
     BevyCLIRunner, EditorWorkspace, ProjectSelection, ProjectSelectionState,
 };
-use bevy_editor_scene::{EditorScene, EditorSceneEntity, OpenScenes, SceneTabChanged};
+use bevy_editor_scene::{EditorScene, EditorSceneEntity, OpenScenes, PlayMode, SceneTabChanged};
 use bevy_editor_tilemap::{CollisionEditor, TilePainter};
 
 /// Main UI system - draws all editor UI panels
 pub fn ui_system(
     mut contexts: EguiContexts,
     mut editor_state: ResMut<EditorState>,
-    _current_level: ResMut<CurrentLevel>,
+    (mut history_panel, mut live_link_window): (ResMut<HistoryPanel>, ResMut<LiveLinkWindow>),
     mut collision_editor: ResMut<CollisionEditor>,
+    mut variation_window: ResMut<VariationBrushWindow>,
     workspace: Option<Res<EditorWorkspace>>,
     mut project_selection: Option<ResMut<ProjectSelection>>,
     mut open_scenes: ResMut<OpenScenes>, // Multi-scene support
     mut tile_painter: ResMut<TilePainter>,
     mut cli_runner: ResMut<BevyCLIRunner>,
     mut cli_panel: ResMut<CliOutputPanelState>,
-    editor_scene: Res<EditorScene>,
+    (editor_scene, selection, mut play_mode): (Res<EditorScene>, Res<Selection>, ResMut<PlayMode>),
     scene_entity_query: Query<Entity, With<EditorSceneEntity>>,
     mut tab_changed_events: EventWriter<SceneTabChanged>,
     mut editor_actions: EventWriter<EditorAction>,
+    (mut history_requests, mut clipboard_requests): (
+        EventWriter<HistoryRequest>,
+        EventWriter<ClipboardRequest>,
+    ),
 ) {
     let Some(ctx) = contexts.try_ctx_mut() else {
         return;
@@ -131,11 +140,35 @@ pub fn ui_system(
 
             ui.menu_button("Edit", |ui| {
                 if ui.button("Undo (Ctrl+Z)").clicked() {
-                    // TODO: Undo system
+                    history_requests.write(HistoryRequest::Undo);
                     ui.close_menu();
                 }
                 if ui.button("Redo (Ctrl+Y)").clicked() {
-                    // TODO: Redo system
+                    history_requests.write(HistoryRequest::Redo);
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button("Copy (Ctrl+C)").clicked() {
+                    clipboard_requests.write(ClipboardRequest::Copy);
+                    ui.close_menu();
+                }
+                if ui.button("Paste (Ctrl+V)").clicked() {
+                    clipboard_requests.write(ClipboardRequest::Paste { scene: None });
+                    ui.close_menu();
+                }
+                ui.add_enabled_ui(!play_mode.is_active(), |ui| {
+                    ui.menu_button("Paste Into", |ui| {
+                        for (index, scene) in open_scenes.scenes.iter().enumerate() {
+                            if ui.button(&scene.name).clicked() {
+                                clipboard_requests
+                                    .write(ClipboardRequest::Paste { scene: Some(index) });
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                });
+                if ui.button("Duplicate (Ctrl+D)").clicked() {
+                    clipboard_requests.write(ClipboardRequest::Duplicate);
                     ui.close_menu();
                 }
             });
@@ -154,6 +187,21 @@ pub fn ui_system(
                 {
                     ui.close_menu();
                 }
+                if ui
+                    .checkbox(&mut variation_window.open, "Variation Brush")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui.checkbox(&mut history_panel.open, "History").clicked() {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut live_link_window.open, "Live Game")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
             });
         });
     });
@@ -165,12 +213,16 @@ pub fn ui_system(
             &mut editor_state,
             &mut tile_painter,
             &*cli_runner,
+            &mut play_mode,
+            &selection,
             &mut editor_actions,
         );
     });
 
-    // Scene tabs panel
+    // Scene tabs panel, locked while the active scene is playing
     egui::TopBottomPanel::top("scene_tabs").show(ctx, |ui| {
-        render_scene_tabs_content(ui, &mut open_scenes, &mut tab_changed_events);
+        ui.add_enabled_ui(!play_mode.is_active(), |ui| {
+            render_scene_tabs_content(ui, &mut open_scenes, &mut tab_changed_events);
+        });
     });
 }
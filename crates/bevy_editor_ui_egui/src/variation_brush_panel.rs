@@ -0,0 +1,190 @@
+use bevy::prelude::*;
+use bevy_editor_formats::WeightedTile;
+use bevy_editor_project::CurrentProject;
+use bevy_editor_tilemap::{TilePainter, TilesetManager};
+use bevy_egui::{egui, EguiContexts};
+
+/// UI state for the variation brush window
+#[derive(Resource, Default)]
+pub struct VariationBrushWindow {
+    pub open: bool,
+    /// Name used when saving the current brush as a project preset
+    pub preset_name: String,
+}
+
+/// UI system for editing the random/weighted variation brush and its presets
+pub fn variation_brush_ui(
+    mut contexts: EguiContexts,
+    mut window: ResMut<VariationBrushWindow>,
+    mut tile_painter: ResMut<TilePainter>,
+    tileset_manager: Res<TilesetManager>,
+    mut current_project: Option<ResMut<CurrentProject>>,
+) {
+    if !window.open {
+        return;
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    egui::Window::new("Variation Brush")
+        .open(&mut open)
+        .default_width(280.0)
+        .show(ctx, |ui| {
+            let variation = &mut tile_painter.variation;
+
+            ui.checkbox(&mut variation.enabled, "Paint random variants");
+            ui.label("Applies to brush, rectangle, line and fill tools");
+
+            ui.separator();
+
+            // Seed the set from the tileset selection
+            ui.horizontal(|ui| {
+                let selection: Vec<u32> = if tileset_manager.selected_tiles.is_empty() {
+                    tileset_manager.selected_tile_id.into_iter().collect()
+                } else {
+                    tileset_manager.selected_tiles.clone()
+                };
+
+                let tileset_id = tileset_manager.selected_tileset_id;
+                if ui
+                    .add_enabled(
+                        tileset_id.is_some() && !selection.is_empty(),
+                        egui::Button::new("Use Tileset Selection"),
+                    )
+                    .on_hover_text("Replace the variants with the selected tiles")
+                    .clicked()
+                {
+                    if let Some(tileset_id) = tileset_id {
+                        variation.set_tiles(tileset_id, &selection);
+                        variation.enabled = true;
+                    }
+                }
+
+                if let Some(tile) = tileset_manager.selected_tile_ref() {
+                    if ui
+                        .button("Add Selected")
+                        .on_hover_text("Add the selected tile as another variant")
+                        .clicked()
+                    {
+                        variation.tiles.push(WeightedTile::new(tile, 1.0));
+                    }
+                }
+            });
+
+            // Variant list with weights
+            let total_weight: f32 = variation.tiles.iter().map(|t| t.weight.max(0.0)).sum();
+            let mut to_remove = None;
+
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    if variation.tiles.is_empty() {
+                        ui.label("No variants - select tiles in the tileset first");
+                    }
+
+                    for (idx, tile) in variation.tiles.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Tile {}:{}", tile.tileset_id, tile.tile_id));
+                            ui.add(
+                                egui::DragValue::new(&mut tile.weight)
+                                    .speed(0.1)
+                                    .range(0.0..=100.0)
+                                    .prefix("weight "),
+                            );
+                            if total_weight > 0.0 {
+                                ui.label(format!(
+                                    "{:.0}%",
+                                    tile.weight.max(0.0) / total_weight * 100.0
+                                ));
+                            }
+                            if ui.small_button("✖").clicked() {
+                                to_remove = Some(idx);
+                            }
+                        });
+                    }
+                });
+
+            if let Some(idx) = to_remove {
+                variation.tiles.remove(idx);
+            }
+
+            ui.separator();
+
+            // Optional fixed seed for reproducible strokes
+            ui.horizontal(|ui| {
+                let mut use_seed = variation.seed.is_some();
+                if ui
+                    .checkbox(&mut use_seed, "Fixed seed")
+                    .on_hover_text("Repeat the same pattern for every stroke")
+                    .changed()
+                {
+                    variation.seed = use_seed.then_some(0);
+                }
+                if let Some(seed) = variation.seed.as_mut() {
+                    ui.add(egui::DragValue::new(seed));
+                }
+            });
+
+            ui.separator();
+
+            // Project presets
+            ui.label("Presets:");
+            let Some(project) = current_project.as_mut() else {
+                ui.label("Open a project to save presets");
+                return;
+            };
+
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut window.preset_name);
+                let can_save = !window.preset_name.trim().is_empty() && !variation.tiles.is_empty();
+                if ui
+                    .add_enabled(can_save, egui::Button::new("Save"))
+                    .clicked()
+                {
+                    let preset = variation.to_preset(window.preset_name.trim(), &tileset_manager);
+                    let result = project.update_config(|config| {
+                        // Saving under an existing name overwrites that preset
+                        config.brush_presets.retain(|p| p.name != preset.name);
+                        config.brush_presets.push(preset);
+                    });
+                    match result {
+                        Ok(()) => window.preset_name.clear(),
+                        Err(e) => error!("Failed to save brush preset: {}", e),
+                    }
+                }
+            });
+
+            let mut to_load = None;
+            let mut to_delete = None;
+            for (idx, preset) in project.metadata.config.brush_presets.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} ({} tiles)", preset.name, preset.tiles.len()));
+                    if ui.small_button("Load").clicked() {
+                        to_load = Some(idx);
+                    }
+                    if ui.small_button("✖").clicked() {
+                        to_delete = Some(idx);
+                    }
+                });
+            }
+
+            if let Some(idx) = to_load {
+                variation.apply_preset(
+                    &project.metadata.config.brush_presets[idx],
+                    &tileset_manager,
+                );
+            }
+            if let Some(idx) = to_delete {
+                if let Err(e) = project.update_config(|config| {
+                    config.brush_presets.remove(idx);
+                }) {
+                    error!("Failed to delete brush preset: {}", e);
+                }
+            }
+        });
+
+    window.open = open;
+}
@@ -5,11 +5,17 @@ use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_egui::EguiContext;
 
-use crate::editor_commands::TransformCommand;
-use crate::{GizmoMode, GizmoState};
+use crate::editor_commands::{TransformChange, TransformCommand, TransformEntitiesCommand};
+use crate::entity_bounds::EntityBounds;
+use crate::entity_fields::EntityPickState;
+use crate::{GizmoMode, GizmoState, PivotMode, SnapGuide, SnapSettings};
 use bevy_editor_commands::EditorHistory;
+use bevy_editor_core::snapping::{snap_angle, snap_to_bounds, snap_to_grid, snap_to_step};
 use bevy_editor_core::EditorCamera;
-use bevy_editor_scene::{EditorScene, EditorSceneEntity, TransformEditEvent};
+use bevy_editor_foundation::{EditorState, EditorTool, SelectMode, Selection};
+use bevy_editor_scene::{EditorEntityId, EditorSceneEntity, EditorViewState, TransformEditEvent};
+use bevy_editor_tilemap::TileGrid;
+use std::collections::HashMap;
 
 /// Which specific gizmo handle is being dragged
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -26,392 +32,787 @@ pub enum GizmoHandle {
 #[derive(Resource, Default)]
 pub struct GizmoDragState {
     pub is_dragging: bool,
-    pub dragged_entity: Option<Entity>,
+    /// Selected entities being dragged; selected children of dragged entities
+    /// are left to follow their parent
+    pub dragged: Vec<DraggedEntity>,
     pub drag_start_world: Vec2,
-    pub entity_start_pos: Vec2,
-    pub entity_start_rotation: f32,
-    pub entity_start_scale: Vec3,
+    /// Bounding box of the selection when the drag started; the handles sat
+    /// at its center
+    pub start_bounds: Rect,
+    /// Pivot of the drag, `None` for individual origins
+    pub pivot: Option<Vec2>,
     pub active_handle: Option<GizmoHandle>,
+    /// What the drag snapped to this frame
+    pub guides: Vec<SnapGuide>,
 }
 
-/// System to handle clicking entities in the viewport
+/// Keys that invert snapping while held during a drag
+const SNAP_INVERT_KEYS: [KeyCode; 2] = [KeyCode::ControlLeft, KeyCode::ControlRight];
+
+/// Distance in pixels the cursor must move before a press becomes a box selection
+const MARQUEE_DRAG_THRESHOLD: f32 = 4.0;
+
+/// Entities under the last viewport click, which Tab cycles through
+#[derive(Resource, Default)]
+pub struct ViewportPickState {
+    /// Topmost first
+    candidates: Vec<Entity>,
+    /// The candidate selected by the last click or Tab
+    current: usize,
+    /// Selected entity pressed without modifiers, with the cursor position;
+    /// the selection is kept for a group drag, and narrowed to it on release
+    /// when the cursor didn't move
+    pressed_selected: Option<(Entity, Vec2)>,
+}
+
+impl ViewportPickState {
+    /// Swap the selected candidate for the next one (the previous one with
+    /// `reverse`), keeping the rest of the selection
+    fn cycle(&mut self, selection: &mut Selection, reverse: bool) {
+        let count = self.candidates.len();
+        if count < 2 {
+            return;
+        }
+        let previous = self.candidates[self.current];
+        self.current = if reverse {
+            (self.current + count - 1) % count
+        } else {
+            (self.current + 1) % count
+        };
+        selection.remove(previous);
+        selection.add(self.candidates[self.current]);
+    }
+}
+
+/// Rubber-band rectangle dragged from empty viewport space
+#[derive(Resource, Default)]
+pub struct MarqueeSelection {
+    /// Where the press started, in window and world coordinates
+    start: Option<(Vec2, Vec2)>,
+    /// Cursor position in world coordinates
+    end: Vec2,
+    /// Whether the cursor moved far enough to select a box instead of clicking
+    dragging: bool,
+    mode: SelectMode,
+}
+
+impl MarqueeSelection {
+    /// The dragged rectangle in world coordinates
+    pub fn rect(&self) -> Option<Rect> {
+        let (_, start) = self.start?;
+        self.dragging.then(|| Rect::from_corners(start, self.end))
+    }
+
+    /// Forget the press, e.g. when it grabbed a gizmo handle
+    pub fn cancel(&mut self) {
+        *self = Self::default();
+    }
+}
+
+type PickableQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static GlobalTransform,
+        Option<&'static Sprite>,
+        Option<&'static InheritedVisibility>,
+        Option<&'static EditorViewState>,
+    ),
+    With<EditorSceneEntity>,
+>;
+
+/// Bounds of the entities that can be picked in the viewport, skipping hidden
+/// and locked ones
+fn pickable_entities(
+    entity_query: &PickableQuery,
+    images: &Assets<Image>,
+    atlas_layouts: &Assets<TextureAtlasLayout>,
+) -> Vec<(Entity, f32, EntityBounds)> {
+    entity_query
+        .iter()
+        .filter(|(_, _, _, visibility, view_state)| {
+            visibility.is_none_or(|visibility| visibility.get())
+                && view_state.is_none_or(|view_state| !view_state.locked && !view_state.hidden)
+        })
+        .map(|(entity, transform, sprite, ..)| {
+            let bounds = EntityBounds::of_sprite(transform, sprite, images, atlas_layouts);
+            (entity, transform.translation().z, bounds)
+        })
+        .collect()
+}
+
+/// System to handle clicking entities in the viewport.
+///
+/// A click selects the topmost entity under the cursor and Tab cycles
+/// through the others there. With the Select tool, a drag from empty space
+/// selects a box and a click there clears the selection. Shift adds, Ctrl
+/// toggles and Alt removes.
 pub fn viewport_entity_selection_system(
     mouse_button: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
     windows: Query<&Window, With<PrimaryWindow>>,
     camera_q: Query<(&Camera, &GlobalTransform), With<EditorCamera>>,
-    entity_query: Query<(Entity, &GlobalTransform, Option<&Sprite>), With<EditorSceneEntity>>,
-    mut editor_scene: ResMut<EditorScene>,
+    entity_query: PickableQuery,
+    (images, atlas_layouts): (Res<Assets<Image>>, Res<Assets<TextureAtlasLayout>>),
+    editor_state: Res<EditorState>,
+    mut selection: ResMut<Selection>,
+    mut pick_state: ResMut<EntityPickState>,
+    (mut viewport_picks, mut marquee): (ResMut<ViewportPickState>, ResMut<MarqueeSelection>),
     mut egui_contexts: Query<&mut EguiContext, With<PrimaryWindow>>,
 ) {
-    // Only handle left click
-    if !mouse_button.just_pressed(MouseButton::Left) {
-        return;
+    let (pointer_over_ui, ui_wants_keyboard) = egui_contexts
+        .iter_mut()
+        .next()
+        .map(|mut egui_context| {
+            let ctx = egui_context.get_mut();
+            (ctx.is_pointer_over_area(), ctx.wants_keyboard_input())
+        })
+        .unwrap_or_default();
+    let select_tool = editor_state.current_tool == EditorTool::Select;
+
+    if select_tool && keyboard.just_pressed(KeyCode::Tab) && !ui_wants_keyboard {
+        viewport_picks
+            .candidates
+            .retain(|entity| entity_query.contains(*entity));
+        viewport_picks.current = viewport_picks
+            .current
+            .min(viewport_picks.candidates.len().saturating_sub(1));
+        let reverse = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        viewport_picks.cycle(&mut selection, reverse);
     }
 
-    // Don't select if mouse is over egui UI
-    if let Some(mut egui_context) = egui_contexts.iter_mut().next() {
-        if egui_context.get_mut().is_pointer_over_area() {
-            return;
+    // Cursor position in the window and in the world
+    let cursor = windows
+        .single()
+        .ok()
+        .and_then(Window::cursor_position)
+        .and_then(|cursor_position| {
+            let (camera, camera_transform) = camera_q.single().ok()?;
+            let ray = camera
+                .viewport_to_world(camera_transform, cursor_position)
+                .ok()?;
+            Some((cursor_position, ray.origin.truncate()))
+        });
+
+    if let Some((start, _)) = marquee.start {
+        if let Some((cursor_position, world_position)) = cursor {
+            marquee.end = world_position;
+            if start.distance(cursor_position) > MARQUEE_DRAG_THRESHOLD {
+                marquee.dragging = true;
+            }
+        }
+        if !mouse_button.pressed(MouseButton::Left) {
+            if let Some(rect) = marquee.rect() {
+                let boxed = pickable_entities(&entity_query, &images, &atlas_layouts)
+                    .into_iter()
+                    .filter(|(_, _, bounds)| bounds.intersects(rect))
+                    .map(|(entity, _, _)| entity);
+                selection.apply(boxed, marquee.mode);
+            } else if marquee.mode == SelectMode::Replace {
+                // A click on empty space
+                selection.clear();
+            }
+            marquee.cancel();
         }
     }
 
-    let Ok(window) = windows.single() else {
-        return;
-    };
+    if mouse_button.just_released(MouseButton::Left) {
+        if let Some((entity, start)) = viewport_picks.pressed_selected.take() {
+            let moved = cursor.is_some_and(|(cursor_position, _)| {
+                cursor_position.distance(start) > MARQUEE_DRAG_THRESHOLD
+            });
+            if !moved {
+                selection.select(entity);
+            }
+        }
+    }
 
-    let Some(cursor_position) = window.cursor_position() else {
+    // Only handle left click
+    if !mouse_button.just_pressed(MouseButton::Left) || pointer_over_ui {
         return;
-    };
-
-    let Ok((camera, camera_transform)) = camera_q.single() else {
+    }
+    let Some((cursor_position, world_position)) = cursor else {
         return;
     };
 
-    // Convert cursor position to world position
-    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_position) else {
+    // Everything under the cursor, topmost and then smallest first
+    let mut hits: Vec<(Entity, f32, f32)> =
+        pickable_entities(&entity_query, &images, &atlas_layouts)
+            .into_iter()
+            .filter(|(_, _, bounds)| bounds.contains(world_position))
+            .map(|(entity, z, bounds)| (entity, z, bounds.area()))
+            .collect();
+    hits.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.2.total_cmp(&b.2)));
+    let hits: Vec<Entity> = hits.into_iter().map(|(entity, _, _)| entity).collect();
+
+    // In pick mode the click assigns an `EntityRef` field instead of selecting
+    if pick_state.is_active() {
+        if let Some(&entity) = hits.first() {
+            pick_state.pick(entity);
+        }
         return;
-    };
-
-    let world_position = ray.origin.truncate();
-
-    // Find the closest entity to the click position
-    let mut closest_entity: Option<Entity> = None;
-    let mut closest_distance = f32::MAX;
-
-    for (entity, transform, sprite) in entity_query.iter() {
-        let entity_pos = transform.translation().truncate();
+    }
 
-        // Calculate bounds based on sprite size or default
-        let size = if let Some(sprite_comp) = sprite {
-            sprite_comp.custom_size.unwrap_or(Vec2::new(32.0, 32.0))
+    let mode = SelectMode::from_keyboard(&keyboard);
+    if let Some(&entity) = hits.first() {
+        if mode == SelectMode::Replace && selection.is_selected(entity) && selection.len() > 1 {
+            selection.set_primary(entity);
+            viewport_picks.pressed_selected = Some((entity, cursor_position));
         } else {
-            Vec2::new(32.0, 32.0) // Default size
+            selection.click(entity, mode);
+        }
+        info!("Selected entity in viewport: {:?}", entity);
+        viewport_picks.candidates = hits;
+        viewport_picks.current = 0;
+    } else if select_tool {
+        viewport_picks.candidates.clear();
+        *marquee = MarqueeSelection {
+            start: Some((cursor_position, world_position)),
+            end: world_position,
+            dragging: false,
+            mode,
         };
+    }
+}
+
+/// Where the gizmo handles sit for the current selection, and the point it
+/// turns and scales around
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelectionGizmo {
+    /// Center of the selection's world-space bounding box, where the handles sit
+    pub center: Vec2,
+    /// Size of that bounding box
+    pub size: Vec2,
+    /// `None` when each entity turns and scales around its own origin
+    pub pivot: Option<Vec2>,
+}
 
-        let half_size = size / 2.0;
-
-        // Check if click is within entity bounds
-        let min = entity_pos - half_size;
-        let max = entity_pos + half_size;
-
-        if world_position.x >= min.x
-            && world_position.x <= max.x
-            && world_position.y >= min.y
-            && world_position.y <= max.y
-        {
-            let distance = entity_pos.distance(world_position);
-            if distance < closest_distance {
-                closest_distance = distance;
-                closest_entity = Some(entity);
+impl SelectionGizmo {
+    /// Gizmo for the selected entities `bounds` returns bounds for, or `None`
+    /// when none of them has any
+    pub fn new(
+        selection: &Selection,
+        gizmo_state: &GizmoState,
+        bounds: impl Fn(Entity) -> Option<EntityBounds>,
+    ) -> Option<Self> {
+        let mut selected = selection
+            .iter()
+            .filter_map(|entity| Some((entity, bounds(entity)?)))
+            .peekable();
+        selected.peek()?;
+        let mut rect = Rect::EMPTY;
+        let mut primary_origin = None;
+        for (entity, bounds) in selected {
+            rect = rect.union(bounds.aabb());
+            if selection.primary() == Some(entity) {
+                primary_origin = Some(bounds.origin());
             }
         }
+        let pivot = match gizmo_state.pivot {
+            PivotMode::SelectionCenter => Some(rect.center()),
+            PivotMode::Primary => primary_origin.or(Some(rect.center())),
+            PivotMode::IndividualOrigins => None,
+            PivotMode::Cursor => Some(gizmo_state.cursor),
+        };
+        Some(Self {
+            center: rect.center(),
+            size: rect.size(),
+            pivot,
+        })
     }
+}
 
-    // Update selection
-    if let Some(entity) = closest_entity {
-        editor_scene.select_entity(entity);
-        info!("Selected entity in viewport: {:?}", entity);
-    }
-    // Note: Don't clear selection when clicking empty space
-    // This allows clicking on panels without losing selection
+/// An entity moved by the current gizmo drag
+#[derive(Debug, Clone, Copy)]
+pub struct DraggedEntity {
+    pub entity: Entity,
+    /// Local transform when the drag started
+    pub start: Transform,
+    /// World position when the drag started
+    pub start_world: Vec2,
 }
 
-/// System to handle dragging selected entities via gizmo
+pub(crate) type DraggableQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Transform,
+        &'static GlobalTransform,
+        Option<&'static Sprite>,
+        Option<&'static ChildOf>,
+        Option<&'static EditorEntityId>,
+    ),
+    With<EditorSceneEntity>,
+>;
+
+/// System to move, rotate and scale the selected entities with the gizmo.
+///
+/// The whole selection follows the handles, turning and scaling around the
+/// pivot chosen in [`GizmoState`]. Moves, rotations and scales snap as set
+/// in [`SnapSettings`] and the editor grid; holding Ctrl inverts snapping.
+/// Each drag is recorded as one undo step.
 pub fn gizmo_drag_interaction_system(
-    mouse_button: Res<ButtonInput<MouseButton>>,
+    (mouse_button, keyboard): (Res<ButtonInput<MouseButton>>, Res<ButtonInput<KeyCode>>),
     windows: Query<&Window, With<PrimaryWindow>>,
     camera_q: Query<(&Camera, &GlobalTransform), With<EditorCamera>>,
-    mut editor_scene: ResMut<EditorScene>,
+    selection: Res<Selection>,
     mut drag_state: ResMut<GizmoDragState>,
-    mut entity_query: Query<
+    mut entity_query: DraggableQuery,
+    parent_query: Query<&GlobalTransform>,
+    (images, atlas_layouts): (Res<Assets<Image>>, Res<Assets<TextureAtlasLayout>>),
+    mut marquee: ResMut<MarqueeSelection>,
+    mut history: ResMut<EditorHistory>,
+    mut egui_contexts: Query<&mut EguiContext, With<PrimaryWindow>>,
+    gizmo_state: Res<GizmoState>,
+    pick_state: Res<EntityPickState>,
+    snap_targets: Query<
         (
-            &mut Transform,
+            Entity,
             &GlobalTransform,
             Option<&Sprite>,
-            Option<&ChildOf>,
+            Option<&EditorViewState>,
         ),
         With<EditorSceneEntity>,
     >,
-    parent_query: Query<&GlobalTransform>,
-    images: Res<Assets<Image>>,
-    mut egui_contexts: Query<&mut EguiContext, With<PrimaryWindow>>,
-    mut transform_events: EventWriter<TransformEditEvent>,
-    gizmo_state: Res<GizmoState>,
+    (editor_state, snap_settings, tile_grid): (
+        Res<EditorState>,
+        Res<SnapSettings>,
+        Option<Res<TileGrid>>,
+    ),
 ) {
-    // Don't interact if mouse is over egui UI
-    if let Some(mut egui_context) = egui_contexts.iter_mut().next() {
-        if egui_context.get_mut().is_pointer_over_area() {
-            return;
+    let cursor = windows.single().ok().and_then(Window::cursor_position);
+    let camera = camera_q.single().ok();
+    let world_pos = cursor
+        .zip(camera)
+        .and_then(|(cursor_position, (camera, camera_transform))| {
+            let ray = camera
+                .viewport_to_world(camera_transform, cursor_position)
+                .ok()?;
+            Some(ray.origin.truncate())
+        });
+
+    // Start dragging
+    if mouse_button.just_pressed(MouseButton::Left)
+        && !drag_state.is_dragging
+        && !pick_state.is_active()
+    {
+        // Don't interact if mouse is over egui UI
+        let over_ui = egui_contexts
+            .iter_mut()
+            .next()
+            .is_some_and(|mut egui_context| egui_context.get_mut().is_pointer_over_area());
+        let bounds = |entity: Entity| {
+            let (_, global_transform, sprite, _, _) = entity_query.get(entity).ok()?;
+            Some(EntityBounds::of_sprite(
+                global_transform,
+                sprite,
+                &images,
+                &atlas_layouts,
+            ))
+        };
+        let gizmo = SelectionGizmo::new(&selection, &gizmo_state, bounds);
+        if let (Some(world_pos), Some(gizmo), false) = (world_pos, gizmo, over_ui) {
+            // Detect which handle is being clicked based on gizmo mode; in
+            // move mode the selection can be dragged from any selected entity
+            let handle = detect_gizmo_handle(world_pos, gizmo.center, gizmo.size, gizmo_state.mode)
+                .or_else(|| {
+                    let inside = selection
+                        .iter()
+                        .any(|entity| bounds(entity).is_some_and(|b| b.contains(world_pos)));
+                    (gizmo_state.mode == GizmoMode::Move && inside).then_some(GizmoHandle::Center)
+                });
+
+            if let Some(handle) = handle {
+                marquee.cancel();
+                drag_state.dragged = selection
+                    .iter()
+                    .filter_map(|entity| {
+                        let (transform, global_transform, ..) = entity_query.get(entity).ok()?;
+                        Some(DraggedEntity {
+                            entity,
+                            start: *transform,
+                            start_world: global_transform.translation().truncate(),
+                        })
+                    })
+                    .collect();
+                // Children follow their parents, so only the topmost selected move
+                let dragged: Vec<Entity> = drag_state.dragged.iter().map(|d| d.entity).collect();
+                drag_state.dragged.retain(|dragged_entity| {
+                    !has_selected_ancestor(dragged_entity.entity, &dragged, &entity_query)
+                });
+                drag_state.is_dragging = true;
+                drag_state.drag_start_world = world_pos;
+                drag_state.start_bounds = Rect::from_center_size(gizmo.center, gizmo.size);
+                drag_state.pivot = gizmo.pivot;
+                drag_state.active_handle = Some(handle);
+                info!(
+                    "Started dragging {:?} handle on {} entities",
+                    handle,
+                    drag_state.dragged.len()
+                );
+            }
         }
     }
 
-    let Ok(window) = windows.single() else {
+    if !drag_state.is_dragging {
         return;
-    };
-
-    let Ok((camera, camera_transform)) = camera_q.single() else {
-        return;
-    };
+    }
 
-    // Helper function to get world position from cursor
-    let get_world_pos = || -> Option<Vec2> {
-        let cursor_position = window.cursor_position()?;
-        let ray = camera
-            .viewport_to_world(camera_transform, cursor_position)
-            .ok()?;
-        Some(ray.origin.truncate())
-    };
+    // During drag - update entity transforms based on active handle
+    if mouse_button.pressed(MouseButton::Left) {
+        let (Some(current_world_pos), Some(handle)) = (world_pos, drag_state.active_handle) else {
+            return;
+        };
+        let delta = current_world_pos - drag_state.drag_start_world;
+        // Rotation and scale are measured around the pivot, or the gizmo
+        // center when each entity keeps its own origin
+        let pivot = drag_state.pivot.unwrap_or(drag_state.start_bounds.center());
+        let invert = keyboard.any_pressed(SNAP_INVERT_KEYS);
+        let mut guides = Vec::new();
+
+        let mut move_delta = match handle {
+            GizmoHandle::XAxis => Vec2::new(delta.x, 0.0),
+            GizmoHandle::YAxis => Vec2::new(0.0, delta.y),
+            _ => delta,
+        };
+        let mut angle = 0.0;
+        let mut scale_factor = Vec2::ONE;
+        match handle {
+            // Grabbing without moving leaves everything where it was
+            GizmoHandle::Center | GizmoHandle::XAxis | GizmoHandle::YAxis
+                if delta == Vec2::ZERO => {}
+            GizmoHandle::Center | GizmoHandle::XAxis | GizmoHandle::YAxis => {
+                // Snap the primary entity's origin, or the first dragged one's
+                let origin = drag_state
+                    .dragged
+                    .iter()
+                    .find(|dragged| selection.primary() == Some(dragged.entity))
+                    .or(drag_state.dragged.first())
+                    .map_or(drag_state.start_bounds.center(), |dragged| {
+                        dragged.start_world
+                    });
+                let mut snapped = None;
+                if snap_settings.to_tiles != invert {
+                    snapped = tile_grid.as_ref().and_then(|grid| {
+                        let (x, y) = grid.world_to_tile(origin + move_delta)?;
+                        Some(grid.tile_center(x, y))
+                    });
+                }
+                if snapped.is_none() && editor_state.grid_snap_enabled != invert {
+                    snapped = Some(snap_to_grid(
+                        origin + move_delta,
+                        Vec2::splat(editor_state.grid_size),
+                    ));
+                }
+                if let Some(snapped) = snapped {
+                    move_delta = snapped - origin;
+                    guides.push(SnapGuide::Point(snapped));
+                }
 
-    // Start dragging
-    if mouse_button.just_pressed(MouseButton::Left) && !drag_state.is_dragging {
-        if let Some(selected_entity) = editor_scene.selected_entity {
-            if let Ok((transform, global_transform, sprite, _)) = entity_query.get(selected_entity)
-            {
-                if let Some(world_pos) = get_world_pos() {
-                    // Use GlobalTransform for accurate world position
-                    let entity_world_pos = global_transform.translation().truncate();
-
-                    // Get entity bounds for handle positioning
-                    let bounds = if let Some(sprite_comp) = sprite {
-                        let base_size = if let Some(custom_size) = sprite_comp.custom_size {
-                            custom_size
-                        } else if let Some(image) = images.get(&sprite_comp.image) {
-                            image.size().as_vec2()
-                        } else {
-                            Vec2::new(64.0, 64.0)
-                        };
-                        base_size * transform.scale.xy()
-                    } else {
-                        Vec2::new(32.0, 32.0) * transform.scale.xy()
+                // Lining up with other entities wins over the grid
+                if snap_settings.to_entities != invert {
+                    let dragged: Vec<Entity> =
+                        drag_state.dragged.iter().map(|d| d.entity).collect();
+                    let targets: Vec<Rect> = snap_targets
+                        .iter()
+                        .filter(|(entity, _, _, view_state)| {
+                            !dragged.contains(entity)
+                                && !has_selected_ancestor(*entity, &dragged, &entity_query)
+                                && view_state.is_none_or(|view_state| !view_state.hidden)
+                        })
+                        .map(|(_, transform, sprite, _)| {
+                            EntityBounds::of_sprite(transform, sprite, &images, &atlas_layouts)
+                                .aabb()
+                        })
+                        .collect();
+                    // Distance is given in pixels, so snapping feels the same at any zoom
+                    let pixel_size = cursor
+                        .zip(camera)
+                        .and_then(|(cursor_position, (camera, camera_transform))| {
+                            let a = camera
+                                .viewport_to_world_2d(camera_transform, cursor_position)
+                                .ok()?;
+                            let b = camera
+                                .viewport_to_world_2d(camera_transform, cursor_position + Vec2::X)
+                                .ok()?;
+                            Some(a.distance(b))
+                        })
+                        .unwrap_or(1.0);
+                    let moving = Rect {
+                        min: drag_state.start_bounds.min + move_delta,
+                        max: drag_state.start_bounds.max + move_delta,
                     };
-
-                    // Detect which handle is being clicked based on gizmo mode
-                    let handle =
-                        detect_gizmo_handle(world_pos, entity_world_pos, bounds, gizmo_state.mode);
-
-                    if let Some(handle) = handle {
-                        drag_state.is_dragging = true;
-                        drag_state.dragged_entity = Some(selected_entity);
-                        drag_state.drag_start_world = world_pos;
-                        drag_state.entity_start_pos = entity_world_pos;
-                        drag_state.entity_start_rotation =
-                            transform.rotation.to_euler(EulerRot::XYZ).2;
-                        drag_state.entity_start_scale = transform.scale;
-                        drag_state.active_handle = Some(handle);
-                        info!(
-                            "Started dragging {:?} handle on entity {:?}",
-                            handle, selected_entity
-                        );
+                    let (mut offset, mut entity_guides) = snap_to_bounds(
+                        moving,
+                        &targets,
+                        snap_settings.entity_distance * pixel_size,
+                    );
+                    // Axis handles only move, and so only snap, along their axis
+                    let vertical = |guide: &SnapGuide| matches!(guide, SnapGuide::Line { from, to } if from.x == to.x);
+                    match handle {
+                        GizmoHandle::XAxis => {
+                            offset.y = 0.0;
+                            entity_guides.retain(vertical);
+                        }
+                        GizmoHandle::YAxis => {
+                            offset.x = 0.0;
+                            entity_guides.retain(|guide| !vertical(guide));
+                        }
+                        _ => {}
+                    }
+                    if offset != Vec2::ZERO {
+                        move_delta += offset;
+                        guides.retain(|guide| !matches!(guide, SnapGuide::Point(_)));
                     }
+                    guides.extend(entity_guides);
                 }
             }
-        }
-    }
-
-    // During drag - update entity transform based on active handle
-    if drag_state.is_dragging {
-        if mouse_button.pressed(MouseButton::Left) {
-            if let Some(dragged_entity) = drag_state.dragged_entity {
-                if let Ok((mut transform, global_transform, _, parent)) =
-                    entity_query.get_mut(dragged_entity)
-                {
-                    if let Some(current_world_pos) = get_world_pos() {
-                        let delta = current_world_pos - drag_state.drag_start_world;
-
-                        match drag_state.active_handle {
-                            Some(GizmoHandle::Center)
-                            | Some(GizmoHandle::XAxis)
-                            | Some(GizmoHandle::YAxis) => {
-                                // Move mode - translate entity
-                                let constrained_delta = match drag_state.active_handle {
-                                    Some(GizmoHandle::XAxis) => Vec2::new(delta.x, 0.0),
-                                    Some(GizmoHandle::YAxis) => Vec2::new(0.0, delta.y),
-                                    _ => delta,
-                                };
-
-                                let new_world_pos = drag_state.entity_start_pos + constrained_delta;
-
-                                // Convert to local space if needed
-                                let local_pos = if let Some(child_of) = parent {
-                                    if let Ok(parent_global_transform) =
-                                        parent_query.get(child_of.0)
-                                    {
-                                        let parent_inverse =
-                                            parent_global_transform.affine().inverse();
-                                        let local_pos_3d = parent_inverse
-                                            .transform_point3(new_world_pos.extend(0.0));
-                                        local_pos_3d.truncate()
-                                    } else {
-                                        new_world_pos
-                                    }
-                                } else {
-                                    new_world_pos
-                                };
-
-                                transform.translation.x = local_pos.x;
-                                transform.translation.y = local_pos.y;
-                            }
-
-                            Some(GizmoHandle::RotateHandle) => {
-                                // Rotate mode - rotate around center
-                                let entity_world_pos = global_transform.translation().truncate();
-                                let start_vec =
-                                    (drag_state.drag_start_world - entity_world_pos).normalize();
-                                let current_vec =
-                                    (current_world_pos - entity_world_pos).normalize();
-
-                                let angle =
-                                    start_vec.x * current_vec.y - start_vec.y * current_vec.x;
-                                let angle = angle.atan2(start_vec.dot(current_vec));
-
-                                let new_rotation = drag_state.entity_start_rotation + angle;
-                                transform.rotation = Quat::from_rotation_z(new_rotation);
-                            }
-
-                            Some(GizmoHandle::ScaleCorner) => {
-                                // Uniform scale from corner
-                                let entity_world_pos = global_transform.translation().truncate();
-                                let start_dist =
-                                    drag_state.drag_start_world.distance(entity_world_pos);
-                                let current_dist = current_world_pos.distance(entity_world_pos);
-
-                                if start_dist > 0.1 {
-                                    let scale_factor = current_dist / start_dist;
-                                    transform.scale = drag_state.entity_start_scale * scale_factor;
-                                }
-                            }
-
-                            Some(GizmoHandle::ScaleEdge(axis)) => {
-                                // Axis-aligned scale
-                                let projected_delta = delta.dot(axis);
-                                let scale_factor = 1.0 + (projected_delta / 100.0);
-
-                                if axis.x.abs() > 0.5 {
-                                    // X-axis scale
-                                    transform.scale.x =
-                                        (drag_state.entity_start_scale.x * scale_factor).max(0.1);
-                                } else {
-                                    // Y-axis scale
-                                    transform.scale.y =
-                                        (drag_state.entity_start_scale.y * scale_factor).max(0.1);
-                                }
-                            }
-
-                            None => {}
-                        }
-                    }
+            GizmoHandle::RotateHandle => {
+                let start_vec = (drag_state.drag_start_world - pivot).normalize_or_zero();
+                let current_vec = (current_world_pos - pivot).normalize_or_zero();
+                angle = start_vec
+                    .perp_dot(current_vec)
+                    .atan2(start_vec.dot(current_vec));
+                if snap_settings.rotation != invert {
+                    angle = snap_angle(angle, snap_settings.rotation_step);
+                    let radius = drag_state.drag_start_world.distance(pivot);
+                    guides.push(SnapGuide::Line {
+                        from: pivot,
+                        to: pivot + start_vec * radius,
+                    });
+                    guides.push(SnapGuide::Line {
+                        from: pivot,
+                        to: pivot + Vec2::from_angle(angle).rotate(start_vec) * radius,
+                    });
                 }
             }
-        } else {
-            // Mouse released - end drag and send appropriate event with undo history
-            if let Some(dragged_entity) = drag_state.dragged_entity {
-                if let Ok((transform, _, _, _)) = entity_query.get(dragged_entity) {
-                    // Send appropriate transform event based on what was modified
-                    // Events will be intercepted by transform_with_undo system
-                    match drag_state.active_handle {
-                        Some(GizmoHandle::Center)
-                        | Some(GizmoHandle::XAxis)
-                        | Some(GizmoHandle::YAxis) => {
-                            transform_events.write(TransformEditEvent::SetPosition {
-                                entity: dragged_entity,
-                                position: transform.translation.truncate(),
-                            });
-                        }
-                        Some(GizmoHandle::RotateHandle) => {
-                            transform_events.write(TransformEditEvent::SetRotation {
-                                entity: dragged_entity,
-                                rotation: transform.rotation.to_euler(EulerRot::XYZ).2,
-                            });
+            GizmoHandle::ScaleCorner | GizmoHandle::ScaleEdge(_) => {
+                scale_factor = match handle {
+                    GizmoHandle::ScaleEdge(axis) => {
+                        // Axis-aligned scale
+                        let factor = 1.0 + delta.dot(axis) / 100.0;
+                        if axis.x.abs() > 0.5 {
+                            Vec2::new(factor, 1.0)
+                        } else {
+                            Vec2::new(1.0, factor)
                         }
-                        Some(GizmoHandle::ScaleCorner) | Some(GizmoHandle::ScaleEdge(_)) => {
-                            transform_events.write(TransformEditEvent::SetScale {
-                                entity: dragged_entity,
-                                scale: transform.scale.truncate(),
-                            });
+                    }
+                    // Uniform scale from corner
+                    _ => {
+                        let start_dist = drag_state.drag_start_world.distance(pivot);
+                        if start_dist > 0.1 {
+                            Vec2::splat(current_world_pos.distance(pivot) / start_dist)
+                        } else {
+                            Vec2::ONE
                         }
-                        None => {}
                     }
-
-                    editor_scene.mark_modified();
-                    info!("Ended dragging entity {:?}", dragged_entity);
+                };
+                if snap_settings.scale != invert {
+                    let step = snap_settings.scale_step;
+                    scale_factor = Vec2::new(
+                        snap_to_step(scale_factor.x, step),
+                        snap_to_step(scale_factor.y, step),
+                    )
+                    .max(Vec2::splat(step));
+                    let bounds = drag_state.start_bounds;
+                    guides.extend(rect_guides(Rect::from_corners(
+                        pivot + (bounds.min - pivot) * scale_factor,
+                        pivot + (bounds.max - pivot) * scale_factor,
+                    )));
                 }
             }
+        }
+        drag_state.guides = guides;
+
+        for dragged in drag_state.dragged.clone() {
+            let Ok((mut transform, _, _, parent, _)) = entity_query.get_mut(dragged.entity) else {
+                continue;
+            };
+            let new_world_pos = match handle {
+                GizmoHandle::Center | GizmoHandle::XAxis | GizmoHandle::YAxis => {
+                    dragged.start_world + move_delta
+                }
+                GizmoHandle::RotateHandle => {
+                    transform.rotation = dragged.start.rotation * Quat::from_rotation_z(angle);
+                    match drag_state.pivot {
+                        Some(pivot) => {
+                            pivot + Vec2::from_angle(angle).rotate(dragged.start_world - pivot)
+                        }
+                        None => dragged.start_world,
+                    }
+                }
+                GizmoHandle::ScaleCorner | GizmoHandle::ScaleEdge(_) => {
+                    let scale = dragged.start.scale.truncate() * scale_factor;
+                    transform.scale = scale.max(Vec2::splat(0.1)).extend(dragged.start.scale.z);
+                    match drag_state.pivot {
+                        Some(pivot) => pivot + (dragged.start_world - pivot) * scale_factor,
+                        None => dragged.start_world,
+                    }
+                }
+            };
+
+            // Convert to local space if needed
+            let local_pos = parent
+                .and_then(|child_of| parent_query.get(child_of.parent()).ok())
+                .map_or(new_world_pos, |parent_global_transform| {
+                    parent_global_transform
+                        .affine()
+                        .inverse()
+                        .transform_point3(new_world_pos.extend(0.0))
+                        .truncate()
+                });
+            transform.translation.x = local_pos.x;
+            transform.translation.y = local_pos.y;
+        }
+        return;
+    }
+
+    // Mouse released - end drag and record it as one undo step
+    let changes: Vec<TransformChange> = drag_state
+        .dragged
+        .iter()
+        .filter_map(|dragged| {
+            let (transform, _, _, _, id) = entity_query.get(dragged.entity).ok()?;
+            let entity = *id?;
+            (*transform != dragged.start).then_some(TransformChange {
+                entity,
+                old: dragged.start,
+                new: *transform,
+            })
+        })
+        .collect();
+    if !changes.is_empty() {
+        let property = match drag_state.active_handle {
+            Some(GizmoHandle::RotateHandle) => "Rotation",
+            Some(GizmoHandle::ScaleCorner | GizmoHandle::ScaleEdge(_)) => "Scale",
+            _ => "Position",
+        };
+        history.add_executed(Box::new(TransformEntitiesCommand::new(changes, property)));
+    }
+    info!("Ended dragging {} entities", drag_state.dragged.len());
 
-            drag_state.is_dragging = false;
-            drag_state.dragged_entity = None;
-            drag_state.active_handle = None;
+    drag_state.is_dragging = false;
+    drag_state.dragged.clear();
+    drag_state.active_handle = None;
+    drag_state.guides.clear();
+}
+
+/// Guides along the edges of `rect`
+fn rect_guides(rect: Rect) -> [SnapGuide; 4] {
+    let corners = [
+        rect.min,
+        Vec2::new(rect.max.x, rect.min.y),
+        rect.max,
+        Vec2::new(rect.min.x, rect.max.y),
+    ];
+    std::array::from_fn(|index| SnapGuide::Line {
+        from: corners[index],
+        to: corners[(index + 1) % 4],
+    })
+}
+
+/// Whether any ancestor of `entity` is among `selected`
+pub(crate) fn has_selected_ancestor(
+    entity: Entity,
+    selected: &[Entity],
+    entity_query: &DraggableQuery,
+) -> bool {
+    let mut current = entity;
+    while let Ok((_, _, _, Some(child_of), _)) = entity_query.get(current) {
+        current = child_of.parent();
+        if selected.contains(&current) {
+            return true;
         }
     }
+    false
 }
 
-/// System to convert transform edits into undo/redo commands
-/// Runs AFTER handle_transform_edit_events (transform already applied)
+/// System recording transform edit events (e.g. from the inspector) in the
+/// history. Runs BEFORE handle_transform_edit_events, so the current transform
+/// is the value to undo to. Consecutive edits of one entity merge into one step.
 pub fn transform_with_undo_system(
     mut events: EventReader<TransformEditEvent>,
-    entity_query: Query<&Transform, With<EditorSceneEntity>>,
-    drag_state: Res<GizmoDragState>,
+    entity_query: Query<(&Transform, &EditorEntityId), With<EditorSceneEntity>>,
     mut history: ResMut<EditorHistory>,
 ) {
+    // Transforms after the events already read this frame are applied
+    let mut edited: HashMap<Entity, Transform> = HashMap::new();
     for event in events.read() {
-        match event {
-            TransformEditEvent::SetPosition { entity, position } => {
-                // Get old position from drag_state (if available)
-                if let Ok(transform) = entity_query.get(*entity) {
-                    let old_pos = if drag_state.dragged_entity == Some(*entity) {
-                        drag_state.entity_start_pos
-                    } else {
-                        // Fallback: use current position (no undo possible)
-                        *position
-                    };
-
-                    // Only create command if we have different old/new values
-                    if old_pos != *position {
-                        let command =
-                            TransformCommand::new_position(*entity, old_pos, *position, *transform);
+        let (TransformEditEvent::SetPosition { entity, .. }
+        | TransformEditEvent::Translate { entity, .. }
+        | TransformEditEvent::SetRotation { entity, .. }
+        | TransformEditEvent::SetScale { entity, .. }) = event;
+        let Ok((transform, id)) = entity_query.get(*entity) else {
+            continue;
+        };
 
-                        // Add to history without re-executing (transform already applied)
-                        history.add_executed(Box::new(command));
-                    }
-                }
+        let old = edited.get(entity).copied().unwrap_or(*transform);
+        let mut new = old;
+        let property = match event {
+            TransformEditEvent::SetPosition { position, .. } => {
+                new.translation.x = position.x;
+                new.translation.y = position.y;
+                "Position"
             }
-            TransformEditEvent::SetRotation { entity, rotation } => {
-                if let Ok(transform) = entity_query.get(*entity) {
-                    let old_rot = if drag_state.dragged_entity == Some(*entity) {
-                        drag_state.entity_start_rotation
-                    } else {
-                        *rotation
-                    };
-
-                    if (old_rot - *rotation).abs() > 0.001 {
-                        let command =
-                            TransformCommand::new_rotation(*entity, old_rot, *rotation, *transform);
-
-                        history.add_executed(Box::new(command));
-                    }
-                }
+            TransformEditEvent::Translate { delta, .. } => {
+                new.translation.x += delta.x;
+                new.translation.y += delta.y;
+                "Position"
             }
-            TransformEditEvent::SetScale { entity, scale } => {
-                if let Ok(transform) = entity_query.get(*entity) {
-                    let old_scale = if drag_state.dragged_entity == Some(*entity) {
-                        drag_state.entity_start_scale.truncate()
-                    } else {
-                        *scale
-                    };
-
-                    if old_scale != *scale {
-                        let command =
-                            TransformCommand::new_scale(*entity, old_scale, *scale, *transform);
-
-                        history.add_executed(Box::new(command));
-                    }
-                }
+            TransformEditEvent::SetRotation { rotation, .. } => {
+                new.rotation = Quat::from_rotation_z(*rotation);
+                "Rotation"
             }
-            TransformEditEvent::Translate { .. } => {
-                // Translate events don't support undo yet - they modify incrementally
-                // TODO: Implement if needed
+            TransformEditEvent::SetScale { scale, .. } => {
+                new.scale.x = scale.x;
+                new.scale.y = scale.y;
+                "Scale"
             }
+        };
+        if new == old {
+            continue;
+        }
+
+        edited.insert(*entity, new);
+        history.add_executed(Box::new(TransformCommand::new(*id, old, new, property)));
+    }
+}
+
+/// System placing the 2D cursor, the pivot in [`PivotMode::Cursor`], with
+/// Shift + right click
+pub fn place_pivot_cursor_system(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_q: Query<(&Camera, &GlobalTransform), With<EditorCamera>>,
+    mut gizmo_state: ResMut<GizmoState>,
+    mut egui_contexts: Query<&mut EguiContext, With<PrimaryWindow>>,
+) {
+    if !mouse_button.just_pressed(MouseButton::Right)
+        || !keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+    {
+        return;
+    }
+    if let Some(mut egui_context) = egui_contexts.iter_mut().next() {
+        if egui_context.get_mut().is_pointer_over_area() {
+            return;
         }
     }
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera_q.single() else {
+        return;
+    };
+    let Some(ray) = window.cursor_position().and_then(|cursor_position| {
+        camera
+            .viewport_to_world(camera_transform, cursor_position)
+            .ok()
+    }) else {
+        return;
+    };
+    gizmo_state.cursor = ray.origin.truncate();
 }
 
 /// Detect which gizmo handle (if any) is being clicked
@@ -443,19 +844,6 @@ fn detect_gizmo_handle(
                 return Some(GizmoHandle::Center);
             }
 
-            // Allow clicking anywhere within entity bounds for free movement
-            let half_size = bounds / 2.0;
-            let min = entity_pos - half_size;
-            let max = entity_pos + half_size;
-
-            if click_pos.x >= min.x
-                && click_pos.x <= max.x
-                && click_pos.y >= min.y
-                && click_pos.y <= max.y
-            {
-                return Some(GizmoHandle::Center);
-            }
-
             None
         }
 
@@ -520,3 +908,44 @@ fn detect_gizmo_handle(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_editor_scene::handle_transform_edit_events;
+
+    #[test]
+    fn consecutive_transform_edits_undo_as_one_step() {
+        let mut world = World::new();
+        world.init_resource::<Events<TransformEditEvent>>();
+        world.init_resource::<EditorHistory>();
+        let entity = world
+            .spawn((
+                Transform::from_xyz(1.0, 2.0, 0.0),
+                EditorSceneEntity,
+                EditorEntityId::new(),
+            ))
+            .id();
+
+        for position in [Vec2::new(5.0, 2.0), Vec2::new(8.0, 3.0)] {
+            world.send_event(TransformEditEvent::SetPosition { entity, position });
+            world.run_system_once(transform_with_undo_system).unwrap();
+            world.run_system_once(handle_transform_edit_events).unwrap();
+            world.resource_mut::<Events<TransformEditEvent>>().clear();
+        }
+        assert_eq!(
+            world.get::<Transform>(entity).unwrap().translation,
+            Vec3::new(8.0, 3.0, 0.0)
+        );
+
+        world.resource_scope(|world, mut history: Mut<EditorHistory>| {
+            assert!(history.undo(world));
+            assert!(!history.can_undo());
+        });
+        assert_eq!(
+            world.get::<Transform>(entity).unwrap().translation,
+            Vec3::new(1.0, 2.0, 0.0)
+        );
+    }
+}